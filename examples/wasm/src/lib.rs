@@ -0,0 +1,143 @@
+//! In-browser ACH file preview.
+//!
+//! Build with `wasm-pack build --target web examples/wasm` (or plain
+//! `cargo build --target wasm32-unknown-unknown -p rs-ach-wasm`) and call
+//! `validate` from JavaScript:
+//!
+//! ```js
+//! import init, { validate } from "./pkg/rs_ach_wasm.js";
+//!
+//! await init();
+//! const report = JSON.parse(validate(await file.text()));
+//! if (!report.valid) console.log(report.errors);
+//! ```
+//!
+//! wasm-bindgen copies the JS string into linear memory for the duration of
+//! the call; the parsed file borrows from that copy and is dropped before
+//! returning, so only the owned JSON string crosses back to JavaScript.
+
+use wasm_bindgen::prelude::*;
+
+use ach::AchFile;
+
+/// Parse an ACH file and return a JSON validation report.
+///
+/// The report always has `valid` and `errors`; when the file parses it also
+/// carries `batch_count`, `entry_count`, `total_debit_amount`, and
+/// `total_credit_amount` (amounts in cents, computed from the entries).
+#[wasm_bindgen]
+pub fn validate(content: &str) -> String {
+    let file = match AchFile::parse(content) {
+        Ok(file) => file,
+        Err(e) => return report(&[e.to_string()], None),
+    };
+
+    let mut errors = Vec::new();
+    let mut totals = Totals::default();
+    for batch in &file.batches {
+        let mut debit = 0;
+        let mut credit = 0;
+        for entry in &batch.entries {
+            match entry.transaction_code.as_bytes().get(1) {
+                Some(b'2' | b'3' | b'4') => credit += entry.amount,
+                Some(b'7' | b'8' | b'9') => debit += entry.amount,
+                _ => {}
+            }
+        }
+        if debit != batch.control.total_debit_amount || credit != batch.control.total_credit_amount
+        {
+            errors.push(format!(
+                "Batch {}: control totals do not match its entries",
+                batch.header.batch_number
+            ));
+        }
+        totals.batches += 1;
+        totals.entries += batch.entries.len();
+        totals.debit += debit;
+        totals.credit += credit;
+    }
+    if totals.debit != file.file_control.total_debit_amount
+        || totals.credit != file.file_control.total_credit_amount
+    {
+        errors.push("File control totals do not match the batches".to_string());
+    }
+
+    report(&errors, Some(&totals))
+}
+
+#[derive(Debug, Default)]
+struct Totals {
+    batches: usize,
+    entries: usize,
+    debit: u64,
+    credit: u64,
+}
+
+fn report(errors: &[String], totals: Option<&Totals>) -> String {
+    let errors: Vec<String> = errors.iter().map(|e| json_string(e)).collect();
+    let mut json = format!(
+        "{{\"valid\":{},\"errors\":[{}]",
+        errors.is_empty(),
+        errors.join(",")
+    );
+    if let Some(t) = totals {
+        json.push_str(&format!(
+            ",\"batch_count\":{},\"entry_count\":{},\"total_debit_amount\":{},\"total_credit_amount\":{}",
+            t.batches, t.entries, t.debit, t.credit
+        ));
+    }
+    json.push('}');
+    json
+}
+
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = concat!(
+        "101 12345678012345678011409020123A094101YOUR BANK              YOUR COMPANY                   \n",
+        "5200YOUR COMPANY                        1234567890PPDPAYROLL         140903   1123456780000001\n",
+        "62212345678011232132         0000001000               ALICE WANDERDUST        1123456780000001\n",
+        "820000000100123456780000000000000000000010001234567890                         123456780000001\n",
+        "9000001000001000000010012345678000000000000000000001000                                       ",
+    );
+
+    #[test]
+    fn test_valid_file() {
+        assert_eq!(
+            validate(SAMPLE),
+            "{\"valid\":true,\"errors\":[],\"batch_count\":1,\"entry_count\":1,\
+             \"total_debit_amount\":0,\"total_credit_amount\":1000}"
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let json = validate("101 short");
+        assert!(json.starts_with("{\"valid\":false,\"errors\":[\""));
+        assert!(!json.contains("batch_count"));
+    }
+
+    #[test]
+    fn test_json_string_escapes() {
+        assert_eq!(json_string("a\"b\\c\n"), "\"a\\\"b\\\\c\\n\"");
+    }
+}
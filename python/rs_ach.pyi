@@ -0,0 +1,94 @@
+"""Type stubs for the rs_ach extension module."""
+
+from typing import List, Optional
+
+class AchError(ValueError):
+    line: Optional[int]
+
+class FileHeader:
+    record_type: str
+    priority_code: str
+    immediate_destination: str
+    immediate_origin: str
+    file_creation_date: str
+    file_creation_time: str
+    file_id_modifier: str
+    record_size: str
+    blocking_factor: str
+    format_code: str
+    immediate_destination_name: str
+    immediate_origin_name: str
+    reference_code: str
+
+class BatchHeader:
+    record_type: str
+    service_class_code: str
+    company_name: str
+    company_discretionary_data: str
+    company_identification: str
+    standard_entry_class_code: str
+    company_entry_description: str
+    company_descriptive_date: str
+    effective_entry_date: str
+    settlement_date: str
+    originator_status_code: str
+    originating_dfi_identification: str
+    batch_number: str
+
+class Addenda:
+    record_type: str
+    addenda_type_code: str
+    payment_related_information: str
+    addenda_sequence_number: str
+    entry_detail_sequence_number: str
+
+class EntryDetail:
+    record_type: str
+    transaction_code: str
+    receiving_dfi_identification: str
+    check_digit: str
+    dfi_account_number: str
+    amount: int
+    individual_identification_number: str
+    individual_name: str
+    discretionary_data: str
+    addenda_record_indicator: str
+    trace_number: str
+    addenda: List[Addenda]
+
+class BatchControl:
+    record_type: str
+    service_class_code: str
+    entry_addenda_count: int
+    entry_hash: int
+    total_debit_amount: int
+    total_credit_amount: int
+    company_identification: str
+    message_authentication_code: str
+    reserved: str
+    originating_dfi_identification: str
+    batch_number: str
+
+class FileControl:
+    record_type: str
+    batch_count: int
+    block_count: int
+    entry_addenda_count: int
+    entry_hash: int
+    total_debit_amount: int
+    total_credit_amount: int
+    reserved: str
+
+class Batch:
+    header: BatchHeader
+    entries: List[EntryDetail]
+    control: BatchControl
+    def __len__(self) -> int: ...
+
+class AchFile:
+    file_header: FileHeader
+    batches: List[Batch]
+    file_control: FileControl
+    def entries(self) -> List[EntryDetail]: ...
+
+def parse(content: str) -> AchFile: ...
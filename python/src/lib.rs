@@ -0,0 +1,414 @@
+//! Python bindings for rs-ach.
+//!
+//! Exposes `rs_ach.parse(content)` returning an `AchFile` whose batches,
+//! entries, and addenda are plain Python objects holding copies of the
+//! parsed fields, so nothing on the Python side borrows from the input.
+//! Parse failures raise `rs_ach.AchError`.
+
+use ach::AchError as RustAchError;
+use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+create_exception!(
+    rs_ach,
+    AchError,
+    PyValueError,
+    "Raised when an ACH file cannot be parsed. `args[0]` is the message and \
+     `line` is the 1-based line number when the error carries one."
+);
+
+/// File header record (record type 1).
+#[pyclass(
+    module = "rs_ach",
+    name = "FileHeader",
+    get_all,
+    frozen,
+    skip_from_py_object
+)]
+#[derive(Debug, Clone)]
+pub struct FileHeader {
+    pub record_type: String,
+    pub priority_code: String,
+    pub immediate_destination: String,
+    pub immediate_origin: String,
+    pub file_creation_date: String,
+    pub file_creation_time: String,
+    pub file_id_modifier: String,
+    pub record_size: String,
+    pub blocking_factor: String,
+    pub format_code: String,
+    pub immediate_destination_name: String,
+    pub immediate_origin_name: String,
+    pub reference_code: String,
+}
+
+/// Batch header record (record type 5).
+#[pyclass(
+    module = "rs_ach",
+    name = "BatchHeader",
+    get_all,
+    frozen,
+    skip_from_py_object
+)]
+#[derive(Debug, Clone)]
+pub struct BatchHeader {
+    pub record_type: String,
+    pub service_class_code: String,
+    pub company_name: String,
+    pub company_discretionary_data: String,
+    pub company_identification: String,
+    pub standard_entry_class_code: String,
+    pub company_entry_description: String,
+    pub company_descriptive_date: String,
+    pub effective_entry_date: String,
+    pub settlement_date: String,
+    pub originator_status_code: String,
+    pub originating_dfi_identification: String,
+    pub batch_number: String,
+}
+
+/// Addenda record (record type 7).
+#[pyclass(
+    module = "rs_ach",
+    name = "Addenda",
+    get_all,
+    frozen,
+    skip_from_py_object
+)]
+#[derive(Debug, Clone)]
+pub struct Addenda {
+    pub record_type: String,
+    pub addenda_type_code: String,
+    pub payment_related_information: String,
+    pub addenda_sequence_number: String,
+    pub entry_detail_sequence_number: String,
+}
+
+/// Entry detail record (record type 6).
+#[pyclass(
+    module = "rs_ach",
+    name = "EntryDetail",
+    get_all,
+    frozen,
+    skip_from_py_object
+)]
+#[derive(Debug, Clone)]
+pub struct EntryDetail {
+    pub record_type: String,
+    pub transaction_code: String,
+    pub receiving_dfi_identification: String,
+    pub check_digit: String,
+    pub dfi_account_number: String,
+    pub amount: u64,
+    pub individual_identification_number: String,
+    pub individual_name: String,
+    pub discretionary_data: String,
+    pub addenda_record_indicator: String,
+    pub trace_number: String,
+    pub addenda: Vec<Addenda>,
+}
+
+/// Batch control record (record type 8).
+#[pyclass(
+    module = "rs_ach",
+    name = "BatchControl",
+    get_all,
+    frozen,
+    skip_from_py_object
+)]
+#[derive(Debug, Clone)]
+pub struct BatchControl {
+    pub record_type: String,
+    pub service_class_code: String,
+    pub entry_addenda_count: u64,
+    pub entry_hash: u64,
+    pub total_debit_amount: u64,
+    pub total_credit_amount: u64,
+    pub company_identification: String,
+    pub message_authentication_code: String,
+    pub reserved: String,
+    pub originating_dfi_identification: String,
+    pub batch_number: String,
+}
+
+/// File control record (record type 9).
+#[pyclass(
+    module = "rs_ach",
+    name = "FileControl",
+    get_all,
+    frozen,
+    skip_from_py_object
+)]
+#[derive(Debug, Clone)]
+pub struct FileControl {
+    pub record_type: String,
+    pub batch_count: u64,
+    pub block_count: u64,
+    pub entry_addenda_count: u64,
+    pub entry_hash: u64,
+    pub total_debit_amount: u64,
+    pub total_credit_amount: u64,
+    pub reserved: String,
+}
+
+/// A batch: header, entries, and control.
+#[pyclass(
+    module = "rs_ach",
+    name = "Batch",
+    get_all,
+    frozen,
+    skip_from_py_object
+)]
+#[derive(Debug, Clone)]
+pub struct Batch {
+    pub header: BatchHeader,
+    pub entries: Vec<EntryDetail>,
+    pub control: BatchControl,
+}
+
+/// A parsed ACH file.
+#[pyclass(
+    module = "rs_ach",
+    name = "AchFile",
+    get_all,
+    frozen,
+    skip_from_py_object
+)]
+#[derive(Debug, Clone)]
+pub struct AchFile {
+    pub file_header: FileHeader,
+    pub batches: Vec<Batch>,
+    pub file_control: FileControl,
+}
+
+#[pymethods]
+impl EntryDetail {
+    fn __repr__(&self) -> String {
+        format!(
+            "EntryDetail(transaction_code={:?}, amount={}, trace_number={:?})",
+            self.transaction_code, self.amount, self.trace_number
+        )
+    }
+}
+
+#[pymethods]
+impl Batch {
+    fn __repr__(&self) -> String {
+        format!(
+            "Batch(batch_number={:?}, company_name={:?}, entries={})",
+            self.header.batch_number,
+            self.header.company_name.trim(),
+            self.entries.len()
+        )
+    }
+
+    fn __len__(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+#[pymethods]
+impl AchFile {
+    fn __repr__(&self) -> String {
+        format!(
+            "AchFile(immediate_origin={:?}, batches={})",
+            self.file_header.immediate_origin.trim(),
+            self.batches.len()
+        )
+    }
+
+    /// All entries across all batches, in file order.
+    fn entries(&self) -> Vec<EntryDetail> {
+        self.batches
+            .iter()
+            .flat_map(|b| b.entries.iter().cloned())
+            .collect()
+    }
+}
+
+impl From<&ach::AchFile<'_>> for AchFile {
+    fn from(file: &ach::AchFile<'_>) -> Self {
+        let h = &file.file_header;
+        let fc = &file.file_control;
+        AchFile {
+            file_header: FileHeader {
+                record_type: h.record_type.to_string(),
+                priority_code: h.priority_code.to_string(),
+                immediate_destination: h.immediate_destination.to_string(),
+                immediate_origin: h.immediate_origin.to_string(),
+                file_creation_date: h.file_creation_date.to_string(),
+                file_creation_time: h.file_creation_time.to_string(),
+                file_id_modifier: h.file_id_modifier.to_string(),
+                record_size: h.record_size.to_string(),
+                blocking_factor: h.blocking_factor.to_string(),
+                format_code: h.format_code.to_string(),
+                immediate_destination_name: h.immediate_destination_name.to_string(),
+                immediate_origin_name: h.immediate_origin_name.to_string(),
+                reference_code: h.reference_code.to_string(),
+            },
+            batches: file.batches.iter().map(Batch::from).collect(),
+            file_control: FileControl {
+                record_type: fc.record_type.clone(),
+                batch_count: fc.batch_count,
+                block_count: fc.block_count,
+                entry_addenda_count: fc.entry_addenda_count,
+                entry_hash: fc.entry_hash,
+                total_debit_amount: fc.total_debit_amount,
+                total_credit_amount: fc.total_credit_amount,
+                reserved: fc.reserved.clone(),
+            },
+        }
+    }
+}
+
+impl From<&ach::Batch<'_>> for Batch {
+    fn from(batch: &ach::Batch<'_>) -> Self {
+        let h = &batch.header;
+        let c = &batch.control;
+        Batch {
+            header: BatchHeader {
+                record_type: h.record_type.to_string(),
+                service_class_code: h.service_class_code.to_string(),
+                company_name: h.company_name.to_string(),
+                company_discretionary_data: h.company_discretionary_data.to_string(),
+                company_identification: h.company_identification.to_string(),
+                standard_entry_class_code: h.standard_entry_class_code.to_string(),
+                company_entry_description: h.company_entry_description.to_string(),
+                company_descriptive_date: h.company_descriptive_date.to_string(),
+                effective_entry_date: h.effective_entry_date.to_string(),
+                settlement_date: h.settlement_date.to_string(),
+                originator_status_code: h.originator_status_code.to_string(),
+                originating_dfi_identification: h.originating_dfi_identification.to_string(),
+                batch_number: h.batch_number.to_string(),
+            },
+            entries: batch.entries.iter().map(EntryDetail::from).collect(),
+            control: BatchControl {
+                record_type: c.record_type.clone(),
+                service_class_code: c.service_class_code.clone(),
+                entry_addenda_count: c.entry_addenda_count,
+                entry_hash: c.entry_hash,
+                total_debit_amount: c.total_debit_amount,
+                total_credit_amount: c.total_credit_amount,
+                company_identification: c.company_identification.clone(),
+                message_authentication_code: c.message_authentication_code.clone(),
+                reserved: c.reserved.clone(),
+                originating_dfi_identification: c.originating_dfi_identification.clone(),
+                batch_number: c.batch_number.clone(),
+            },
+        }
+    }
+}
+
+impl From<&ach::EntryDetail<'_>> for EntryDetail {
+    fn from(e: &ach::EntryDetail<'_>) -> Self {
+        EntryDetail {
+            record_type: e.record_type.to_string(),
+            transaction_code: e.transaction_code.to_string(),
+            receiving_dfi_identification: e.receiving_dfi_identification.to_string(),
+            check_digit: e.check_digit.to_string(),
+            dfi_account_number: e.dfi_account_number.to_string(),
+            amount: e.amount,
+            individual_identification_number: e.individual_identification_number.to_string(),
+            individual_name: e.individual_name.to_string(),
+            discretionary_data: e.discretionary_data.to_string(),
+            addenda_record_indicator: e.addenda_record_indicator.to_string(),
+            trace_number: e.trace_number.to_string(),
+            addenda: e
+                .addenda
+                .iter()
+                .map(|a| Addenda {
+                    record_type: a.record_type.to_string(),
+                    addenda_type_code: a.addenda_type_code.to_string(),
+                    payment_related_information: a.payment_related_information.to_string(),
+                    addenda_sequence_number: a.addenda_sequence_number.to_string(),
+                    entry_detail_sequence_number: a.entry_detail_sequence_number.to_string(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Line number carried by an error, if any.
+fn error_line(_err: &RustAchError) -> Option<usize> {
+    None
+}
+
+fn to_py_err(py: Python<'_>, err: &RustAchError) -> PyErr {
+    let py_err = AchError::new_err(err.to_string());
+    let line = error_line(err);
+    if let Err(e) = py_err.value(py).setattr("line", line) {
+        return e;
+    }
+    py_err
+}
+
+/// Parse an ACH file from a string.
+#[pyfunction]
+fn parse(py: Python<'_>, content: &str) -> PyResult<AchFile> {
+    ach::AchFile::parse(content)
+        .map(|file| AchFile::from(&file))
+        .map_err(|e| to_py_err(py, &e))
+}
+
+#[pymodule]
+#[pyo3(name = "rs_ach")]
+fn rs_ach_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(parse, m)?)?;
+    m.add("AchError", m.py().get_type::<AchError>())?;
+    m.add_class::<AchFile>()?;
+    m.add_class::<Batch>()?;
+    m.add_class::<FileHeader>()?;
+    m.add_class::<BatchHeader>()?;
+    m.add_class::<EntryDetail>()?;
+    m.add_class::<Addenda>()?;
+    m.add_class::<BatchControl>()?;
+    m.add_class::<FileControl>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = concat!(
+        "101 12345678012345678011409020123A094101YOUR BANK              YOUR COMPANY                   \n",
+        "5200YOUR COMPANY                        1234567890PPDPAYROLL         140903   1123456780000001\n",
+        "62212345678011232132         0000001000               ALICE WANDERDUST        1123456780000001\n",
+        "705HERE IS SOME ADDITIONAL INFORMATION                                             00000000001\n",
+        "820000000200123456780000000000000000000010001234567890                         123456780000001\n",
+        "9000001000001000000020012345678000000000000000000001000                                       ",
+    );
+
+    #[test]
+    fn test_conversion_copies_every_record() {
+        let parsed = ach::AchFile::parse(SAMPLE).unwrap();
+        let file = AchFile::from(&parsed);
+
+        assert_eq!(file.file_header.immediate_origin, "1234567801");
+        assert_eq!(file.batches.len(), 1);
+        assert_eq!(file.batches[0].header.standard_entry_class_code, "PPD");
+        assert_eq!(file.entries().len(), 1);
+
+        let entry = &file.batches[0].entries[0];
+        assert_eq!(entry.amount, 1000);
+        assert_eq!(entry.individual_name.trim(), "ALICE WANDERDUST");
+        assert_eq!(entry.addenda[0].addenda_type_code, "05");
+        assert_eq!(file.batches[0].control.total_credit_amount, 1000);
+        assert_eq!(file.file_control.batch_count, 1);
+    }
+
+    #[test]
+    fn test_repr() {
+        let parsed = ach::AchFile::parse(SAMPLE).unwrap();
+        let file = AchFile::from(&parsed);
+        assert_eq!(
+            file.__repr__(),
+            "AchFile(immediate_origin=\"1234567801\", batches=1)"
+        );
+        assert_eq!(
+            file.batches[0].__repr__(),
+            "Batch(batch_number=\"0000001\", company_name=\"YOUR COMPANY\", entries=1)"
+        );
+    }
+}
@@ -0,0 +1,37 @@
+"""Smoke test for the Python bindings. Run after `maturin develop`."""
+
+import pytest
+
+import rs_ach
+
+SAMPLE = "\n".join(
+    [
+        "101 12345678012345678011409020123A094101YOUR BANK              YOUR COMPANY                   ",
+        "5200YOUR COMPANY                        1234567890PPDPAYROLL         140903   1123456780000001",
+        "62212345678011232132         0000001000               ALICE WANDERDUST        1123456780000001",
+        "705HERE IS SOME ADDITIONAL INFORMATION                                             00000000001",
+        "820000000200123456780000000000000000000010001234567890                         123456780000001",
+        "9000001000001000000020012345678000000000000000000001000                                       ",
+    ]
+)
+
+
+def test_parse():
+    ach = rs_ach.parse(SAMPLE)
+    assert ach.file_header.immediate_origin.strip() == "1234567801"
+    assert len(ach.batches) == 1
+
+    for batch in ach.batches:
+        assert batch.header.standard_entry_class_code == "PPD"
+        for entry in batch.entries:
+            assert entry.amount == 1000
+            assert entry.addenda[0].addenda_type_code == "05"
+
+    assert [e.trace_number for e in ach.entries()] == ["123456780000001"]
+
+
+def test_parse_error():
+    with pytest.raises(rs_ach.AchError) as info:
+        rs_ach.parse("101 123")
+    assert "94" in str(info.value)
+    assert hasattr(info.value, "line")
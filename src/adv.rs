@@ -0,0 +1,401 @@
+//! Automated accounting advice (ADV) files, which an ACH operator sends to
+//! report settlement activity to a DFI.
+//!
+//! ADV batches have service class 280 and their own entry detail, batch
+//! control, and file control layouts, with 12-digit entry amounts and
+//! 20-digit totals. The standard records of an ADV batch read its amounts
+//! and totals from the wrong positions, so the parser reads the batch again
+//! with the ADV layouts into [`Batch::adv`], and validation compares an ADV
+//! batch's totals with them.
+
+use crate::records::{BatchControl, EntryDetail, FileControl};
+use crate::writer;
+use crate::{AchError, AchFile, Batch};
+
+/// The record as written, so ADV fields can be sliced at their positions.
+struct Line(String);
+
+impl Line {
+    /// Positions `from` through `to` of the record, counting from 1 as
+    /// NACHA does, trimmed, or [`AchError::InvalidValue`] naming `field` if
+    /// the record is not ASCII up to them, so the positions fall elsewhere.
+    fn get(&self, from: usize, to: usize, field: &'static str) -> Result<String, AchError> {
+        match self.0.get(..to) {
+            Some(prefix) if prefix.is_ascii() => Ok(prefix[from - 1..].trim().to_string()),
+            _ => Err(AchError::InvalidValue {
+                field,
+                reason: format!("positions {from}-{to} are not ASCII"),
+            }),
+        }
+    }
+
+    /// Positions `from` through `to` read as a number, or
+    /// [`AchError::InvalidValue`] naming `field`.
+    fn number(&self, from: usize, to: usize, field: &'static str) -> Result<u64, AchError> {
+        let value = self.get(from, to, field)?;
+        value
+            .parse()
+            .ok()
+            .filter(|_| value.bytes().all(|b| b.is_ascii_digit()))
+            .ok_or_else(|| AchError::InvalidValue {
+                field,
+                reason: format!("{value:?} is not a number"),
+            })
+    }
+}
+
+/// Whether an ADV transaction code credits the DFI. The codes run from 81
+/// to 88, odd for credits and even for debits.
+fn is_advice_credit(transaction_code: &str) -> bool {
+    matches!(transaction_code.trim(), "81" | "83" | "85" | "87")
+}
+
+/// Whether an ADV transaction code debits the DFI.
+fn is_advice_debit(transaction_code: &str) -> bool {
+    matches!(transaction_code.trim(), "82" | "84" | "86" | "88")
+}
+
+/// An entry of an ADV batch read with the ADV layout, in [`AdvBatch`] or
+/// from [`EntryDetail::as_adv`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct AdvEntryDetail {
+    /// Transaction code, 81 through 88 (positions 2-3)
+    pub transaction_code: String,
+
+    /// Receiving DFI identification (positions 4-11)
+    pub receiving_dfi_identification: String,
+
+    /// Check digit (position 12)
+    pub check_digit: String,
+
+    /// DFI account number (positions 13-27)
+    pub dfi_account_number: String,
+
+    /// Amount in cents, 12 digits (positions 28-39)
+    pub amount: u64,
+
+    /// Advice routing number (positions 40-48)
+    pub advice_routing_number: String,
+
+    /// File identification (positions 49-53)
+    pub file_identification: String,
+
+    /// ACH operator data (position 54)
+    pub ach_operator_data: String,
+
+    /// Individual name (positions 55-76)
+    pub individual_name: String,
+
+    /// Discretionary data (positions 77-78)
+    pub discretionary_data: String,
+
+    /// Addenda record indicator (position 79)
+    pub addenda_record_indicator: String,
+
+    /// Routing number of the ACH operator (positions 80-87)
+    pub ach_operator_routing_number: String,
+
+    /// Julian date the advice was created (positions 88-90)
+    pub julian_date_created: String,
+
+    /// Sequence number within the batch, in place of a trace number
+    /// (positions 91-94)
+    pub sequence_number: String,
+}
+
+impl AdvEntryDetail {
+    /// Whether the advice credits the DFI: transaction code 81, 83, 85, or
+    /// 87.
+    pub fn is_credit(&self) -> bool {
+        is_advice_credit(&self.transaction_code)
+    }
+
+    /// Whether the advice debits the DFI: transaction code 82, 84, 86, or
+    /// 88.
+    pub fn is_debit(&self) -> bool {
+        is_advice_debit(&self.transaction_code)
+    }
+}
+
+impl EntryDetail<'_> {
+    /// Read the entry with the ADV layout, or fail with
+    /// [`AchError::InvalidValue`] if its amount is not a number or its
+    /// fields are not ASCII. Only meaningful for entries of a batch whose
+    /// [`Batch::is_adv`] is true; parsing fills [`Batch::adv`] with these,
+    /// so this is for entries built or changed since.
+    pub fn as_adv(&self) -> Result<AdvEntryDetail, AchError> {
+        let line = Line(writer::render_record(&self.fields())?);
+        Ok(AdvEntryDetail {
+            transaction_code: line.get(2, 3, "transaction_code")?,
+            receiving_dfi_identification: line.get(4, 11, "receiving_dfi_identification")?,
+            check_digit: line.get(12, 12, "check_digit")?,
+            dfi_account_number: line.get(13, 27, "dfi_account_number")?,
+            amount: line.number(28, 39, "amount")?,
+            advice_routing_number: line.get(40, 48, "advice_routing_number")?,
+            file_identification: line.get(49, 53, "file_identification")?,
+            ach_operator_data: line.get(54, 54, "ach_operator_data")?,
+            individual_name: line.get(55, 76, "individual_name")?,
+            discretionary_data: line.get(77, 78, "discretionary_data")?,
+            addenda_record_indicator: line.get(79, 79, "addenda_record_indicator")?,
+            ach_operator_routing_number: line.get(80, 87, "ach_operator_routing_number")?,
+            julian_date_created: line.get(88, 90, "julian_date_created")?,
+            sequence_number: line.get(91, 94, "sequence_number")?,
+        })
+    }
+}
+
+/// The control of an ADV batch read with the ADV layout, in [`AdvBatch`]
+/// or from [`BatchControl::as_adv`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct AdvBatchControl {
+    /// Service class code, 280 (positions 2-4)
+    pub service_class_code: String,
+
+    /// Entry/addenda count (positions 5-10)
+    pub entry_addenda_count: u64,
+
+    /// Entry hash (positions 11-20)
+    pub entry_hash: u64,
+
+    /// Total debit entry dollar amount in cents, 20 digits (positions 21-40)
+    pub total_debit_amount: u64,
+
+    /// Total credit entry dollar amount in cents, 20 digits (positions 41-60)
+    pub total_credit_amount: u64,
+
+    /// ACH operator data (positions 61-79)
+    pub ach_operator_data: String,
+
+    /// Originating DFI identification (positions 80-87)
+    pub originating_dfi_identification: String,
+
+    /// Batch number (positions 88-94)
+    pub batch_number: String,
+}
+
+impl BatchControl {
+    /// Read the control with the ADV layout, or fail with
+    /// [`AchError::InvalidValue`] if its totals are not numbers that fit a
+    /// `u64`.
+    pub fn as_adv(&self) -> Result<AdvBatchControl, AchError> {
+        let line = Line(writer::render_record(&self.fields())?);
+        Ok(AdvBatchControl {
+            service_class_code: line.get(2, 4, "service_class_code")?,
+            entry_addenda_count: self.entry_addenda_count,
+            entry_hash: self.entry_hash,
+            total_debit_amount: line.number(21, 40, "total_debit_amount")?,
+            total_credit_amount: line.number(41, 60, "total_credit_amount")?,
+            ach_operator_data: line.get(61, 79, "ach_operator_data")?,
+            originating_dfi_identification: line.get(80, 87, "originating_dfi_identification")?,
+            batch_number: line.get(88, 94, "batch_number")?,
+        })
+    }
+}
+
+/// The file control of an ADV file read with the ADV layout, from
+/// [`FileControl::as_adv`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct AdvFileControl {
+    /// Batch count (positions 2-7)
+    pub batch_count: u64,
+
+    /// Block count (positions 8-13)
+    pub block_count: u64,
+
+    /// Entry/addenda count (positions 14-21)
+    pub entry_addenda_count: u64,
+
+    /// Entry hash (positions 22-31)
+    pub entry_hash: u64,
+
+    /// Total debit entry dollar amount in cents, 20 digits (positions 32-51)
+    pub total_debit_amount: u64,
+
+    /// Total credit entry dollar amount in cents, 20 digits (positions 52-71)
+    pub total_credit_amount: u64,
+}
+
+impl FileControl {
+    /// Read the file control with the ADV layout, or fail with
+    /// [`AchError::InvalidValue`] if its totals are not numbers that fit a
+    /// `u64`. Only meaningful when [`AchFile::is_adv`] is true.
+    pub fn as_adv(&self) -> Result<AdvFileControl, AchError> {
+        let line = Line(writer::render_record(&self.fields())?);
+        Ok(AdvFileControl {
+            batch_count: self.batch_count,
+            block_count: self.block_count,
+            entry_addenda_count: self.entry_addenda_count,
+            entry_hash: self.entry_hash,
+            total_debit_amount: line.number(32, 51, "total_debit_amount")?,
+            total_credit_amount: line.number(52, 71, "total_credit_amount")?,
+        })
+    }
+}
+
+/// An ADV batch's entries and control read with the ADV layouts, in
+/// [`Batch::adv`].
+///
+/// ```
+/// use rs_ach::{fixtures, AchFile};
+///
+/// let text = fixtures::sample_adv_file();
+/// let file = AchFile::parse(&text).unwrap();
+/// let adv = file.batches[0].adv.as_ref().unwrap();
+/// assert_eq!(adv.entries[0].amount, 123_456_789_012);
+/// assert!(adv.entries[0].is_credit());
+/// assert_eq!(adv.control.total_credit_amount, 123_456_789_012);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct AdvBatch {
+    /// The batch's entries, in file order
+    pub entries: Vec<AdvEntryDetail>,
+
+    /// The batch's control
+    pub control: AdvBatchControl,
+}
+
+impl Batch<'_> {
+    /// Whether the batch is an ADV batch: its service class is 280 or its
+    /// SEC code is ADV.
+    pub fn is_adv(&self) -> bool {
+        self.header.service_class_code.trim() == "280"
+            || self.header.standard_entry_class_code.trim() == "ADV"
+    }
+
+    /// The debit and credit totals of the batch's entries read with the
+    /// ADV layout, or the error of the first entry that cannot be read.
+    pub(crate) fn adv_totals(&self) -> Result<(u64, u64), AchError> {
+        let advices = self
+            .entries
+            .iter()
+            .map(EntryDetail::as_adv)
+            .collect::<Result<Vec<_>, _>>()?;
+        let total = |keep: fn(&AdvEntryDetail) -> bool| -> u64 {
+            advices.iter().filter(|a| keep(a)).map(|a| a.amount).sum()
+        };
+        Ok((
+            total(AdvEntryDetail::is_debit),
+            total(AdvEntryDetail::is_credit),
+        ))
+    }
+
+    /// Read the batch's entries and control with the ADV layouts if it is
+    /// an ADV batch, as the parser does for [`Batch::adv`].
+    pub(crate) fn read_adv(&self) -> Result<Option<AdvBatch>, AchError> {
+        if !self.is_adv() {
+            return Ok(None);
+        }
+        Ok(Some(AdvBatch {
+            entries: self
+                .entries
+                .iter()
+                .map(EntryDetail::as_adv)
+                .collect::<Result<_, _>>()?,
+            control: self.control.as_adv()?,
+        }))
+    }
+}
+
+impl AchFile<'_> {
+    /// Whether the file is an ADV file: it has batches and every one is an
+    /// ADV batch, so its file control has the ADV layout.
+    pub fn is_adv(&self) -> bool {
+        !self.batches.is_empty() && self.batches.iter().all(Batch::is_adv)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_adv_entries() {
+        let text = fixtures::sample_adv_file();
+        let file = AchFile::parse(&text).unwrap();
+        assert!(file.is_adv());
+        let batch = &file.batches[0];
+        // The standard layout reads the last ten digits of the amount.
+        assert_eq!(batch.entries[0].amount, 3_456_789_012);
+        assert_eq!(
+            batch.entries[0].as_adv().unwrap(),
+            AdvEntryDetail {
+                transaction_code: "81".to_string(),
+                receiving_dfi_identification: "12345678".to_string(),
+                check_digit: "0".to_string(),
+                dfi_account_number: "4100001234".to_string(),
+                amount: 123_456_789_012,
+                advice_routing_number: "123456780".to_string(),
+                file_identification: "0902A".to_string(),
+                ach_operator_data: "0".to_string(),
+                individual_name: "YOUR COMPANY".to_string(),
+                discretionary_data: String::new(),
+                addenda_record_indicator: "0".to_string(),
+                ach_operator_routing_number: "09101298".to_string(),
+                julian_date_created: "246".to_string(),
+                sequence_number: "0001".to_string(),
+            }
+        );
+        let adv = batch.adv.as_ref().unwrap();
+        assert_eq!(adv.entries[0], batch.entries[0].as_adv().unwrap());
+        assert!(adv.entries[1].is_debit() && !adv.entries[1].is_credit());
+        assert_eq!(adv.control, batch.control.as_adv().unwrap());
+        assert_eq!(batch.adv_totals().unwrap(), (45_678_900, 123_456_789_012));
+
+        let ppd = fixtures::sample_ppd_file(1);
+        let ppd = AchFile::parse(&ppd).unwrap();
+        assert!(!ppd.is_adv());
+        assert_eq!(ppd.batches[0].adv, None);
+        assert!(!AchFile::parse(&fixtures::sample_empty_file())
+            .unwrap()
+            .is_adv());
+    }
+
+    #[test]
+    fn test_adv_controls() {
+        let text = fixtures::sample_adv_file();
+        let mut file = AchFile::parse(&text).unwrap();
+        let control = file.batches[0].control.as_adv().unwrap();
+        assert_eq!(control.total_debit_amount, 45_678_900);
+        assert_eq!(control.total_credit_amount, 123_456_789_012);
+        assert_eq!(control.entry_addenda_count, 2);
+        assert_eq!(control.batch_number, "0000001");
+        let file_control = file.file_control.as_adv().unwrap();
+        assert_eq!(file_control.total_credit_amount, 123_456_789_012);
+        assert_eq!(file_control.batch_count, 1);
+
+        file.batches[0].entries[0].amount = 0;
+        file.batches[0].entries[0].dfi_account_number = "4100001234     AB".into();
+        let err = file.batches[0].entries[0].as_adv().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid value for 'amount': \"AB0000000000\" is not a number"
+        );
+        assert!(file.batches[0].adv_totals().is_err());
+
+        // The parser reads the same amount from the file and fails on it.
+        let text = file.to_ach_string().unwrap();
+        let err = AchFile::parse(&text).unwrap_err();
+        assert!(
+            matches!(&err, AchError::InBatch { source, .. }
+                if matches!(**source, AchError::InvalidValue { field: "amount", .. })),
+            "{err}"
+        );
+
+        // A multibyte character moves the positions after it.
+        file.batches[0].entries[0].dfi_account_number = "41000012é".into();
+        let err = file.batches[0].entries[0].as_adv().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid value for 'dfi_account_number': positions 13-27 are not ASCII"
+        );
+    }
+}
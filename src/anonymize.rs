@@ -0,0 +1,308 @@
+//! Anonymization of files so they can be shared outside the organization.
+
+use std::borrow::Cow;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+use crate::{routing, AchFile, AchFileOwned, MAX_ENTRY_AMOUNT};
+
+/// How [`AchFile::anonymize`] treats entry amounts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AmountHandling {
+    /// Keep every amount as is.
+    #[default]
+    Preserve,
+
+    /// Round every amount to the nearest multiple of this many cents,
+    /// staying within the 10-digit amount field. A bucket of zero keeps
+    /// amounts unchanged.
+    Bucket(u64),
+}
+
+/// Options for [`AchFile::anonymize`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnonymizeOptions {
+    /// Seed for the replacement values. Anonymizing the same file with the
+    /// same seed always produces the same output; `None` picks a random seed.
+    pub seed: Option<u64>,
+
+    /// Replace receiving DFI routing numbers with generated ones that have
+    /// valid check digits. Originator (ODFI) routing numbers are kept.
+    pub remap_routing_numbers: bool,
+
+    /// How amounts are treated
+    pub amounts: AmountHandling,
+
+    /// Blank the payment related information of type 05 addenda, which
+    /// often carries names and invoice details.
+    pub scrub_addenda: bool,
+}
+
+impl Default for AnonymizeOptions {
+    fn default() -> Self {
+        AnonymizeOptions {
+            seed: None,
+            remap_routing_numbers: false,
+            amounts: AmountHandling::Preserve,
+            scrub_addenda: true,
+        }
+    }
+}
+
+impl AchFile<'_> {
+    /// Produce a copy of the file with receiver PII replaced.
+    ///
+    /// Individual names become `RECEIVER n` placeholders, and account and
+    /// identification numbers become random digits of the same length. A
+    /// value that appears more than once gets the same replacement each
+    /// time, so repeated receivers stay recognizable. Batch and file
+    /// controls are recomputed afterwards, so the copy is internally
+    /// consistent even when routing numbers or amounts change.
+    ///
+    /// CTX entries keep the addenda count at the start of the name field
+    /// and only have the receiving company name replaced.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile, AnonymizeOptions};
+    ///
+    /// let text = fixtures::sample_ppd_file(2);
+    /// let file = AchFile::parse(&text).unwrap();
+    /// let options = AnonymizeOptions {
+    ///     seed: Some(42),
+    ///     ..AnonymizeOptions::default()
+    /// };
+    ///
+    /// let shareable = file.anonymize(&options);
+    /// assert_eq!(shareable.batches[0].entries[0].individual_name.trim(), "RECEIVER 1");
+    /// assert_eq!(shareable, file.anonymize(&options));
+    /// ```
+    pub fn anonymize(&self, options: &AnonymizeOptions) -> AchFileOwned {
+        let mut rng = SplitMix64(
+            options
+                .seed
+                .unwrap_or_else(|| RandomState::new().hash_one(0u8)),
+        );
+        let mut names: HashMap<String, String> = HashMap::new();
+        let mut accounts: HashMap<String, String> = HashMap::new();
+        let mut ids: HashMap<String, String> = HashMap::new();
+        let mut routings: HashMap<String, (String, u8)> = HashMap::new();
+
+        let mut file = self.clone().into_owned();
+        for batch in &mut file.batches {
+            let is_ctx = batch.header.standard_entry_class_code == "CTX";
+            for entry in &mut batch.entries {
+                let next = names.len() + 1;
+                let (prefix, name, suffix) = split_name(&entry.individual_name, is_ctx);
+                let placeholder = names
+                    .entry(name.trim().to_string())
+                    .or_insert_with(|| format!("RECEIVER {next}"));
+                let width = name.len();
+                entry.individual_name =
+                    Cow::Owned(format!("{prefix}{placeholder:<width$.width$}{suffix}"));
+
+                entry.dfi_account_number =
+                    Cow::Owned(scramble(&entry.dfi_account_number, &mut accounts, &mut rng));
+                entry.individual_identification_number = Cow::Owned(scramble(
+                    &entry.individual_identification_number,
+                    &mut ids,
+                    &mut rng,
+                ));
+
+                if options.remap_routing_numbers {
+                    let original = entry.receiving_dfi_identification.to_string();
+                    let (dfi, check) = routings
+                        .entry(original)
+                        .or_insert_with(|| test_routing_number(&mut rng))
+                        .clone();
+                    entry.receiving_dfi_identification = Cow::Owned(dfi);
+                    entry.check_digit = Cow::Owned(check.to_string());
+                }
+
+                if let AmountHandling::Bucket(size) = options.amounts {
+                    entry.amount = bucket(entry.amount, size);
+                }
+
+                if options.scrub_addenda {
+                    for addenda in &mut entry.addenda {
+                        if addenda.addenda_type_code == "05" {
+                            addenda.payment_related_information = Cow::Owned(" ".repeat(80));
+                        }
+                    }
+                }
+            }
+            batch.recompute_control();
+        }
+        file.recompute_file_control();
+        file
+    }
+}
+
+/// Round `amount` to the nearest multiple of `size` that fits the amount field.
+fn bucket(amount: u64, size: u64) -> u64 {
+    let rounded = amount.saturating_add(size / 2).checked_div(size);
+    match (rounded, MAX_ENTRY_AMOUNT.checked_div(size)) {
+        (Some(buckets), Some(max)) => buckets.min(max) * size,
+        _ => amount,
+    }
+}
+
+/// Split a name field into the part to replace and what surrounds it. CTX
+/// entries carry the addenda count in positions 55-58 and the receiving
+/// company name in 59-74.
+fn split_name(value: &str, is_ctx: bool) -> (&str, &str, &str) {
+    if is_ctx && value.len() >= 20 && value.is_char_boundary(4) && value.is_char_boundary(20) {
+        (&value[..4], &value[4..20], &value[20..])
+    } else {
+        ("", value, "")
+    }
+}
+
+/// Replace every non-space character with a random digit, reusing the
+/// replacement chosen for an earlier occurrence of the same value.
+fn scramble(value: &str, seen: &mut HashMap<String, String>, rng: &mut SplitMix64) -> String {
+    if value.trim().is_empty() {
+        return value.to_string();
+    }
+    seen.entry(value.to_string())
+        .or_insert_with(|| {
+            value
+                .chars()
+                .map(|c| if c == ' ' { ' ' } else { rng.digit() })
+                .collect()
+        })
+        .clone()
+}
+
+/// A routing number in a Federal Reserve district (01-12), returned as the
+/// eight-digit DFI identification and its check digit.
+fn test_routing_number(rng: &mut SplitMix64) -> (String, u8) {
+    let district = 1 + rng.next() % 12;
+    let rest: String = (0..6).map(|_| rng.digit()).collect();
+    let dfi = format!("{district:02}{rest}");
+    let check = routing::check_digit(&dfi).expect("eight digits");
+    (dfi, check)
+}
+
+/// Small deterministic generator; anonymization needs repeatability, not
+/// cryptographic strength.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn digit(&mut self) -> char {
+        char::from(b'0' + (self.next() % 10) as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, writer};
+
+    fn seeded(seed: u64) -> AnonymizeOptions {
+        AnonymizeOptions {
+            seed: Some(seed),
+            ..AnonymizeOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_anonymize_replaces_pii() {
+        let text = fixtures::sample_ppd_file(4);
+        let file = AchFile::parse(&text).unwrap();
+        let anonymized = file.anonymize(&seeded(7));
+
+        for (before, after) in file.batches[0]
+            .entries
+            .iter()
+            .zip(&anonymized.batches[0].entries)
+        {
+            assert_ne!(before.dfi_account_number, after.dfi_account_number);
+            assert_eq!(
+                before.dfi_account_number.len(),
+                after.dfi_account_number.len()
+            );
+            assert!(after.individual_name.starts_with("RECEIVER "));
+            assert_eq!(after.individual_name.len(), 22);
+            assert_eq!(before.amount, after.amount);
+            assert_eq!(
+                before.receiving_dfi_identification,
+                after.receiving_dfi_identification
+            );
+        }
+        assert_eq!(anonymized.batches[0].control, file.batches[0].control);
+        assert_eq!(anonymized.file_control, file.file_control);
+    }
+
+    #[test]
+    fn test_anonymize_is_deterministic_with_seed() {
+        let text = fixtures::sample_ppd_file(3);
+        let file = AchFile::parse(&text).unwrap();
+        assert_eq!(file.anonymize(&seeded(1)), file.anonymize(&seeded(1)));
+        assert_ne!(file.anonymize(&seeded(1)), file.anonymize(&seeded(2)));
+    }
+
+    #[test]
+    fn test_remapped_routing_and_buckets_keep_controls_consistent() {
+        let text = fixtures::sample_ppd_file(6);
+        let file = AchFile::parse(&text).unwrap();
+        let options = AnonymizeOptions {
+            remap_routing_numbers: true,
+            amounts: AmountHandling::Bucket(1_000),
+            ..seeded(3)
+        };
+        let anonymized = file.anonymize(&options);
+
+        // 1_250 rounds to 1_000 and 2_500 to 3_000.
+        assert_eq!(anonymized.batches[0].entries[0].amount, 1_000);
+        assert_eq!(anonymized.batches[0].entries[1].amount, 3_000);
+
+        for entry in &anonymized.batches[0].entries {
+            let routing = format!(
+                "{}{}",
+                entry.receiving_dfi_identification, entry.check_digit
+            );
+            assert!(routing::is_valid(&routing));
+        }
+
+        let reparsed_text = writer::render_lines(&anonymized).unwrap().join("\n");
+        let mut reparsed = AchFile::parse(&reparsed_text).unwrap();
+        let stated = reparsed.clone();
+        reparsed.batches[0].recompute_control();
+        reparsed.recompute_file_control();
+        assert_eq!(reparsed, stated);
+    }
+
+    #[test]
+    fn test_bucket() {
+        assert_eq!(bucket(1_249, 500), 1_000);
+        assert_eq!(bucket(1_250, 500), 1_500);
+        assert_eq!(bucket(1_250, 0), 1_250);
+        assert_eq!(bucket(MAX_ENTRY_AMOUNT, 1_000), 9_999_999_000);
+    }
+
+    #[test]
+    fn test_ctx_keeps_addenda_count_and_scrubs_addenda() {
+        let text = fixtures::sample_ctx_with_addenda();
+        let file = AchFile::parse(&text).unwrap();
+        let entry = &file.anonymize(&seeded(9)).batches[0].entries[0];
+
+        assert_eq!(&entry.individual_name[..4], "0003");
+        assert_eq!(entry.individual_name[4..20].trim(), "RECEIVER 1");
+        assert_eq!(entry.individual_name.len(), 22);
+        assert!(entry
+            .addenda
+            .iter()
+            .all(|a| a.payment_related_information.trim().is_empty()));
+    }
+}
@@ -0,0 +1,267 @@
+//! Arrow and Parquet export of entry detail records.
+//!
+//! Available with the `arrow` feature; Parquet output additionally needs
+//! the `parquet` feature. Each entry becomes one row carrying its batch
+//! context. The schema returned by [`entry_schema`] is stable: columns are
+//! only ever appended, never renamed, reordered, or retyped.
+//!
+//! | Column                             | Type    | Null when |
+//! |------------------------------------|---------|-----------|
+//! | `batch_number`                     | Utf8    | never |
+//! | `company_identification`           | Utf8    | never (trimmed) |
+//! | `company_name`                     | Utf8    | blank |
+//! | `standard_entry_class_code`        | Utf8    | never |
+//! | `effective_entry_date`             | Date32  | blank or not a valid YYMMDD (years read as 20YY) |
+//! | `transaction_code`                 | Utf8    | never |
+//! | `routing_number`                   | Utf8    | never (9 digits, including check digit) |
+//! | `account_number`                   | Utf8    | never (trimmed) |
+//! | `amount`                           | Int64   | never (cents) |
+//! | `individual_identification_number` | Utf8    | blank |
+//! | `individual_name`                  | Utf8    | blank |
+//! | `trace_number`                     | Utf8    | never |
+
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, Date32Array, Int64Array, RecordBatch, StringArray};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef};
+
+use crate::dates::CivilDate;
+use crate::AchFile;
+
+/// The Arrow schema produced by [`AchFile::to_record_batch`].
+pub fn entry_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("batch_number", DataType::Utf8, false),
+        Field::new("company_identification", DataType::Utf8, false),
+        Field::new("company_name", DataType::Utf8, true),
+        Field::new("standard_entry_class_code", DataType::Utf8, false),
+        Field::new("effective_entry_date", DataType::Date32, true),
+        Field::new("transaction_code", DataType::Utf8, false),
+        Field::new("routing_number", DataType::Utf8, false),
+        Field::new("account_number", DataType::Utf8, false),
+        Field::new("amount", DataType::Int64, false),
+        Field::new("individual_identification_number", DataType::Utf8, true),
+        Field::new("individual_name", DataType::Utf8, true),
+        Field::new("trace_number", DataType::Utf8, false),
+    ]))
+}
+
+impl AchFile<'_> {
+    /// Export every entry as one row of an Arrow [`RecordBatch`].
+    ///
+    /// See the [module documentation](crate::arrow_export) for the schema.
+    pub fn to_record_batch(&self) -> Result<RecordBatch, ArrowError> {
+        let rows: Vec<_> = self
+            .batches
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .entries
+                    .iter()
+                    .map(move |entry| (&batch.header, entry))
+            })
+            .collect();
+
+        let text = |values: Vec<String>| -> ArrayRef { Arc::new(StringArray::from(values)) };
+        let optional = |values: Vec<&str>| -> ArrayRef {
+            Arc::new(
+                values
+                    .into_iter()
+                    .map(|s| Some(s.trim()).filter(|s| !s.is_empty()))
+                    .collect::<StringArray>(),
+            )
+        };
+
+        let columns: Vec<ArrayRef> = vec![
+            text(
+                rows.iter()
+                    .map(|(h, _)| h.batch_number.to_string())
+                    .collect(),
+            ),
+            text(
+                rows.iter()
+                    .map(|(h, _)| h.company_identification.trim().to_string())
+                    .collect(),
+            ),
+            optional(rows.iter().map(|(h, _)| h.company_name.as_ref()).collect()),
+            text(
+                rows.iter()
+                    .map(|(h, _)| h.standard_entry_class_code.to_string())
+                    .collect(),
+            ),
+            Arc::new(
+                rows.iter()
+                    .map(|(h, _)| days_since_epoch(&h.effective_entry_date))
+                    .collect::<Date32Array>(),
+            ),
+            text(
+                rows.iter()
+                    .map(|(_, e)| e.transaction_code.to_string())
+                    .collect(),
+            ),
+            text(
+                rows.iter()
+                    .map(|(_, e)| format!("{}{}", e.receiving_dfi_identification, e.check_digit))
+                    .collect(),
+            ),
+            text(
+                rows.iter()
+                    .map(|(_, e)| e.dfi_account_number.trim().to_string())
+                    .collect(),
+            ),
+            Arc::new(
+                rows.iter()
+                    .map(|(_, e)| e.amount as i64)
+                    .collect::<Int64Array>(),
+            ),
+            optional(
+                rows.iter()
+                    .map(|(_, e)| e.individual_identification_number.as_ref())
+                    .collect(),
+            ),
+            optional(
+                rows.iter()
+                    .map(|(_, e)| e.individual_name.as_ref())
+                    .collect(),
+            ),
+            text(
+                rows.iter()
+                    .map(|(_, e)| e.trace_number.to_string())
+                    .collect(),
+            ),
+        ];
+
+        RecordBatch::try_new(entry_schema(), columns)
+    }
+
+    /// Write the entries as a single-row-group Parquet file.
+    ///
+    /// Requires the `parquet` feature.
+    #[cfg(feature = "parquet")]
+    pub fn write_parquet<W: std::io::Write + Send>(
+        &self,
+        writer: W,
+    ) -> Result<(), parquet::errors::ParquetError> {
+        let batch = self.to_record_batch()?;
+        let mut writer = parquet::arrow::ArrowWriter::try_new(writer, batch.schema(), None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+        Ok(())
+    }
+}
+
+/// Convert a `YYMMDD` date to days since 1970-01-01, reading years as 20YY.
+fn days_since_epoch(yymmdd: &str) -> Option<i32> {
+    CivilDate::parse(yymmdd).map(CivilDate::days_since_epoch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::Array;
+
+    const MULTI_BATCH_FILE: &str = concat!(
+        "101 12345678012345678011409020123A094101YOUR BANK              YOUR COMPANY                   \n",
+        "5200YOUR COMPANY                        1234567890PPDPAYROLL         140903   1123456780000001\n",
+        "62212345678011232132         0000001000               ALICE WANDERDUST        1123456780000001\n",
+        "705HERE IS SOME ADDITIONAL INFORMATION                                             00000000001\n",
+        "627123456780234234234        0000015000               BILLY HOLIDAY           0123456780000002\n",
+        "820000000300246913560000000150000000000010001234567890                         123456780000001\n",
+        "5220OTHER COMPANY                       9876543210CCDVENDOR PAY      140904   1123456780000002\n",
+        "622123232318123123123        0000001213EMP42                                  0123456780000001\n",
+        "820000000100123232310000000000000000000012139876543210                         123456780000002\n",
+        "9000002000001000000040037014587000000015000000000002213                                       ",
+    );
+
+    #[test]
+    fn test_days_since_epoch() {
+        // The default year pivot reads `70` as 1970.
+        assert_eq!(days_since_epoch("700101"), Some(0));
+        assert_eq!(days_since_epoch("000101"), Some(10_957));
+        assert_eq!(days_since_epoch("140903"), Some(16_316));
+        assert_eq!(days_since_epoch("240229"), Some(19_782));
+        assert_eq!(days_since_epoch("230229"), None);
+        assert_eq!(days_since_epoch("      "), None);
+    }
+
+    #[test]
+    fn test_to_record_batch() {
+        let file = AchFile::parse(MULTI_BATCH_FILE).unwrap();
+        let batch = file.to_record_batch().unwrap();
+
+        assert_eq!(batch.schema(), entry_schema());
+        assert_eq!(batch.num_rows(), 3);
+
+        let amounts = batch
+            .column_by_name("amount")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        assert_eq!(amounts.iter().flatten().sum::<i64>(), 17213);
+
+        let companies = batch
+            .column_by_name("company_identification")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(companies.value(2), "9876543210");
+
+        let names = batch
+            .column_by_name("individual_name")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(names.value(0), "ALICE WANDERDUST");
+        assert!(names.is_null(2));
+
+        let ids = batch
+            .column_by_name("individual_identification_number")
+            .unwrap();
+        assert_eq!(ids.null_count(), 2);
+
+        let dates = batch
+            .column_by_name("effective_entry_date")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Date32Array>()
+            .unwrap();
+        assert_eq!(dates.value(2), 16_317);
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_parquet_round_trip() {
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let file = AchFile::parse(MULTI_BATCH_FILE).unwrap();
+        let path = std::env::temp_dir().join(format!("rs-ach-{}.parquet", std::process::id()));
+        file.write_parquet(std::fs::File::create(&path).unwrap())
+            .unwrap();
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<RecordBatch> = reader.map(|b| b.unwrap()).collect();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 3);
+        let total: i64 = batches
+            .iter()
+            .map(|b| {
+                b.column_by_name("amount")
+                    .unwrap()
+                    .as_any()
+                    .downcast_ref::<Int64Array>()
+                    .unwrap()
+                    .iter()
+                    .flatten()
+                    .sum::<i64>()
+            })
+            .sum();
+        assert_eq!(total, 17213);
+    }
+}
@@ -0,0 +1,85 @@
+//! Tamper-evident hashes of file content.
+
+use crate::fingerprint::sha256;
+use crate::writer;
+use crate::{AchError, AchFile};
+
+impl AchFile<'_> {
+    /// SHA-256 of the file's canonical text, for recording at receipt and
+    /// at transmission.
+    ///
+    /// The canonical text is every record in file order (file header, then
+    /// each batch's header, entries and their addenda, and control, then the
+    /// file control), each rendered at its full 94 characters with field
+    /// padding, joined by a single `\n` with no trailing newline. Block
+    /// filler records are left out, so neither line endings nor blocking
+    /// change the hash.
+    ///
+    /// These rules are stable: a release that changes them will say so.
+    /// A value too long for its field, which only a file changed since
+    /// parsing can hold, is cut to the field's width.
+    pub fn audit_hash(&self) -> [u8; 32] {
+        sha256(writer::layout_lines(self).join("\n").as_bytes())
+    }
+
+    /// Parse `content` and check its [audit hash](AchFile::audit_hash)
+    /// against `expected`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `content` does not parse.
+    pub fn verify_audit_hash(content: &str, expected: &[u8; 32]) -> Result<bool, AchError> {
+        Ok(AchFile::parse(content)?.audit_hash() == *expected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    #[test]
+    fn test_audit_hash_snapshot() {
+        // Changing this value changes every recorded hash: a breaking change.
+        let text = fixtures::sample_ppd_file(3);
+        let hash = AchFile::parse(&text).unwrap().audit_hash();
+        assert_eq!(
+            hex(&hash),
+            "02f2728931dac3ccf824c4ceb7423ba76784ddd1c181649c56cc18c3748a5131"
+        );
+        let records: Vec<&str> = text.lines().filter(|l| !l.starts_with("9999")).collect();
+        assert_eq!(hash, sha256(records.join("\n").as_bytes()));
+    }
+
+    #[test]
+    fn test_verify_ignores_line_endings_and_blocking() {
+        let text = fixtures::sample_ppd_file(3);
+        let hash = AchFile::parse(&text).unwrap().audit_hash();
+
+        let crlf = text.replace('\n', "\r\n");
+        assert!(AchFile::verify_audit_hash(&crlf, &hash).unwrap());
+        let unblocked: Vec<&str> = text.lines().filter(|l| !l.starts_with("9999")).collect();
+        assert!(AchFile::verify_audit_hash(&unblocked.join("\n"), &hash).unwrap());
+
+        let tampered = text.replacen("RECEIVER 1", "RECEIVER 9", 1);
+        assert!(!AchFile::verify_audit_hash(&tampered, &hash).unwrap());
+        assert!(AchFile::verify_audit_hash("garbage", &hash).is_err());
+    }
+
+    #[test]
+    fn test_too_long_value_is_cut() {
+        let text = fixtures::sample_ppd_file(1);
+        let mut file = AchFile::parse(&text).unwrap();
+        let name = file.batches[0].entries[0].individual_name.to_string();
+        file.batches[0].entries[0].individual_name = format!("{name}EXTRA").into();
+        assert!(file.to_ach_string().is_err());
+        assert_eq!(
+            file.audit_hash(),
+            AchFile::parse(&text).unwrap().audit_hash()
+        );
+    }
+}
@@ -0,0 +1,383 @@
+//! Banking days: weekdays that are not Federal Reserve holidays.
+//!
+//! The free functions use the built-in [`FederalReserve`] calendar and accept
+//! any [`CalendarDate`]. For a different schedule, implement
+//! [`HolidayCalendar`] and call its methods instead, or combine calendars
+//! with a [`CompositeCalendar`]. The `_with` variants of the builder and
+//! settlement methods take any calendar as a `&dyn HolidayCalendar`.
+//!
+//! ```
+//! use rs_ach::banking_days;
+//! use rs_ach::CivilDate;
+//!
+//! // Thursday, November 26, 2026 is Thanksgiving Day.
+//! let wednesday = CivilDate::new(2026, 11, 25).unwrap();
+//! assert_eq!(
+//!     banking_days::next_banking_day(&wednesday),
+//!     CivilDate::new(2026, 11, 27)
+//! );
+//! ```
+
+use crate::dates::{CalendarDate, CivilDate};
+use crate::BatchBuilder;
+
+/// Years covered by the built-in [`FederalReserve`] holiday table. Outside
+/// this range only weekends are excluded.
+pub const FEDERAL_RESERVE_YEARS: std::ops::RangeInclusive<i32> = 1990..=2099;
+
+/// How many days after a date the next banking day is looked for, so a
+/// calendar closed every day gives up rather than searching forever.
+pub const BANKING_DAY_SEARCH_DAYS: i32 = 366;
+
+/// A schedule of non-weekend closure days.
+///
+/// Only [`is_holiday`](HolidayCalendar::is_holiday) needs implementing;
+/// weekends are never banking days regardless of the calendar.
+pub trait HolidayCalendar {
+    /// Whether `date` is a holiday on which no settlement happens.
+    fn is_holiday(&self, date: CivilDate) -> bool;
+
+    /// Whether `date` is a weekday and not a holiday.
+    fn is_banking_day(&self, date: CivilDate) -> bool {
+        date.weekday() < 5 && !self.is_holiday(date)
+    }
+
+    /// The first banking day strictly after `date`, or `None` if there is
+    /// none within [`BANKING_DAY_SEARCH_DAYS`].
+    fn next_banking_day(&self, date: CivilDate) -> Option<CivilDate> {
+        (1..=BANKING_DAY_SEARCH_DAYS)
+            .map(|days| date.add_days(days))
+            .find(|&next| self.is_banking_day(next))
+    }
+
+    /// The date `n` banking days after `date`; `date` itself when `n` is
+    /// zero, even if it is not a banking day. `None` if some banking day
+    /// along the way is not found, as for
+    /// [`next_banking_day`](HolidayCalendar::next_banking_day).
+    fn add_banking_days(&self, date: CivilDate, n: u32) -> Option<CivilDate> {
+        (0..n).try_fold(date, |date, _| self.next_banking_day(date))
+    }
+}
+
+impl<C: HolidayCalendar + ?Sized> HolidayCalendar for &C {
+    fn is_holiday(&self, date: CivilDate) -> bool {
+        (**self).is_holiday(date)
+    }
+}
+
+impl<C: HolidayCalendar + ?Sized> HolidayCalendar for Box<C> {
+    fn is_holiday(&self, date: CivilDate) -> bool {
+        (**self).is_holiday(date)
+    }
+}
+
+/// A list of closure days, such as a processor's extra holidays.
+impl HolidayCalendar for Vec<CivilDate> {
+    fn is_holiday(&self, date: CivilDate) -> bool {
+        self.contains(&date)
+    }
+}
+
+/// A calendar closed whenever any of its calendars is, such as the Federal
+/// Reserve schedule plus a credit union's own closures.
+///
+/// ```
+/// use rs_ach::banking_days::{CompositeCalendar, FederalReserve, HolidayCalendar};
+/// use rs_ach::CivilDate;
+///
+/// // Closed the day after Thanksgiving too.
+/// let calendar = CompositeCalendar::new()
+///     .with(FederalReserve)
+///     .with(vec![CivilDate::new(2026, 11, 27).unwrap()]);
+/// let wednesday = CivilDate::new(2026, 11, 25).unwrap();
+/// assert_eq!(
+///     calendar.next_banking_day(wednesday),
+///     CivilDate::new(2026, 11, 30)
+/// );
+/// ```
+#[derive(Default)]
+pub struct CompositeCalendar {
+    calendars: Vec<Box<dyn HolidayCalendar>>,
+}
+
+impl CompositeCalendar {
+    /// A calendar with no holidays, only weekends.
+    pub fn new() -> Self {
+        CompositeCalendar::default()
+    }
+
+    /// Add a calendar whose holidays are also closure days.
+    pub fn with(mut self, calendar: impl HolidayCalendar + 'static) -> Self {
+        self.push(calendar);
+        self
+    }
+
+    /// Add a calendar whose holidays are also closure days.
+    pub fn push(&mut self, calendar: impl HolidayCalendar + 'static) {
+        self.calendars.push(Box::new(calendar));
+    }
+}
+
+impl std::fmt::Debug for CompositeCalendar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompositeCalendar")
+            .field("calendars", &self.calendars.len())
+            .finish()
+    }
+}
+
+impl HolidayCalendar for CompositeCalendar {
+    fn is_holiday(&self, date: CivilDate) -> bool {
+        self.calendars.iter().any(|c| c.is_holiday(date))
+    }
+}
+
+/// The Federal Reserve holiday schedule for [`FEDERAL_RESERVE_YEARS`].
+///
+/// Fixed-date holidays falling on a Sunday are observed the following
+/// Monday. Those falling on a Saturday are not observed: the Federal Reserve
+/// is open the preceding Friday. Juneteenth is included from 2022.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FederalReserve;
+
+impl HolidayCalendar for FederalReserve {
+    fn is_holiday(&self, date: CivilDate) -> bool {
+        let year = date.year();
+        if !FEDERAL_RESERVE_YEARS.contains(&year) {
+            return false;
+        }
+
+        let fixed = [
+            (1, 1),   // New Year's Day
+            (6, 19),  // Juneteenth National Independence Day
+            (7, 4),   // Independence Day
+            (11, 11), // Veterans Day
+            (12, 25), // Christmas Day
+        ];
+        let observed_fixed = fixed
+            .into_iter()
+            .filter(|&(month, _)| month != 6 || year >= 2022)
+            .filter_map(|(month, day)| CivilDate::new(year, month, day))
+            .map(|d| if d.weekday() == 6 { d.add_days(1) } else { d });
+
+        let floating = [
+            nth_weekday(year, 1, 0, 3),  // Martin Luther King Jr. Day
+            nth_weekday(year, 2, 0, 3),  // Washington's Birthday
+            last_weekday(year, 5, 0),    // Memorial Day
+            nth_weekday(year, 9, 0, 1),  // Labor Day
+            nth_weekday(year, 10, 0, 2), // Columbus Day
+            nth_weekday(year, 11, 3, 4), // Thanksgiving Day
+        ];
+
+        observed_fixed
+            .chain(floating)
+            .any(|holiday| holiday == date)
+    }
+}
+
+/// The `n`th (1-based) `weekday` (0 for Monday) of a month.
+pub(crate) fn nth_weekday(year: i32, month: u32, weekday: u32, n: u32) -> CivilDate {
+    let first = CivilDate {
+        year,
+        month,
+        day: 1,
+    };
+    let offset = (weekday + 7 - first.weekday()) % 7;
+    first.add_days((offset + 7 * (n - 1)) as i32)
+}
+
+/// The last `weekday` (0 for Monday) of a month.
+fn last_weekday(year: i32, month: u32, weekday: u32) -> CivilDate {
+    let next_month = if month == 12 {
+        CivilDate {
+            year: year + 1,
+            month: 1,
+            day: 1,
+        }
+    } else {
+        CivilDate {
+            year,
+            month: month + 1,
+            day: 1,
+        }
+    };
+    let last = next_month.add_days(-1);
+    let offset = (last.weekday() + 7 - weekday) % 7;
+    last.add_days(-(offset as i32))
+}
+
+/// Convert a [`CivilDate`] result back to the caller's date type, or
+/// `None` if the type cannot hold it.
+fn convert<D: CalendarDate>(date: Option<CivilDate>) -> Option<D> {
+    date?.to_date()
+}
+
+/// Whether `date` is a weekday and not a Federal Reserve holiday.
+pub fn is_banking_day<D: CalendarDate>(date: &D) -> bool {
+    FederalReserve.is_banking_day(CivilDate::of(date))
+}
+
+/// The first Federal Reserve banking day strictly after `date`, or `None`
+/// if `D` cannot hold it.
+pub fn next_banking_day<D: CalendarDate>(date: &D) -> Option<D> {
+    convert(FederalReserve.next_banking_day(CivilDate::of(date)))
+}
+
+/// The date `n` Federal Reserve banking days after `date`, or `None` if
+/// `D` cannot hold it.
+pub fn add_banking_days<D: CalendarDate>(date: &D, n: u32) -> Option<D> {
+    convert(FederalReserve.add_banking_days(CivilDate::of(date), n))
+}
+
+impl BatchBuilder {
+    /// If no effective entry date has been set, use the first banking day
+    /// after `file_creation_date`.
+    pub fn default_effective_date<D: CalendarDate>(&mut self, file_creation_date: &D) -> &mut Self {
+        self.default_effective_date_with(file_creation_date, &FederalReserve)
+    }
+
+    /// Like [`default_effective_date`](BatchBuilder::default_effective_date),
+    /// counting banking days with `calendar`. The date stays unset if the
+    /// calendar has no banking day within [`BANKING_DAY_SEARCH_DAYS`].
+    pub fn default_effective_date_with<D: CalendarDate>(
+        &mut self,
+        file_creation_date: &D,
+        calendar: &dyn HolidayCalendar,
+    ) -> &mut Self {
+        if self.effective_entry_date.trim().is_empty() {
+            if let Some(date) = calendar.next_banking_day(CivilDate::of(file_creation_date)) {
+                self.effective_date(date);
+            }
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> CivilDate {
+        CivilDate::new(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_federal_reserve_holidays_2025() {
+        #[rustfmt::skip]
+        let holidays = [
+            (1, 1), (1, 20), (2, 17), (5, 26), (6, 19), (7, 4),
+            (9, 1), (10, 13), (11, 11), (11, 27), (12, 25),
+        ];
+        let found: Vec<(u32, u32)> = (0..365)
+            .map(|i| date(2025, 1, 1).add_days(i))
+            .filter(|&d| FederalReserve.is_holiday(d))
+            .map(|d| (d.month(), d.day()))
+            .collect();
+        assert_eq!(found, holidays);
+    }
+
+    #[test]
+    fn test_weekend_holidays() {
+        // Sunday, July 4, 2027 is observed on Monday.
+        assert!(FederalReserve.is_holiday(date(2027, 7, 5)));
+        // Saturday, July 4, 2026 is not observed on Friday.
+        assert!(!FederalReserve.is_holiday(date(2026, 7, 3)));
+        assert!(is_banking_day(&date(2026, 7, 3)));
+        // Juneteenth only from 2022.
+        assert!(!FederalReserve.is_holiday(date(2021, 6, 18)));
+        assert!(FederalReserve.is_holiday(date(2023, 6, 19)));
+        // Outside the table only weekends count; December 25, 2100 is a
+        // Saturday.
+        assert!(is_banking_day(&date(2100, 12, 24)));
+        assert!(!is_banking_day(&date(2100, 12, 25)));
+    }
+
+    #[test]
+    fn test_next_and_add_banking_days() {
+        // Friday before Memorial Day 2025.
+        let friday = date(2025, 5, 23);
+        assert!(!is_banking_day(&date(2025, 5, 26)));
+        assert_eq!(next_banking_day(&friday), Some(date(2025, 5, 27)));
+        assert_eq!(add_banking_days(&friday, 0), Some(friday));
+        assert_eq!(add_banking_days(&friday, 3), Some(date(2025, 5, 29)));
+        // Across Christmas and New Year's Day.
+        assert_eq!(
+            add_banking_days(&date(2025, 12, 24), 5),
+            Some(date(2026, 1, 2))
+        );
+    }
+
+    #[test]
+    fn test_custom_calendar() {
+        struct ClosedOnFirsts;
+
+        impl HolidayCalendar for ClosedOnFirsts {
+            fn is_holiday(&self, date: CivilDate) -> bool {
+                date.day() == 1
+            }
+        }
+
+        assert_eq!(
+            ClosedOnFirsts.next_banking_day(date(2025, 7, 31)),
+            Some(date(2025, 8, 4))
+        );
+    }
+
+    #[test]
+    fn test_always_closed_calendar() {
+        struct AlwaysClosed;
+
+        impl HolidayCalendar for AlwaysClosed {
+            fn is_holiday(&self, _: CivilDate) -> bool {
+                true
+            }
+        }
+
+        assert_eq!(AlwaysClosed.next_banking_day(date(2025, 7, 31)), None);
+        assert_eq!(AlwaysClosed.add_banking_days(date(2025, 7, 31), 2), None);
+        assert_eq!(
+            AlwaysClosed.add_banking_days(date(2025, 7, 31), 0),
+            Some(date(2025, 7, 31))
+        );
+
+        let mut builder = BatchBuilder::new("ACME", "1234567890", "PPD", "PAYROLL", "12345678");
+        builder.default_effective_date_with(&date(2025, 11, 26), &AlwaysClosed);
+        assert_eq!(builder.effective_entry_date.trim(), "");
+    }
+
+    #[test]
+    fn test_composite_calendar() {
+        let extra = vec![date(2025, 7, 3)];
+        let calendar = CompositeCalendar::new().with(FederalReserve).with(extra);
+        // Closed Thursday by the list and Friday, July 4 by the Fed.
+        assert_eq!(
+            calendar.next_banking_day(date(2025, 7, 2)),
+            Some(date(2025, 7, 7))
+        );
+        assert!(!CompositeCalendar::new().is_holiday(date(2025, 12, 25)));
+
+        let boxed: Box<dyn HolidayCalendar> = Box::new(calendar);
+        assert_eq!(
+            boxed.add_banking_days(date(2025, 7, 2), 2),
+            Some(date(2025, 7, 8))
+        );
+    }
+
+    #[test]
+    fn test_default_effective_date() {
+        let mut builder = BatchBuilder::new("ACME", "1234567890", "PPD", "PAYROLL", "12345678");
+        // Created Wednesday, November 26, 2025; Thursday is Thanksgiving.
+        builder.default_effective_date(&date(2025, 11, 26));
+        assert_eq!(builder.effective_entry_date, "251128");
+
+        builder.default_effective_date(&date(2025, 12, 1));
+        assert_eq!(builder.effective_entry_date, "251128");
+
+        let mut builder = BatchBuilder::new("ACME", "1234567890", "PPD", "PAYROLL", "12345678");
+        let closed_friday = CompositeCalendar::new()
+            .with(FederalReserve)
+            .with(vec![date(2025, 11, 28)]);
+        builder.default_effective_date_with(&date(2025, 11, 26), &closed_friday);
+        assert_eq!(builder.effective_entry_date, "251201");
+    }
+}
@@ -0,0 +1,230 @@
+//! `ach-validate`: check, summarize, or dump an ACH file from the command
+//! line.
+//!
+//! Built with the `cli` feature. All parsing and checking is done by the
+//! library; this binary only handles arguments, output, and exit codes.
+
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+
+use rs_ach::AchFile;
+
+const USAGE: &str = "\
+usage: ach-validate <command> <file>
+
+commands:
+  validate   check the file against its control records
+  summary    print the file summary
+  json       print the parsed file as JSON
+
+Use `-` as <file> to read standard input.
+
+exit codes:
+  0  success (validate: no errors, warnings allowed)
+  1  the file could not be parsed or failed validation
+  2  usage or I/O error
+";
+
+/// The command succeeded and, for `validate`, found no errors.
+const EXIT_OK: u8 = 0;
+
+/// The file did not parse, or `validate` found errors.
+const EXIT_INVALID: u8 = 1;
+
+/// Bad arguments, or the file could not be read.
+const EXIT_USAGE: u8 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Validate,
+    Summary,
+    Json,
+}
+
+impl Command {
+    fn from_name(name: &str) -> Option<Command> {
+        match name {
+            "validate" => Some(Command::Validate),
+            "summary" => Some(Command::Summary),
+            "json" => Some(Command::Json),
+            _ => None,
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let code = run(&args, &mut io::stdout().lock(), &mut io::stderr().lock()).unwrap_or(EXIT_USAGE);
+    ExitCode::from(code)
+}
+
+/// Parse `args` (without the program name), run the command, and return the
+/// exit code.
+fn run(args: &[String], out: &mut dyn Write, err: &mut dyn Write) -> io::Result<u8> {
+    let (command, path) = match args {
+        [flag] if flag == "-h" || flag == "--help" => {
+            out.write_all(USAGE.as_bytes())?;
+            return Ok(EXIT_OK);
+        }
+        [command, path] => (command, path),
+        _ => {
+            err.write_all(USAGE.as_bytes())?;
+            return Ok(EXIT_USAGE);
+        }
+    };
+    let Some(command) = Command::from_name(command) else {
+        writeln!(err, "ach-validate: unknown command '{command}'\n")?;
+        err.write_all(USAGE.as_bytes())?;
+        return Ok(EXIT_USAGE);
+    };
+
+    let content = if path == "-" {
+        let mut content = String::new();
+        io::stdin().read_to_string(&mut content).map(|_| content)
+    } else {
+        std::fs::read_to_string(path)
+    };
+    match content {
+        Ok(content) => execute(command, &content, out, err),
+        Err(e) => {
+            writeln!(err, "ach-validate: {path}: {e}")?;
+            Ok(EXIT_USAGE)
+        }
+    }
+}
+
+/// Run `command` on the file `content`.
+fn execute(
+    command: Command,
+    content: &str,
+    out: &mut dyn Write,
+    err: &mut dyn Write,
+) -> io::Result<u8> {
+    let file = match AchFile::parse(content) {
+        Ok(file) => file,
+        Err(e) => {
+            writeln!(err, "ach-validate: parse error: {e}")?;
+            return Ok(EXIT_INVALID);
+        }
+    };
+
+    match command {
+        Command::Validate => {
+            let report = file.validate();
+            writeln!(out, "{report}")?;
+            Ok(if report.is_valid() {
+                EXIT_OK
+            } else {
+                EXIT_INVALID
+            })
+        }
+        Command::Summary => {
+            writeln!(out, "{}", file.summary())?;
+            Ok(EXIT_OK)
+        }
+        Command::Json => {
+            serde_json::to_writer_pretty(&mut *out, &file)?;
+            writeln!(out)?;
+            Ok(EXIT_OK)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rs_ach::fixtures::{self, Corruption};
+
+    /// Run with `args`, returning the exit code, stdout, and stderr.
+    fn run_with(args: &[&str]) -> (u8, String, String) {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        let code = run(&args, &mut out, &mut err).unwrap();
+        (
+            code,
+            String::from_utf8(out).unwrap(),
+            String::from_utf8(err).unwrap(),
+        )
+    }
+
+    /// Run `command` on `content` directly, skipping the file system.
+    fn execute_with(command: Command, content: &str) -> (u8, String, String) {
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        let code = execute(command, content, &mut out, &mut err).unwrap();
+        (
+            code,
+            String::from_utf8(out).unwrap(),
+            String::from_utf8(err).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_usage() {
+        let (code, out, _) = run_with(&["--help"]);
+        assert_eq!(code, EXIT_OK);
+        assert!(out.starts_with("usage: ach-validate"));
+
+        let (code, out, err) = run_with(&[]);
+        assert_eq!(code, EXIT_USAGE);
+        assert!(out.is_empty());
+        assert!(err.starts_with("usage:"));
+
+        let (code, _, err) = run_with(&["lint", "file.ach"]);
+        assert_eq!(code, EXIT_USAGE);
+        assert!(err.starts_with("ach-validate: unknown command 'lint'"));
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let (code, _, err) = run_with(&["validate", "/nonexistent/file.ach"]);
+        assert_eq!(code, EXIT_USAGE);
+        assert!(err.starts_with("ach-validate: /nonexistent/file.ach: "));
+    }
+
+    #[test]
+    fn test_reads_file_from_disk() {
+        let path = std::env::temp_dir().join(format!("ach-validate-{}.ach", std::process::id()));
+        std::fs::write(&path, fixtures::sample_ppd_file(2)).unwrap();
+        let (code, out, _) = run_with(&["validate", path.to_str().unwrap()]);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(code, EXIT_OK);
+        assert_eq!(out, "0 error(s), 0 warning(s)\n");
+    }
+
+    #[test]
+    fn test_validate_reports_errors() {
+        let text = fixtures::corrupt(&fixtures::sample_ppd_file(1), Corruption::TotalsMismatch);
+        let (code, out, _) = execute_with(Command::Validate, &text);
+        assert_eq!(code, EXIT_INVALID);
+        assert!(out.contains("error: batch 0000001: total_credit_amount"));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let text = fixtures::corrupt(&fixtures::sample_ppd_file(1), Corruption::BadAmount);
+        let (code, out, err) = execute_with(Command::Summary, &text);
+        assert_eq!(code, EXIT_INVALID);
+        assert!(out.is_empty());
+        assert!(err.starts_with("ach-validate: parse error: "));
+    }
+
+    #[test]
+    fn test_summary() {
+        let (code, out, _) = execute_with(Command::Summary, &fixtures::sample_returns_file());
+        assert_eq!(code, EXIT_OK);
+        assert!(out.starts_with("Destination: 123456780 YOUR BANK\n"));
+        assert!(out.ends_with(
+            "Debits: 150.00  Credits: 10.00\nBy code: 21: 1 for 10.00, 26: 1 for 150.00\n"
+        ));
+    }
+
+    #[test]
+    fn test_json() {
+        let (code, out, _) = execute_with(Command::Json, &fixtures::sample_ppd_file(2));
+        assert_eq!(code, EXIT_OK);
+        let json: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(json["batches"][0]["entries"][1]["amount"], 2_500);
+        assert_eq!(json["file_control"]["batch_count"], 1);
+    }
+}
@@ -0,0 +1,841 @@
+//! Builders for constructing ACH records in code.
+//!
+//! Builders validate every value against its NACHA field width as it is
+//! added, assign trace numbers, and compute control totals so the produced
+//! records are internally consistent. Names and descriptions are first put
+//! through [`normalize_to_nacha`](crate::normalize_to_nacha) unless that is
+//! turned off.
+
+use std::borrow::Cow;
+
+use crate::error::AchError;
+use crate::normalize::normalize_to_nacha;
+use crate::records::*;
+use crate::{routing, AchFile, Batch, ServiceClassCode, TransactionCode};
+
+/// Largest amount that fits the 10-digit entry amount field, in cents.
+pub const MAX_ENTRY_AMOUNT: u64 = 9_999_999_999;
+
+/// Direction of funds for an entry, from the originator's point of view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Funds are deposited into the receiver's account.
+    Credit,
+
+    /// Funds are withdrawn from the receiver's account.
+    Debit,
+}
+
+/// A minimal description of a payment to or from a checking account.
+///
+/// This is the input type accepted by [`BatchBuilder::add_entry`]; the
+/// builder derives the transaction code, trace number, and padding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimpleEntry {
+    /// Receiving routing number (9 digits, including check digit)
+    pub routing_number: String,
+
+    /// Receiver's account number (up to 17 characters)
+    pub account_number: String,
+
+    /// Amount in cents
+    pub amount: u64,
+
+    /// Receiver's name (up to 22 characters)
+    pub individual_name: String,
+
+    /// Individual identification number (up to 15 characters)
+    pub identification: Option<String>,
+
+    /// Credit or debit
+    pub direction: Direction,
+}
+
+/// A payment-like type that [`BatchBuilder::extend_from`] can add as an
+/// entry, so a domain type needs no conversion code of its own.
+///
+/// ```
+/// use rs_ach::{BatchBuilder, Direction, IntoEntryDetail};
+///
+/// struct Payment {
+///     routing: String,
+///     account: String,
+///     cents: u64,
+///     payee: String,
+///     refund: bool,
+/// }
+///
+/// impl IntoEntryDetail for Payment {
+///     fn routing(&self) -> &str {
+///         &self.routing
+///     }
+///     fn account(&self) -> &str {
+///         &self.account
+///     }
+///     fn amount_cents(&self) -> u64 {
+///         self.cents
+///     }
+///     fn name(&self) -> &str {
+///         &self.payee
+///     }
+///     fn direction(&self) -> Direction {
+///         if self.refund { Direction::Debit } else { Direction::Credit }
+///     }
+/// }
+///
+/// let payments = vec![
+///     Payment { routing: "123456780".into(), account: "111".into(), cents: 500, payee: "Ann".into(), refund: false },
+///     Payment { routing: "123456781".into(), account: "222".into(), cents: 700, payee: "Bob".into(), refund: false },
+/// ];
+/// let mut builder = BatchBuilder::new("YOUR COMPANY", "1234567890", "PPD", "PAYROLL", "12345678");
+/// builder.effective_entry_date("140903");
+/// let failures = builder.extend_from(&payments).unwrap_err();
+/// assert_eq!(failures.len(), 1);
+/// assert_eq!(failures[0].0, 1);
+/// assert_eq!(builder.len(), 1);
+/// ```
+pub trait IntoEntryDetail {
+    /// Receiving routing number (9 digits, including check digit)
+    fn routing(&self) -> &str;
+
+    /// Receiver's account number (up to 17 characters)
+    fn account(&self) -> &str;
+
+    /// Amount in cents
+    fn amount_cents(&self) -> u64;
+
+    /// Receiver's name (up to 22 characters)
+    fn name(&self) -> &str;
+
+    /// Credit or debit
+    fn direction(&self) -> Direction;
+
+    /// Individual identification number (up to 15 characters); none by
+    /// default
+    fn identification(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl IntoEntryDetail for SimpleEntry {
+    fn routing(&self) -> &str {
+        &self.routing_number
+    }
+
+    fn account(&self) -> &str {
+        &self.account_number
+    }
+
+    fn amount_cents(&self) -> u64 {
+        self.amount
+    }
+
+    fn name(&self) -> &str {
+        &self.individual_name
+    }
+
+    fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    fn identification(&self) -> Option<&str> {
+        self.identification.as_deref()
+    }
+}
+
+impl<T: IntoEntryDetail + ?Sized> IntoEntryDetail for &T {
+    fn routing(&self) -> &str {
+        (**self).routing()
+    }
+
+    fn account(&self) -> &str {
+        (**self).account()
+    }
+
+    fn amount_cents(&self) -> u64 {
+        (**self).amount_cents()
+    }
+
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn direction(&self) -> Direction {
+        (**self).direction()
+    }
+
+    fn identification(&self) -> Option<&str> {
+        (**self).identification()
+    }
+}
+
+/// Builder for a single batch.
+///
+/// # Example
+///
+/// ```
+/// use rs_ach::{BatchBuilder, Direction, SimpleEntry};
+///
+/// let mut builder = BatchBuilder::new("YOUR COMPANY", "1234567890", "PPD", "PAYROLL", "12345678");
+/// builder.effective_entry_date("140903");
+/// builder
+///     .add_entry(SimpleEntry {
+///         routing_number: "123456780".to_string(),
+///         account_number: "11232132".to_string(),
+///         amount: 1000,
+///         individual_name: "ALICE WANDERDUST".to_string(),
+///         identification: None,
+///         direction: Direction::Credit,
+///     })
+///     .unwrap();
+///
+/// let batch = builder.build().unwrap();
+/// assert_eq!(batch.control.total_credit_amount, 1000);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BatchBuilder {
+    company_name: String,
+    company_discretionary_data: String,
+    company_identification: String,
+    standard_entry_class_code: String,
+    company_entry_description: String,
+    company_descriptive_date: String,
+    pub(crate) effective_entry_date: String,
+    originating_dfi_identification: String,
+    batch_number: u64,
+    normalize_text: bool,
+    entries: Vec<EntryDetail<'static>>,
+}
+
+impl BatchBuilder {
+    /// Create a builder for a batch originated by the given company.
+    ///
+    /// # Arguments
+    ///
+    /// * `company_name` - Company name (up to 16 characters)
+    /// * `company_identification` - Company identification (up to 10 characters)
+    /// * `standard_entry_class_code` - SEC code such as `PPD` or `CCD`
+    /// * `company_entry_description` - Entry description (up to 10 characters)
+    /// * `originating_dfi_identification` - First 8 digits of the ODFI routing number
+    pub fn new(
+        company_name: &str,
+        company_identification: &str,
+        standard_entry_class_code: &str,
+        company_entry_description: &str,
+        originating_dfi_identification: &str,
+    ) -> Self {
+        BatchBuilder {
+            company_name: company_name.to_string(),
+            company_discretionary_data: String::new(),
+            company_identification: company_identification.to_string(),
+            standard_entry_class_code: standard_entry_class_code.to_string(),
+            company_entry_description: company_entry_description.to_string(),
+            company_descriptive_date: String::new(),
+            effective_entry_date: String::new(),
+            originating_dfi_identification: originating_dfi_identification.to_string(),
+            batch_number: 1,
+            normalize_text: true,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Set the company discretionary data (up to 20 characters).
+    pub fn company_discretionary_data(&mut self, value: &str) -> &mut Self {
+        self.company_discretionary_data = value.to_string();
+        self
+    }
+
+    /// Set the company descriptive date (up to 6 characters).
+    pub fn company_descriptive_date(&mut self, value: &str) -> &mut Self {
+        self.company_descriptive_date = value.to_string();
+        self
+    }
+
+    /// Set the effective entry date (YYMMDD).
+    pub fn effective_entry_date(&mut self, yymmdd: &str) -> &mut Self {
+        self.effective_entry_date = yymmdd.to_string();
+        self
+    }
+
+    /// Set the batch number (defaults to 1).
+    pub fn batch_number(&mut self, number: u64) -> &mut Self {
+        self.batch_number = number;
+        self
+    }
+
+    /// Whether to [normalize](crate::normalize_to_nacha) the company name,
+    /// discretionary data, and entry description, and the individual name
+    /// of entries added from now on (defaults to `true`). Normalized values
+    /// are truncated to their field; values that are not are rejected if
+    /// they are too long or contain characters outside printable ASCII.
+    pub fn normalize_text(&mut self, normalize: bool) -> &mut Self {
+        self.normalize_text = normalize;
+        self
+    }
+
+    /// Number of entries added so far.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no entries have been added.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Validate an entry and add it to the batch, as a live checking credit
+    /// (`22`) or debit (`27`).
+    ///
+    /// On error the builder is left unchanged, so callers importing many
+    /// entries can collect failures and continue. A zero amount is an
+    /// error, since live entries must move money; use
+    /// [`add_prenote`](BatchBuilder::add_prenote) for a prenote.
+    pub fn add_entry(&mut self, entry: SimpleEntry) -> Result<(), AchError> {
+        if entry.amount == 0 {
+            return Err(AchError::InvalidValue {
+                field: "amount",
+                reason: "live entries must be for more than zero; add a prenote instead"
+                    .to_string(),
+            });
+        }
+        let transaction_code = match entry.direction {
+            Direction::Credit => "22",
+            Direction::Debit => "27",
+        };
+        self.push_entry(entry, transaction_code)
+    }
+
+    /// Validate a zero-dollar prenote and add it to the batch, as a checking
+    /// credit (`23`) or debit (`28`) prenote verifying the account before
+    /// live entries are sent. The amount must be zero.
+    ///
+    /// ```
+    /// use rs_ach::{BatchBuilder, Direction, SimpleEntry};
+    ///
+    /// let mut builder = BatchBuilder::new("YOUR COMPANY", "1234567890", "PPD", "PAYROLL", "12345678");
+    /// builder.effective_entry_date("140903");
+    /// let mut entry = SimpleEntry {
+    ///     routing_number: "123456780".to_string(),
+    ///     account_number: "11232132".to_string(),
+    ///     amount: 0,
+    ///     individual_name: "ALICE WANDERDUST".to_string(),
+    ///     identification: None,
+    ///     direction: Direction::Debit,
+    /// };
+    /// assert!(builder.add_entry(entry.clone()).is_err());
+    /// builder.add_prenote(entry.clone()).unwrap();
+    /// entry.amount = 100;
+    /// assert!(builder.add_prenote(entry).is_err());
+    ///
+    /// let batch = builder.build().unwrap();
+    /// assert_eq!(batch.entries[0].transaction_code, "28");
+    /// assert!(batch.entries[0].is_prenote());
+    /// ```
+    pub fn add_prenote(&mut self, entry: SimpleEntry) -> Result<(), AchError> {
+        if entry.amount != 0 {
+            return Err(AchError::InvalidValue {
+                field: "amount",
+                reason: format!("prenotes must be for zero, not {}", entry.amount),
+            });
+        }
+        let transaction_code = match entry.direction {
+            Direction::Credit => "23",
+            Direction::Debit => "28",
+        };
+        self.push_entry(entry, transaction_code)
+    }
+
+    /// Validate the fields of an entry and add it with `transaction_code`.
+    fn push_entry(
+        &mut self,
+        entry: SimpleEntry,
+        transaction_code: &'static str,
+    ) -> Result<(), AchError> {
+        let routing_number = digits("routing_number", &entry.routing_number, 9)?;
+        if !routing::is_valid(&routing_number) {
+            return Err(AchError::InvalidValue {
+                field: "routing_number",
+                reason: format!("'{routing_number}' has an incorrect check digit"),
+            });
+        }
+
+        if entry.account_number.trim().is_empty() {
+            return Err(AchError::InvalidValue {
+                field: "account_number",
+                reason: "must not be blank".to_string(),
+            });
+        }
+        let account_number = alphanumeric("account_number", &entry.account_number, 17)?;
+        let individual_name = self.text("individual_name", &entry.individual_name, 22)?;
+        let identification = alphanumeric(
+            "identification",
+            entry.identification.as_deref().unwrap_or(""),
+            15,
+        )?;
+
+        if entry.amount > MAX_ENTRY_AMOUNT {
+            return Err(AchError::InvalidValue {
+                field: "amount",
+                reason: format!("{} exceeds the maximum of {MAX_ENTRY_AMOUNT}", entry.amount),
+            });
+        }
+
+        self.entries.push(EntryDetail {
+            record_type: Cow::Borrowed("6"),
+            transaction_code: Cow::Borrowed(transaction_code),
+            receiving_dfi_identification: Cow::Owned(routing_number[..8].to_string()),
+            check_digit: Cow::Owned(routing_number[8..].to_string()),
+            dfi_account_number: Cow::Owned(account_number),
+            amount: entry.amount,
+            individual_identification_number: Cow::Owned(identification),
+            individual_name: Cow::Owned(individual_name),
+            discretionary_data: Cow::Borrowed("  "),
+            addenda_record_indicator: Cow::Borrowed("0"),
+            trace_number: Cow::Borrowed(""),
+            addenda: Vec::new(),
+        });
+
+        Ok(())
+    }
+
+    /// Validate and add each item as an entry, as
+    /// [`add_entry`](BatchBuilder::add_entry) does.
+    ///
+    /// Items that fail are skipped and the rest are still added; the
+    /// failures are returned with the index of their item, counting from 0.
+    pub fn extend_from<T: IntoEntryDetail>(
+        &mut self,
+        items: impl IntoIterator<Item = T>,
+    ) -> Result<(), Vec<(usize, AchError)>> {
+        let failures: Vec<(usize, AchError)> = items
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, item)| {
+                let entry = SimpleEntry {
+                    routing_number: item.routing().to_string(),
+                    account_number: item.account().to_string(),
+                    amount: item.amount_cents(),
+                    individual_name: item.name().to_string(),
+                    identification: item.identification().map(str::to_string),
+                    direction: item.direction(),
+                };
+                self.add_entry(entry).err().map(|err| (i, err))
+            })
+            .collect();
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failures)
+        }
+    }
+
+    /// Build the batch, assigning trace numbers and computing the batch control.
+    ///
+    /// The service class code is derived from the entries: `220` when all
+    /// entries are credits, `225` when all are debits, and `200` otherwise.
+    pub fn build(&self) -> Result<Batch<'static>, AchError> {
+        let company_name = self.text("company_name", &self.company_name, 16)?;
+        let company_discretionary_data = self.text(
+            "company_discretionary_data",
+            &self.company_discretionary_data,
+            20,
+        )?;
+        let company_identification =
+            alphanumeric("company_identification", &self.company_identification, 10)?;
+        let standard_entry_class_code = self.standard_entry_class_code.to_ascii_uppercase();
+        if standard_entry_class_code.len() != 3
+            || !standard_entry_class_code
+                .bytes()
+                .all(|b| b.is_ascii_alphabetic())
+        {
+            return Err(AchError::InvalidValue {
+                field: "standard_entry_class_code",
+                reason: format!(
+                    "'{}' is not a 3-letter code",
+                    self.standard_entry_class_code
+                ),
+            });
+        }
+        let company_entry_description = self.text(
+            "company_entry_description",
+            &self.company_entry_description,
+            10,
+        )?;
+        let company_descriptive_date = alphanumeric(
+            "company_descriptive_date",
+            &self.company_descriptive_date,
+            6,
+        )?;
+        let effective_entry_date = digits("effective_entry_date", &self.effective_entry_date, 6)?;
+        let odfi = digits(
+            "originating_dfi_identification",
+            &self.originating_dfi_identification,
+            8,
+        )?;
+        let batch_number = numeric("batch_number", self.batch_number, 7)?;
+
+        let mut entries = self.entries.clone();
+        if entries.len() > 9_999_999 {
+            return Err(AchError::InvalidValue {
+                field: "trace_number",
+                reason: "batch has more entries than trace numbers".to_string(),
+            });
+        }
+        for (i, entry) in entries.iter_mut().enumerate() {
+            entry.trace_number = Cow::Owned(format!("{odfi}{:07}", i + 1));
+        }
+
+        let code = |e: &EntryDetail<'_>| TransactionCode::from_code(&e.transaction_code);
+        let has_credits = entries.iter().any(|e| code(e).is_credit());
+        let has_debits = entries.iter().any(|e| code(e).is_debit());
+        let service_class_code = match (has_credits, has_debits) {
+            (true, false) => ServiceClassCode::CreditsOnly,
+            (false, true) => ServiceClassCode::DebitsOnly,
+            _ => ServiceClassCode::Mixed,
+        };
+
+        let control = BatchControl {
+            record_type: "8".to_string(),
+            service_class_code: service_class_code.to_string(),
+            entry_addenda_count: 0,
+            entry_hash: 0,
+            total_debit_amount: 0,
+            total_credit_amount: 0,
+            company_identification: company_identification.clone(),
+            message_authentication_code: " ".repeat(19),
+            reserved: " ".repeat(6),
+            originating_dfi_identification: odfi.clone(),
+            batch_number: batch_number.clone(),
+        };
+
+        let header = BatchHeader {
+            record_type: Cow::Borrowed("5"),
+            service_class_code: Cow::Borrowed(service_class_code.code()),
+            company_name: Cow::Owned(company_name),
+            company_discretionary_data: Cow::Owned(company_discretionary_data),
+            company_identification: Cow::Owned(company_identification),
+            standard_entry_class_code: Cow::Owned(standard_entry_class_code),
+            company_entry_description: Cow::Owned(company_entry_description),
+            company_descriptive_date: Cow::Owned(company_descriptive_date),
+            effective_entry_date: Cow::Owned(effective_entry_date),
+            settlement_date: Cow::Borrowed("   "),
+            originator_status_code: Cow::Borrowed("1"),
+            originating_dfi_identification: Cow::Owned(odfi),
+            batch_number: Cow::Owned(batch_number),
+        };
+
+        let mut batch = Batch {
+            header,
+            entries,
+            control,
+            unknown_records: Vec::new(),
+            adv: None,
+            iat: None,
+        };
+        batch.recompute_control();
+        Ok(batch)
+    }
+
+    /// A name or description field, normalized unless turned off.
+    fn text(&self, field: &'static str, value: &str, width: usize) -> Result<String, AchError> {
+        if self.normalize_text {
+            alphanumeric(field, &normalize_to_nacha(value, width), width)
+        } else {
+            alphanumeric(field, value, width)
+        }
+    }
+}
+
+impl<'a> AchFile<'a> {
+    /// Assemble a file from its header and batches, such as those
+    /// [`BatchBuilder::build`] returns, computing the file control: batch
+    /// and entry counts, entry hash, totals, and the block count for the
+    /// header's blocking factor.
+    pub fn from_batches(file_header: FileHeader<'a>, batches: Vec<Batch<'a>>) -> AchFile<'a> {
+        let mut file = AchFile {
+            file_header,
+            batches,
+            file_control: FileControl {
+                record_type: "9".to_string(),
+                batch_count: 0,
+                block_count: 0,
+                entry_addenda_count: 0,
+                entry_hash: 0,
+                total_debit_amount: 0,
+                total_credit_amount: 0,
+                reserved: " ".repeat(39),
+            },
+            unknown_records: Vec::new(),
+        };
+        file.recompute_file_control();
+        file
+    }
+
+    /// A file with no batches, as some operators expect on days without
+    /// activity: the header and a file control of zero counts and totals
+    /// in one block, which [`to_ach_string`](AchFile::to_ach_string) pads
+    /// out with filler records.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile};
+    ///
+    /// let text = fixtures::sample_ppd_file(1);
+    /// let header = AchFile::parse(&text).unwrap().file_header;
+    /// let empty = AchFile::empty(header);
+    /// assert_eq!(empty.file_control.block_count, 1);
+    /// assert_eq!(empty.to_ach_string().unwrap().lines().count(), 10);
+    /// ```
+    pub fn empty(file_header: FileHeader<'a>) -> AchFile<'a> {
+        AchFile::from_batches(file_header, Vec::new())
+    }
+}
+
+/// Validate a left-justified alphanumeric field and pad it with spaces.
+fn alphanumeric(field: &'static str, value: &str, width: usize) -> Result<String, AchError> {
+    if let Some(c) = value.chars().find(|c| !(' '..='~').contains(c)) {
+        return Err(AchError::InvalidValue {
+            field,
+            reason: format!("contains unsupported character {c:?}"),
+        });
+    }
+    if value.len() > width {
+        return Err(AchError::InvalidValue {
+            field,
+            reason: format!("'{value}' is longer than {width} characters"),
+        });
+    }
+    Ok(format!("{value:<width$}"))
+}
+
+/// Validate a field that must be exactly `width` ASCII digits.
+fn digits(field: &'static str, value: &str, width: usize) -> Result<String, AchError> {
+    if value.len() != width || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(AchError::InvalidValue {
+            field,
+            reason: format!("'{value}' must be exactly {width} digits"),
+        });
+    }
+    Ok(value.to_string())
+}
+
+/// Render a number right-justified and zero-padded to `width` digits.
+fn numeric(field: &'static str, value: u64, width: usize) -> Result<String, AchError> {
+    let rendered = format!("{value:0width$}");
+    if rendered.len() > width {
+        return Err(AchError::InvalidValue {
+            field,
+            reason: format!("{value} does not fit in {width} digits"),
+        });
+    }
+    Ok(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(routing: &str, amount: u64, direction: Direction) -> SimpleEntry {
+        SimpleEntry {
+            routing_number: routing.to_string(),
+            account_number: "11232132".to_string(),
+            amount,
+            individual_name: "ALICE WANDERDUST".to_string(),
+            identification: None,
+            direction,
+        }
+    }
+
+    fn builder() -> BatchBuilder {
+        let mut builder =
+            BatchBuilder::new("YOUR COMPANY", "1234567890", "PPD", "PAYROLL", "12345678");
+        builder.effective_entry_date("140903");
+        builder
+    }
+
+    #[test]
+    fn test_build_mixed_batch() {
+        let mut builder = builder();
+        builder
+            .add_entry(entry("123456780", 1000, Direction::Credit))
+            .unwrap();
+        builder
+            .add_entry(entry("021000021", 15000, Direction::Debit))
+            .unwrap();
+
+        let batch = builder.build().unwrap();
+        assert_eq!(batch.header.service_class_code, "200");
+        assert_eq!(batch.header.company_name, "YOUR COMPANY    ");
+        assert_eq!(batch.header.batch_number, "0000001");
+        assert_eq!(batch.entries[0].trace_number, "123456780000001");
+        assert_eq!(batch.entries[1].trace_number, "123456780000002");
+        assert_eq!(batch.entries[1].transaction_code, "27");
+        assert_eq!(batch.control.entry_addenda_count, 2);
+        assert_eq!(batch.control.entry_hash, 12345678 + 2100002);
+        assert_eq!(batch.control.total_credit_amount, 1000);
+        assert_eq!(batch.control.total_debit_amount, 15000);
+    }
+
+    #[test]
+    fn test_service_class_follows_entries() {
+        let mut credits = builder();
+        credits
+            .add_entry(entry("123456780", 1000, Direction::Credit))
+            .unwrap();
+        assert_eq!(credits.build().unwrap().header.service_class_code, "220");
+
+        let mut debits = builder();
+        debits
+            .add_entry(entry("123456780", 1000, Direction::Debit))
+            .unwrap();
+        assert_eq!(debits.build().unwrap().control.service_class_code, "225");
+    }
+
+    #[test]
+    fn test_add_entry_rejects_invalid_values() {
+        let mut builder = builder();
+
+        let err = builder
+            .add_entry(entry("123456781", 1000, Direction::Credit))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AchError::InvalidValue {
+                field: "routing_number",
+                ..
+            }
+        ));
+
+        let err = builder
+            .add_entry(entry("123456780", MAX_ENTRY_AMOUNT + 1, Direction::Credit))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AchError::InvalidValue {
+                field: "amount",
+                ..
+            }
+        ));
+
+        let mut long_name = entry("123456780", 1000, Direction::Credit);
+        long_name.individual_name = "A".repeat(23);
+        builder.normalize_text(false);
+        assert!(builder.add_entry(long_name).is_err());
+
+        assert!(builder.is_empty());
+    }
+
+    #[test]
+    fn test_zero_amounts_are_prenotes() {
+        let mut builder = builder();
+        let err = builder
+            .add_entry(entry("123456780", 0, Direction::Credit))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            AchError::InvalidValue {
+                field: "amount",
+                ..
+            }
+        ));
+        assert!(builder.is_empty());
+
+        builder
+            .add_prenote(entry("123456780", 0, Direction::Credit))
+            .unwrap();
+        builder
+            .add_entry(entry("021000021", 1000, Direction::Debit))
+            .unwrap();
+        let batch = builder.build().unwrap();
+        let codes: Vec<&str> = batch
+            .entries
+            .iter()
+            .map(|e| e.transaction_code.as_ref())
+            .collect();
+        assert_eq!(codes, ["23", "27"]);
+        // The validator accepts what the builder produces.
+        let report = batch.validate();
+        assert!(report.is_valid(), "{report}");
+    }
+
+    #[test]
+    fn test_extend_from() {
+        let mut payroll = builder();
+        let entries = [
+            entry("123456780", 1000, Direction::Credit),
+            entry("123456781", 1000, Direction::Credit),
+            entry("021000021", MAX_ENTRY_AMOUNT + 1, Direction::Debit),
+            entry("021000021", 2500, Direction::Debit),
+        ];
+        let failures = payroll.extend_from(&entries).unwrap_err();
+        let failed: Vec<usize> = failures.iter().map(|(i, _)| *i).collect();
+        assert_eq!(failed, [1, 2]);
+        assert!(matches!(
+            failures[1].1,
+            AchError::InvalidValue {
+                field: "amount",
+                ..
+            }
+        ));
+
+        let batch = payroll.build().unwrap();
+        assert_eq!(batch.entries.len(), 2);
+        assert_eq!(batch.entries[1].trace_number, "123456780000002");
+        assert_eq!(batch.entries[1].transaction_code, "27");
+
+        let mut owned = builder();
+        assert!(owned.extend_from(entries[..1].to_vec()).is_ok());
+        assert_eq!(owned.len(), 1);
+    }
+
+    #[test]
+    fn test_normalize_text() {
+        let mut builder =
+            BatchBuilder::new("Crème Brûlée Co", "1234567890", "PPD", "Nómina", "12345678");
+        builder.effective_entry_date("140903");
+        let mut named = entry("123456780", 1000, Direction::Credit);
+        named.individual_name = "María José Fernández-López".to_string();
+        builder.add_entry(named.clone()).unwrap();
+
+        let batch = builder.build().unwrap();
+        assert_eq!(batch.header.company_name, "CREME BRULEE CO ");
+        assert_eq!(batch.header.company_entry_description, "NOMINA    ");
+        assert_eq!(batch.entries[0].individual_name, "MARIA JOSE FERNANDEZ-L");
+
+        builder.normalize_text(false);
+        assert!(builder.add_entry(named).is_err());
+        assert!(builder.build().is_err());
+    }
+
+    #[test]
+    fn test_empty_file_round_trips() {
+        let text = crate::fixtures::sample_empty_file();
+        let header = AchFile::parse(&text).unwrap().file_header;
+        let file = AchFile::empty(header);
+        assert_eq!(file.to_ach_string().unwrap(), text);
+        assert_eq!(AchFile::parse(&text).unwrap(), file);
+        assert!(file.validate().is_valid());
+
+        let mut builder = builder();
+        builder
+            .add_entry(entry("123456780", 1000, Direction::Credit))
+            .unwrap();
+        let batch = builder.build().unwrap();
+        let file = AchFile::from_batches(file.file_header, vec![batch]);
+        assert_eq!(file.file_control.batch_count, 1);
+        assert_eq!(file.file_control.total_credit_amount, 1000);
+        let written = file.to_ach_string().unwrap();
+        assert_eq!(AchFile::parse(&written).unwrap(), file);
+    }
+
+    #[test]
+    fn test_build_requires_effective_date() {
+        let builder = BatchBuilder::new("YOUR COMPANY", "1234567890", "PPD", "PAYROLL", "12345678");
+        assert!(matches!(
+            builder.build(),
+            Err(AchError::InvalidValue {
+                field: "effective_entry_date",
+                ..
+            })
+        ));
+    }
+}
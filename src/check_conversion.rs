@@ -0,0 +1,114 @@
+//! Check conversion entries (ARC, BOC, POP, and RCK), which carry the
+//! check serial number where other entries carry the individual
+//! identification number.
+
+use crate::records::{BatchHeader, EntryDetail};
+
+/// The largest amount, in cents, of one entry of a check conversion SEC
+/// code, or `None` for other codes: $2,500.00 for RCK and $25,000.00 for
+/// ARC, BOC, and POP.
+pub(crate) fn entry_limit(sec: &str) -> Option<u64> {
+    match sec {
+        "RCK" => Some(250_000),
+        "ARC" | "BOC" | "POP" => Some(2_500_000),
+        _ => None,
+    }
+}
+
+/// The POP reading of an entry's individual identification number field
+/// (positions 40-54), from [`EntryDetail::as_pop`]. The individual name
+/// field is the receiver's name, as for other entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PopFields {
+    /// Check serial number (positions 40-48)
+    pub check_serial_number: String,
+
+    /// Terminal city, abbreviated (positions 49-52)
+    pub terminal_city: String,
+
+    /// Terminal state (positions 53-54)
+    pub terminal_state: String,
+}
+
+impl EntryDetail<'_> {
+    /// The check serial number of an ARC, BOC, POP, or RCK entry, trimmed,
+    /// or `None` for other SEC codes. For POP it is the first nine
+    /// characters of the field; the rest are [`EntryDetail::as_pop`]'s
+    /// terminal city and state.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile};
+    ///
+    /// let text = fixtures::sample_ppd_file(1);
+    /// let mut file = AchFile::parse(&text).unwrap();
+    /// let batch = &mut file.batches[0];
+    /// batch.entries[0].individual_identification_number = "000004721      ".into();
+    /// assert_eq!(batch.entries[0].check_serial_number(&batch.header), None);
+    /// batch.header.standard_entry_class_code = "BOC".into();
+    /// assert_eq!(batch.entries[0].check_serial_number(&batch.header), Some("000004721"));
+    /// ```
+    pub fn check_serial_number(&self, header: &BatchHeader<'_>) -> Option<&str> {
+        let field: &str = &self.individual_identification_number;
+        match header.standard_entry_class_code.trim() {
+            "ARC" | "BOC" | "RCK" => Some(field.trim()),
+            "POP" => Some(field.get(..9).unwrap_or(field).trim()),
+            _ => None,
+        }
+    }
+
+    /// Read the entry with the POP layout. Only meaningful for entries of
+    /// a POP batch.
+    pub fn as_pop(&self) -> PopFields {
+        let field = &self.individual_identification_number;
+        let slice = |from: usize, to: usize| {
+            field
+                .get(from..to.min(field.len()))
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        };
+        PopFields {
+            check_serial_number: slice(0, 9),
+            terminal_city: slice(9, 13),
+            terminal_state: slice(13, 15),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, AchFile};
+
+    #[test]
+    fn test_pop_fields() {
+        let text = fixtures::sample_ppd_file(1);
+        let mut file = AchFile::parse(&text).unwrap();
+        let batch = &mut file.batches[0];
+        batch.header.standard_entry_class_code = "POP".into();
+        batch.entries[0].individual_identification_number = "000018842CHICIL".into();
+        assert_eq!(
+            batch.entries[0].as_pop(),
+            PopFields {
+                check_serial_number: "000018842".to_string(),
+                terminal_city: "CHIC".to_string(),
+                terminal_state: "IL".to_string(),
+            }
+        );
+        let entry = &batch.entries[0];
+        assert_eq!(entry.check_serial_number(&batch.header), Some("000018842"));
+
+        batch.entries[0].individual_identification_number = "0001".into();
+        assert_eq!(batch.entries[0].as_pop().check_serial_number, "0001");
+        assert_eq!(batch.entries[0].as_pop().terminal_state, "");
+    }
+
+    #[test]
+    fn test_entry_limit() {
+        assert_eq!(entry_limit("RCK"), Some(250_000));
+        assert_eq!(entry_limit("ARC"), Some(2_500_000));
+        assert_eq!(entry_limit("PPD"), None);
+    }
+}
@@ -0,0 +1,201 @@
+//! Recomputation of batch and file control records from their contents.
+
+use crate::records::{BatchControl, EntryDetail, FileControl, FileHeader};
+use crate::{AchFile, Batch};
+
+/// Entry hash fields are the low ten digits of the sum of RDFI identifications.
+const ENTRY_HASH_MODULUS: u64 = 10_000_000_000;
+
+/// Returns `true` for credit transaction codes (second digit 1 through 4).
+pub(crate) fn is_credit(transaction_code: &str) -> bool {
+    matches!(transaction_code.as_bytes().get(1), Some(b'1'..=b'4'))
+}
+
+/// Returns `true` for debit transaction codes (second digit 6 through 9).
+pub(crate) fn is_debit(transaction_code: &str) -> bool {
+    matches!(transaction_code.as_bytes().get(1), Some(b'6'..=b'9'))
+}
+
+/// Sum of the entries' 8-digit RDFI identifications, truncated to ten digits.
+pub(crate) fn entry_hash<'e>(entries: impl IntoIterator<Item = &'e EntryDetail<'e>>) -> u64 {
+    entries
+        .into_iter()
+        .map(|e| {
+            e.receiving_dfi_identification
+                .trim()
+                .parse::<u64>()
+                .unwrap_or(0)
+        })
+        .sum::<u64>()
+        % ENTRY_HASH_MODULUS
+}
+
+impl Batch<'_> {
+    /// The entry hash of the batch's entries: the sum of their 8-digit RDFI
+    /// identifications, keeping the rightmost ten digits. Validation compares
+    /// it with the batch control's entry hash.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile};
+    ///
+    /// let text = fixtures::sample_ppd_file(3);
+    /// let file = AchFile::parse(&text).unwrap();
+    /// let batch = &file.batches[0];
+    /// assert_eq!(batch.computed_entry_hash(), batch.control.entry_hash);
+    /// ```
+    pub fn computed_entry_hash(&self) -> u64 {
+        entry_hash(&self.entries)
+    }
+
+    /// Recompute the batch control's entry/addenda count, entry hash, and
+    /// debit and credit totals from the entries.
+    pub(crate) fn recompute_control(&mut self) {
+        let total = |keep: fn(&str) -> bool| -> u64 {
+            self.entries
+                .iter()
+                .filter(|e| keep(&e.transaction_code))
+                .map(|e| e.amount)
+                .sum()
+        };
+        let total_debit_amount = total(is_debit);
+        let total_credit_amount = total(is_credit);
+
+        self.control.entry_addenda_count = self
+            .entries
+            .iter()
+            .map(|e| 1 + e.addenda.len() as u64)
+            .sum();
+        self.control.entry_hash = self.computed_entry_hash();
+        self.control.total_debit_amount = total_debit_amount;
+        self.control.total_credit_amount = total_credit_amount;
+    }
+}
+
+impl AchFile<'_> {
+    /// The entry hash of every entry in the file, truncated to ten digits as
+    /// [`Batch::computed_entry_hash`] is. It is also the truncated sum of the
+    /// batches' hashes, which is what validation compares with the file
+    /// control's entry hash.
+    pub fn computed_entry_hash(&self) -> u64 {
+        entry_hash(self.batches.iter().flat_map(|b| &b.entries))
+    }
+
+    /// Recompute the file control from the batch controls, including the
+    /// block count implied by the header's blocking factor. Records of
+    /// unknown type count towards the blocks.
+    pub(crate) fn recompute_file_control(&mut self) {
+        let mut totals = FileTotals::default();
+        totals.add_unknown(self.unknown_records.len());
+        for batch in &self.batches {
+            totals.add_batch(&batch.control, batch.unknown_records.len());
+        }
+        totals.apply(blocking_factor(&self.file_header), &mut self.file_control);
+    }
+}
+
+/// The header's blocking factor, or 10 if it is not a positive number.
+pub(crate) fn blocking_factor(header: &FileHeader<'_>) -> u64 {
+    header
+        .blocking_factor
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .filter(|&b| b > 0)
+        .unwrap_or(10)
+}
+
+/// Running sums of a file's batch controls, from which its file control is
+/// computed.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FileTotals {
+    batches: u64,
+    entry_addenda_count: u64,
+    entry_hash: u64,
+    total_debit_amount: u64,
+    total_credit_amount: u64,
+    unknown_records: u64,
+}
+
+impl FileTotals {
+    /// Add a batch with its control and its records of unknown type.
+    pub(crate) fn add_batch(&mut self, control: &BatchControl, unknown_records: usize) {
+        self.batches += 1;
+        self.entry_addenda_count += control.entry_addenda_count;
+        self.entry_hash = (self.entry_hash + control.entry_hash) % ENTRY_HASH_MODULUS;
+        self.total_debit_amount += control.total_debit_amount;
+        self.total_credit_amount += control.total_credit_amount;
+        self.add_unknown(unknown_records);
+    }
+
+    /// Add records of unknown type outside any batch.
+    pub(crate) fn add_unknown(&mut self, unknown_records: usize) {
+        self.unknown_records += unknown_records as u64;
+    }
+
+    /// Set the computed fields of `control` from the sums.
+    pub(crate) fn apply(&self, blocking_factor: u64, control: &mut FileControl) {
+        let records = 2 + 2 * self.batches + self.entry_addenda_count + self.unknown_records;
+        control.batch_count = self.batches;
+        control.block_count = records.div_ceil(blocking_factor);
+        control.entry_addenda_count = self.entry_addenda_count;
+        control.entry_hash = self.entry_hash;
+        control.total_debit_amount = self.total_debit_amount;
+        control.total_credit_amount = self.total_credit_amount;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_transaction_code_direction() {
+        assert!(is_credit("22"));
+        assert!(is_credit("21"));
+        assert!(is_debit("27"));
+        assert!(is_debit("26"));
+        assert!(!is_credit("27"));
+        assert!(!is_debit("2"));
+    }
+
+    #[test]
+    fn test_entry_hash_truncation() {
+        let text = fixtures::sample_ppd_file(120);
+        let mut file = AchFile::parse(&text).unwrap();
+        for entry in &mut file.batches[0].entries {
+            entry.receiving_dfi_identification = "99999999".into();
+            entry.check_digit = "2".into();
+        }
+        // 120 * 99999999 = 11999999880, which needs 11 digits.
+        let batch = &file.batches[0];
+        assert_eq!(batch.computed_entry_hash(), 1_999_999_880);
+        assert_eq!(file.computed_entry_hash(), 1_999_999_880);
+
+        file.batches[0].recompute_control();
+        file.recompute_file_control();
+        assert_eq!(file.file_control.entry_hash, 1_999_999_880);
+        assert!(file.validate().is_valid());
+
+        // Keeping the leftmost ten digits instead is a mismatch.
+        file.batches[0].control.entry_hash = 1_199_999_988;
+        let report = file.validate();
+        let mismatch = report.control_mismatches().next().unwrap();
+        assert_eq!(mismatch.field, crate::ControlField::EntryHash);
+        assert_eq!(mismatch.computed, 1_999_999_880);
+    }
+
+    #[test]
+    fn test_recompute_matches_generated_controls() {
+        let text = fixtures::sample_returns_file();
+        let parsed = AchFile::parse(&text).unwrap();
+
+        let mut file = parsed.clone();
+        file.batches[0].control.total_debit_amount = 0;
+        file.batches[0].control.entry_hash = 0;
+        file.file_control.block_count = 0;
+        file.batches[0].recompute_control();
+        file.recompute_file_control();
+        assert_eq!(file, parsed);
+    }
+}
@@ -0,0 +1,311 @@
+//! Import entries from CSV payment runs.
+//!
+//! Available with the `csv` feature. Each data row is mapped to a
+//! [`SimpleEntry`] by header name and added to a [`BatchBuilder`]. Rows that
+//! fail to parse or validate are collected as [`CsvRowError`]s instead of
+//! aborting the import.
+
+use std::fmt;
+use std::io;
+
+use crate::builder::{BatchBuilder, Direction, SimpleEntry};
+use crate::error::AchError;
+
+/// Header names of the CSV columns holding each entry field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvMapping {
+    /// Column with the 9-digit routing number
+    pub routing_number: String,
+
+    /// Column with the account number
+    pub account_number: String,
+
+    /// Column with the amount in dollars, e.g. `1234.56`
+    pub amount: String,
+
+    /// Column with the receiver's name
+    pub individual_name: String,
+
+    /// Optional column with the individual identification number
+    pub identification: Option<String>,
+
+    /// Column with `credit`/`debit` (or `C`/`D`, `CR`/`DR`)
+    pub direction: String,
+}
+
+impl Default for CsvMapping {
+    fn default() -> Self {
+        CsvMapping {
+            routing_number: "routing".to_string(),
+            account_number: "account".to_string(),
+            amount: "amount".to_string(),
+            individual_name: "name".to_string(),
+            identification: Some("id".to_string()),
+            direction: "type".to_string(),
+        }
+    }
+}
+
+/// A problem with a single CSV row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvRowError {
+    /// 1-based row number in the CSV input, counting the header as row 1
+    pub row: usize,
+
+    /// Header name of the offending column, when the problem is column-specific
+    pub column: Option<String>,
+
+    /// Description of the problem
+    pub problem: String,
+}
+
+impl fmt::Display for CsvRowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.column {
+            Some(column) => write!(f, "row {}, column '{column}': {}", self.row, self.problem),
+            None => write!(f, "row {}: {}", self.row, self.problem),
+        }
+    }
+}
+
+/// Outcome of a CSV import.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CsvImport {
+    /// Number of rows added to the builder
+    pub imported: usize,
+
+    /// Rows that were skipped, in input order
+    pub errors: Vec<CsvRowError>,
+}
+
+/// Read entries from CSV and add them to `builder`.
+///
+/// Returns an error only when the input as a whole is unusable (unreadable
+/// header or a mapped column missing from it); row-level problems are
+/// reported in [`CsvImport::errors`].
+///
+/// # Example
+///
+/// ```
+/// use rs_ach::BatchBuilder;
+/// use rs_ach::csv_import::{from_csv, CsvMapping};
+///
+/// let csv = "routing,account,amount,name,id,type\n\
+///            123456780,11232132,10.00,ALICE WANDERDUST,,credit\n";
+///
+/// let mut builder = BatchBuilder::new("YOUR COMPANY", "1234567890", "PPD", "PAYROLL", "12345678");
+/// let import = from_csv(csv.as_bytes(), &CsvMapping::default(), &mut builder).unwrap();
+/// assert_eq!(import.imported, 1);
+/// assert!(import.errors.is_empty());
+/// ```
+pub fn from_csv<R: io::Read>(
+    reader: R,
+    mapping: &CsvMapping,
+    builder: &mut BatchBuilder,
+) -> Result<CsvImport, AchError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+
+    let headers = reader
+        .headers()
+        .map_err(|e| AchError::InvalidStructure(format!("Unreadable CSV header: {e}")))?
+        .clone();
+    let column = |name: &str| -> Result<usize, AchError> {
+        headers.iter().position(|h| h == name).ok_or_else(|| {
+            AchError::InvalidStructure(format!("CSV header is missing column '{name}'"))
+        })
+    };
+
+    let routing_idx = column(&mapping.routing_number)?;
+    let account_idx = column(&mapping.account_number)?;
+    let amount_idx = column(&mapping.amount)?;
+    let name_idx = column(&mapping.individual_name)?;
+    let id_idx = mapping.identification.as_deref().map(column).transpose()?;
+    let direction_idx = column(&mapping.direction)?;
+
+    let mut import = CsvImport::default();
+    for (i, record) in reader.records().enumerate() {
+        let row = i + 2;
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                import.errors.push(CsvRowError {
+                    row,
+                    column: None,
+                    problem: e.to_string(),
+                });
+                continue;
+            }
+        };
+
+        let get = |idx: usize| record.get(idx).unwrap_or("");
+        let row_error = |column: &str, problem: String| CsvRowError {
+            row,
+            column: Some(column.to_string()),
+            problem,
+        };
+
+        let amount = match parse_dollars(get(amount_idx)) {
+            Ok(amount) => amount,
+            Err(problem) => {
+                import.errors.push(row_error(&mapping.amount, problem));
+                continue;
+            }
+        };
+        let direction = match parse_direction(get(direction_idx)) {
+            Ok(direction) => direction,
+            Err(problem) => {
+                import.errors.push(row_error(&mapping.direction, problem));
+                continue;
+            }
+        };
+
+        let entry = SimpleEntry {
+            routing_number: get(routing_idx).to_string(),
+            account_number: get(account_idx).to_string(),
+            amount,
+            individual_name: get(name_idx).to_string(),
+            identification: id_idx.map(get).filter(|s| !s.is_empty()).map(String::from),
+            direction,
+        };
+
+        match builder.add_entry(entry) {
+            Ok(()) => import.imported += 1,
+            Err(AchError::InvalidValue { field, reason }) => {
+                let column = match field {
+                    "routing_number" => Some(mapping.routing_number.clone()),
+                    "account_number" => Some(mapping.account_number.clone()),
+                    "amount" => Some(mapping.amount.clone()),
+                    "individual_name" => Some(mapping.individual_name.clone()),
+                    "identification" => mapping.identification.clone(),
+                    _ => None,
+                };
+                import.errors.push(CsvRowError {
+                    row,
+                    column,
+                    problem: reason,
+                });
+            }
+            Err(e) => import.errors.push(CsvRowError {
+                row,
+                column: None,
+                problem: e.to_string(),
+            }),
+        }
+    }
+
+    Ok(import)
+}
+
+/// Parse a dollar amount such as `1,234.5` or `$10` into cents without
+/// going through floating point.
+fn parse_dollars(value: &str) -> Result<u64, String> {
+    let cleaned: String = value
+        .trim()
+        .trim_start_matches('$')
+        .chars()
+        .filter(|&c| c != ',')
+        .collect();
+
+    let (whole, fraction) = cleaned.split_once('.').unwrap_or((&cleaned, ""));
+    let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if whole.is_empty() || !all_digits(whole) || !all_digits(fraction) || fraction.len() > 2 {
+        return Err(format!("'{value}' is not a dollar amount"));
+    }
+
+    let cents = format!("{fraction:0<2}");
+    whole
+        .parse::<u64>()
+        .ok()
+        .and_then(|dollars| dollars.checked_mul(100))
+        .and_then(|c| c.checked_add(cents.parse::<u64>().unwrap_or(0)))
+        .ok_or_else(|| format!("'{value}' is too large"))
+}
+
+fn parse_direction(value: &str) -> Result<Direction, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "credit" | "cr" | "c" => Ok(Direction::Credit),
+        "debit" | "dr" | "d" => Ok(Direction::Debit),
+        _ => Err(format!("'{value}' is not 'credit' or 'debit'")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn builder() -> BatchBuilder {
+        let mut builder =
+            BatchBuilder::new("YOUR COMPANY", "1234567890", "PPD", "PAYROLL", "12345678");
+        builder.effective_entry_date("140903");
+        builder
+    }
+
+    #[test]
+    fn test_parse_dollars() {
+        assert_eq!(parse_dollars("10"), Ok(1000));
+        assert_eq!(parse_dollars("10.5"), Ok(1050));
+        assert_eq!(parse_dollars("$1,234.56"), Ok(123456));
+        assert_eq!(parse_dollars("0.07"), Ok(7));
+        assert!(parse_dollars("1.234").is_err());
+        assert!(parse_dollars("-5.00").is_err());
+        assert!(parse_dollars("").is_err());
+        assert!(parse_dollars(".50").is_err());
+    }
+
+    #[test]
+    fn test_import_collects_row_errors() {
+        let csv = "routing,account,amount,name,id,type\n\
+                   123456780,11232132,10.00,ALICE WANDERDUST,,credit\n\
+                   123456781,234234234,150.00,BILLY HOLIDAY,,debit\n\
+                   021000021,123123123,12.13,RACHEL WELCH,EMP42,CR\n\
+                   021000021,999999999,abc,JANE DOE,,credit\n\
+                   021000021,999999999,5.00,JANE DOE,,refund\n";
+
+        let mut builder = builder();
+        let import = from_csv(csv.as_bytes(), &CsvMapping::default(), &mut builder).unwrap();
+
+        assert_eq!(import.imported, 2);
+        assert_eq!(import.errors.len(), 3);
+        assert_eq!(import.errors[0].row, 3);
+        assert_eq!(import.errors[0].column.as_deref(), Some("routing"));
+        assert_eq!(import.errors[1].row, 5);
+        assert_eq!(import.errors[1].column.as_deref(), Some("amount"));
+        assert_eq!(import.errors[2].row, 6);
+        assert_eq!(import.errors[2].column.as_deref(), Some("type"));
+
+        let batch = builder.build().unwrap();
+        assert_eq!(batch.entries.len(), 2);
+        assert_eq!(
+            batch.entries[1].individual_identification_number.trim(),
+            "EMP42"
+        );
+        assert_eq!(batch.control.total_credit_amount, 2213);
+    }
+
+    #[test]
+    fn test_custom_mapping() {
+        let csv = "Routing No,Acct,USD,Payee,Dir\n123456780,11232132,1.00,ALICE,D\n";
+        let mapping = CsvMapping {
+            routing_number: "Routing No".to_string(),
+            account_number: "Acct".to_string(),
+            amount: "USD".to_string(),
+            individual_name: "Payee".to_string(),
+            identification: None,
+            direction: "Dir".to_string(),
+        };
+
+        let mut builder = builder();
+        let import = from_csv(csv.as_bytes(), &mapping, &mut builder).unwrap();
+        assert_eq!(import.imported, 1);
+        assert_eq!(builder.build().unwrap().control.total_debit_amount, 100);
+    }
+
+    #[test]
+    fn test_missing_column_is_an_error() {
+        let csv = "routing,account,amount,name\n";
+        let result = from_csv(csv.as_bytes(), &CsvMapping::default(), &mut builder());
+        assert!(matches!(result, Err(AchError::InvalidStructure(_))));
+    }
+}
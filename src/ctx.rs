@@ -0,0 +1,104 @@
+//! Corporate Trade Exchange (CTX) entries, whose individual name field
+//! holds an addenda count and the receiving company's name.
+
+use crate::records::EntryDetail;
+
+/// The CTX reading of an entry's individual name field (positions 55-76),
+/// from [`EntryDetail::as_ctx`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct CtxEntryDetail {
+    /// Number of addenda records the entry declares, or `None` unless the
+    /// field is four digits (positions 55-58)
+    pub addenda_count: Option<u16>,
+
+    /// Receiving company name, trimmed (positions 59-74)
+    pub receiving_company_name: String,
+}
+
+impl EntryDetail<'_> {
+    /// Read the entry with the CTX layout. Only meaningful for entries of
+    /// a CTX batch; [`payment_related_data`](EntryDetail::payment_related_data)
+    /// gives the X12 payload of its addenda.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile};
+    ///
+    /// let text = fixtures::sample_ctx_with_addenda();
+    /// let file = AchFile::parse(&text).unwrap();
+    /// let ctx = file.batches[0].entries[0].as_ctx();
+    /// assert_eq!(ctx.addenda_count, Some(3));
+    /// assert_eq!(ctx.receiving_company_name, "ACME CORP");
+    /// ```
+    pub fn as_ctx(&self) -> CtxEntryDetail {
+        let raw = self.addenda_count_field();
+        let name = &self.individual_name;
+        CtxEntryDetail {
+            addenda_count: if raw.len() == 4 && raw.bytes().all(|b| b.is_ascii_digit()) {
+                raw.parse().ok()
+            } else {
+                None
+            },
+            receiving_company_name: name
+                .chars()
+                .skip(4)
+                .take(16)
+                .collect::<String>()
+                .trim()
+                .to_string(),
+        }
+    }
+
+    /// The raw CTX addenda count, positions 55-58.
+    pub(crate) fn addenda_count_field(&self) -> &str {
+        self.individual_name
+            .get(..4)
+            .unwrap_or(&self.individual_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use crate::{fixtures, AchFile};
+
+    #[test]
+    fn test_ctx_entry() {
+        let text = fixtures::sample_ctx_with_addenda();
+        let mut file = AchFile::parse(&text).unwrap();
+        let entry = &mut file.batches[0].entries[0];
+        entry.individual_name = Cow::Borrowed("12A ACME");
+        let ctx = entry.as_ctx();
+        assert_eq!(ctx.addenda_count, None);
+        assert_eq!(ctx.receiving_company_name, "ACME");
+
+        entry.individual_name = Cow::Borrowed("00");
+        assert_eq!(entry.as_ctx().addenda_count, None);
+        assert_eq!(entry.as_ctx().receiving_company_name, "");
+    }
+
+    #[test]
+    fn test_ctx_entry_field_edges() {
+        let text = fixtures::sample_ctx_with_addenda();
+        let mut file = AchFile::parse(&text).unwrap();
+        let entry = &mut file.batches[0].entries[0];
+
+        // Positions 75-76 are reserved, not part of the name.
+        entry.individual_name = Cow::Borrowed("0012ACME CORPORATIONXY");
+        let ctx = entry.as_ctx();
+        assert_eq!(ctx.addenda_count, Some(12));
+        assert_eq!(ctx.receiving_company_name, "ACME CORPORATION");
+
+        // The count must be four digits.
+        for count in [" 003", "+003", "-003", "0x03"] {
+            entry.individual_name = format!("{count}ACME").into();
+            assert_eq!(entry.as_ctx().addenda_count, None, "{count}");
+        }
+
+        // Positions count characters, so an accented name is not cut short.
+        entry.individual_name = Cow::Borrowed("0001AÉÉÉÉÉÉÉÉÉÉÉÉÉÉÉ");
+        assert_eq!(entry.as_ctx().receiving_company_name, "AÉÉÉÉÉÉÉÉÉÉÉÉÉÉÉ");
+    }
+}
@@ -0,0 +1,909 @@
+//! Calendar helpers for NACHA's six-digit `YYMMDD` dates.
+//!
+//! Two-digit years are placed in a 100-year window chosen by a
+//! [`YearPivot`], by default 1969-2068. All date parsing lives here; each
+//! date library gets a thin [`CalendarDate`] adapter so they cannot drift
+//! apart.
+
+use crate::records::{BatchHeader, FileHeader};
+use crate::{AchError, BatchBuilder};
+
+/// A date type the typed date accessors can produce and the builders accept.
+///
+/// Implemented for [`CivilDate`], for `time::Date` with the `time` feature,
+/// and for `chrono::NaiveDate` with the `chrono` feature. Implement it for
+/// your own type to use the accessors without either library.
+pub trait CalendarDate: Sized {
+    /// Build a date from a year, a month (1-12), and a day (1-31), returning
+    /// `None` when they do not form a real date.
+    fn from_ymd(year: i32, month: u32, day: u32) -> Option<Self>;
+
+    /// The year, month (1-12), and day (1-31) of this date.
+    fn to_ymd(&self) -> (i32, u32, u32);
+}
+
+/// A date-and-time type built from a [`CalendarDate`] and an hour and minute.
+///
+/// Implemented for `time::PrimitiveDateTime` with the `time` feature and
+/// `chrono::NaiveDateTime` with the `chrono` feature.
+pub trait CalendarDateTime: Sized {
+    /// The date half of this type.
+    type Date: CalendarDate;
+
+    /// Combine a date with an hour (0-23) and minute (0-59), returning
+    /// `None` when they are out of range.
+    fn from_date_hm(date: Self::Date, hour: u32, minute: u32) -> Option<Self>;
+}
+
+/// How a two-digit year is placed in a century.
+///
+/// Either pivot picks a window of 100 consecutive years, and `YY` is read as
+/// the one year in it ending in those digits.
+///
+/// ```
+/// use rs_ach::YearPivot;
+///
+/// let pivot = YearPivot::Fixed(1970);
+/// assert_eq!((pivot.resolve(70), pivot.resolve(69)), (1970, 2069));
+///
+/// let pivot = YearPivot::Sliding {
+///     years_ahead: 20,
+///     reference_year: Some(2026),
+/// };
+/// assert_eq!((pivot.resolve(46), pivot.resolve(47)), (2046, 1947));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum YearPivot {
+    /// The window starts at this year: `Fixed(1970)` reads `70` as 1970
+    /// and `69` as 2069. `Fixed(2000)` reads every year as 20YY.
+    Fixed(i32),
+
+    /// The window ends `years_ahead` years after `reference_year`, and
+    /// moves with it.
+    Sliding {
+        /// Years after the reference year the window reaches
+        years_ahead: u32,
+
+        /// Year the window is relative to; `None` for the current year, or
+        /// the default window where there is no clock to read it from
+        reference_year: Option<i32>,
+    },
+}
+
+impl Default for YearPivot {
+    /// `Fixed(1969)`: `00`-`68` are 2000-2068 and `69`-`99` are 1969-1999,
+    /// the same every year, so a file reads the same whenever it is
+    /// parsed. Use [`YearPivot::sliding`] for a window that moves.
+    fn default() -> Self {
+        YearPivot::Fixed(1969)
+    }
+}
+
+impl YearPivot {
+    /// A window ending `years_ahead` years after the current year.
+    pub fn sliding(years_ahead: u32) -> Self {
+        YearPivot::Sliding {
+            years_ahead,
+            reference_year: None,
+        }
+    }
+
+    /// First year of the window.
+    pub fn first_year(self) -> i32 {
+        match self {
+            YearPivot::Fixed(first) => first,
+            YearPivot::Sliding {
+                years_ahead,
+                reference_year,
+            } => match reference_year.or_else(current_year) {
+                Some(reference) => reference.saturating_add_unsigned(years_ahead) - 99,
+                None => YearPivot::default().first_year(),
+            },
+        }
+    }
+
+    /// The four-digit year for two digits `yy` (0-99).
+    pub fn resolve(self, yy: u32) -> i32 {
+        let first = self.first_year();
+        first + (yy as i32 - first).rem_euclid(100)
+    }
+}
+
+/// The current UTC year, or `None` without a system clock
+/// (`wasm32-unknown-unknown`) or before 1970.
+fn current_year() -> Option<i32> {
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    if let Ok(elapsed) = std::time::UNIX_EPOCH.elapsed() {
+        let days = elapsed.as_secs() / 86_400;
+        return Some(CivilDate::from_days_since_epoch(days as i32).year);
+    }
+    None
+}
+
+/// A validated Gregorian calendar date with no time zone, used when no date
+/// library is enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct CivilDate {
+    pub(crate) year: i32,
+    pub(crate) month: u32,
+    pub(crate) day: u32,
+}
+
+impl CivilDate {
+    /// Build a date, returning `None` when the parts do not form a real
+    /// date.
+    pub fn new(year: i32, month: u32, day: u32) -> Option<CivilDate> {
+        if day == 0 || day > days_in_month(year, month)? {
+            return None;
+        }
+        Some(CivilDate { year, month, day })
+    }
+
+    /// The year.
+    pub fn year(self) -> i32 {
+        self.year
+    }
+
+    /// The month, 1 through 12.
+    pub fn month(self) -> u32 {
+        self.month
+    }
+
+    /// The day of the month, starting at 1.
+    pub fn day(self) -> u32 {
+        self.day
+    }
+
+    /// Convert any [`CalendarDate`].
+    pub(crate) fn of<D: CalendarDate>(date: &D) -> CivilDate {
+        let (year, month, day) = date.to_ymd();
+        CivilDate { year, month, day }
+    }
+
+    /// Parse a `YYMMDD` field with the default [`YearPivot`], returning
+    /// `None` when it is blank or not a real date.
+    pub(crate) fn parse(yymmdd: &str) -> Option<CivilDate> {
+        CivilDate::parse_with(yymmdd, YearPivot::default())
+    }
+
+    /// Parse a `YYMMDD` field, placing the year with `pivot`.
+    pub(crate) fn parse_with(yymmdd: &str, pivot: YearPivot) -> Option<CivilDate> {
+        if yymmdd.len() != 6 || !yymmdd.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let year = pivot.resolve(yymmdd[0..2].parse().ok()?);
+        let month = yymmdd[2..4].parse::<u32>().ok()?;
+        let day = yymmdd[4..6].parse::<u32>().ok()?;
+
+        CivilDate::new(year, month, day)
+    }
+
+    /// Day of the year, 1 for January 1st.
+    fn ordinal(self) -> u32 {
+        (1..self.month)
+            .filter_map(|m| days_in_month(self.year, m))
+            .sum::<u32>()
+            + self.day
+    }
+
+    /// The date with the given day of the year, if the year has that many.
+    fn from_ordinal(year: i32, ordinal: u32) -> Option<CivilDate> {
+        let mut day = ordinal;
+        for month in 1..=12 {
+            let length = days_in_month(year, month)?;
+            if (1..=length).contains(&day) {
+                return Some(CivilDate { year, month, day });
+            }
+            day = day.checked_sub(length)?;
+        }
+        None
+    }
+
+    /// Resolve a three-digit Julian day-of-year (the batch header's
+    /// settlement date) against this date, choosing whichever of the
+    /// previous, same, or next year lands closest.
+    pub(crate) fn resolve_julian(self, julian: &str) -> Option<CivilDate> {
+        if julian.len() != 3 || !julian.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let ordinal: u32 = julian.parse().ok()?;
+        let anchor = i64::from(self.year) * 366 + i64::from(self.ordinal());
+        [self.year - 1, self.year, self.year + 1]
+            .into_iter()
+            .filter_map(|year| CivilDate::from_ordinal(year, ordinal))
+            .min_by_key(|d| (i64::from(d.year) * 366 + i64::from(d.ordinal()) - anchor).abs())
+    }
+
+    /// `YYMMDD`, keeping only the last two digits of the year.
+    pub(crate) fn to_yymmdd(self) -> String {
+        format!(
+            "{:02}{:02}{:02}",
+            self.year.rem_euclid(100),
+            self.month,
+            self.day
+        )
+    }
+
+    pub(crate) fn to_date<D: CalendarDate>(self) -> Option<D> {
+        D::from_ymd(self.year, self.month, self.day)
+    }
+
+    /// Days since 1970-01-01.
+    pub(crate) fn days_since_epoch(self) -> i32 {
+        // Days from civil, shifting the year to start in March.
+        let y = if self.month <= 2 {
+            self.year - 1
+        } else {
+            self.year
+        };
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let mp = (self.month as i32 + 9) % 12;
+        let doy = (153 * mp + 2) / 5 + self.day as i32 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    /// The date `days` days after 1970-01-01.
+    pub(crate) fn from_days_since_epoch(days: i32) -> CivilDate {
+        // Civil from days, the inverse of `days_since_epoch`.
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z - era * 146_097;
+        let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = yoe + era * 400 + i32::from(month <= 2);
+        CivilDate { year, month, day }
+    }
+
+    /// The date `days` days later (or earlier, if negative).
+    pub(crate) fn add_days(self, days: i32) -> CivilDate {
+        CivilDate::from_days_since_epoch(self.days_since_epoch() + days)
+    }
+
+    /// Day of the week, 0 for Monday through 6 for Sunday.
+    pub(crate) fn weekday(self) -> u32 {
+        // 1970-01-01 was a Thursday.
+        (self.days_since_epoch() + 3).rem_euclid(7) as u32
+    }
+
+    /// `YYYY-MM-DD`.
+    pub(crate) fn to_iso(self) -> String {
+        format!("{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+fn days_in_month(year: i32, month: u32) -> Option<u32> {
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => Some(31),
+        4 | 6 | 9 | 11 => Some(30),
+        2 if leap => Some(29),
+        2 => Some(28),
+        _ => None,
+    }
+}
+
+/// Parse an `HHMM` field into an hour and minute.
+pub(crate) fn parse_hhmm(hhmm: &str) -> Option<(u32, u32)> {
+    if hhmm.len() != 4 || !hhmm.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let hour = hhmm[0..2].parse().ok()?;
+    let minute = hhmm[2..4].parse().ok()?;
+    (hour < 24 && minute < 60).then_some((hour, minute))
+}
+
+/// The field `value` read with `read`: `None` when it is blank, and
+/// [`AchError::InvalidDate`] when `read` cannot read it.
+pub(crate) fn read_field<T>(
+    field: &'static str,
+    value: &str,
+    read: impl FnOnce(&str) -> Option<T>,
+) -> Result<Option<T>, AchError> {
+    if value.trim().is_empty() {
+        return Ok(None);
+    }
+    match read(value) {
+        Some(read) => Ok(Some(read)),
+        None => Err(AchError::InvalidDate {
+            field,
+            value: value.to_string(),
+            line: None,
+        }),
+    }
+}
+
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+/// Read a company descriptive date such as `140903` (YYMMDD), `090314`
+/// (MMDDYY), `0903` (MMDD), or `SEP 03`, choosing the reading nearest
+/// `near` when the year is missing or the format is ambiguous, and placing
+/// two-digit years with `pivot`. The last word is used, so `PAY 0601` reads
+/// as June 1. Returns `None` for values such as `PAYROLL`.
+pub(crate) fn descriptive_date(
+    value: &str,
+    near: CivilDate,
+    pivot: YearPivot,
+) -> Option<CivilDate> {
+    let value = value.trim().to_ascii_uppercase();
+    let (month, day) = if let Some(month) = MONTHS.iter().position(|m| value.starts_with(m)) {
+        // `SEP 03` or `SEP3`.
+        let day = value[3..].trim().parse::<u32>().ok()?;
+        (month as u32 + 1, day)
+    } else {
+        let word = value.split_whitespace().last()?;
+        if !word.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let number = |range: std::ops::Range<usize>| word[range].parse::<u32>().ok();
+        match word.len() {
+            6 => {
+                let yymmdd = CivilDate::parse_with(word, pivot);
+                let mmddyy =
+                    CivilDate::new(pivot.resolve(number(4..6)?), number(0..2)?, number(2..4)?);
+                return nearest(yymmdd.into_iter().chain(mmddyy), near);
+            }
+            4 => (number(0..2)?, number(2..4)?),
+            _ => return None,
+        }
+    };
+    let year = near.year();
+    nearest(
+        (year - 1..=year + 1).filter_map(|y| CivilDate::new(y, month, day)),
+        near,
+    )
+}
+
+/// The candidate closest to `near`.
+fn nearest(candidates: impl IntoIterator<Item = CivilDate>, near: CivilDate) -> Option<CivilDate> {
+    candidates
+        .into_iter()
+        .min_by_key(|d| (d.days_since_epoch() - near.days_since_epoch()).unsigned_abs())
+}
+
+impl FileHeader<'_> {
+    /// The file creation date, or `None` when it is blank or invalid.
+    pub fn creation_date<D: CalendarDate>(&self) -> Option<D> {
+        self.creation_date_with(YearPivot::default())
+    }
+
+    /// Like [`creation_date`](Self::creation_date), placing the year with
+    /// `pivot`.
+    pub fn creation_date_with<D: CalendarDate>(&self, pivot: YearPivot) -> Option<D> {
+        CivilDate::parse_with(&self.file_creation_date, pivot)?.to_date()
+    }
+
+    /// The file creation date and time, or `None` when either is blank or
+    /// invalid.
+    pub fn creation_datetime<T: CalendarDateTime>(&self) -> Option<T> {
+        self.creation_datetime_with(YearPivot::default())
+    }
+
+    /// Like [`creation_datetime`](Self::creation_datetime), placing the
+    /// year with `pivot`.
+    pub fn creation_datetime_with<T: CalendarDateTime>(&self, pivot: YearPivot) -> Option<T> {
+        let (hour, minute) = parse_hhmm(&self.file_creation_time)?;
+        T::from_date_hm(self.creation_date_with(pivot)?, hour, minute)
+    }
+
+    /// The file creation date and time, `None` when either is blank, or
+    /// [`AchError::InvalidDate`] naming the field that is not a `YYMMDD`
+    /// date or `HHMM` time. The year is placed with the default
+    /// [`YearPivot`].
+    pub fn try_creation_datetime<T: CalendarDateTime>(&self) -> Result<Option<T>, AchError> {
+        self.try_creation_datetime_with(YearPivot::default())
+    }
+
+    /// Like [`try_creation_datetime`](Self::try_creation_datetime), placing
+    /// the year with `pivot`.
+    pub fn try_creation_datetime_with<T: CalendarDateTime>(
+        &self,
+        pivot: YearPivot,
+    ) -> Result<Option<T>, AchError> {
+        let date = read_field("file_creation_date", &self.file_creation_date, |value| {
+            CivilDate::parse_with(value, pivot)?.to_date()
+        })?;
+        let time = read_field("file_creation_time", &self.file_creation_time, parse_hhmm)?;
+        let (Some(date), Some((hour, minute))) = (date, time) else {
+            return Ok(None);
+        };
+        Ok(T::from_date_hm(date, hour, minute))
+    }
+}
+
+impl BatchHeader<'_> {
+    /// The effective entry date, or `None` when it is blank or invalid.
+    pub fn effective_date<D: CalendarDate>(&self) -> Option<D> {
+        self.effective_date_with(YearPivot::default())
+    }
+
+    /// Like [`effective_date`](Self::effective_date), placing the year with
+    /// `pivot`.
+    pub fn effective_date_with<D: CalendarDate>(&self, pivot: YearPivot) -> Option<D> {
+        CivilDate::parse_with(&self.effective_entry_date, pivot)?.to_date()
+    }
+
+    /// The effective entry date, `None` when it is blank, as it may be in a
+    /// return, or [`AchError::InvalidDate`] when it is not a `YYMMDD` date.
+    ///
+    /// The year is placed with the default [`YearPivot`], `Fixed(1969)`,
+    /// which reads `00`-`68` as 2000-2068 and `69`-`99` as 1969-1999.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchError, AchFile, CivilDate, YearPivot};
+    ///
+    /// let text = fixtures::sample_ppd_file(1);
+    /// let mut file = AchFile::parse(&text).unwrap();
+    /// let header = &mut file.batches[0].header;
+    /// let pivot = YearPivot::Fixed(1969);
+    ///
+    /// header.effective_entry_date = "681231".into();
+    /// let date = header.try_effective_date_with(pivot).unwrap();
+    /// assert_eq!(date, CivilDate::new(2068, 12, 31));
+    ///
+    /// header.effective_entry_date = "690101".into();
+    /// let date = header.try_effective_date_with(pivot).unwrap();
+    /// assert_eq!(date, CivilDate::new(1969, 1, 1));
+    ///
+    /// header.effective_entry_date = "      ".into();
+    /// assert_eq!(header.try_effective_date::<CivilDate>().unwrap(), None);
+    ///
+    /// header.effective_entry_date = "141332".into();
+    /// let err = header.try_effective_date::<CivilDate>().unwrap_err();
+    /// assert!(matches!(err, AchError::InvalidDate { field: "effective_entry_date", .. }));
+    /// ```
+    pub fn try_effective_date<D: CalendarDate>(&self) -> Result<Option<D>, AchError> {
+        self.try_effective_date_with(YearPivot::default())
+    }
+
+    /// Like [`try_effective_date`](Self::try_effective_date), placing the
+    /// year with `pivot`.
+    pub fn try_effective_date_with<D: CalendarDate>(
+        &self,
+        pivot: YearPivot,
+    ) -> Result<Option<D>, AchError> {
+        let read = |value: &str| CivilDate::parse_with(value, pivot)?.to_date();
+        read_field("effective_entry_date", &self.effective_entry_date, read)
+    }
+
+    /// The company descriptive date read as a date, or `None` when it does
+    /// not read as one or the effective entry date is not a date.
+    ///
+    /// The field is free text. `140903` (YYMMDD), `090314` (MMDDYY), `0903`
+    /// (MMDD), and `SEP 03` are read, taking the last word, as in
+    /// `PAY 0903`, and choosing the reading nearest the effective entry
+    /// date when the year is missing or the format is ambiguous.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile, CivilDate};
+    ///
+    /// let text = fixtures::sample_ppd_file(1);
+    /// let mut file = AchFile::parse(&text).unwrap();
+    /// let header = &mut file.batches[0].header;
+    /// header.company_descriptive_date = "SEP 01".into();
+    /// assert_eq!(header.descriptive_date(), CivilDate::new(2014, 9, 1));
+    /// header.company_descriptive_date = "PAY   ".into();
+    /// assert_eq!(header.descriptive_date::<CivilDate>(), None);
+    /// ```
+    pub fn descriptive_date<D: CalendarDate>(&self) -> Option<D> {
+        self.descriptive_date_with(YearPivot::default())
+    }
+
+    /// Like [`descriptive_date`](Self::descriptive_date), placing two-digit
+    /// years with `pivot`.
+    pub fn descriptive_date_with<D: CalendarDate>(&self, pivot: YearPivot) -> Option<D> {
+        let near = CivilDate::parse_with(&self.effective_entry_date, pivot)?;
+        descriptive_date(&self.company_descriptive_date, near, pivot)?.to_date()
+    }
+
+    /// The operator-stamped Julian settlement date as a calendar date.
+    ///
+    /// The day-of-year is placed in the year that puts it closest to the
+    /// effective entry date, so a December batch settling on day `002`
+    /// resolves to January of the next year. `None` when either field is
+    /// blank or invalid.
+    pub fn settlement_date<D: CalendarDate>(&self) -> Option<D> {
+        self.settlement_date_with(YearPivot::default())
+    }
+
+    /// Like [`settlement_date`](Self::settlement_date), placing the year of
+    /// the effective entry date with `pivot`. The settlement date may then
+    /// fall just outside the pivot's window.
+    pub fn settlement_date_with<D: CalendarDate>(&self, pivot: YearPivot) -> Option<D> {
+        CivilDate::parse_with(&self.effective_entry_date, pivot)?
+            .resolve_julian(&self.settlement_date)?
+            .to_date()
+    }
+}
+
+impl BatchBuilder {
+    /// Set the effective entry date from a typed date.
+    ///
+    /// Only the last two digits of the year are kept.
+    pub fn effective_date<D: CalendarDate>(&mut self, date: D) -> &mut Self {
+        let yymmdd = CivilDate::of(&date).to_yymmdd();
+        self.effective_entry_date(&yymmdd)
+    }
+}
+
+impl CalendarDate for CivilDate {
+    fn from_ymd(year: i32, month: u32, day: u32) -> Option<Self> {
+        CivilDate::new(year, month, day)
+    }
+
+    fn to_ymd(&self) -> (i32, u32, u32) {
+        (self.year, self.month, self.day)
+    }
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_adapter {
+    use super::{CalendarDate, CalendarDateTime};
+    use chrono::Datelike;
+
+    impl CalendarDate for chrono::NaiveDate {
+        fn from_ymd(year: i32, month: u32, day: u32) -> Option<Self> {
+            chrono::NaiveDate::from_ymd_opt(year, month, day)
+        }
+
+        fn to_ymd(&self) -> (i32, u32, u32) {
+            (self.year(), self.month(), self.day())
+        }
+    }
+
+    impl CalendarDateTime for chrono::NaiveDateTime {
+        type Date = chrono::NaiveDate;
+
+        fn from_date_hm(date: chrono::NaiveDate, hour: u32, minute: u32) -> Option<Self> {
+            date.and_hms_opt(hour, minute, 0)
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+mod time_adapter {
+    use super::{CalendarDate, CalendarDateTime};
+
+    impl CalendarDate for time::Date {
+        fn from_ymd(year: i32, month: u32, day: u32) -> Option<Self> {
+            let month = time::Month::try_from(u8::try_from(month).ok()?).ok()?;
+            time::Date::from_calendar_date(year, month, u8::try_from(day).ok()?).ok()
+        }
+
+        fn to_ymd(&self) -> (i32, u32, u32) {
+            (
+                self.year(),
+                u8::from(self.month()).into(),
+                self.day().into(),
+            )
+        }
+    }
+
+    impl CalendarDateTime for time::PrimitiveDateTime {
+        type Date = time::Date;
+
+        fn from_date_hm(date: time::Date, hour: u32, minute: u32) -> Option<Self> {
+            let time =
+                time::Time::from_hms(u8::try_from(hour).ok()?, u8::try_from(minute).ok()?, 0)
+                    .ok()?;
+            Some(time::PrimitiveDateTime::new(date, time))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_year_pivot() {
+        let pivot = YearPivot::Sliding {
+            years_ahead: 20,
+            reference_year: Some(2026),
+        };
+        assert_eq!(pivot.first_year(), 1947);
+        assert_eq!(CivilDate::parse_with("461231", pivot).unwrap().year, 2046);
+        assert_eq!(CivilDate::parse_with("470101", pivot).unwrap().year, 1947);
+        assert_eq!(CivilDate::parse_with("991231", pivot).unwrap().year, 1999);
+
+        let fixed = YearPivot::Fixed(2000);
+        assert_eq!((fixed.resolve(0), fixed.resolve(99)), (2000, 2099));
+        assert_eq!(YearPivot::Fixed(1950).resolve(49), 2049);
+        assert_eq!(YearPivot::Fixed(1950).resolve(50), 1950);
+
+        // 1900 was not a leap year; 2000 was.
+        assert_eq!(
+            CivilDate::parse_with("000229", YearPivot::Fixed(1900)),
+            None
+        );
+        assert!(CivilDate::parse_with("000229", YearPivot::Fixed(1901)).is_some());
+
+        // The default does not move with the clock.
+        assert_eq!(YearPivot::default(), YearPivot::Fixed(1969));
+        assert_eq!(YearPivot::default().resolve(68), 2068);
+        assert_eq!(YearPivot::default().resolve(69), 1969);
+
+        let current = current_year().unwrap();
+        let sliding = YearPivot::sliding(20);
+        assert_eq!(sliding.first_year(), current - 79);
+        assert_eq!(sliding.resolve((current % 100) as u32), current);
+    }
+
+    #[test]
+    fn test_settlement_date_across_pivot() {
+        let text = crate::fixtures::sample_ppd_file(1);
+        let file = crate::AchFile::parse(&text).unwrap();
+        let mut header = file.batches[0].header.clone();
+        header.effective_entry_date = "991231".into();
+        header.settlement_date = "001".into();
+
+        // Read in the 1990s, the new year settles in 2000...
+        let late = YearPivot::Fixed(1950);
+        assert_eq!(
+            header.effective_date_with(late),
+            CivilDate::new(1999, 12, 31)
+        );
+        assert_eq!(
+            header.settlement_date_with(late),
+            CivilDate::new(2000, 1, 1)
+        );
+
+        // ...and just outside a window ending in 1999.
+        let window = YearPivot::Fixed(1900);
+        assert_eq!(
+            header.settlement_date_with::<CivilDate>(window),
+            CivilDate::new(2000, 1, 1)
+        );
+
+        // Read in the 2090s, it settles in 2100.
+        let early = YearPivot::Fixed(2000);
+        assert_eq!(
+            header.settlement_date_with(early),
+            CivilDate::new(2100, 1, 1)
+        );
+
+        // A January effective date settling on day 365 falls back a year.
+        header.effective_entry_date = "000102".into();
+        header.settlement_date = "365".into();
+        assert_eq!(
+            header.settlement_date_with(late),
+            CivilDate::new(1999, 12, 31)
+        );
+        assert_eq!(
+            header.settlement_date_with(YearPivot::Fixed(2000)),
+            CivilDate::new(1999, 12, 31)
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            CivilDate::parse("140903"),
+            Some(CivilDate {
+                year: 2014,
+                month: 9,
+                day: 3
+            })
+        );
+        assert!(CivilDate::parse("240229").is_some());
+        assert_eq!(CivilDate::parse("230229"), None);
+        assert_eq!(CivilDate::parse("141301"), None);
+        assert_eq!(CivilDate::parse("      "), None);
+    }
+
+    #[test]
+    fn test_days_since_epoch_and_iso() {
+        let date = CivilDate::parse("000101").unwrap();
+        assert_eq!(date.days_since_epoch(), 10_957);
+        assert_eq!(date.to_iso(), "2000-01-01");
+        assert_eq!(
+            CivilDate::parse_with("700101", YearPivot::Fixed(2000))
+                .unwrap()
+                .days_since_epoch(),
+            36_525
+        );
+        assert_eq!(
+            CivilDate::parse_with("700101", YearPivot::Fixed(1970))
+                .unwrap()
+                .days_since_epoch(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_days_since_epoch_round_trip() {
+        for days in [-1, 0, 10_957, 19_782, 47_481] {
+            let date = CivilDate::from_days_since_epoch(days);
+            assert_eq!(date.days_since_epoch(), days);
+        }
+        let date = CivilDate::new(2024, 2, 28).unwrap();
+        assert_eq!(date.add_days(1), CivilDate::new(2024, 2, 29).unwrap());
+        assert_eq!(date.add_days(2), CivilDate::new(2024, 3, 1).unwrap());
+        assert_eq!(date.add_days(-59), CivilDate::new(2023, 12, 31).unwrap());
+        assert_eq!(date.weekday(), 2);
+    }
+
+    #[test]
+    fn test_ordinal_round_trip() {
+        let date = CivilDate::parse("241231").unwrap();
+        assert_eq!(date.ordinal(), 366);
+        assert_eq!(CivilDate::from_ordinal(2024, 366), Some(date));
+        assert_eq!(CivilDate::from_ordinal(2023, 366), None);
+        assert_eq!(CivilDate::from_ordinal(2023, 0), None);
+    }
+
+    #[test]
+    fn test_resolve_julian() {
+        let effective = CivilDate::parse("241230").unwrap();
+        assert_eq!(effective.resolve_julian("365"), CivilDate::parse("241230"));
+        assert_eq!(effective.resolve_julian("002"), CivilDate::parse("250102"));
+        assert_eq!(
+            CivilDate::parse("250102").unwrap().resolve_julian("366"),
+            CivilDate::parse("241231")
+        );
+        assert_eq!(effective.resolve_julian("   "), None);
+        assert_eq!(effective.resolve_julian("400"), None);
+    }
+
+    #[test]
+    fn test_descriptive_date() {
+        let near = CivilDate::new(2025, 3, 14).unwrap();
+        let read = |value| descriptive_date(value, near, YearPivot::Fixed(2000));
+        assert_eq!(read("250601"), CivilDate::new(2025, 6, 1));
+        assert_eq!(read("060125"), CivilDate::new(2025, 6, 1));
+        assert_eq!(read("0601"), CivilDate::new(2025, 6, 1));
+        assert_eq!(read("PAY 0601"), CivilDate::new(2025, 6, 1));
+        assert_eq!(read("jun 01"), CivilDate::new(2025, 6, 1));
+        assert_eq!(read("DEC 28"), CivilDate::new(2024, 12, 28));
+        assert_eq!(read("PAYROLL"), None);
+        assert_eq!(read("      "), None);
+        assert_eq!(read("1399"), None);
+
+        // Both six-digit readings follow the pivot.
+        let near = CivilDate::new(1999, 12, 30).unwrap();
+        let pivot = YearPivot::Fixed(1950);
+        assert_eq!(
+            descriptive_date("991231", near, pivot),
+            CivilDate::new(1999, 12, 31)
+        );
+        assert_eq!(
+            descriptive_date("123199", near, pivot),
+            CivilDate::new(1999, 12, 31)
+        );
+    }
+
+    #[test]
+    fn test_read_field() {
+        #[derive(Debug, PartialEq)]
+        struct Stamp(CivilDate, u32, u32);
+
+        impl CalendarDateTime for Stamp {
+            type Date = CivilDate;
+
+            fn from_date_hm(date: CivilDate, hour: u32, minute: u32) -> Option<Self> {
+                Some(Stamp(date, hour, minute))
+            }
+        }
+
+        let text = crate::fixtures::sample_ppd_file(1);
+        let mut header = crate::AchFile::parse(&text)
+            .unwrap()
+            .file_header
+            .into_owned();
+        let pivot = YearPivot::Fixed(1969);
+        let read = |header: &FileHeader<'_>| {
+            header
+                .try_creation_datetime_with(pivot)
+                .map_err(|err| err.to_string())
+        };
+        let stamp = |y, m, d| Ok(Some(Stamp(CivilDate::new(y, m, d).unwrap(), 1, 23)));
+        assert_eq!(read(&header), stamp(2014, 9, 2));
+
+        // The window of a fixed pivot starts at its year.
+        header.file_creation_date = "690228".into();
+        assert_eq!(read(&header), stamp(1969, 2, 28));
+        header.file_creation_date = "680229".into();
+        assert_eq!(read(&header), stamp(2068, 2, 29));
+        header.file_creation_date = "690229".into();
+        assert!(read(&header).is_err());
+        header.file_creation_date = "680229".into();
+
+        header.file_creation_time = "    ".into();
+        assert_eq!(read(&header), Ok(None));
+        header.file_creation_time = "2460".into();
+        assert_eq!(
+            read(&header),
+            Err("Invalid date field 'file_creation_time': \"2460\"".to_string())
+        );
+        header.file_creation_date = "1409 2".into();
+        assert_eq!(
+            read(&header),
+            Err("Invalid date field 'file_creation_date': \"1409 2\"".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_hhmm() {
+        assert_eq!(parse_hhmm("0123"), Some((1, 23)));
+        assert_eq!(parse_hhmm("2400"), None);
+        assert_eq!(parse_hhmm("    "), None);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono_accessors() {
+        let date = |y, m, d| chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+        let text = crate::fixtures::sample_ppd_file(1);
+        let file = crate::AchFile::parse(&text).unwrap();
+        assert_eq!(
+            file.file_header.try_creation_datetime().unwrap(),
+            date(2014, 9, 2).and_hms_opt(1, 23, 0)
+        );
+        let mut header = file.batches[0].header.clone();
+        assert_eq!(header.try_effective_date().unwrap(), Some(date(2014, 9, 3)));
+        header.effective_entry_date = "      ".into();
+        assert_eq!(
+            header.try_effective_date::<chrono::NaiveDate>().unwrap(),
+            None
+        );
+        header.effective_entry_date = "14 903".into();
+        assert!(header.try_effective_date::<chrono::NaiveDate>().is_err());
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_time_accessors() {
+        let date = |y, m, d| <time::Date as CalendarDate>::from_ymd(y, m, d).unwrap();
+
+        let text = crate::fixtures::sample_ppd_file(1);
+        let file = crate::AchFile::parse(&text).unwrap();
+        let header = &file.batches[0].header;
+
+        assert_eq!(
+            file.file_header
+                .creation_datetime::<time::PrimitiveDateTime>(),
+            time::PrimitiveDateTime::new(date(2014, 9, 2), time::Time::from_hms(1, 23, 0).unwrap())
+                .into()
+        );
+        assert_eq!(
+            header.effective_date::<time::Date>(),
+            Some(date(2014, 9, 3))
+        );
+        assert_eq!(header.settlement_date::<time::Date>(), None);
+
+        let mut settled = header.clone();
+        settled.settlement_date = "247".into();
+        assert_eq!(
+            settled.settlement_date::<time::Date>(),
+            Some(date(2014, 9, 4))
+        );
+
+        let mut builder = BatchBuilder::new("ACME", "1234567890", "PPD", "PAYROLL", "12345678");
+        builder.effective_date(date(2031, 2, 28));
+        builder
+            .add_entry(crate::SimpleEntry {
+                routing_number: "123456780".to_string(),
+                account_number: "1".to_string(),
+                amount: 1,
+                individual_name: "A".to_string(),
+                identification: None,
+                direction: crate::Direction::Credit,
+            })
+            .unwrap();
+        assert_eq!(
+            builder.build().unwrap().header.effective_entry_date,
+            "310228"
+        );
+    }
+}
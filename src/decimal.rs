@@ -0,0 +1,186 @@
+//! Exact dollar amounts with [`rust_decimal::Decimal`].
+//!
+//! Available with the `decimal` feature. Amounts are stored in cents as
+//! `u64`; these helpers convert to and from two-decimal-place `Decimal`
+//! values without ever going through floating point.
+
+use rust_decimal::Decimal;
+
+use crate::error::AchError;
+use crate::records::{BatchControl, EntryDetail, FileControl};
+use crate::{Batch, BatchBuilder, FileSummary, SimpleEntry, MAX_ENTRY_AMOUNT};
+
+/// Convert cents to dollars with two decimal places.
+pub fn from_cents(cents: u64) -> Decimal {
+    Decimal::from_i128_with_scale(i128::from(cents), 2)
+}
+
+/// Convert a dollar amount to cents.
+///
+/// Fails unless the amount is non-negative, has at most two decimal places,
+/// and fits the 10-digit entry amount field.
+///
+/// ```
+/// use rust_decimal::Decimal;
+/// use rs_ach::decimal::to_cents;
+///
+/// assert_eq!(to_cents(Decimal::new(12345, 2)).unwrap(), 12345);
+/// assert!(to_cents(Decimal::new(12345, 3)).is_err());
+/// ```
+pub fn to_cents(amount: Decimal) -> Result<u64, AchError> {
+    let invalid = |reason: String| AchError::InvalidValue {
+        field: "amount",
+        reason,
+    };
+
+    if amount.is_sign_negative() && !amount.is_zero() {
+        return Err(invalid(format!("{amount} is negative")));
+    }
+    if amount.normalize().scale() > 2 {
+        return Err(invalid(format!("{amount} has more than 2 decimal places")));
+    }
+
+    let mut scaled = amount;
+    scaled.rescale(2);
+    u64::try_from(scaled.mantissa())
+        .ok()
+        .filter(|&cents| cents <= MAX_ENTRY_AMOUNT)
+        .ok_or_else(|| invalid(format!("{amount} exceeds the 10-digit amount field")))
+}
+
+impl EntryDetail<'_> {
+    /// The amount in dollars.
+    pub fn amount_decimal(&self) -> Decimal {
+        from_cents(self.amount)
+    }
+}
+
+impl BatchControl {
+    /// The declared total debit amount in dollars.
+    pub fn total_debit_decimal(&self) -> Decimal {
+        from_cents(self.total_debit_amount)
+    }
+
+    /// The declared total credit amount in dollars.
+    pub fn total_credit_decimal(&self) -> Decimal {
+        from_cents(self.total_credit_amount)
+    }
+}
+
+impl FileControl {
+    /// The declared total debit amount in dollars.
+    pub fn total_debit_decimal(&self) -> Decimal {
+        from_cents(self.total_debit_amount)
+    }
+
+    /// The declared total credit amount in dollars.
+    pub fn total_credit_decimal(&self) -> Decimal {
+        from_cents(self.total_credit_amount)
+    }
+}
+
+impl Batch<'_> {
+    /// Sum of the batch's entry amounts in dollars, computed from the
+    /// entries rather than the control record.
+    pub fn entry_total_decimal(&self) -> Decimal {
+        self.entries.iter().map(EntryDetail::amount_decimal).sum()
+    }
+}
+
+impl FileSummary {
+    /// Total debits in dollars.
+    pub fn total_debit_decimal(&self) -> Decimal {
+        from_cents(self.total_debit_amount)
+    }
+
+    /// Total credits in dollars.
+    pub fn total_credit_decimal(&self) -> Decimal {
+        from_cents(self.total_credit_amount)
+    }
+}
+
+impl BatchBuilder {
+    /// Add an entry whose amount is given in dollars.
+    ///
+    /// `amount` replaces `entry.amount` after validation by [`to_cents`];
+    /// otherwise this behaves exactly like [`BatchBuilder::add_entry`].
+    pub fn add_entry_decimal(
+        &mut self,
+        mut entry: SimpleEntry,
+        amount: Decimal,
+    ) -> Result<(), AchError> {
+        entry.amount = to_cents(amount)?;
+        self.add_entry(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, AchFile, Direction};
+
+    #[test]
+    fn test_to_cents() {
+        assert_eq!(to_cents(Decimal::new(10, 0)).unwrap(), 1_000);
+        assert_eq!(to_cents(Decimal::new(105, 1)).unwrap(), 1_050);
+        assert_eq!(to_cents(Decimal::new(123_400, 4)).unwrap(), 1_234);
+        assert_eq!(to_cents(Decimal::ZERO).unwrap(), 0);
+        assert_eq!(
+            to_cents(Decimal::new(9_999_999_999, 2)).unwrap(),
+            MAX_ENTRY_AMOUNT
+        );
+
+        assert!(to_cents(Decimal::new(-1, 2)).is_err());
+        assert!(to_cents(Decimal::new(1_001, 3)).is_err());
+        assert!(to_cents(Decimal::new(100_000_000, 0)).is_err());
+    }
+
+    #[test]
+    fn test_from_cents() {
+        assert_eq!(from_cents(1_250).to_string(), "12.50");
+        assert_eq!(from_cents(7).to_string(), "0.07");
+    }
+
+    #[test]
+    fn test_decimal_accessors() {
+        let text = fixtures::sample_ppd_file(3);
+        let file = AchFile::parse(&text).unwrap();
+        let batch = &file.batches[0];
+
+        assert_eq!(batch.entries[0].amount_decimal(), Decimal::new(1_250, 2));
+        assert_eq!(batch.control.total_credit_decimal(), Decimal::new(5_000, 2));
+        assert_eq!(batch.control.total_debit_decimal(), Decimal::new(2_500, 2));
+        assert_eq!(batch.entry_total_decimal(), Decimal::new(7_500, 2));
+        assert_eq!(
+            file.file_control.total_credit_decimal(),
+            file.summary().total_credit_decimal()
+        );
+    }
+
+    #[test]
+    fn test_add_entry_decimal() {
+        let mut builder =
+            BatchBuilder::new("YOUR COMPANY", "1234567890", "PPD", "PAYROLL", "12345678");
+        builder.effective_entry_date("140903");
+        let entry = SimpleEntry {
+            routing_number: "123456780".to_string(),
+            account_number: "11232132".to_string(),
+            amount: 0,
+            individual_name: "ALICE WANDERDUST".to_string(),
+            identification: None,
+            direction: Direction::Credit,
+        };
+
+        builder
+            .add_entry_decimal(entry.clone(), Decimal::new(1999, 2))
+            .unwrap();
+        assert!(matches!(
+            builder.add_entry_decimal(entry, Decimal::new(-5, 0)),
+            Err(AchError::InvalidValue {
+                field: "amount",
+                ..
+            })
+        ));
+        assert_eq!(builder.build().unwrap().control.total_credit_amount, 1_999);
+    }
+}
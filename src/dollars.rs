@@ -0,0 +1,94 @@
+//! Amounts as dollar text, such as `150.00`.
+//!
+//! Amounts are stored in cents as `u64`; these render them exactly, with
+//! integer arithmetic rather than floating point. For `Decimal` values see
+//! the `decimal` feature.
+
+use crate::records::{BatchControl, EntryDetail, FileControl};
+
+/// Render cents as dollars with two decimal places and no separators, e.g.
+/// `1234.56`.
+pub(crate) fn dollars(cents: u64) -> String {
+    format!("{}.{:02}", cents / 100, cents % 100)
+}
+
+impl EntryDetail<'_> {
+    /// The amount in dollars, e.g. `150.00` for 15,000 cents.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile};
+    ///
+    /// let text = fixtures::sample_ppd_file(1);
+    /// let file = AchFile::parse(&text).unwrap();
+    /// let entry = &file.batches[0].entries[0];
+    /// assert_eq!(entry.amount, 1_250);
+    /// assert_eq!(entry.amount_dollars(), "12.50");
+    /// ```
+    pub fn amount_dollars(&self) -> String {
+        dollars(self.amount)
+    }
+}
+
+impl BatchControl {
+    /// The declared total debit amount in dollars.
+    pub fn total_debit_dollars(&self) -> String {
+        dollars(self.total_debit_amount)
+    }
+
+    /// The declared total credit amount in dollars.
+    pub fn total_credit_dollars(&self) -> String {
+        dollars(self.total_credit_amount)
+    }
+}
+
+impl FileControl {
+    /// The declared total debit amount in dollars.
+    pub fn total_debit_dollars(&self) -> String {
+        dollars(self.total_debit_amount)
+    }
+
+    /// The declared total credit amount in dollars.
+    pub fn total_credit_dollars(&self) -> String {
+        dollars(self.total_credit_amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, AchFile, MAX_ENTRY_AMOUNT};
+
+    #[test]
+    fn test_dollars() {
+        assert_eq!(dollars(0), "0.00");
+        assert_eq!(dollars(7), "0.07");
+        assert_eq!(dollars(99), "0.99");
+        assert_eq!(dollars(100), "1.00");
+        assert_eq!(dollars(15_000), "150.00");
+        assert_eq!(dollars(MAX_ENTRY_AMOUNT), "99999999.99");
+        // Control totals have two more digits.
+        assert_eq!(dollars(999_999_999_999), "9999999999.99");
+    }
+
+    #[test]
+    fn test_control_totals() {
+        let text = fixtures::sample_multi_company_file();
+        let file = AchFile::parse(&text).unwrap();
+        let control = &file.batches[0].control;
+        assert_eq!(
+            (
+                control.total_debit_dollars(),
+                control.total_credit_dollars()
+            ),
+            ("0.00".to_string(), "4300.00".to_string())
+        );
+        let control = &file.file_control;
+        assert_eq!(
+            (
+                control.total_debit_dollars(),
+                control.total_credit_dollars()
+            ),
+            ("260.00".to_string(), "13300.00".to_string())
+        );
+    }
+}
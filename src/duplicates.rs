@@ -0,0 +1,297 @@
+//! Detection of likely duplicate payments within and across files.
+//!
+//! [`detect_duplicates`] groups entries that pay the same account the same
+//! amount from the same company, with effective dates close together. Each
+//! reported entry carries its file index, batch, trace number, and line
+//! number so a reviewer can find it quickly.
+
+use std::collections::HashMap;
+
+use crate::controls::{is_credit, is_debit};
+use crate::dates::CivilDate;
+use crate::AchFile;
+
+/// What counts as a duplicate for [`detect_duplicates`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct DuplicatePolicy {
+    /// Largest number of calendar days between the effective dates of
+    /// neighbouring entries in a group
+    pub date_window_days: u32,
+
+    /// Entries below this amount, in cents, are never reported, so small
+    /// recurring charges do not flood the results
+    pub amount_floor: u64,
+
+    /// Also require identical individual identification numbers
+    pub require_same_identification: bool,
+}
+
+impl Default for DuplicatePolicy {
+    /// A three-day window, no amount floor, and identification numbers
+    /// ignored.
+    fn default() -> Self {
+        DuplicatePolicy {
+            date_window_days: 3,
+            amount_floor: 0,
+            require_same_identification: false,
+        }
+    }
+}
+
+/// Where one entry of a [`DuplicateGroup`] was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct DuplicateEntry {
+    /// Index of the entry's file in the slice given to [`detect_duplicates`]
+    pub file_index: usize,
+
+    /// Batch number of the entry's batch, trimmed
+    pub batch_number: String,
+
+    /// Trace number of the entry
+    pub trace_number: String,
+
+    /// Line of the entry detail record, counted from 1 for the file header
+    pub line_number: usize,
+
+    /// Effective entry date of the entry's batch, or `None` when blank or
+    /// invalid
+    pub effective_entry_date: Option<CivilDate>,
+
+    /// Individual name, trimmed
+    pub individual_name: String,
+}
+
+/// Entries that look like the same payment made more than once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct DuplicateGroup {
+    /// Receiving routing number, including the check digit
+    pub routing_number: String,
+
+    /// Receiving DFI account number, trimmed
+    pub account_number: String,
+
+    /// Amount in cents
+    pub amount: u64,
+
+    /// Whether the entries are debits rather than credits
+    pub debit: bool,
+
+    /// Company identification of the batches, trimmed
+    pub company_identification: String,
+
+    /// The matching entries, ordered by effective date, then file order
+    pub entries: Vec<DuplicateEntry>,
+}
+
+/// Entries matched on everything but the effective date.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Key {
+    routing_number: String,
+    account_number: String,
+    amount: u64,
+    debit: bool,
+    company_identification: String,
+    identification: Option<String>,
+}
+
+/// Find groups of two or more entries in `files` with the same receiving
+/// routing and account number, amount, direction, and company
+/// identification, whose effective dates are chained within
+/// [`DuplicatePolicy::date_window_days`] of each other.
+///
+/// Zero-amount entries such as prenotes are ignored. Entries without a
+/// readable effective date only match each other. Groups are returned in
+/// order of their first entry in the files.
+pub fn detect_duplicates(files: &[&AchFile<'_>], policy: &DuplicatePolicy) -> Vec<DuplicateGroup> {
+    let mut keys: Vec<Key> = Vec::new();
+    let mut candidates: HashMap<Key, Vec<DuplicateEntry>> = HashMap::new();
+
+    for (file_index, file) in files.iter().enumerate() {
+        // Line 1 is the file header.
+        let mut line_number = 1;
+        for batch in &file.batches {
+            line_number += 1;
+            let header = &batch.header;
+            let effective_entry_date = CivilDate::parse(&header.effective_entry_date);
+            for entry in &batch.entries {
+                line_number += 1;
+                let entry_line = line_number;
+                line_number += entry.addenda.len();
+
+                let debit = is_debit(&entry.transaction_code);
+                if entry.amount == 0
+                    || entry.amount < policy.amount_floor
+                    || (!debit && !is_credit(&entry.transaction_code))
+                {
+                    continue;
+                }
+                let key = Key {
+                    routing_number: format!(
+                        "{}{}",
+                        entry.receiving_dfi_identification.trim(),
+                        entry.check_digit.trim()
+                    ),
+                    account_number: entry.dfi_account_number.trim().to_string(),
+                    amount: entry.amount,
+                    debit,
+                    company_identification: header.company_identification.trim().to_string(),
+                    identification: policy
+                        .require_same_identification
+                        .then(|| entry.individual_identification_number.trim().to_string()),
+                };
+                let found = DuplicateEntry {
+                    file_index,
+                    batch_number: header.batch_number.trim().to_string(),
+                    trace_number: entry.trace_number.to_string(),
+                    line_number: entry_line,
+                    effective_entry_date,
+                    individual_name: entry.individual_name.trim().to_string(),
+                };
+                candidates
+                    .entry(key.clone())
+                    .or_insert_with(|| {
+                        keys.push(key);
+                        Vec::new()
+                    })
+                    .push(found);
+            }
+            line_number += 1;
+        }
+    }
+
+    let mut groups = Vec::new();
+    for key in keys {
+        let mut entries = candidates.remove(&key).unwrap_or_default();
+        if entries.len() < 2 {
+            continue;
+        }
+        // Stable, so entries on the same date stay in file order.
+        entries.sort_by_key(|e| e.effective_entry_date);
+        let mut start = 0;
+        for end in 1..=entries.len() {
+            let split = end == entries.len()
+                || !within_window(
+                    entries[end - 1].effective_entry_date,
+                    entries[end].effective_entry_date,
+                    policy.date_window_days,
+                );
+            if split {
+                if end - start >= 2 {
+                    groups.push(DuplicateGroup {
+                        routing_number: key.routing_number.clone(),
+                        account_number: key.account_number.clone(),
+                        amount: key.amount,
+                        debit: key.debit,
+                        company_identification: key.company_identification.clone(),
+                        entries: entries[start..end].to_vec(),
+                    });
+                }
+                start = end;
+            }
+        }
+    }
+    groups
+}
+
+/// Whether two sorted effective dates are at most `days` apart.
+fn within_window(earlier: Option<CivilDate>, later: Option<CivilDate>, days: u32) -> bool {
+    match (earlier, later) {
+        (Some(earlier), Some(later)) => {
+            (later.days_since_epoch() - earlier.days_since_epoch()) as u32 <= days
+        }
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    fn parse(text: &str) -> AchFile<'_> {
+        AchFile::parse(text).unwrap()
+    }
+
+    #[test]
+    fn test_duplicate_across_files() {
+        let text = fixtures::sample_ppd_file(3);
+        let original = parse(&text);
+        let mut resent = parse(&text);
+        resent.batches[0].header.effective_entry_date = "140905".into();
+
+        let groups = detect_duplicates(&[&original, &resent], &DuplicatePolicy::default());
+        assert_eq!(groups.len(), 3);
+        let group = &groups[1];
+        assert_eq!(group.amount, 2_500);
+        assert!(group.debit);
+        assert_eq!(group.company_identification, "1234567890");
+        assert_eq!(group.entries.len(), 2);
+        assert_eq!(group.entries[0].file_index, 0);
+        assert_eq!(group.entries[1].file_index, 1);
+        assert_eq!(group.entries[1].batch_number, "0000001");
+        // File header, batch header, then entries.
+        assert_eq!(group.entries[1].line_number, 4);
+        assert_eq!(
+            group.entries[1].effective_entry_date,
+            CivilDate::new(2014, 9, 5)
+        );
+
+        // Four days apart is outside a three-day window.
+        resent.batches[0].header.effective_entry_date = "140907".into();
+        assert_eq!(
+            detect_duplicates(&[&original, &resent], &DuplicatePolicy::default()),
+            []
+        );
+    }
+
+    #[test]
+    fn test_duplicate_within_file() {
+        let text = fixtures::sample_ctx_with_addenda();
+        let mut file = parse(&text);
+        let entry = file.batches[0].entries[0].clone();
+        file.batches[0].entries.push(entry);
+        let other = parse(&text);
+
+        let groups = detect_duplicates(&[&other, &file], &DuplicatePolicy::default());
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].entries.len(), 3);
+        // Each CTX entry is followed by three addenda.
+        let lines: Vec<(usize, usize)> = groups[0]
+            .entries
+            .iter()
+            .map(|e| (e.file_index, e.line_number))
+            .collect();
+        assert_eq!(lines, [(0, 3), (1, 3), (1, 7)]);
+    }
+
+    #[test]
+    fn test_policy_options() {
+        let text = fixtures::sample_ppd_file(2);
+        let original = parse(&text);
+        let mut resent = parse(&text);
+        resent.batches[0].entries[0].individual_identification_number = "EMP0042        ".into();
+
+        let floor = DuplicatePolicy {
+            amount_floor: 2_000,
+            ..DuplicatePolicy::default()
+        };
+        let groups = detect_duplicates(&[&original, &resent], &floor);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].amount, 2_500);
+
+        let same_id = DuplicatePolicy {
+            require_same_identification: true,
+            ..DuplicatePolicy::default()
+        };
+        let groups = detect_duplicates(&[&original, &resent], &same_id);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].amount, 2_500);
+    }
+}
@@ -0,0 +1,247 @@
+//! Automated enrollment (ENR) entries, which enroll a receiver's account
+//! for federal benefit payments. Their type 05 addenda carry the payee's
+//! details as asterisk-delimited data ending in a backslash.
+
+use crate::records::{Addenda, BatchHeader};
+use crate::{AchError, TransactionCode};
+
+/// The number of fields in an ENR addenda's payment related information.
+const ENR_FIELDS: usize = 9;
+
+/// Whether the enrollee is a person or a company, from the enrollee
+/// classification code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum EnrolleeClassification {
+    /// `A`: a consumer
+    Consumer,
+
+    /// `B`: a company
+    Company,
+}
+
+/// The payee details of an ENR addenda, from [`Addenda::as_enrollment`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct EnrPayeeInformation {
+    /// Transaction code of the account being enrolled, e.g. `22` for
+    /// checking
+    pub transaction_code: TransactionCode,
+
+    /// Receiving DFI identification (8 digits)
+    pub receiving_dfi_identification: String,
+
+    /// Check digit of the receiving DFI's routing number
+    pub check_digit: String,
+
+    /// DFI account number
+    pub dfi_account_number: String,
+
+    /// Individual identification number, usually the Social Security
+    /// number
+    pub individual_identification_number: String,
+
+    /// Surname of the individual
+    pub individual_surname: String,
+
+    /// First name of the individual
+    pub individual_first_name: String,
+
+    /// Whether the receiver is a representative payee, receiving benefits
+    /// on another's behalf
+    pub representative_payee: bool,
+
+    /// Whether the enrollee is a consumer or a company
+    pub enrollee_classification: EnrolleeClassification,
+}
+
+impl EnrPayeeInformation {
+    /// The receiving DFI's 9-digit routing number.
+    pub fn routing_number(&self) -> String {
+        format!("{}{}", self.receiving_dfi_identification, self.check_digit)
+    }
+
+    /// Read an ENR addenda's payment related information, such as
+    /// `22*12200004*3*123987654321*777777777*DOE*JOHN*0*A\`, or fail with
+    /// [`AchError::InvalidValue`] saying what is wrong with it.
+    pub fn parse(information: &str) -> Result<EnrPayeeInformation, AchError> {
+        let invalid = |reason: String| AchError::InvalidValue {
+            field: "payment_related_information",
+            reason,
+        };
+        let Some((data, _)) = information.split_once('\\') else {
+            return Err(invalid("ENR data does not end in \\".to_string()));
+        };
+        let fields: Vec<&str> = data.split('*').map(str::trim).collect();
+        let [code, dfi, check, account, identification, surname, first_name, payee, class] =
+            fields[..]
+        else {
+            return Err(invalid(format!(
+                "ENR data has {} fields, {ENR_FIELDS} expected",
+                fields.len()
+            )));
+        };
+
+        let digits = |value: &str, len: usize| {
+            value.len() == len && value.bytes().all(|b| b.is_ascii_digit())
+        };
+        if !digits(code, 2) {
+            return Err(invalid(format!(
+                "transaction code {code:?} is not two digits"
+            )));
+        }
+        if !digits(dfi, 8) {
+            return Err(invalid(format!(
+                "receiving DFI identification {dfi:?} is not eight digits"
+            )));
+        }
+        if !digits(check, 1) {
+            return Err(invalid(format!("check digit {check:?} is not a digit")));
+        }
+        if account.is_empty() {
+            return Err(invalid("DFI account number is blank".to_string()));
+        }
+        let representative_payee = match payee {
+            "0" => false,
+            "1" => true,
+            other => {
+                return Err(invalid(format!(
+                    "representative payee indicator {other:?} is not 0 or 1"
+                )))
+            }
+        };
+        let enrollee_classification = match class {
+            "A" => EnrolleeClassification::Consumer,
+            "B" => EnrolleeClassification::Company,
+            other => {
+                return Err(invalid(format!(
+                    "enrollee classification {other:?} is not A or B"
+                )))
+            }
+        };
+
+        Ok(EnrPayeeInformation {
+            transaction_code: TransactionCode::from_code(code),
+            receiving_dfi_identification: dfi.to_string(),
+            check_digit: check.to_string(),
+            dfi_account_number: account.to_string(),
+            individual_identification_number: identification.to_string(),
+            individual_surname: surname.to_string(),
+            individual_first_name: first_name.to_string(),
+            representative_payee,
+            enrollee_classification,
+        })
+    }
+}
+
+impl Addenda<'_> {
+    /// Read this addenda's payee details, or `None` unless it is a type 05
+    /// addenda of an ENR batch. Malformed data is an
+    /// [`AchError::InvalidValue`]; see [`EnrPayeeInformation::parse`].
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile, EnrolleeClassification};
+    ///
+    /// let text = fixtures::sample_ppd_file(1);
+    /// let mut file = AchFile::parse(&text).unwrap();
+    /// let batch = &mut file.batches[0];
+    /// let addenda = rs_ach::Addenda {
+    ///     record_type: "7".into(),
+    ///     addenda_type_code: "05".into(),
+    ///     payment_related_information: "22*12200004*3*123987654321*777777777*DOE*JOHN*0*A\\".into(),
+    ///     addenda_sequence_number: "0001".into(),
+    ///     entry_detail_sequence_number: "0000001".into(),
+    /// };
+    /// assert!(addenda.as_enrollment(&batch.header).is_none());
+    ///
+    /// batch.header.standard_entry_class_code = "ENR".into();
+    /// let payee = addenda.as_enrollment(&batch.header).unwrap().unwrap();
+    /// assert_eq!(payee.routing_number(), "122000043");
+    /// assert_eq!(payee.individual_surname, "DOE");
+    /// assert_eq!(payee.enrollee_classification, EnrolleeClassification::Consumer);
+    /// ```
+    pub fn as_enrollment(
+        &self,
+        header: &BatchHeader<'_>,
+    ) -> Option<Result<EnrPayeeInformation, AchError>> {
+        if header.standard_entry_class_code.trim() != "ENR" || self.addenda_type_code != "05" {
+            return None;
+        }
+        Some(EnrPayeeInformation::parse(
+            &self.payment_related_information,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_enrollment() {
+        let payee =
+            EnrPayeeInformation::parse("32*02100002*1*0001234567*123456789*ROE*JANE*1*B\\     ")
+                .unwrap();
+        assert_eq!(
+            payee,
+            EnrPayeeInformation {
+                transaction_code: TransactionCode::from_code("32"),
+                receiving_dfi_identification: "02100002".to_string(),
+                check_digit: "1".to_string(),
+                dfi_account_number: "0001234567".to_string(),
+                individual_identification_number: "123456789".to_string(),
+                individual_surname: "ROE".to_string(),
+                individual_first_name: "JANE".to_string(),
+                representative_payee: true,
+                enrollee_classification: EnrolleeClassification::Company,
+            }
+        );
+    }
+
+    #[test]
+    fn test_malformed_enrollment() {
+        for (information, reason) in [
+            (
+                "22*12200004*3*1239*777*DOE*JOHN*0*A",
+                "ENR data does not end in \\",
+            ),
+            (
+                "22*12200004*3*1239*777*DOE*0*A\\",
+                "ENR data has 8 fields, 9 expected",
+            ),
+            (
+                "22*1220000X*3*1239*777*DOE*JOHN*0*A\\",
+                "receiving DFI identification \"1220000X\" is not eight digits",
+            ),
+            (
+                "2*12200004*3*1239*777*DOE*JOHN*0*A\\",
+                "transaction code \"2\" is not two digits",
+            ),
+            (
+                "22*12200004*33*1239*777*DOE*JOHN*0*A\\",
+                "check digit \"33\" is not a digit",
+            ),
+            (
+                "22*12200004*3**777*DOE*JOHN*0*A\\",
+                "DFI account number is blank",
+            ),
+            (
+                "22*12200004*3*1239*777*DOE*JOHN*Y*A\\",
+                "representative payee indicator \"Y\" is not 0 or 1",
+            ),
+            (
+                "22*12200004*3*1239*777*DOE*JOHN*0*C\\",
+                "enrollee classification \"C\" is not A or B",
+            ),
+        ] {
+            let err = EnrPayeeInformation::parse(information).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!("Invalid value for 'payment_related_information': {reason}"),
+                "{information}"
+            );
+        }
+    }
+}
@@ -0,0 +1,147 @@
+//! Company entry descriptions reserved by the NACHA rules.
+
+use std::fmt;
+
+use crate::records::BatchHeader;
+
+/// A company entry description with special meaning under the NACHA rules,
+/// from [`BatchHeader::entry_description_kind`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub enum EntryDescriptionKind {
+    /// `REVERSAL`: reverses an erroneous entry or file
+    Reversal,
+
+    /// `RECLAIM`: reclamation of benefit payments made after death
+    Reclaim,
+
+    /// `REDEPCHECK`: a re-presented check (RCK)
+    RedepositedCheck,
+
+    /// `RETRY PYMT`: reinitiates a debit returned R01 or R09. The return is
+    /// in an earlier file, so validation checks only that the entries are
+    /// debits
+    RetryPayment,
+
+    /// `AUTOENROLL`: automated enrollment (ENR)
+    AutoEnroll,
+
+    /// `HCCLAIMPMT`: health care claim payment (CCD)
+    HealthCareClaimPayment,
+
+    /// `ACCTVERIFY`: micro-entries verifying a receiver's account
+    AccountVerification,
+
+    /// Any other description, trimmed and upper-cased
+    #[cfg_attr(feature = "serde", serde(untagged))]
+    Other(String),
+}
+
+/// The reserved descriptions, as they appear in the field.
+const RESERVED: &[(&str, EntryDescriptionKind)] = &[
+    ("REVERSAL", EntryDescriptionKind::Reversal),
+    ("RECLAIM", EntryDescriptionKind::Reclaim),
+    ("REDEPCHECK", EntryDescriptionKind::RedepositedCheck),
+    ("RETRY PYMT", EntryDescriptionKind::RetryPayment),
+    ("AUTOENROLL", EntryDescriptionKind::AutoEnroll),
+    ("HCCLAIMPMT", EntryDescriptionKind::HealthCareClaimPayment),
+    ("ACCTVERIFY", EntryDescriptionKind::AccountVerification),
+];
+
+impl EntryDescriptionKind {
+    /// Classify a company entry description. Surrounding spaces and case
+    /// are ignored.
+    pub fn from_description(description: &str) -> EntryDescriptionKind {
+        let description = description.trim().to_ascii_uppercase();
+        RESERVED
+            .iter()
+            .find(|(reserved, _)| *reserved == description)
+            .map_or(EntryDescriptionKind::Other(description), |(_, kind)| {
+                kind.clone()
+            })
+    }
+
+    /// The description as it appears in the field, e.g. `RETRY PYMT`.
+    pub fn description(&self) -> &str {
+        match self {
+            EntryDescriptionKind::Other(description) => description,
+            kind => RESERVED
+                .iter()
+                .find(|(_, reserved)| reserved == kind)
+                .map_or("", |(description, _)| description),
+        }
+    }
+
+    /// Whether this is one of the reserved descriptions.
+    pub fn is_reserved(&self) -> bool {
+        !matches!(self, EntryDescriptionKind::Other(_))
+    }
+
+    /// The standard entry class code this description is limited to, if
+    /// any.
+    pub(crate) fn required_sec_code(&self) -> Option<&'static str> {
+        match self {
+            EntryDescriptionKind::RedepositedCheck => Some("RCK"),
+            EntryDescriptionKind::AutoEnroll => Some("ENR"),
+            EntryDescriptionKind::HealthCareClaimPayment => Some("CCD"),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for EntryDescriptionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+impl BatchHeader<'_> {
+    /// The company entry description, classified against the reserved
+    /// descriptions.
+    pub fn entry_description_kind(&self) -> EntryDescriptionKind {
+        EntryDescriptionKind::from_description(&self.company_entry_description)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_description() {
+        assert_eq!(
+            EntryDescriptionKind::from_description("RETRY PYMT"),
+            EntryDescriptionKind::RetryPayment
+        );
+        assert_eq!(
+            EntryDescriptionKind::from_description(" reversal "),
+            EntryDescriptionKind::Reversal
+        );
+        let other = EntryDescriptionKind::from_description("payroll   ");
+        assert_eq!(other, EntryDescriptionKind::Other("PAYROLL".to_string()));
+        assert!(!other.is_reserved());
+        assert_eq!(other.to_string(), "PAYROLL");
+        assert_eq!(
+            EntryDescriptionKind::RedepositedCheck.to_string(),
+            "REDEPCHECK"
+        );
+    }
+
+    #[test]
+    fn test_batch_header_accessor() {
+        let text = crate::fixtures::sample_ppd_file(1);
+        let mut file = crate::AchFile::parse(&text).unwrap();
+        let header = &mut file.batches[0].header;
+        header.company_entry_description = "REDEPCHECK".into();
+        assert_eq!(
+            header.entry_description_kind(),
+            EntryDescriptionKind::RedepositedCheck
+        );
+        assert_eq!(
+            header.entry_description_kind().required_sec_code(),
+            Some("RCK")
+        );
+    }
+}
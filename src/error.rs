@@ -1,9 +1,20 @@
 //! Error types for ACH file parsing.
 
+use std::ops::Range;
+
 use thiserror::Error;
 
+use crate::parse_options::ParseWarning;
+
+/// A `Result` with an [`AchError`].
+pub type AchResult<T> = Result<T, AchError>;
+
 /// Errors that can occur during ACH file parsing.
+///
+/// Variants may be added in any release; match on [`kind`](AchError::kind)
+/// where an exhaustive match is needed.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum AchError {
     /// The record type is invalid or unsupported.
     #[error("Invalid record type: {0}")]
@@ -13,17 +24,66 @@ pub enum AchError {
     #[error("Invalid line length: expected 94, got {0}")]
     InvalidLineLength(usize),
 
+    /// A line holds a control character, such as a tab or a carriage
+    /// return not ending the line.
+    #[error("Control character U+{:04X} at position {position}", *character as u32)]
+    ControlCharacter {
+        /// The character found
+        character: char,
+        /// Position of the character within the line, counting from 1
+        position: usize,
+    },
+
     /// A numeric field could not be parsed.
+    ///
+    /// The parser reports [`InvalidField`](AchError::InvalidField) instead,
+    /// which also says where the field is; this variant is kept for code that
+    /// builds or matches it.
     #[error("Invalid numeric field '{field}': {source}")]
     InvalidNumber {
         field: &'static str,
         source: std::num::ParseIntError,
     },
 
+    /// A field of a record could not be read.
+    #[error(
+        "Invalid field '{field}' in {record_type} record at line {line}, positions {}-{}: {reason}",
+        range.start + 1,
+        range.end
+    )]
+    InvalidField {
+        /// Kind of record, e.g. `entry detail`
+        record_type: &'static str,
+        /// Name of the field
+        field: &'static str,
+        /// Line of the record in the file, counting from 1
+        line: usize,
+        /// Offsets of the field within the record, counting from 0
+        range: Range<usize>,
+        /// What is wrong with the value
+        reason: String,
+    },
+
     /// The file structure is invalid (e.g., missing header or control records).
     #[error("Invalid file structure: {0}")]
     InvalidStructure(String),
 
+    /// A record appears where none of its type may, such as an entry
+    /// before any batch header or a file control inside a batch.
+    #[error(
+        "Invalid file structure: Unexpected {}{} at line {line}",
+        record_label(record_type),
+        if *in_batch { " in batch" } else { "" }
+    )]
+    UnexpectedRecord {
+        /// Record type code found, e.g. `6`
+        record_type: String,
+        /// Line of the record in the file, counting from 1
+        line: usize,
+        /// Whether the record was found inside a batch
+        in_batch: bool,
+    },
+
     /// The file is empty or contains no valid records.
     #[error("Empty file")]
     EmptyFile,
@@ -31,4 +91,364 @@ pub enum AchError {
     /// A batch is missing required records.
     #[error("Incomplete batch: {0}")]
     IncompleteBatch(String),
+
+    /// A date or time field is neither blank nor a valid date or time.
+    #[error("Invalid date field '{field}'{}: {value:?}", line_label(*line))]
+    InvalidDate {
+        /// Name of the field
+        field: &'static str,
+        /// The value of the field
+        value: String,
+        /// Line of the record in the file, counting from 1, when known:
+        /// the parser knows it, the record accessors do not
+        line: Option<usize>,
+    },
+
+    /// A value supplied when building records does not fit its field.
+    #[error("Invalid value for '{field}': {reason}")]
+    InvalidValue { field: &'static str, reason: String },
+
+    /// A field was looked up by a name its record does not have.
+    #[error("Unknown field '{name}' in {record_type} record")]
+    UnknownField {
+        /// Kind of record, e.g. `entry detail`
+        record_type: &'static str,
+        /// The name looked up
+        name: String,
+    },
+
+    /// A warning that [`ParseOptions::deny`](crate::ParseOptions::deny)
+    /// turns into an error.
+    #[error("Denied warning at {0}")]
+    DeniedWarning(ParseWarning),
+
+    /// The last item of a list of errors cut short by a `max_errors` limit,
+    /// such as [`ParseOptions::max_errors`](crate::ParseOptions::max_errors).
+    #[error("Too many errors: stopped after {reported}")]
+    TooManyErrors {
+        /// Errors reported before this one
+        reported: usize,
+        /// Whether errors were left out; always `true` when produced
+        truncated: bool,
+    },
+
+    /// Reading the input failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Compressed input could not be decompressed.
+    #[error(
+        "Decompression failed{} after {decompressed} bytes: {source}",
+        path.as_ref().map(|p| format!(" in {}", p.display())).unwrap_or_default()
+    )]
+    Decompression {
+        /// The file being read, when reading from disk
+        path: Option<std::path::PathBuf>,
+        /// Bytes decompressed before the failure
+        decompressed: usize,
+        /// The error from the decompressor
+        source: std::io::Error,
+    },
+
+    /// The input is not valid UTF-8.
+    #[error("Invalid UTF-8 at byte {offset}")]
+    InvalidEncoding {
+        /// Offset of the first byte that is not valid UTF-8, counting from 0
+        offset: usize,
+    },
+
+    /// An error in a record, with the kind of record and where it is.
+    #[error("{record_type} record at line {line}: {source}")]
+    InRecord {
+        /// Kind of record, e.g. `entry detail`
+        record_type: &'static str,
+        /// Line of the record in the file, counting from 1
+        line: usize,
+        /// The error
+        source: Box<AchError>,
+    },
+
+    /// An error in a batch's header, control, or structure, with the batch
+    /// it occurred in.
+    #[error("in batch {}: {source}", batch_label(*batch_index, batch_number))]
+    InBatch {
+        /// Index of the batch in the file, counting from 0
+        batch_index: usize,
+        /// Batch number from the batch header, trimmed; empty when the
+        /// header is unreadable
+        batch_number: String,
+        /// The error
+        source: Box<AchError>,
+    },
+
+    /// An error in an entry or its addenda, with the batch and entry it
+    /// occurred in.
+    #[error(
+        "in batch {}, entry {}{}: {source}",
+        batch_label(*batch_index, batch_number),
+        entry_index + 1,
+        trace_number.as_ref().map(|t| format!(" (trace {t})")).unwrap_or_default()
+    )]
+    InEntry {
+        /// Index of the batch in the file, counting from 0
+        batch_index: usize,
+        /// Batch number from the batch header, trimmed
+        batch_number: String,
+        /// Index of the entry in its batch, counting from 0; messages count
+        /// from 1
+        entry_index: usize,
+        /// Trace number of the entry, when its record is long enough to hold
+        /// one
+        trace_number: Option<String>,
+        /// The error
+        source: Box<AchError>,
+    },
+}
+
+/// Coarse category of an [`AchError`], from [`AchError::kind`].
+///
+/// The kinds are stable: new kinds are only added in a breaking release, and
+/// an error keeps its kind across releases, so a `match` on them need not
+/// have a catch-all arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum ErrorKind {
+    /// Records are missing or out of order, or the file is empty
+    Structure,
+
+    /// A record as a whole is malformed: wrong length or unknown type
+    Record,
+
+    /// A field of a record, or a value supplied for one, is invalid
+    Field,
+
+    /// A configured limit was exceeded
+    Limit,
+
+    /// Reading or writing failed
+    Io,
+
+    /// The input is not in the expected character encoding
+    Encoding,
+}
+
+impl AchError {
+    /// The category of this error. Record, batch, and entry context does not
+    /// change it: the kind is that of the [root cause](AchError::root_cause).
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            AchError::InvalidStructure(_)
+            | AchError::UnexpectedRecord { .. }
+            | AchError::EmptyFile
+            | AchError::IncompleteBatch(_) => ErrorKind::Structure,
+            AchError::InvalidRecordType(_)
+            | AchError::InvalidLineLength(_)
+            | AchError::ControlCharacter { .. } => ErrorKind::Record,
+            AchError::InvalidNumber { .. }
+            | AchError::InvalidField { .. }
+            | AchError::InvalidDate { .. }
+            | AchError::InvalidValue { .. }
+            | AchError::UnknownField { .. } => ErrorKind::Field,
+            AchError::DeniedWarning(warning) => warning.code.kind(),
+            AchError::TooManyErrors { .. } => ErrorKind::Limit,
+            AchError::Io(_) | AchError::Decompression { .. } => ErrorKind::Io,
+            AchError::InvalidEncoding { .. } => ErrorKind::Encoding,
+            AchError::InRecord { source, .. }
+            | AchError::InBatch { source, .. }
+            | AchError::InEntry { source, .. } => source.kind(),
+        }
+    }
+
+    /// The error without the record, batch, and entry context wrapped
+    /// around it.
+    ///
+    /// ```
+    /// use rs_ach::fixtures::{self, Corruption};
+    /// use rs_ach::{AchError, AchFile};
+    ///
+    /// let broken = fixtures::corrupt(&fixtures::sample_ppd_file(1), Corruption::ShortLine);
+    /// let err = AchFile::parse(&broken).unwrap_err();
+    /// assert!(matches!(err, AchError::InEntry { entry_index: 0, .. }));
+    /// assert!(matches!(err.root_cause(), AchError::InvalidLineLength(93)));
+    /// ```
+    pub fn root_cause(&self) -> &AchError {
+        match self {
+            AchError::InRecord { source, .. }
+            | AchError::InBatch { source, .. }
+            | AchError::InEntry { source, .. } => source.root_cause(),
+            other => other,
+        }
+    }
+
+    /// The line of the file, counting from 1, of the record the error is
+    /// about, through any batch and entry context. `None` for errors about
+    /// the file as a whole, such as a missing file control, and errors not
+    /// from parsing.
+    ///
+    /// ```
+    /// use rs_ach::fixtures::{self, Corruption};
+    /// use rs_ach::AchFile;
+    ///
+    /// let broken = fixtures::corrupt(&fixtures::sample_ppd_file(1), Corruption::BadAmount);
+    /// assert_eq!(AchFile::parse(&broken).unwrap_err().line(), Some(3));
+    ///
+    /// let broken = fixtures::corrupt(&fixtures::sample_ppd_file(1), Corruption::MissingFileControl);
+    /// assert_eq!(AchFile::parse(&broken).unwrap_err().line(), None);
+    /// ```
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            AchError::InRecord { line, .. }
+            | AchError::InvalidField { line, .. }
+            | AchError::UnexpectedRecord { line, .. } => Some(*line),
+            AchError::InvalidDate { line, .. } => *line,
+            AchError::DeniedWarning(warning) => Some(warning.line),
+            AchError::InBatch { source, .. } | AchError::InEntry { source, .. } => source.line(),
+            _ => None,
+        }
+    }
+}
+
+/// Errors serialize as an object with the [root cause](AchError::root_cause)'s
+/// variant name as `code`, its `kind`, the full `message`, and where known
+/// the `line`, `field`, `batch` number, and `trace` number:
+///
+/// ```json
+/// {"code":"InvalidField","kind":"Field","message":"in batch 0000001, ...","line":3,"field":"amount","batch":"0000001","trace":"123456780000001"}
+/// ```
+///
+/// This shape is stable; fields that do not apply are left out.
+#[cfg(feature = "serde")]
+impl serde::Serialize for AchError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(serde::Serialize)]
+        struct Wire<'a> {
+            code: &'static str,
+            kind: ErrorKind,
+            message: String,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            line: Option<usize>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            field: Option<&'a str>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            batch: Option<&'a str>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            trace: Option<&'a str>,
+        }
+
+        let mut wire = Wire {
+            code: self.root_cause().variant_name(),
+            kind: self.kind(),
+            message: self.to_string(),
+            line: None,
+            field: None,
+            batch: None,
+            trace: None,
+        };
+        let mut error = self;
+        loop {
+            match error {
+                AchError::InRecord { line, source, .. } => {
+                    wire.line = Some(*line);
+                    error = source;
+                }
+                AchError::InBatch {
+                    batch_number,
+                    source,
+                    ..
+                } => {
+                    wire.batch = Some(batch_number.as_str()).filter(|b| !b.is_empty());
+                    error = source;
+                }
+                AchError::InEntry {
+                    batch_number,
+                    trace_number,
+                    source,
+                    ..
+                } => {
+                    wire.batch = Some(batch_number.as_str()).filter(|b| !b.is_empty());
+                    wire.trace = trace_number.as_deref();
+                    error = source;
+                }
+                AchError::InvalidField { field, line, .. } => {
+                    wire.field = Some(field);
+                    wire.line = Some(*line);
+                    break;
+                }
+                AchError::InvalidDate { field, line, .. } => {
+                    wire.field = Some(field);
+                    wire.line = *line;
+                    break;
+                }
+                AchError::InvalidNumber { field, .. } | AchError::InvalidValue { field, .. } => {
+                    wire.field = Some(field);
+                    break;
+                }
+                AchError::UnknownField { name, .. } => {
+                    wire.field = Some(name);
+                    break;
+                }
+                AchError::DeniedWarning(warning) => {
+                    wire.line = Some(warning.line);
+                    break;
+                }
+                AchError::UnexpectedRecord { line, .. } => {
+                    wire.line = Some(*line);
+                    break;
+                }
+                _ => break,
+            }
+        }
+        wire.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl AchError {
+    fn variant_name(&self) -> &'static str {
+        match self {
+            AchError::InvalidRecordType(_) => "InvalidRecordType",
+            AchError::InvalidLineLength(_) => "InvalidLineLength",
+            AchError::ControlCharacter { .. } => "ControlCharacter",
+            AchError::InvalidNumber { .. } => "InvalidNumber",
+            AchError::InvalidField { .. } => "InvalidField",
+            AchError::InvalidStructure(_) => "InvalidStructure",
+            AchError::UnexpectedRecord { .. } => "UnexpectedRecord",
+            AchError::EmptyFile => "EmptyFile",
+            AchError::IncompleteBatch(_) => "IncompleteBatch",
+            AchError::InvalidDate { .. } => "InvalidDate",
+            AchError::InvalidValue { .. } => "InvalidValue",
+            AchError::UnknownField { .. } => "UnknownField",
+            AchError::DeniedWarning(_) => "DeniedWarning",
+            AchError::TooManyErrors { .. } => "TooManyErrors",
+            AchError::Io(_) => "Io",
+            AchError::Decompression { .. } => "Decompression",
+            AchError::InvalidEncoding { .. } => "InvalidEncoding",
+            AchError::InRecord { .. } => "InRecord",
+            AchError::InBatch { .. } => "InBatch",
+            AchError::InEntry { .. } => "InEntry",
+        }
+    }
+}
+
+/// The record with this type code, as named in errors.
+fn record_label(record_type: &str) -> String {
+    match crate::parser::record_name(record_type) {
+        Some(name) => format!("{name} record"),
+        None => format!("record type '{record_type}'"),
+    }
+}
+
+/// ` at line N` when the line is known.
+fn line_label(line: Option<usize>) -> String {
+    line.map_or_else(String::new, |line| format!(" at line {line}"))
+}
+
+/// The batch number, or the batch's position when the number is unknown.
+fn batch_label(batch_index: usize, batch_number: &str) -> String {
+    if batch_number.is_empty() {
+        format!("#{}", batch_index + 1)
+    } else {
+        batch_number.to_string()
+    }
 }
@@ -0,0 +1,142 @@
+//! Per-company exposure totals for pre-release limit checks.
+
+use std::collections::BTreeMap;
+
+use crate::controls::{is_credit, is_debit};
+use crate::AchFile;
+
+/// Aggregates for one originating company, from [`AchFile::exposure_summary`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct CompanyExposure {
+    /// Company identification, trimmed
+    pub company_identification: String,
+
+    /// Company name from the company's first batch header, trimmed
+    pub company_name: String,
+
+    /// Sum of debit entry amounts, in cents
+    pub gross_debit_amount: u64,
+
+    /// Sum of credit entry amounts, in cents
+    pub gross_credit_amount: u64,
+
+    /// Number of entries per standard entry class code
+    pub entry_counts: BTreeMap<String, usize>,
+
+    /// Amount of the largest single entry, debit or credit, in cents
+    pub largest_entry_amount: u64,
+
+    /// Trace number of the largest entry (the first, on ties), or `None` if
+    /// the company has no entries
+    pub largest_entry_trace_number: Option<String>,
+}
+
+impl AchFile<'_> {
+    /// Debit and credit exposure per originating company, in order of first
+    /// appearance.
+    ///
+    /// Batches are grouped by trimmed company identification, so one
+    /// company's batches are combined wherever they appear in the file.
+    pub fn exposure_summary(&self) -> Vec<CompanyExposure> {
+        let mut companies: Vec<CompanyExposure> = Vec::new();
+        for batch in &self.batches {
+            let header = &batch.header;
+            let id = header.company_identification.trim();
+            let index = match companies
+                .iter()
+                .position(|c| c.company_identification == id)
+            {
+                Some(index) => index,
+                None => {
+                    companies.push(CompanyExposure {
+                        company_identification: id.to_string(),
+                        company_name: header.company_name.trim().to_string(),
+                        gross_debit_amount: 0,
+                        gross_credit_amount: 0,
+                        entry_counts: BTreeMap::new(),
+                        largest_entry_amount: 0,
+                        largest_entry_trace_number: None,
+                    });
+                    companies.len() - 1
+                }
+            };
+            let company = &mut companies[index];
+
+            let sec = header.standard_entry_class_code.trim();
+            for entry in &batch.entries {
+                if is_debit(&entry.transaction_code) {
+                    company.gross_debit_amount += entry.amount;
+                } else if is_credit(&entry.transaction_code) {
+                    company.gross_credit_amount += entry.amount;
+                }
+                *company.entry_counts.entry(sec.to_string()).or_default() += 1;
+                if company.largest_entry_trace_number.is_none()
+                    || entry.amount > company.largest_entry_amount
+                {
+                    company.largest_entry_amount = entry.amount;
+                    company.largest_entry_trace_number = Some(entry.trace_number.to_string());
+                }
+            }
+        }
+        companies
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_exposure_summary() {
+        let text = fixtures::sample_multi_company_file();
+        let file = AchFile::parse(&text).unwrap();
+        let exposure = file.exposure_summary();
+        assert_eq!(exposure.len(), 2);
+
+        let acme = &exposure[0];
+        assert_eq!(acme.company_identification, "1111111111");
+        assert_eq!(acme.company_name, "ACME CORP");
+        assert_eq!(acme.gross_credit_amount, 250_000 + 180_000 + 900_000);
+        assert_eq!(acme.gross_debit_amount, 5_000);
+        assert_eq!(
+            acme.entry_counts,
+            BTreeMap::from([("CCD".to_string(), 2), ("PPD".to_string(), 2)])
+        );
+        assert_eq!(acme.largest_entry_amount, 900_000);
+        assert_eq!(
+            acme.largest_entry_trace_number.as_deref(),
+            Some(file.batches[1].entries[0].trace_number.as_ref())
+        );
+
+        let beta = &exposure[1];
+        assert_eq!(beta.company_name, "BETA LLC");
+        assert_eq!(beta.gross_credit_amount, 0);
+        assert_eq!(beta.gross_debit_amount, 21_000);
+        assert_eq!(beta.entry_counts, BTreeMap::from([("PPD".to_string(), 3)]));
+        assert_eq!(beta.largest_entry_amount, 12_000);
+    }
+
+    #[test]
+    fn test_company_without_entries() {
+        let text = fixtures::sample_ppd_file(0);
+        let exposure = AchFile::parse(&text).unwrap().exposure_summary();
+        assert_eq!(exposure.len(), 1);
+        assert_eq!(exposure[0].largest_entry_amount, 0);
+        assert_eq!(exposure[0].largest_entry_trace_number, None);
+        assert!(exposure[0].entry_counts.is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize() {
+        let text = fixtures::sample_multi_company_file();
+        let exposure = AchFile::parse(&text).unwrap().exposure_summary();
+        let json = serde_json::to_value(&exposure[1]).unwrap();
+        assert_eq!(json["company_identification"], "2222222222");
+        assert_eq!(json["entry_counts"]["PPD"], 3);
+        assert_eq!(json["gross_debit_amount"], 21_000);
+    }
+}
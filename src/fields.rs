@@ -0,0 +1,720 @@
+//! Field-by-field view of records.
+//!
+//! Every record type exposes a `fields()` method returning its NACHA fields
+//! in position order, so generic tooling (viewers, rules engines) can walk a
+//! record without knowing its struct.
+//!
+//! `get_field` and `set_field` read and write one field by its name, as in
+//! [`FieldLayout::name`]. A value set is checked against the field's layout
+//! and stored in the typed struct field, so setting `amount` updates
+//! [`EntryDetail::amount`]:
+//!
+//! ```
+//! use rs_ach::{fixtures, AchFile};
+//!
+//! let text = fixtures::sample_ppd_file(1);
+//! let mut file = AchFile::parse(&text).unwrap();
+//! let entry = &mut file.batches[0].entries[0];
+//! entry.set_field("amount", "2500").unwrap();
+//! assert_eq!(entry.amount, 2_500);
+//! assert_eq!(entry.get_field("amount").unwrap(), "0000002500");
+//! assert!(entry.set_field("amount", "25.00").is_err());
+//! assert!(entry.set_field("amount_in_dollars", "25").is_err());
+//! ```
+
+use std::borrow::Cow;
+
+use crate::error::{AchError, AchResult};
+use crate::record_iter::Record;
+use crate::records::layout::{self, FieldClass, FieldLayout, InclusionRequirement};
+use crate::records::*;
+
+/// One field of a record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldInfo<'a> {
+    /// Field identifier, matching the struct field name (e.g. `individual_name`)
+    pub name: &'static str,
+
+    /// NACHA field name (e.g. `Individual Name`)
+    pub label: &'static str,
+
+    /// First position of the field, 1-based
+    pub start: usize,
+
+    /// Last position of the field, 1-based and inclusive
+    pub end: usize,
+
+    /// Character class
+    pub class: FieldClass,
+
+    /// Inclusion requirement
+    pub inclusion: InclusionRequirement,
+
+    /// Field contents as they appear in the record, padding included
+    pub raw: Cow<'a, str>,
+}
+
+impl FieldInfo<'_> {
+    /// Field contents with surrounding padding spaces removed.
+    pub fn trimmed(&self) -> &str {
+        self.raw.trim()
+    }
+
+    /// Field width in characters.
+    pub fn width(&self) -> usize {
+        self.end - self.start + 1
+    }
+}
+
+/// How `set_field_with` treats a value shorter than its field.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum FieldPadding {
+    /// Fill it as NACHA does: numeric values with leading zeros, others
+    /// with trailing spaces
+    #[default]
+    Pad,
+
+    /// Reject it: the value must fill the field
+    Exact,
+}
+
+/// Check `value` against the field called `name` in `layout`, returning the
+/// field and the value as it is stored.
+///
+/// Values must be ASCII and no wider than the field. Numeric fields take
+/// digits only, or spaces when the field is optional.
+fn prepare(
+    layout: &'static [FieldLayout],
+    record_type: &'static str,
+    name: &str,
+    value: &str,
+    padding: FieldPadding,
+) -> AchResult<(&'static FieldLayout, String)> {
+    let field = layout
+        .iter()
+        .find(|f| f.name == name)
+        .ok_or_else(|| AchError::UnknownField {
+            record_type,
+            name: name.to_string(),
+        })?;
+    let invalid = |reason: String| AchError::InvalidValue {
+        field: field.name,
+        reason,
+    };
+
+    let width = field.width();
+    if !value.is_ascii() {
+        return Err(invalid(format!("{value:?} is not ASCII")));
+    }
+    if value.len() > width {
+        return Err(invalid(format!(
+            "{value:?} is longer than {width} characters"
+        )));
+    }
+    if value.len() < width && padding == FieldPadding::Exact {
+        return Err(invalid(format!("{value:?} is not {width} characters")));
+    }
+
+    let blank = value.trim().is_empty();
+    match field.class {
+        FieldClass::Numeric if blank && field.inclusion == InclusionRequirement::Optional => {
+            Ok((field, " ".repeat(width)))
+        }
+        FieldClass::Numeric => {
+            if blank || !value.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(invalid(format!("{value:?} is not all digits")));
+            }
+            Ok((field, format!("{value:0>width$}")))
+        }
+        FieldClass::Alphameric => Ok((field, format!("{value:<width$}"))),
+    }
+}
+
+/// The raw value of the field called `name` among `fields`.
+fn get<'r>(fields: Vec<FieldInfo<'r>>, name: &str) -> Option<Cow<'r, str>> {
+    fields.into_iter().find(|f| f.name == name).map(|f| f.raw)
+}
+
+/// A numeric field value checked by [`prepare`], which always fits a `u64`.
+fn digits(value: &str) -> u64 {
+    value.parse().unwrap_or(0)
+}
+
+/// Pair a layout table with the record's values, in the same order.
+fn zip<'a, const N: usize>(
+    layout: &[FieldLayout; N],
+    values: [Cow<'a, str>; N],
+) -> Vec<FieldInfo<'a>> {
+    layout
+        .iter()
+        .zip(values)
+        .map(|(field, raw)| FieldInfo {
+            name: field.name,
+            label: field.label,
+            start: field.start,
+            end: field.end,
+            class: field.class,
+            inclusion: field.inclusion,
+            raw,
+        })
+        .collect()
+}
+
+fn text(value: &str) -> Cow<'_, str> {
+    Cow::Borrowed(value)
+}
+
+fn number(value: u64, width: usize) -> Cow<'static, str> {
+    Cow::Owned(format!("{value:0width$}"))
+}
+
+impl FileHeader<'_> {
+    /// Fields of the file header record, in position order.
+    pub fn fields(&self) -> Vec<FieldInfo<'_>> {
+        zip(
+            &layout::FILE_HEADER,
+            [
+                text(&self.record_type),
+                text(&self.priority_code),
+                text(&self.immediate_destination),
+                text(&self.immediate_origin),
+                text(&self.file_creation_date),
+                text(&self.file_creation_time),
+                text(&self.file_id_modifier),
+                text(&self.record_size),
+                text(&self.blocking_factor),
+                text(&self.format_code),
+                text(&self.immediate_destination_name),
+                text(&self.immediate_origin_name),
+                text(&self.reference_code),
+            ],
+        )
+    }
+
+    /// The raw value of the field called `name`, padding included, or
+    /// `None` when the file header record has no such field.
+    pub fn get_field(&self, name: &str) -> Option<Cow<'_, str>> {
+        get(self.fields(), name)
+    }
+
+    /// Set the field called `name`, padding a short value; see
+    /// [`set_field_with`](Self::set_field_with).
+    pub fn set_field(&mut self, name: &str, value: &str) -> AchResult<()> {
+        self.set_field_with(name, value, FieldPadding::Pad)
+    }
+
+    /// Set the field called `name` to `value`, checked against its layout.
+    /// An unknown name is [`AchError::UnknownField`] and a value that does
+    /// not fit is [`AchError::InvalidValue`]; either leaves the record as it
+    /// was.
+    pub fn set_field_with(
+        &mut self,
+        name: &str,
+        value: &str,
+        padding: FieldPadding,
+    ) -> AchResult<()> {
+        let (field, value) = prepare(&layout::FILE_HEADER, "file header", name, value, padding)?;
+        match field.name {
+            "record_type" => self.record_type = Cow::Owned(value),
+            "priority_code" => self.priority_code = Cow::Owned(value),
+            "immediate_destination" => self.immediate_destination = Cow::Owned(value),
+            "immediate_origin" => self.immediate_origin = Cow::Owned(value),
+            "file_creation_date" => self.file_creation_date = Cow::Owned(value),
+            "file_creation_time" => self.file_creation_time = Cow::Owned(value),
+            "file_id_modifier" => self.file_id_modifier = Cow::Owned(value),
+            "record_size" => self.record_size = Cow::Owned(value),
+            "blocking_factor" => self.blocking_factor = Cow::Owned(value),
+            "format_code" => self.format_code = Cow::Owned(value),
+            "immediate_destination_name" => self.immediate_destination_name = Cow::Owned(value),
+            "immediate_origin_name" => self.immediate_origin_name = Cow::Owned(value),
+            "reference_code" => self.reference_code = Cow::Owned(value),
+            _ => unreachable!("{} is in the file header layout", field.name),
+        }
+        Ok(())
+    }
+}
+
+impl BatchHeader<'_> {
+    /// Fields of the batch header record, in position order.
+    pub fn fields(&self) -> Vec<FieldInfo<'_>> {
+        zip(
+            &layout::BATCH_HEADER,
+            [
+                text(&self.record_type),
+                text(&self.service_class_code),
+                text(&self.company_name),
+                text(&self.company_discretionary_data),
+                text(&self.company_identification),
+                text(&self.standard_entry_class_code),
+                text(&self.company_entry_description),
+                text(&self.company_descriptive_date),
+                text(&self.effective_entry_date),
+                text(&self.settlement_date),
+                text(&self.originator_status_code),
+                text(&self.originating_dfi_identification),
+                text(&self.batch_number),
+            ],
+        )
+    }
+
+    /// The raw value of the field called `name`, padding included, or
+    /// `None` when the batch header record has no such field.
+    pub fn get_field(&self, name: &str) -> Option<Cow<'_, str>> {
+        get(self.fields(), name)
+    }
+
+    /// Set the field called `name`, padding a short value; see
+    /// [`set_field_with`](Self::set_field_with).
+    pub fn set_field(&mut self, name: &str, value: &str) -> AchResult<()> {
+        self.set_field_with(name, value, FieldPadding::Pad)
+    }
+
+    /// Set the field called `name` to `value`, checked against its layout.
+    /// An unknown name is [`AchError::UnknownField`] and a value that does
+    /// not fit is [`AchError::InvalidValue`]; either leaves the record as it
+    /// was.
+    pub fn set_field_with(
+        &mut self,
+        name: &str,
+        value: &str,
+        padding: FieldPadding,
+    ) -> AchResult<()> {
+        let (field, value) = prepare(&layout::BATCH_HEADER, "batch header", name, value, padding)?;
+        match field.name {
+            "record_type" => self.record_type = Cow::Owned(value),
+            "service_class_code" => self.service_class_code = Cow::Owned(value),
+            "company_name" => self.company_name = Cow::Owned(value),
+            "company_discretionary_data" => self.company_discretionary_data = Cow::Owned(value),
+            "company_identification" => self.company_identification = Cow::Owned(value),
+            "standard_entry_class_code" => self.standard_entry_class_code = Cow::Owned(value),
+            "company_entry_description" => self.company_entry_description = Cow::Owned(value),
+            "company_descriptive_date" => self.company_descriptive_date = Cow::Owned(value),
+            "effective_entry_date" => self.effective_entry_date = Cow::Owned(value),
+            "settlement_date" => self.settlement_date = Cow::Owned(value),
+            "originator_status_code" => self.originator_status_code = Cow::Owned(value),
+            "originating_dfi_identification" => {
+                self.originating_dfi_identification = Cow::Owned(value)
+            }
+            "batch_number" => self.batch_number = Cow::Owned(value),
+            _ => unreachable!("{} is in the batch header layout", field.name),
+        }
+        Ok(())
+    }
+}
+
+impl EntryDetail<'_> {
+    /// Fields of the entry detail record, in position order.
+    ///
+    /// Addenda records are not included; call [`Addenda::fields`] on each.
+    pub fn fields(&self) -> Vec<FieldInfo<'_>> {
+        zip(
+            &layout::ENTRY_DETAIL,
+            [
+                text(&self.record_type),
+                text(&self.transaction_code),
+                text(&self.receiving_dfi_identification),
+                text(&self.check_digit),
+                text(&self.dfi_account_number),
+                number(self.amount, 10),
+                text(&self.individual_identification_number),
+                text(&self.individual_name),
+                text(&self.discretionary_data),
+                text(&self.addenda_record_indicator),
+                text(&self.trace_number),
+            ],
+        )
+    }
+
+    /// The raw value of the field called `name`, padding included, or
+    /// `None` when the entry detail record has no such field.
+    pub fn get_field(&self, name: &str) -> Option<Cow<'_, str>> {
+        get(self.fields(), name)
+    }
+
+    /// Set the field called `name`, padding a short value; see
+    /// [`set_field_with`](Self::set_field_with).
+    pub fn set_field(&mut self, name: &str, value: &str) -> AchResult<()> {
+        self.set_field_with(name, value, FieldPadding::Pad)
+    }
+
+    /// Set the field called `name` to `value`, checked against its layout.
+    /// An unknown name is [`AchError::UnknownField`] and a value that does
+    /// not fit is [`AchError::InvalidValue`]; either leaves the record as it
+    /// was.
+    pub fn set_field_with(
+        &mut self,
+        name: &str,
+        value: &str,
+        padding: FieldPadding,
+    ) -> AchResult<()> {
+        let (field, value) = prepare(&layout::ENTRY_DETAIL, "entry detail", name, value, padding)?;
+        match field.name {
+            "record_type" => self.record_type = Cow::Owned(value),
+            "transaction_code" => self.transaction_code = Cow::Owned(value),
+            "receiving_dfi_identification" => self.receiving_dfi_identification = Cow::Owned(value),
+            "check_digit" => self.check_digit = Cow::Owned(value),
+            "dfi_account_number" => self.dfi_account_number = Cow::Owned(value),
+            "amount" => self.amount = digits(&value),
+            "individual_identification_number" => {
+                self.individual_identification_number = Cow::Owned(value)
+            }
+            "individual_name" => self.individual_name = Cow::Owned(value),
+            "discretionary_data" => self.discretionary_data = Cow::Owned(value),
+            "addenda_record_indicator" => self.addenda_record_indicator = Cow::Owned(value),
+            "trace_number" => self.trace_number = Cow::Owned(value),
+            _ => unreachable!("{} is in the entry detail layout", field.name),
+        }
+        Ok(())
+    }
+}
+
+impl Addenda<'_> {
+    /// Fields of the addenda record, in position order.
+    pub fn fields(&self) -> Vec<FieldInfo<'_>> {
+        zip(
+            &layout::ADDENDA,
+            [
+                text(&self.record_type),
+                text(&self.addenda_type_code),
+                text(&self.payment_related_information),
+                text(&self.addenda_sequence_number),
+                text(&self.entry_detail_sequence_number),
+            ],
+        )
+    }
+
+    /// The raw value of the field called `name`, padding included, or
+    /// `None` when the addenda record has no such field.
+    pub fn get_field(&self, name: &str) -> Option<Cow<'_, str>> {
+        get(self.fields(), name)
+    }
+
+    /// Set the field called `name`, padding a short value; see
+    /// [`set_field_with`](Self::set_field_with).
+    pub fn set_field(&mut self, name: &str, value: &str) -> AchResult<()> {
+        self.set_field_with(name, value, FieldPadding::Pad)
+    }
+
+    /// Set the field called `name` to `value`, checked against its layout.
+    /// An unknown name is [`AchError::UnknownField`] and a value that does
+    /// not fit is [`AchError::InvalidValue`]; either leaves the record as it
+    /// was.
+    pub fn set_field_with(
+        &mut self,
+        name: &str,
+        value: &str,
+        padding: FieldPadding,
+    ) -> AchResult<()> {
+        let (field, value) = prepare(&layout::ADDENDA, "addenda", name, value, padding)?;
+        match field.name {
+            "record_type" => self.record_type = Cow::Owned(value),
+            "addenda_type_code" => self.addenda_type_code = Cow::Owned(value),
+            "payment_related_information" => self.payment_related_information = Cow::Owned(value),
+            "addenda_sequence_number" => self.addenda_sequence_number = Cow::Owned(value),
+            "entry_detail_sequence_number" => self.entry_detail_sequence_number = Cow::Owned(value),
+            _ => unreachable!("{} is in the addenda layout", field.name),
+        }
+        Ok(())
+    }
+}
+
+impl BatchControl {
+    /// Fields of the batch control record, in position order.
+    pub fn fields(&self) -> Vec<FieldInfo<'_>> {
+        zip(
+            &layout::BATCH_CONTROL,
+            [
+                text(&self.record_type),
+                text(&self.service_class_code),
+                number(self.entry_addenda_count, 6),
+                number(self.entry_hash, 10),
+                number(self.total_debit_amount, 12),
+                number(self.total_credit_amount, 12),
+                text(&self.company_identification),
+                text(&self.message_authentication_code),
+                text(&self.reserved),
+                text(&self.originating_dfi_identification),
+                text(&self.batch_number),
+            ],
+        )
+    }
+
+    /// The raw value of the field called `name`, padding included, or
+    /// `None` when the batch control record has no such field.
+    pub fn get_field(&self, name: &str) -> Option<Cow<'_, str>> {
+        get(self.fields(), name)
+    }
+
+    /// Set the field called `name`, padding a short value; see
+    /// [`set_field_with`](Self::set_field_with).
+    pub fn set_field(&mut self, name: &str, value: &str) -> AchResult<()> {
+        self.set_field_with(name, value, FieldPadding::Pad)
+    }
+
+    /// Set the field called `name` to `value`, checked against its layout.
+    /// An unknown name is [`AchError::UnknownField`] and a value that does
+    /// not fit is [`AchError::InvalidValue`]; either leaves the record as it
+    /// was.
+    pub fn set_field_with(
+        &mut self,
+        name: &str,
+        value: &str,
+        padding: FieldPadding,
+    ) -> AchResult<()> {
+        let (field, value) = prepare(
+            &layout::BATCH_CONTROL,
+            "batch control",
+            name,
+            value,
+            padding,
+        )?;
+        match field.name {
+            "record_type" => self.record_type = value,
+            "service_class_code" => self.service_class_code = value,
+            "entry_addenda_count" => self.entry_addenda_count = digits(&value),
+            "entry_hash" => self.entry_hash = digits(&value),
+            "total_debit_amount" => self.total_debit_amount = digits(&value),
+            "total_credit_amount" => self.total_credit_amount = digits(&value),
+            "company_identification" => self.company_identification = value,
+            "message_authentication_code" => self.message_authentication_code = value,
+            "reserved" => self.reserved = value,
+            "originating_dfi_identification" => self.originating_dfi_identification = value,
+            "batch_number" => self.batch_number = value,
+            _ => unreachable!("{} is in the batch control layout", field.name),
+        }
+        Ok(())
+    }
+}
+
+impl FileControl {
+    /// Fields of the file control record, in position order.
+    pub fn fields(&self) -> Vec<FieldInfo<'_>> {
+        zip(
+            &layout::FILE_CONTROL,
+            [
+                text(&self.record_type),
+                number(self.batch_count, 6),
+                number(self.block_count, 6),
+                number(self.entry_addenda_count, 8),
+                number(self.entry_hash, 10),
+                number(self.total_debit_amount, 12),
+                number(self.total_credit_amount, 12),
+                text(&self.reserved),
+            ],
+        )
+    }
+
+    /// The raw value of the field called `name`, padding included, or
+    /// `None` when the file control record has no such field.
+    pub fn get_field(&self, name: &str) -> Option<Cow<'_, str>> {
+        get(self.fields(), name)
+    }
+
+    /// Set the field called `name`, padding a short value; see
+    /// [`set_field_with`](Self::set_field_with).
+    pub fn set_field(&mut self, name: &str, value: &str) -> AchResult<()> {
+        self.set_field_with(name, value, FieldPadding::Pad)
+    }
+
+    /// Set the field called `name` to `value`, checked against its layout.
+    /// An unknown name is [`AchError::UnknownField`] and a value that does
+    /// not fit is [`AchError::InvalidValue`]; either leaves the record as it
+    /// was.
+    pub fn set_field_with(
+        &mut self,
+        name: &str,
+        value: &str,
+        padding: FieldPadding,
+    ) -> AchResult<()> {
+        let (field, value) = prepare(&layout::FILE_CONTROL, "file control", name, value, padding)?;
+        match field.name {
+            "record_type" => self.record_type = value,
+            "batch_count" => self.batch_count = digits(&value),
+            "block_count" => self.block_count = digits(&value),
+            "entry_addenda_count" => self.entry_addenda_count = digits(&value),
+            "entry_hash" => self.entry_hash = digits(&value),
+            "total_debit_amount" => self.total_debit_amount = digits(&value),
+            "total_credit_amount" => self.total_credit_amount = digits(&value),
+            "reserved" => self.reserved = value,
+            _ => unreachable!("{} is in the file control layout", field.name),
+        }
+        Ok(())
+    }
+}
+
+impl Record<'_> {
+    /// The raw value of the record's field called `name`; see
+    /// [`EntryDetail::get_field`]. Records of unknown type have no fields.
+    pub fn get_field(&self, name: &str) -> Option<Cow<'_, str>> {
+        match self {
+            Record::FileHeader(record) => record.get_field(name),
+            Record::BatchHeader(record) => record.get_field(name),
+            Record::EntryDetail(record) => record.get_field(name),
+            Record::Addenda(record) => record.get_field(name),
+            Record::BatchControl(record) => record.get_field(name),
+            Record::FileControl(record) => record.get_field(name),
+            Record::Unknown(_) => None,
+        }
+    }
+
+    /// Set the record's field called `name`, padding a short value; see
+    /// [`EntryDetail::set_field_with`].
+    pub fn set_field(&mut self, name: &str, value: &str) -> AchResult<()> {
+        self.set_field_with(name, value, FieldPadding::Pad)
+    }
+
+    /// Set the record's field called `name` to `value`; see
+    /// [`EntryDetail::set_field_with`].
+    pub fn set_field_with(
+        &mut self,
+        name: &str,
+        value: &str,
+        padding: FieldPadding,
+    ) -> AchResult<()> {
+        match self {
+            Record::FileHeader(record) => record.set_field_with(name, value, padding),
+            Record::BatchHeader(record) => record.set_field_with(name, value, padding),
+            Record::EntryDetail(record) => record.set_field_with(name, value, padding),
+            Record::Addenda(record) => record.set_field_with(name, value, padding),
+            Record::BatchControl(record) => record.set_field_with(name, value, padding),
+            Record::FileControl(record) => record.set_field_with(name, value, padding),
+            Record::Unknown(_) => Err(AchError::UnknownField {
+                record_type: "unknown",
+                name: name.to_string(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AchFile, ErrorKind};
+
+    const SAMPLE: &str = concat!(
+        "101 12345678012345678011409020123A094101YOUR BANK              YOUR COMPANY                   \n",
+        "5200YOUR COMPANY                        1234567890PPDPAYROLL         140903   1123456780000001\n",
+        "62212345678011232132         0000001000               ALICE WANDERDUST        1123456780000001\n",
+        "705HERE IS SOME ADDITIONAL INFORMATION                                             00000000001\n",
+        "820000000200123456780000000000000000000010001234567890                         123456780000001\n",
+        "9000001000001000000020012345678000000000000000000001000                                       ",
+    );
+
+    #[test]
+    fn test_fields_reassemble_each_record() {
+        let file = AchFile::parse(SAMPLE).unwrap();
+        let batch = &file.batches[0];
+        let entry = &batch.entries[0];
+
+        let records = [
+            file.file_header.fields(),
+            batch.header.fields(),
+            entry.fields(),
+            entry.addenda[0].fields(),
+            batch.control.fields(),
+            file.file_control.fields(),
+        ];
+
+        for (fields, line) in records.iter().zip(SAMPLE.lines()) {
+            let mut next = 1;
+            for field in fields {
+                assert_eq!(field.start, next, "gap before {}", field.name);
+                assert_eq!(field.raw.len(), field.width(), "width of {}", field.name);
+                assert_eq!(field.raw, &line[field.start - 1..field.end]);
+                next = field.end + 1;
+            }
+            assert_eq!(next, 95);
+        }
+    }
+
+    #[test]
+    fn test_field_info() {
+        let file = AchFile::parse(SAMPLE).unwrap();
+        let fields = file.batches[0].entries[0].fields();
+        let name = fields.iter().find(|f| f.name == "individual_name").unwrap();
+
+        assert_eq!(name.label, "Individual Name");
+        assert_eq!((name.start, name.end), (55, 76));
+        assert_eq!(name.inclusion, super::InclusionRequirement::Required);
+        assert_eq!(name.raw, "ALICE WANDERDUST      ");
+        assert_eq!(name.trimmed(), "ALICE WANDERDUST");
+
+        let amount = fields.iter().find(|f| f.name == "amount").unwrap();
+        assert_eq!(amount.raw, "0000001000");
+    }
+
+    #[test]
+    fn test_get_and_set_field() {
+        let mut file = AchFile::parse(SAMPLE).unwrap();
+        let entry = &mut file.batches[0].entries[0];
+        assert_eq!(
+            entry.get_field("individual_name").unwrap(),
+            "ALICE WANDERDUST      "
+        );
+        assert_eq!(entry.get_field("nonexistent"), None);
+
+        entry.set_field("individual_name", "BOB").unwrap();
+        assert_eq!(entry.individual_name, "BOB                   ");
+        entry.set_field("amount", "000001234").unwrap();
+        assert_eq!(entry.amount, 1_234);
+        let err = entry
+            .set_field_with("transaction_code", "7", FieldPadding::Exact)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid value for 'transaction_code': \"7\" is not 2 characters"
+        );
+        assert_eq!(entry.transaction_code, "22");
+
+        let control = &mut file.batches[0].control;
+        control.set_field("total_credit_amount", "1234").unwrap();
+        assert_eq!(control.total_credit_amount, 1_234);
+        assert_eq!(
+            file.batches[0].header.get_field("batch_number").unwrap(),
+            "0000001"
+        );
+        file.batches[0]
+            .header
+            .set_field("settlement_date", "")
+            .unwrap();
+        assert_eq!(file.batches[0].header.settlement_date, "   ");
+    }
+
+    #[test]
+    fn test_set_field_errors() {
+        let mut file = AchFile::parse(SAMPLE).unwrap();
+        let header = &mut file.file_header;
+        for (name, value, reason) in [
+            (
+                "immediate_origin",
+                "12345678901",
+                "is longer than 10 characters",
+            ),
+            ("file_creation_date", "14 902", "is not all digits"),
+            ("file_creation_date", "", "is not all digits"),
+            ("immediate_origin_name", "CAFÉ", "is not ASCII"),
+        ] {
+            let err = header.set_field(name, value).unwrap_err();
+            assert!(err.to_string().ends_with(reason), "{name}: {err}");
+        }
+        let err = header.set_field("origin", "X").unwrap_err();
+        assert!(matches!(
+            err,
+            AchError::UnknownField {
+                record_type: "file header",
+                ..
+            }
+        ));
+        assert_eq!(err.kind(), ErrorKind::Field);
+        assert_eq!(
+            err.to_string(),
+            "Unknown field 'origin' in file header record"
+        );
+
+        let mut record = Record::FileControl(file.file_control.clone());
+        record.set_field("batch_count", "2").unwrap();
+        assert_eq!(record.get_field("batch_count").unwrap(), "000002");
+    }
+}
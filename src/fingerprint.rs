@@ -0,0 +1,260 @@
+//! Keyed fingerprints of receiving accounts.
+//!
+//! A fingerprint is HMAC-SHA256, keyed with a caller-provided salt, of the
+//! receiver's normalized routing and account number. The same account gives
+//! the same fingerprint across files, but without the salt it cannot be
+//! recovered by hashing candidate account numbers.
+//!
+//! SHA-256 and HMAC are implemented here rather than taken from a crate,
+//! and are checked against the FIPS 180-2 and RFC 4231 test vectors.
+
+use crate::records::EntryDetail;
+use crate::AchFile;
+
+impl EntryDetail<'_> {
+    /// HMAC-SHA256 of this entry's receiving account, keyed with `salt`.
+    ///
+    /// The message is the routing number, a `/`, then the account number:
+    ///
+    /// - routing number: the digits of the receiving DFI identification
+    ///   followed by the digits of the check digit, other characters dropped
+    /// - account number: the DFI account number with all whitespace removed
+    ///   and ASCII letters upper-cased; leading zeros are kept
+    ///
+    /// These rules are stable: a release that changes them will say so.
+    pub fn receiver_fingerprint(&self, salt: &[u8]) -> [u8; 32] {
+        let routing = self
+            .receiving_dfi_identification
+            .chars()
+            .chain(self.check_digit.chars())
+            .filter(char::is_ascii_digit);
+        let account = self
+            .dfi_account_number
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| c.to_ascii_uppercase());
+        let message: String = routing.chain(Some('/')).chain(account).collect();
+        hmac_sha256(salt, message.as_bytes())
+    }
+}
+
+impl AchFile<'_> {
+    /// The trace number and [receiver
+    /// fingerprint](EntryDetail::receiver_fingerprint) of every entry, in
+    /// file order.
+    pub fn fingerprints(&self, salt: &[u8]) -> Vec<(String, [u8; 32])> {
+        self.batches
+            .iter()
+            .flat_map(|b| &b.entries)
+            .map(|e| (e.trace_number.to_string(), e.receiver_fingerprint(salt)))
+            .collect()
+    }
+}
+
+/// HMAC (RFC 2104) over SHA-256.
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut block = [0u8; 64];
+    if key.len() > block.len() {
+        block[..32].copy_from_slice(&sha256(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner: Vec<u8> = block.iter().map(|b| b ^ 0x36).collect();
+    inner.extend_from_slice(message);
+    let mut outer: Vec<u8> = block.iter().map(|b| b ^ 0x5c).collect();
+    outer.extend_from_slice(&sha256(&inner));
+    sha256(&outer)
+}
+
+#[rustfmt::skip]
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// SHA-256 (FIPS 180-4).
+pub(crate) fn sha256(data: &[u8]) -> [u8; 32] {
+    let mut h: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+
+    let mut padded = data.to_vec();
+    padded.push(0x80);
+    while padded.len() % 64 != 56 {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    for chunk in padded.chunks_exact(64) {
+        let mut w = [0u32; 64];
+        for (i, word) in chunk.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = hh
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            hh = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (state, value) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+
+    let mut digest = [0u8; 32];
+    for (bytes, word) in digest.chunks_exact_mut(4).zip(h) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    #[test]
+    fn test_known_digests() {
+        assert_eq!(
+            hex(&sha256(b"abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        // RFC 4231, test case 2.
+        assert_eq!(
+            hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_sha256_vectors() {
+        // FIPS 180-2 examples: empty, two-block, and long messages.
+        for (message, digest) in [
+            (
+                "".to_string(),
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq".to_string(),
+                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+            ),
+            (
+                "abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmn\
+                 hijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu"
+                    .to_string(),
+                "cf5b16a778af8380036ce59e7b0492370b249b11e8f07a51afac45037afee9d1",
+            ),
+            (
+                "a".repeat(1_000_000),
+                "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0",
+            ),
+            // Either side of where the length no longer fits the block.
+            (
+                "a".repeat(55),
+                "9f4390f8d30c2dd92ec9f095b65e2b9ae9b0a925a5258e241c9f1e910f734318",
+            ),
+            (
+                "a".repeat(56),
+                "b35439a4ac6f0948b6d6f9e3c6af0f5f590ce20f1bde7090ef7970686ec6738a",
+            ),
+            (
+                "a".repeat(64),
+                "ffe054fe7ae0cb6dc65c3af9b61d5209f439851db43d0ba5997337df154668eb",
+            ),
+        ] {
+            assert_eq!(
+                hex(&sha256(message.as_bytes())),
+                digest,
+                "{}",
+                message.len()
+            );
+        }
+    }
+
+    #[test]
+    fn test_hmac_long_key() {
+        // RFC 4231, test cases 6 and 7: a key longer than the block is
+        // hashed first.
+        let key = [0xaa; 131];
+        assert_eq!(
+            hex(&hmac_sha256(
+                &key,
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            )),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+        assert_eq!(
+            hex(&hmac_sha256(
+                &key,
+                b"This is a test using a larger than block-size key and a larger \
+                  than block-size data. The key needs to be hashed before being \
+                  used by the HMAC algorithm."
+            )),
+            "9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2"
+        );
+    }
+
+    #[test]
+    fn test_receiver_fingerprint() {
+        let text = fixtures::sample_ppd_file(2);
+        let file = AchFile::parse(&text).unwrap();
+        let entry = &file.batches[0].entries[0];
+        assert_eq!(
+            entry.receiver_fingerprint(b"salt"),
+            hmac_sha256(b"salt", b"123456780/000100000")
+        );
+
+        // Spacing and case do not change the fingerprint; the salt does.
+        let mut respaced = entry.clone();
+        respaced.dfi_account_number = " 000 100000      ".into();
+        assert_eq!(
+            respaced.receiver_fingerprint(b"salt"),
+            entry.receiver_fingerprint(b"salt")
+        );
+        assert_ne!(
+            entry.receiver_fingerprint(b"pepper"),
+            entry.receiver_fingerprint(b"salt")
+        );
+
+        let fingerprints = file.fingerprints(b"salt");
+        assert_eq!(fingerprints.len(), 2);
+        assert_eq!(fingerprints[0].0, "123456780000001");
+        assert_ne!(fingerprints[0].1, fingerprints[1].1);
+    }
+}
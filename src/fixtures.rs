@@ -0,0 +1,753 @@
+//! Generators for NACHA fixture files.
+//!
+//! Hand-writing 94-character records is error-prone, so these functions
+//! produce complete files whose controls, entry hashes, trace numbers, and
+//! block padding are all consistent. [`corrupt`] then breaks a sample in one
+//! specific way for exercising error paths.
+//!
+//! ```
+//! use rs_ach::fixtures::{self, Corruption};
+//! use rs_ach::{AchError, AchFile};
+//!
+//! let text = fixtures::sample_ppd_file(3);
+//! assert_eq!(AchFile::parse(&text).unwrap().batches[0].entries.len(), 3);
+//!
+//! let broken = fixtures::corrupt(&text, Corruption::BadAmount);
+//! let err = AchFile::parse(&broken).unwrap_err();
+//! assert!(matches!(err.root_cause(), AchError::InvalidField { .. }));
+//! ```
+
+use std::borrow::Cow;
+
+use crate::records::*;
+use crate::writer;
+use crate::{AchFile, Batch, BatchBuilder, Direction, SimpleEntry};
+
+/// Routing numbers with valid check digits, cycled through by the generators.
+const ROUTING_NUMBERS: &[&str] = &["123456780", "123232315", "021000021", "011000015"];
+
+/// A deliberate defect introduced by [`corrupt`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Corruption {
+    /// Non-digit characters in the first entry's amount field.
+    BadAmount,
+
+    /// The first entry record cut to 93 characters.
+    ShortLine,
+
+    /// An unknown record type code on the first entry record.
+    BadRecordType,
+
+    /// The first batch control record removed.
+    MissingBatchControl,
+
+    /// The file control record and block padding removed.
+    MissingFileControl,
+
+    /// The first batch control's total credit amount increased by one cent.
+    /// The file still parses; only control validation catches it.
+    TotalsMismatch,
+}
+
+/// A PPD file with one batch of `entries` entries, alternating credits and
+/// debits starting with a credit.
+///
+/// # Panics
+///
+/// Panics if `entries` exceeds the 9,999,999 trace numbers a batch can hold.
+pub fn sample_ppd_file(entries: usize) -> String {
+    let mut builder = batch_builder("PPD", "PAYROLL");
+    for i in 0..entries {
+        builder
+            .add_entry(SimpleEntry {
+                routing_number: ROUTING_NUMBERS[i % ROUTING_NUMBERS.len()].to_string(),
+                account_number: format!("{:09}", 100_000 + i),
+                amount: (i as u64 + 1) * 1_250,
+                individual_name: format!("RECEIVER {}", i + 1),
+                identification: None,
+                direction: if i % 2 == 0 {
+                    Direction::Credit
+                } else {
+                    Direction::Debit
+                },
+            })
+            .expect("fixture entry is valid");
+    }
+    let batch = builder.build().expect("fixture batch is valid");
+    render(vec![batch])
+}
+
+/// A CTX file with one corporate credit carrying three addenda of EDI data.
+pub fn sample_ctx_with_addenda() -> String {
+    const EDI: [&str; 3] = [
+        "ISA*00*          *00*          *ZZ*YOURCOMPANY    *ZZ*ACMECORP       *140903~",
+        "BPR*C*1250.00*C*ACH*CTX*01*123456780*DA*1234567890*140903~TRN*1*0001~",
+        "RMR*IV*INV-1001**1250.00~SE*5*0001~",
+    ];
+
+    let mut builder = batch_builder("CTX", "VENDOR PAY");
+    builder
+        .add_entry(SimpleEntry {
+            routing_number: "021000021".to_string(),
+            account_number: "987654321".to_string(),
+            amount: 125_000,
+            individual_name: "0000".to_string(),
+            identification: Some("INV-1001".to_string()),
+            direction: Direction::Credit,
+        })
+        .expect("fixture entry is valid");
+    let mut batch = builder.build().expect("fixture batch is valid");
+
+    let entry = &mut batch.entries[0];
+    // CTX: addenda count in positions 55-58, receiving company in 59-74.
+    entry.individual_name = Cow::Owned(format!("{:04}{:<16}  ", EDI.len(), "ACME CORP"));
+    entry.addenda_record_indicator = Cow::Borrowed("1");
+    let sequence = entry.trace_number[8..].to_string();
+    entry.addenda = EDI
+        .iter()
+        .enumerate()
+        .map(|(i, segment)| Addenda {
+            record_type: Cow::Borrowed("7"),
+            addenda_type_code: Cow::Borrowed("05"),
+            payment_related_information: Cow::Owned(format!("{segment:<80}")),
+            addenda_sequence_number: Cow::Owned(format!("{:04}", i + 1)),
+            entry_detail_sequence_number: Cow::Owned(sequence.clone()),
+        })
+        .collect();
+
+    recompute(&mut batch);
+    render(vec![batch])
+}
+
+/// A file of returned entries: an R01 return of a debit (transaction code
+/// 26) and an R03 return of a credit (transaction code 21), each with its
+/// type 99 addenda.
+pub fn sample_returns_file() -> String {
+    #[rustfmt::skip]
+    let returns = [
+        ("26", "R01", "123456780", "000000001", 15_000, "BILLY HOLIDAY"),
+        ("21", "R03", "021000021", "000000002", 1_000, "ALICE WANDERDUST"),
+    ];
+
+    let mut builder = batch_builder("PPD", "PAYROLL");
+    for &(_, _, routing, account, amount, name) in &returns {
+        builder
+            .add_entry(SimpleEntry {
+                routing_number: routing.to_string(),
+                account_number: account.to_string(),
+                amount,
+                individual_name: name.to_string(),
+                identification: None,
+                direction: Direction::Credit,
+            })
+            .expect("fixture entry is valid");
+    }
+    let mut batch = builder.build().expect("fixture batch is valid");
+
+    for (i, (entry, &(code, reason, routing, ..))) in
+        batch.entries.iter_mut().zip(&returns).enumerate()
+    {
+        entry.transaction_code = Cow::Borrowed(code);
+        entry.addenda_record_indicator = Cow::Borrowed("1");
+        let original_trace = format!("09101298{:07}", i + 1);
+        // Type 99: reason code, original trace, date of death, original
+        // RDFI, addenda information, then the return entry's trace number.
+        let line = format!(
+            "799{reason}{original_trace}{:6}{}{:44}{}",
+            "",
+            &routing[..8],
+            "",
+            entry.trace_number
+        );
+        entry.addenda = vec![Addenda {
+            record_type: Cow::Borrowed("7"),
+            addenda_type_code: Cow::Borrowed("99"),
+            payment_related_information: Cow::Owned(line[3..83].to_string()),
+            addenda_sequence_number: Cow::Owned(line[83..87].to_string()),
+            entry_detail_sequence_number: Cow::Owned(line[87..94].to_string()),
+        }];
+    }
+
+    recompute(&mut batch);
+    render(vec![batch])
+}
+
+/// A COR file of Notifications of Change: a C01 correcting the account
+/// number of a credit (transaction code 21) and a C05 correcting the
+/// transaction code of a debit (transaction code 26), each a zero-dollar
+/// entry with its type 98 addenda.
+pub fn sample_noc_file() -> String {
+    #[rustfmt::skip]
+    let notices = [
+        ("21", "C01", "123456780", "000000001", "1918171614", "BILLY HOLIDAY"),
+        ("26", "C05", "021000021", "000000002", "32", "ALICE WANDERDUST"),
+    ];
+
+    let mut builder = batch_builder("COR", "NOC");
+    for &(_, _, routing, account, _, name) in &notices {
+        builder
+            .add_prenote(SimpleEntry {
+                routing_number: routing.to_string(),
+                account_number: account.to_string(),
+                amount: 0,
+                individual_name: name.to_string(),
+                identification: None,
+                direction: Direction::Credit,
+            })
+            .expect("fixture entry is valid");
+    }
+    let mut batch = builder.build().expect("fixture batch is valid");
+
+    for (i, (entry, &(code, change, routing, _, corrected, _))) in
+        batch.entries.iter_mut().zip(&notices).enumerate()
+    {
+        entry.transaction_code = Cow::Borrowed(code);
+        entry.addenda_record_indicator = Cow::Borrowed("1");
+        let original_trace = format!("09101298{:07}", i + 1);
+        // Type 98: change code, original trace, reserved, original RDFI,
+        // corrected data, reserved, then the notification's trace number.
+        let line = format!(
+            "798{change}{original_trace}{:6}{}{corrected:<29}{:15}{}",
+            "",
+            &routing[..8],
+            "",
+            entry.trace_number
+        );
+        entry.addenda = vec![Addenda {
+            record_type: Cow::Borrowed("7"),
+            addenda_type_code: Cow::Borrowed("98"),
+            payment_related_information: Cow::Owned(line[3..83].to_string()),
+            addenda_sequence_number: Cow::Owned(line[83..87].to_string()),
+            entry_detail_sequence_number: Cow::Owned(line[87..94].to_string()),
+        }];
+    }
+
+    recompute(&mut batch);
+    render(vec![batch])
+}
+
+/// An IAT file of one batch sending a USD salary payment to Germany: one
+/// credit with its seven mandatory addenda (types 10-16), a remittance
+/// addenda (17), and a foreign correspondent bank (18).
+pub fn sample_iat_file() -> String {
+    let mut builder = batch_builder("IAT", "PAYROLL");
+    builder
+        .add_entry(SimpleEntry {
+            routing_number: ROUTING_NUMBERS[2].to_string(),
+            account_number: "0".to_string(),
+            amount: 100_000,
+            individual_name: "HANS MUELLER".to_string(),
+            identification: None,
+            direction: Direction::Credit,
+        })
+        .expect("fixture entry is valid");
+    let mut batch = builder.build().expect("fixture batch is valid");
+
+    // IAT header: no IAT indicator, fixed-to-variable exchange at a rate of
+    // 0.91, destination Germany, USD to EUR.
+    let header = &mut batch.header;
+    header.company_name = Cow::Owned(" ".repeat(16));
+    header.company_discretionary_data = Cow::Owned(format!("FV1{:<15}DE", "0.91"));
+    header.company_descriptive_date = Cow::Borrowed("USDEUR");
+
+    let entry = &mut batch.entries[0];
+    let sequence = entry.trace_number[8..].to_string();
+    #[rustfmt::skip]
+    let lines = [
+        format!("710SAL{:018}{:22}{:<35}{:6}", 91_000, "", "HANS MUELLER", ""),
+        format!("711{:<35}{:<35}{:14}", "ACME CORP", "123 MAIN STREET", ""),
+        format!("712{:<35}{:<35}{:14}", "NEW YORK*NY\\", "US*10001\\", ""),
+        format!("713{:<35}01{:<34}US {:10}", "FIRST NATIONAL BANK", "123456780", ""),
+        format!("714{:<35}02{:<34}DE {:10}", "DEUTSCHE BANK", "DEUTDEFF", ""),
+        format!("715{:15}{:<35}{:34}", "", "HAUPTSTRASSE 1", ""),
+        format!("716{:<35}{:<35}{:14}", "FRANKFURT*HE\\", "DE*60311\\", ""),
+        format!("717{:<80}0001", "INVOICE 42 SALARY AUGUST"),
+        format!("718{:<35}02{:<34}US {:6}0001", "CORRESPONDENT BANK", "CORRUS33", ""),
+    ];
+    // IAT entry: the addenda count in positions 13-16, the foreign account
+    // in 40-74, and the OFAC screening indicators in 77-78.
+    let account = format!("{:<35}", "DE89370400440532013000");
+    entry.dfi_account_number = Cow::Owned(format!("{:04}{:13}", lines.len(), ""));
+    entry.individual_identification_number = Cow::Owned(account[..15].to_string());
+    entry.individual_name = Cow::Owned(format!("{}  ", &account[15..]));
+    entry.discretionary_data = Cow::Borrowed("00");
+    entry.addenda_record_indicator = Cow::Borrowed("1");
+    entry.addenda = lines
+        .iter()
+        .map(|line| addenda(format!("{line}{sequence}")))
+        .collect();
+
+    recompute(&mut batch);
+    render(vec![batch])
+}
+
+/// A POS file of two card purchases debited to the cardholders' accounts,
+/// each with its type 02 terminal addenda.
+pub fn sample_pos_file() -> String {
+    #[rustfmt::skip]
+    let purchases = [
+        (4_215, "JANE DOE", "", "", "T00102", "000731", "0902", "A1B2C3", "MAIN ST MARKET", "SPRINGFIELD", "IL"),
+        (12_999, "JOHN SMITH", "4012", "", "T00417", "000912", "0903", "A7731Q", "4400 LINCOLN AVE", "CHICAGO", "IL"),
+    ];
+
+    let mut builder = batch_builder("POS", "PURCHASE");
+    for (i, &(amount, name, ..)) in purchases.iter().enumerate() {
+        builder
+            .add_entry(SimpleEntry {
+                routing_number: ROUTING_NUMBERS[i % ROUTING_NUMBERS.len()].to_string(),
+                account_number: format!("{:09}", 500_000 + i),
+                amount,
+                individual_name: name.to_string(),
+                identification: None,
+                direction: Direction::Debit,
+            })
+            .expect("fixture entry is valid");
+    }
+    let mut batch = builder.build().expect("fixture batch is valid");
+
+    for (entry, &(_, _, ref1, ref2, terminal, serial, date, auth, location, city, state)) in
+        batch.entries.iter_mut().zip(&purchases)
+    {
+        entry.addenda_record_indicator = Cow::Borrowed("1");
+        // Type 02: references, terminal, serial number, MMDD date,
+        // authorization code, terminal location, city, and state, then the
+        // entry's trace number.
+        let line = format!(
+            "702{ref1:<7}{ref2:<3}{terminal:<6}{serial:<6}{date}{auth:<6}{location:<27}{city:<15}{state:<2}{}",
+            entry.trace_number
+        );
+        entry.addenda = vec![addenda(line)];
+    }
+
+    recompute(&mut batch);
+    render(vec![batch])
+}
+
+/// An ADV file from the ACH operator: one batch of service class 280 with a
+/// credit advice of $1,234,567,890.12, whose amount needs all twelve digits
+/// of the ADV layout, and a debit advice.
+pub fn sample_adv_file() -> String {
+    #[rustfmt::skip]
+    let advices = [
+        ("81", "12345678", "0", "4100001234", 123_456_789_012_u64, "0001"),
+        ("82", "02100002", "1", "4100005678", 45_678_900, "0002"),
+    ];
+
+    let mut lines = vec![
+        writer::render_record(&file_header().fields()).expect("fixture header fits its fields")
+    ];
+    lines.push(format!(
+        "5280{:<16}{:20}{:<10}ADV{:<10}{:6}140903{:3}012345678{:07}",
+        "YOUR BANK", "", "1234567890", "ACH SETTLE", "", "", 1
+    ));
+    // ADV entry: account in positions 13-27, a 12-digit amount in 28-39,
+    // advice routing number, file identification, and ACH operator data
+    // in 40-54, then the name, and the operator's routing number, Julian
+    // creation date, and sequence number in 80-94.
+    for &(code, rdfi, check, account, amount, sequence) in &advices {
+        lines.push(format!(
+            "6{code}{rdfi}{check}{account:<15}{amount:012}1234567800902A0{:<22}  009101298246{sequence}",
+            "YOUR COMPANY"
+        ));
+    }
+
+    let count = advices.len();
+    let hash: u64 = advices.iter().map(|a| a.1.parse::<u64>().unwrap()).sum();
+    let total = |code: &str| -> u64 { advices.iter().filter(|a| a.0 == code).map(|a| a.4).sum() };
+    let (debit, credit) = (total("82"), total("81"));
+    lines.push(format!(
+        "8280{count:06}{hash:010}{debit:020}{credit:020}{:19}12345678{:07}",
+        "", 1
+    ));
+    lines.push(format!(
+        "9{:06}{:06}{count:08}{hash:010}{debit:020}{credit:020}{:23}",
+        1, 1, ""
+    ));
+    writer::pad_block(&mut lines, 10);
+
+    let mut text = lines.join("\n");
+    text.push('\n');
+    text
+}
+
+/// A file from two originating companies across three batches: ACME CORP
+/// (`1111111111`) with a PPD payroll batch and a CCD vendor batch, and BETA
+/// LLC (`2222222222`) with a PPD batch of debits.
+pub fn sample_multi_company_file() -> String {
+    #[rustfmt::skip]
+    let batches = [
+        ("ACME CORP", "1111111111", "PPD", "PAYROLL", &[(250_000, Direction::Credit), (180_000, Direction::Credit)][..]),
+        ("ACME CORP", "1111111111", "CCD", "VENDOR PAY", &[(900_000, Direction::Credit), (5_000, Direction::Debit)][..]),
+        ("BETA LLC", "2222222222", "PPD", "DUES", &[(4_500, Direction::Debit), (4_500, Direction::Debit), (12_000, Direction::Debit)][..]),
+    ];
+
+    let batches = batches
+        .iter()
+        .enumerate()
+        .map(|(i, &(name, id, sec, description, entries))| {
+            let mut builder = BatchBuilder::new(name, id, sec, description, "12345678");
+            builder.effective_entry_date("140903");
+            builder.batch_number(i as u64 + 1);
+            for (j, &(amount, direction)) in entries.iter().enumerate() {
+                builder
+                    .add_entry(SimpleEntry {
+                        routing_number: ROUTING_NUMBERS[j % ROUTING_NUMBERS.len()].to_string(),
+                        account_number: format!("{:09}", 200_000 + 10 * i + j),
+                        amount,
+                        individual_name: format!("RECEIVER {}", j + 1),
+                        identification: None,
+                        direction,
+                    })
+                    .expect("fixture entry is valid");
+            }
+            builder.build().expect("fixture batch is valid")
+        })
+        .collect();
+    render(batches)
+}
+
+/// A file with no batches: the file header and a file control of zero
+/// counts, padded to one block. Some operators send these as end-of-day
+/// markers on days without activity.
+pub fn sample_empty_file() -> String {
+    render(Vec::new())
+}
+
+/// Introduce `corruption` into a fixture produced by this module.
+///
+/// # Panics
+///
+/// Panics if `sample` lacks the record the corruption targets (for example,
+/// [`Corruption::BadAmount`] on a file with no entries).
+pub fn corrupt(sample: &str, corruption: Corruption) -> String {
+    let mut lines: Vec<String> = sample.lines().map(String::from).collect();
+    let find = |lines: &[String], record_type: char| -> usize {
+        lines
+            .iter()
+            .position(|l| l.starts_with(record_type) && !l.chars().all(|c| c == '9'))
+            .unwrap_or_else(|| panic!("sample has no type {record_type} record"))
+    };
+
+    match corruption {
+        Corruption::BadAmount => {
+            let i = find(&lines, '6');
+            lines[i].replace_range(37..39, "XX");
+        }
+        Corruption::ShortLine => {
+            let i = find(&lines, '6');
+            lines[i].truncate(93);
+        }
+        Corruption::BadRecordType => {
+            let i = find(&lines, '6');
+            lines[i].replace_range(0..1, "X");
+        }
+        Corruption::MissingBatchControl => {
+            let i = find(&lines, '8');
+            lines.remove(i);
+        }
+        Corruption::MissingFileControl => {
+            let i = find(&lines, '9');
+            lines.truncate(i);
+        }
+        Corruption::TotalsMismatch => {
+            let i = find(&lines, '8');
+            let total: u64 = lines[i][32..44].parse().expect("numeric credit total");
+            let bumped = format!("{:012}", total + 1);
+            lines[i].replace_range(32..44, &bumped);
+        }
+    }
+
+    let mut text = lines.join("\n");
+    text.push('\n');
+    text
+}
+
+/// `sample` with each line ended by `ending`, such as `"\r\n"` as written
+/// on Windows or a bare `"\r"`.
+pub fn with_line_ending(sample: &str, ending: &str) -> String {
+    sample
+        .lines()
+        .map(|line| format!("{line}{ending}"))
+        .collect()
+}
+
+/// `sample` starting with a UTF-8 byte order mark, as some Windows tools
+/// save text.
+pub fn with_bom(sample: &str) -> String {
+    format!("\u{feff}{sample}")
+}
+
+/// An addenda record from its 94-character line.
+fn addenda(line: String) -> Addenda<'static> {
+    assert_eq!(line.len(), 94, "fixture addenda {line:?}");
+    Addenda {
+        record_type: Cow::Owned(line[..1].to_string()),
+        addenda_type_code: Cow::Owned(line[1..3].to_string()),
+        payment_related_information: Cow::Owned(line[3..83].to_string()),
+        addenda_sequence_number: Cow::Owned(line[83..87].to_string()),
+        entry_detail_sequence_number: Cow::Owned(line[87..94].to_string()),
+    }
+}
+
+fn batch_builder(sec: &str, description: &str) -> BatchBuilder {
+    let mut builder = BatchBuilder::new("YOUR COMPANY", "1234567890", sec, description, "12345678");
+    builder.effective_entry_date("140903");
+    builder
+}
+
+/// Recompute a batch's controls and service class after its entries were
+/// edited.
+fn recompute(batch: &mut Batch<'static>) {
+    batch.recompute_control();
+    let service_class_code = match (
+        batch.control.total_credit_amount > 0,
+        batch.control.total_debit_amount > 0,
+    ) {
+        (true, false) => "220",
+        (false, true) => "225",
+        _ => "200",
+    };
+    batch.header.service_class_code = Cow::Borrowed(service_class_code);
+    batch.control.service_class_code = service_class_code.to_string();
+}
+
+/// Wrap batches in the sample file header and a matching file control, then
+/// render them padded to a whole block of ten records.
+fn render(batches: Vec<Batch<'static>>) -> String {
+    AchFile::from_batches(file_header(), batches)
+        .to_ach_string()
+        .expect("fixture records fit their fields")
+}
+
+fn file_header() -> FileHeader<'static> {
+    FileHeader {
+        record_type: Cow::Borrowed("1"),
+        priority_code: Cow::Borrowed("01"),
+        immediate_destination: Cow::Borrowed(" 123456780"),
+        immediate_origin: Cow::Borrowed("1234567801"),
+        file_creation_date: Cow::Borrowed("140902"),
+        file_creation_time: Cow::Borrowed("0123"),
+        file_id_modifier: Cow::Borrowed("A"),
+        record_size: Cow::Borrowed("094"),
+        blocking_factor: Cow::Borrowed("10"),
+        format_code: Cow::Borrowed("1"),
+        immediate_destination_name: Cow::Borrowed("YOUR BANK              "),
+        immediate_origin_name: Cow::Borrowed("YOUR COMPANY           "),
+        reference_code: Cow::Borrowed("        "),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{writer, AchError};
+
+    fn assert_consistent(file: &AchFile<'_>) {
+        let mut debit = 0;
+        let mut credit = 0;
+        for batch in &file.batches {
+            let count: u64 = batch
+                .entries
+                .iter()
+                .map(|e| 1 + e.addenda.len() as u64)
+                .sum();
+            assert_eq!(batch.control.entry_addenda_count, count);
+            debit += batch.control.total_debit_amount;
+            credit += batch.control.total_credit_amount;
+            for entry in &batch.entries {
+                assert!(entry
+                    .trace_number
+                    .starts_with(batch.header.originating_dfi_identification.as_ref()));
+            }
+        }
+        assert_eq!(file.file_control.total_debit_amount, debit);
+        assert_eq!(file.file_control.total_credit_amount, credit);
+        assert_eq!(file.file_control.batch_count, file.batches.len() as u64);
+    }
+
+    #[test]
+    fn test_sample_ppd_file() {
+        let text = sample_ppd_file(5);
+        assert!(text.lines().all(|l| l.len() == writer::RECORD_LENGTH));
+        assert_eq!(text.lines().count(), 10);
+
+        let file = AchFile::parse(&text).unwrap();
+        assert_consistent(&file);
+        let batch = &file.batches[0];
+        assert_eq!(batch.entries.len(), 5);
+        assert_eq!(batch.header.service_class_code, "200");
+        assert_eq!(batch.entries[1].transaction_code, "27");
+        assert_eq!(batch.control.total_credit_amount, 1_250 + 3_750 + 6_250);
+        assert_eq!(batch.control.total_debit_amount, 2_500 + 5_000);
+        assert_eq!(file.file_control.block_count, 1);
+    }
+
+    #[test]
+    fn test_sample_ppd_file_spans_blocks() {
+        let text = sample_ppd_file(20);
+        assert_eq!(text.lines().count(), 30);
+        let file = AchFile::parse(&text).unwrap();
+        assert_consistent(&file);
+        assert_eq!(file.file_control.block_count, 3);
+
+        let text = sample_ppd_file(0);
+        let file = AchFile::parse(&text).unwrap();
+        assert!(file.batches[0].entries.is_empty());
+    }
+
+    #[test]
+    fn test_sample_ctx_with_addenda() {
+        let text = sample_ctx_with_addenda();
+        let file = AchFile::parse(&text).unwrap();
+        assert_consistent(&file);
+        let entry = &file.batches[0].entries[0];
+        assert_eq!(file.batches[0].header.standard_entry_class_code, "CTX");
+        assert_eq!(&entry.individual_name[..4], "0003");
+        assert_eq!(entry.addenda.len(), 3);
+        assert_eq!(entry.addenda[2].addenda_sequence_number, "0003");
+        assert_eq!(entry.addenda[0].entry_detail_sequence_number, "0000001");
+        assert_eq!(file.batches[0].control.entry_addenda_count, 4);
+    }
+
+    #[test]
+    fn test_sample_returns_file() {
+        let text = sample_returns_file();
+        let file = AchFile::parse(&text).unwrap();
+        assert_consistent(&file);
+        let batch = &file.batches[0];
+        assert_eq!(batch.entries[0].transaction_code, "26");
+        assert_eq!(batch.entries[1].transaction_code, "21");
+        assert_eq!(batch.control.total_debit_amount, 15_000);
+        assert_eq!(batch.control.total_credit_amount, 1_000);
+
+        let addenda = &batch.entries[0].addenda[0];
+        assert_eq!(addenda.addenda_type_code, "99");
+        assert_eq!(&addenda.payment_related_information[..3], "R01");
+        let line = writer::render_record(&addenda.fields()).unwrap();
+        assert_eq!(&line[79..94], batch.entries[0].trace_number);
+    }
+
+    #[test]
+    fn test_sample_noc_file() {
+        let text = sample_noc_file();
+        let file = AchFile::parse(&text).unwrap();
+        assert_consistent(&file);
+        let batch = &file.batches[0];
+        assert_eq!(batch.header.standard_entry_class_code, "COR");
+        assert_eq!(batch.entries[1].transaction_code, "26");
+        assert_eq!(batch.control.total_credit_amount, 0);
+
+        let addenda = &batch.entries[1].addenda[0];
+        assert_eq!(addenda.addenda_type_code, "98");
+        assert_eq!(&addenda.payment_related_information[..3], "C05");
+        let line = writer::render_record(&addenda.fields()).unwrap();
+        assert_eq!(&line[35..37], "32");
+        assert_eq!(&line[79..94], batch.entries[1].trace_number);
+    }
+
+    #[test]
+    fn test_sample_iat_file() {
+        let text = sample_iat_file();
+        let file = AchFile::parse(&text).unwrap();
+        assert_consistent(&file);
+        let entry = &file.batches[0].entries[0];
+        assert_eq!(file.batches[0].header.standard_entry_class_code, "IAT");
+        assert_eq!(entry.addenda.len(), 9);
+        assert!(entry
+            .addenda
+            .iter()
+            .all(|a| a.entry_detail_sequence_number == entry.trace_number[8..]));
+        assert!(file.validate().is_valid());
+    }
+
+    #[test]
+    fn test_sample_pos_file() {
+        let text = sample_pos_file();
+        let file = AchFile::parse(&text).unwrap();
+        assert_consistent(&file);
+        let batch = &file.batches[0];
+        assert_eq!(batch.header.service_class_code, "225");
+        for entry in &batch.entries {
+            assert_eq!(entry.addenda[0].addenda_type_code, "02");
+            let line = writer::render_record(&entry.addenda[0].fields()).unwrap();
+            assert_eq!(&line[79..94], entry.trace_number);
+        }
+        assert!(file.validate().is_valid());
+    }
+
+    #[test]
+    fn test_sample_adv_file() {
+        let text = sample_adv_file();
+        assert!(text.lines().all(|line| line.len() == 94));
+        let file = AchFile::parse(&text).unwrap();
+        let batch = &file.batches[0];
+        assert_eq!(batch.header.standard_entry_class_code, "ADV");
+        assert_eq!(batch.header.service_class_code, "280");
+        assert_eq!(batch.entries.len(), 2);
+        assert_eq!(file.validate().issues, []);
+    }
+
+    #[test]
+    fn test_sample_multi_company_file() {
+        let text = sample_multi_company_file();
+        let file = AchFile::parse(&text).unwrap();
+        assert_consistent(&file);
+        assert_eq!(file.batches.len(), 3);
+        assert_eq!(file.batches[1].header.company_identification, "1111111111");
+        assert_eq!(file.batches[2].header.batch_number, "0000003");
+        assert_eq!(file.batches[2].header.service_class_code, "225");
+    }
+
+    #[test]
+    fn test_sample_empty_file() {
+        let text = sample_empty_file();
+        assert_eq!(text.lines().count(), 10);
+        assert!(text.lines().skip(2).all(|l| l == "9".repeat(94)));
+
+        let file = AchFile::parse(&text).unwrap();
+        assert_consistent(&file);
+        assert!(file.batches.is_empty());
+        let control = &file.file_control;
+        assert_eq!(
+            (
+                control.batch_count,
+                control.entry_addenda_count,
+                control.entry_hash
+            ),
+            (0, 0, 0)
+        );
+        assert_eq!(control.block_count, 1);
+        assert_eq!(
+            writer::render_lines(&file).unwrap(),
+            text.lines().take(2).collect::<Vec<_>>()
+        );
+        assert_eq!(AchFile::records(&text).count(), 2);
+    }
+
+    #[test]
+    fn test_corrupt() {
+        let sample = sample_ppd_file(2);
+        let parse = |c| AchFile::parse(&corrupt(&sample, c)).map(|_| ());
+
+        assert!(matches!(
+            parse(Corruption::BadAmount).unwrap_err().root_cause(),
+            AchError::InvalidField {
+                field: "amount",
+                line: 3,
+                ..
+            }
+        ));
+        assert!(matches!(
+            parse(Corruption::ShortLine).unwrap_err().root_cause(),
+            AchError::InvalidLineLength(93)
+        ));
+        assert!(parse(Corruption::BadRecordType).is_err());
+        assert!(parse(Corruption::MissingBatchControl).is_err());
+        assert!(parse(Corruption::MissingFileControl).is_err());
+
+        let text = corrupt(&sample, Corruption::TotalsMismatch);
+        let file = AchFile::parse(&text).unwrap();
+        assert_eq!(file.batches[0].control.total_credit_amount, 1_251);
+        assert_eq!(file.file_control.total_credit_amount, 1_250);
+    }
+}
@@ -0,0 +1,139 @@
+//! Recognition of federal government payments by their batch headers.
+//!
+//! Treasury disbursements follow naming conventions such as a company name
+//! of `IRS TREAS 310`. [`classify_government`] tries the rows of a pattern
+//! table in order, then falls back to the originator status code, which is
+//! `2` for federal agencies.
+//!
+//! ```
+//! use rs_ach::government::{classify_government, GovPaymentKind};
+//! use rs_ach::{fixtures, AchFile};
+//!
+//! let text = fixtures::sample_ppd_file(1);
+//! let mut file = AchFile::parse(&text).unwrap();
+//! let header = &mut file.batches[0].header;
+//! assert_eq!(classify_government(header), None);
+//!
+//! header.company_name = "SSA  TREAS 310".into();
+//! assert_eq!(classify_government(header), Some(GovPaymentKind::SocialSecurity));
+//! ```
+
+use crate::records::BatchHeader;
+
+/// A kind of federal government payment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub enum GovPaymentKind {
+    /// IRS tax refund
+    IrsRefund,
+
+    /// Social Security or Supplemental Security Income benefit
+    SocialSecurity,
+
+    /// Department of Veterans Affairs benefit
+    Veterans,
+
+    /// Any other federal payment
+    OtherFederal,
+}
+
+/// Pattern rows: company name, company entry description, and kind. An
+/// empty pattern matches anything; otherwise the normalized field must
+/// contain it. The first matching row wins.
+#[rustfmt::skip]
+const PATTERNS: &[(&str, &str, GovPaymentKind)] = &[
+    ("IRS TREAS",  "",         GovPaymentKind::IrsRefund),
+    ("TREAS",      "TAX REF",  GovPaymentKind::IrsRefund),
+    ("SSA TREAS",  "",         GovPaymentKind::SocialSecurity),
+    ("SSI TREAS",  "",         GovPaymentKind::SocialSecurity),
+    ("TREAS",      "SOC SEC",  GovPaymentKind::SocialSecurity),
+    ("VACP TREAS", "",         GovPaymentKind::Veterans),
+    ("VA BENEFIT", "",         GovPaymentKind::Veterans),
+    ("TREAS",      "VA BENEF", GovPaymentKind::Veterans),
+    ("TREAS 310",  "",         GovPaymentKind::OtherFederal),
+    ("TREAS 449",  "",         GovPaymentKind::OtherFederal),
+];
+
+/// Originator status code of federal government agencies.
+const FEDERAL_ORIGINATOR_STATUS: &str = "2";
+
+/// Classify a batch as a kind of federal payment, or `None` if it does not
+/// look like one.
+///
+/// Names and descriptions are compared in upper case with runs of spaces
+/// collapsed, so `IRS  TREAS 310` matches `IRS TREAS`.
+pub fn classify_government(header: &BatchHeader<'_>) -> Option<GovPaymentKind> {
+    let name = normalize(&header.company_name);
+    let description = normalize(&header.company_entry_description);
+    PATTERNS
+        .iter()
+        .find(|(name_pattern, description_pattern, _)| {
+            name.contains(name_pattern) && description.contains(description_pattern)
+        })
+        .map(|&(_, _, kind)| kind)
+        .or_else(|| {
+            (header.originator_status_code.trim() == FEDERAL_ORIGINATOR_STATUS)
+                .then_some(GovPaymentKind::OtherFederal)
+        })
+}
+
+/// Upper case with whitespace runs collapsed to one space.
+fn normalize(field: &str) -> String {
+    field
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_ascii_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, AchFile};
+
+    fn classify(name: &str, description: &str, status: &str) -> Option<GovPaymentKind> {
+        let text = fixtures::sample_ppd_file(1);
+        let mut file = AchFile::parse(&text).unwrap();
+        let header = &mut file.batches[0].header;
+        header.company_name = name.to_string().into();
+        header.company_entry_description = description.to_string().into();
+        header.originator_status_code = status.to_string().into();
+        classify_government(header)
+    }
+
+    #[test]
+    fn test_pattern_table() {
+        use GovPaymentKind::*;
+
+        assert_eq!(classify("IRS TREAS 310", "TAX REF", "1"), Some(IrsRefund));
+        assert_eq!(classify("irs  treas 310", "", "1"), Some(IrsRefund));
+        assert_eq!(classify("US TREASURY", "TAX REF", "1"), Some(IrsRefund));
+        assert_eq!(
+            classify("SSA TREAS 310", "XXSOC SEC", "1"),
+            Some(SocialSecurity)
+        );
+        assert_eq!(classify("SSI TREAS 310", "", "1"), Some(SocialSecurity));
+        assert_eq!(classify("VACP TREAS 310", "", "1"), Some(Veterans));
+        assert_eq!(
+            classify("OPM1 TREAS 310", "CIV SERV", "1"),
+            Some(OtherFederal)
+        );
+        // A state tax refund is not federal.
+        assert_eq!(classify("NYS DTF PIT", "TAX REF", "1"), None);
+        assert_eq!(classify("YOUR COMPANY", "PAYROLL", "1"), None);
+    }
+
+    #[test]
+    fn test_federal_originator_status() {
+        assert_eq!(
+            classify("DEPT OF AGRICULTURE", "PAYMENT", "2"),
+            Some(GovPaymentKind::OtherFederal)
+        );
+        assert_eq!(
+            classify("SSA TREAS 310", "", "2"),
+            Some(GovPaymentKind::SocialSecurity)
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! Reading gzip-compressed files and files on disk.
+//!
+//! Available with the `gzip` feature.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use flate2::read::MultiGzDecoder;
+
+use crate::{AchError, AchFile, AchFileOwned, AchResult, ParseOptions, ParseWarning};
+
+/// The first two bytes of every gzip stream.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+impl AchFile<'_> {
+    /// Decompress a gzip file, such as an `.ach.gz` archive, and parse it.
+    ///
+    /// Files of several concatenated gzip members are read as one. The
+    /// decompressed text is not kept, so the file owns its data.
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use flate2::write::GzEncoder;
+    /// use flate2::Compression;
+    /// use rs_ach::{fixtures, AchFile};
+    ///
+    /// let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    /// encoder.write_all(fixtures::sample_ppd_file(2).as_bytes()).unwrap();
+    /// let compressed = encoder.finish().unwrap();
+    ///
+    /// let file = AchFile::parse_gzip(&compressed).unwrap();
+    /// assert_eq!(file.batches[0].entries.len(), 2);
+    /// ```
+    pub fn parse_gzip(compressed: &[u8]) -> AchResult<AchFileOwned> {
+        Ok(AchFile::parse_gzip_with(compressed, &ParseOptions::default())?.0)
+    }
+
+    /// Like [`parse_gzip`](AchFile::parse_gzip), with the given options, as
+    /// [`parse_with`](AchFile::parse_with).
+    pub fn parse_gzip_with(
+        compressed: &[u8],
+        options: &ParseOptions,
+    ) -> AchResult<(AchFileOwned, Vec<ParseWarning>)> {
+        let text = decompress(compressed, None)?;
+        parse_text(&text, options)
+    }
+
+    /// Read and parse the file at `path`, decompressing it first if it
+    /// starts with the gzip magic bytes, whatever its name.
+    ///
+    /// Read failures are [`AchError::Io`] and a corrupt or truncated gzip
+    /// stream is [`AchError::Decompression`].
+    pub fn parse_path(path: impl AsRef<Path>) -> AchResult<AchFileOwned> {
+        Ok(AchFile::parse_path_with(path, &ParseOptions::default())?.0)
+    }
+
+    /// Like [`parse_path`](AchFile::parse_path), with the given options, as
+    /// [`parse_with`](AchFile::parse_with).
+    pub fn parse_path_with(
+        path: impl AsRef<Path>,
+        options: &ParseOptions,
+    ) -> AchResult<(AchFileOwned, Vec<ParseWarning>)> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)?;
+        let text = if bytes.starts_with(&GZIP_MAGIC) {
+            decompress(&bytes, Some(path))?
+        } else {
+            into_text(bytes)?
+        };
+        parse_text(&text, options)
+    }
+}
+
+/// Decompress all of the gzip members in `compressed`.
+fn decompress(compressed: &[u8], path: Option<&Path>) -> AchResult<String> {
+    let mut decompressed = Vec::new();
+    MultiGzDecoder::new(compressed)
+        .read_to_end(&mut decompressed)
+        .map_err(|source| AchError::Decompression {
+            path: path.map(PathBuf::from),
+            decompressed: decompressed.len(),
+            source,
+        })?;
+    into_text(decompressed)
+}
+
+fn into_text(bytes: Vec<u8>) -> AchResult<String> {
+    String::from_utf8(bytes).map_err(|err| AchError::InvalidEncoding {
+        offset: err.utf8_error().valid_up_to(),
+    })
+}
+
+fn parse_text(text: &str, options: &ParseOptions) -> AchResult<(AchFileOwned, Vec<ParseWarning>)> {
+    let (file, warnings) = AchFile::parse_with(text, options)?;
+    Ok((file.into_owned(), warnings))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    use super::*;
+    use crate::{fixtures, ErrorKind};
+
+    fn compress(text: impl AsRef<[u8]>) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(text.as_ref()).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_parse_gzip() {
+        let text = fixtures::sample_multi_company_file();
+        let file = AchFile::parse_gzip(&compress(&text)).unwrap();
+        assert_eq!(file, AchFile::parse(&text).unwrap());
+
+        // Two members, as from `cat a.gz b.gz`.
+        let (head, tail) = text.split_at(text.len() / 2);
+        let mut members = compress(head);
+        members.extend(compress(tail));
+        assert_eq!(AchFile::parse_gzip(&members).unwrap(), file);
+    }
+
+    #[test]
+    fn test_decompression_errors() {
+        let compressed = compress(fixtures::sample_ppd_file(20));
+        let err = AchFile::parse_gzip(&compressed[..compressed.len() / 2]).unwrap_err();
+        assert!(matches!(err, AchError::Decompression { path: None, .. }));
+        assert_eq!(err.kind(), ErrorKind::Io);
+        assert!(err.to_string().starts_with("Decompression failed after "));
+
+        let err = AchFile::parse_gzip(&compress(b"101 \xe9")).unwrap_err();
+        assert!(matches!(err, AchError::InvalidEncoding { offset: 4 }));
+        assert_eq!(err.kind(), ErrorKind::Encoding);
+    }
+
+    #[test]
+    fn test_parse_path() {
+        let text = fixtures::sample_ppd_file(3);
+        let dir = std::env::temp_dir();
+        let plain = dir.join(format!("rs-ach-{}.ach", std::process::id()));
+        let gzipped = dir.join(format!("rs-ach-{}.ach.gz", std::process::id()));
+        std::fs::write(&plain, &text).unwrap();
+        std::fs::write(&gzipped, compress(&text)).unwrap();
+
+        let expected = AchFile::parse(&text).unwrap();
+        assert_eq!(AchFile::parse_path(&plain).unwrap(), expected);
+        assert_eq!(AchFile::parse_path(&gzipped).unwrap(), expected);
+
+        std::fs::write(&gzipped, &compress(&text)[..40]).unwrap();
+        let err = AchFile::parse_path(&gzipped).unwrap_err();
+        assert!(matches!(&err, AchError::Decompression { path: Some(p), .. } if *p == gzipped));
+        std::fs::remove_file(&plain).unwrap();
+        std::fs::remove_file(&gzipped).unwrap();
+
+        let err = AchFile::parse_path(&plain).unwrap_err();
+        assert!(matches!(err, AchError::Io(_)));
+        assert_eq!(err.kind(), ErrorKind::Io);
+    }
+}
@@ -0,0 +1,461 @@
+//! International ACH Transaction (IAT) batches: the IAT reading of the
+//! batch header and entry detail, and the type 10-18 addenda.
+//!
+//! The standard records of an IAT batch have their fields sliced at the
+//! wrong places, so the parser reads the batch again with the IAT layouts
+//! into [`Batch::iat`].
+
+use crate::records::{Addenda, BatchHeader, EntryDetail};
+use crate::writer;
+use crate::Batch;
+
+/// The addenda types every IAT entry must carry, 10 through 16.
+const MANDATORY_IAT_ADDENDA: [&str; 7] = ["10", "11", "12", "13", "14", "15", "16"];
+
+/// The record as written, so IAT fields can be sliced at their positions.
+struct Line(String);
+
+impl Line {
+    /// Positions `from` through `to` of the record, counting from 1 as
+    /// NACHA does, trimmed. Positions count characters, as the layout
+    /// places them, so a name with accents moves nothing after it.
+    fn get(&self, from: usize, to: usize) -> String {
+        let field: String = self.0.chars().skip(from - 1).take(to + 1 - from).collect();
+        field.trim().to_string()
+    }
+}
+
+/// The fields of an IAT batch header that differ from [`BatchHeader`]'s,
+/// from [`BatchHeader::as_iat`].
+///
+/// The rest are read as for any batch; the originator identification is
+/// [`company_identification`](BatchHeader::company_identification) and the
+/// gateway operator is
+/// [`originating_dfi_identification`](BatchHeader::originating_dfi_identification).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct IatBatchHeader {
+    /// IAT indicator, usually blank (positions 5-20)
+    pub iat_indicator: String,
+
+    /// Foreign exchange indicator: `FV`, `VF`, or `FF` (positions 21-22)
+    pub foreign_exchange_indicator: String,
+
+    /// What the foreign exchange reference is: `1` a rate, `2` a reference
+    /// number, `3` nothing (position 23)
+    pub foreign_exchange_reference_indicator: String,
+
+    /// Foreign exchange rate or reference (positions 24-38)
+    pub foreign_exchange_reference: String,
+
+    /// ISO 3166 code of the destination country (positions 39-40)
+    pub iso_destination_country_code: String,
+
+    /// ISO 4217 code of the originating currency (positions 64-66)
+    pub iso_originating_currency_code: String,
+
+    /// ISO 4217 code of the destination currency (positions 67-69)
+    pub iso_destination_currency_code: String,
+}
+
+impl BatchHeader<'_> {
+    /// Read the header with the IAT layout, or `None` unless its SEC code
+    /// is `IAT`.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile};
+    ///
+    /// let text = fixtures::sample_iat_file();
+    /// let file = AchFile::parse(&text).unwrap();
+    /// let header = file.batches[0].header.as_iat().unwrap();
+    /// assert_eq!(header.iso_destination_country_code, "DE");
+    /// assert_eq!(header.iso_destination_currency_code, "EUR");
+    /// ```
+    pub fn as_iat(&self) -> Option<IatBatchHeader> {
+        if self.standard_entry_class_code.trim() != "IAT" {
+            return None;
+        }
+        let line = Line(writer::layout_line(&self.fields()));
+        Some(IatBatchHeader {
+            iat_indicator: line.get(5, 20),
+            foreign_exchange_indicator: line.get(21, 22),
+            foreign_exchange_reference_indicator: line.get(23, 23),
+            foreign_exchange_reference: line.get(24, 38),
+            iso_destination_country_code: line.get(39, 40),
+            iso_originating_currency_code: line.get(64, 66),
+            iso_destination_currency_code: line.get(67, 69),
+        })
+    }
+}
+
+/// An entry of an IAT batch read with the IAT layout, in [`IatBatch`] or
+/// from [`EntryDetail::as_iat`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct IatEntryDetail {
+    /// Number of addenda records the entry declares (positions 13-16)
+    pub number_of_addenda_records: String,
+
+    /// The receiver's account number at the foreign DFI (positions 40-74)
+    pub foreign_receiver_account_number: String,
+
+    /// Gateway operator OFAC screening indicator: `1` if the gateway
+    /// found a possible match (position 77)
+    pub gateway_ofac_screening_indicator: String,
+
+    /// Secondary OFAC screening indicator (position 78)
+    pub secondary_ofac_screening_indicator: String,
+
+    /// The entry's addenda of types 10 through 18, in order
+    pub addenda: Vec<IatAddenda>,
+}
+
+impl EntryDetail<'_> {
+    /// Read the entry with the IAT layout. Only meaningful for entries of
+    /// a batch whose [`BatchHeader::as_iat`] is `Some`.
+    pub fn as_iat(&self) -> IatEntryDetail {
+        let line = Line(writer::layout_line(&self.fields()));
+        IatEntryDetail {
+            number_of_addenda_records: line.get(13, 16),
+            foreign_receiver_account_number: line.get(40, 74),
+            gateway_ofac_screening_indicator: line.get(77, 77),
+            secondary_ofac_screening_indicator: line.get(78, 78),
+            addenda: self.addenda.iter().filter_map(Addenda::as_iat).collect(),
+        }
+    }
+
+    /// The mandatory IAT addenda types, 10 through 16, the entry lacks.
+    pub fn missing_iat_addenda(&self) -> Vec<&'static str> {
+        MANDATORY_IAT_ADDENDA
+            .into_iter()
+            .filter(|code| !self.addenda.iter().any(|a| a.addenda_type_code == *code))
+            .collect()
+    }
+}
+
+/// A financial institution named in an IAT addenda.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct IatBank {
+    /// Name of the institution
+    pub name: String,
+
+    /// What the identification is: `01` a national clearing system
+    /// number, `02` a BIC, `03` an IBAN
+    pub identification_number_qualifier: String,
+
+    /// The institution's identification
+    pub identification: String,
+
+    /// ISO 3166 code of the branch's country
+    pub branch_country_code: String,
+}
+
+/// A city and country pair of an IAT addenda (types 12 and 16). NACHA
+/// separates the parts with `*` and ends each field with `\`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct IatLocation {
+    /// City and state or province, e.g. `NEW YORK*NY\`
+    pub city_and_state: String,
+
+    /// Country and postal code, e.g. `US*10001\`
+    pub country_and_postal_code: String,
+}
+
+/// An IAT addenda, read by its type code, from [`Addenda::as_iat`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum IatAddenda {
+    /// Type 10: the payment and the receiver's name
+    Transaction {
+        /// Transaction type code, e.g. `SAL` for salary
+        transaction_type_code: String,
+        /// Amount in the foreign currency, for FF and VF entries
+        foreign_payment_amount: String,
+        /// Trace number given by the foreign payment system
+        foreign_trace_number: String,
+        /// Receiving company or individual name
+        receiver_name: String,
+    },
+
+    /// Type 11: the originator's name and street address
+    Originator {
+        /// Originator name
+        name: String,
+        /// Street address
+        street_address: String,
+    },
+
+    /// Type 12: the originator's city and country
+    OriginatorLocation(IatLocation),
+
+    /// Type 13: the originating DFI
+    OriginatingDfi(IatBank),
+
+    /// Type 14: the receiving DFI
+    ReceivingDfi(IatBank),
+
+    /// Type 15: the receiver's identification and street address
+    Receiver {
+        /// Receiver identification number
+        identification_number: String,
+        /// Street address
+        street_address: String,
+    },
+
+    /// Type 16: the receiver's city and country
+    ReceiverLocation(IatLocation),
+
+    /// Type 17: remittance information, up to two per entry
+    Remittance {
+        /// Payment related information
+        payment_related_information: String,
+        /// Addenda sequence number
+        addenda_sequence_number: String,
+    },
+
+    /// Type 18: a foreign correspondent bank, up to five per entry
+    ForeignCorrespondentBank {
+        /// The bank
+        bank: IatBank,
+        /// Addenda sequence number
+        addenda_sequence_number: String,
+    },
+}
+
+impl Addenda<'_> {
+    /// Read this addenda with its IAT layout, or `None` unless its type
+    /// code is 10 through 18.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile, IatAddenda};
+    ///
+    /// let text = fixtures::sample_iat_file();
+    /// let file = AchFile::parse(&text).unwrap();
+    /// let addenda = &file.batches[0].entries[0].addenda[1];
+    /// let Some(IatAddenda::Originator { name, .. }) = addenda.as_iat() else {
+    ///     panic!("type 11 is the originator");
+    /// };
+    /// assert_eq!(name, "ACME CORP");
+    /// ```
+    pub fn as_iat(&self) -> Option<IatAddenda> {
+        let line = Line(writer::layout_line(&self.fields()));
+        let bank = || IatBank {
+            name: line.get(4, 38),
+            identification_number_qualifier: line.get(39, 40),
+            identification: line.get(41, 74),
+            branch_country_code: line.get(75, 77),
+        };
+        let location = || IatLocation {
+            city_and_state: line.get(4, 38),
+            country_and_postal_code: line.get(39, 73),
+        };
+        Some(match self.addenda_type_code.as_ref() {
+            "10" => IatAddenda::Transaction {
+                transaction_type_code: line.get(4, 6),
+                foreign_payment_amount: line.get(7, 24),
+                foreign_trace_number: line.get(25, 46),
+                receiver_name: line.get(47, 81),
+            },
+            "11" => IatAddenda::Originator {
+                name: line.get(4, 38),
+                street_address: line.get(39, 73),
+            },
+            "12" => IatAddenda::OriginatorLocation(location()),
+            "13" => IatAddenda::OriginatingDfi(bank()),
+            "14" => IatAddenda::ReceivingDfi(bank()),
+            "15" => IatAddenda::Receiver {
+                identification_number: line.get(4, 18),
+                street_address: line.get(19, 53),
+            },
+            "16" => IatAddenda::ReceiverLocation(location()),
+            "17" => IatAddenda::Remittance {
+                payment_related_information: line.get(4, 83),
+                addenda_sequence_number: line.get(84, 87),
+            },
+            "18" => IatAddenda::ForeignCorrespondentBank {
+                bank: bank(),
+                addenda_sequence_number: line.get(84, 87),
+            },
+            _ => return None,
+        })
+    }
+}
+
+/// An IAT batch's header and entries read with the IAT layouts, in
+/// [`Batch::iat`].
+///
+/// ```
+/// use rs_ach::{fixtures, AchFile};
+///
+/// let text = fixtures::sample_iat_file();
+/// let file = AchFile::parse(&text).unwrap();
+/// let iat = file.batches[0].iat.as_ref().unwrap();
+/// assert_eq!(iat.header.iso_destination_currency_code, "EUR");
+/// assert_eq!(iat.entries[0].addenda.len(), 9);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct IatBatch {
+    /// The batch's header
+    pub header: IatBatchHeader,
+
+    /// The batch's entries with their addenda, in file order
+    pub entries: Vec<IatEntryDetail>,
+}
+
+impl Batch<'_> {
+    /// Read the batch's header and entries with the IAT layouts if its SEC
+    /// code is `IAT`, as the parser does for [`Batch::iat`].
+    pub(crate) fn read_iat(&self) -> Option<IatBatch> {
+        Some(IatBatch {
+            header: self.header.as_iat()?,
+            entries: self.entries.iter().map(EntryDetail::as_iat).collect(),
+        })
+    }
+}
+
+impl IatAddenda {
+    /// The addenda type code, e.g. `10`.
+    pub fn type_code(&self) -> &'static str {
+        match self {
+            IatAddenda::Transaction { .. } => "10",
+            IatAddenda::Originator { .. } => "11",
+            IatAddenda::OriginatorLocation(_) => "12",
+            IatAddenda::OriginatingDfi(_) => "13",
+            IatAddenda::ReceivingDfi(_) => "14",
+            IatAddenda::Receiver { .. } => "15",
+            IatAddenda::ReceiverLocation(_) => "16",
+            IatAddenda::Remittance { .. } => "17",
+            IatAddenda::ForeignCorrespondentBank { .. } => "18",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, AchFile};
+
+    #[test]
+    fn test_iat_header() {
+        let text = fixtures::sample_iat_file();
+        let file = AchFile::parse(&text).unwrap();
+        assert_eq!(
+            file.batches[0].header.as_iat().unwrap(),
+            IatBatchHeader {
+                iat_indicator: String::new(),
+                foreign_exchange_indicator: "FV".to_string(),
+                foreign_exchange_reference_indicator: "1".to_string(),
+                foreign_exchange_reference: "0.91".to_string(),
+                iso_destination_country_code: "DE".to_string(),
+                iso_originating_currency_code: "USD".to_string(),
+                iso_destination_currency_code: "EUR".to_string(),
+            }
+        );
+
+        let ppd = fixtures::sample_ppd_file(1);
+        let ppd = AchFile::parse(&ppd).unwrap();
+        assert_eq!(ppd.batches[0].header.as_iat(), None);
+        assert_eq!(ppd.batches[0].iat, None);
+    }
+
+    #[test]
+    fn test_iat_entry_and_addenda() {
+        let text = fixtures::sample_iat_file();
+        let file = AchFile::parse(&text).unwrap();
+        let iat = file.batches[0].iat.as_ref().unwrap();
+        assert_eq!(Some(&iat.header), file.batches[0].header.as_iat().as_ref());
+        assert_eq!(iat.entries, [file.batches[0].entries[0].as_iat()]);
+        let entry = &iat.entries[0];
+        assert_eq!(entry.number_of_addenda_records, "0009");
+        assert_eq!(
+            entry.foreign_receiver_account_number,
+            "DE89370400440532013000"
+        );
+        assert_eq!(entry.gateway_ofac_screening_indicator, "0");
+
+        let types: Vec<&str> = entry.addenda.iter().map(IatAddenda::type_code).collect();
+        assert_eq!(
+            types,
+            ["10", "11", "12", "13", "14", "15", "16", "17", "18"]
+        );
+        assert_eq!(
+            entry.addenda[0],
+            IatAddenda::Transaction {
+                transaction_type_code: "SAL".to_string(),
+                foreign_payment_amount: "000000000000091000".to_string(),
+                foreign_trace_number: String::new(),
+                receiver_name: "HANS MUELLER".to_string(),
+            }
+        );
+        assert_eq!(
+            entry.addenda[3],
+            IatAddenda::OriginatingDfi(IatBank {
+                name: "FIRST NATIONAL BANK".to_string(),
+                identification_number_qualifier: "01".to_string(),
+                identification: "123456780".to_string(),
+                branch_country_code: "US".to_string(),
+            })
+        );
+        assert_eq!(
+            entry.addenda[6],
+            IatAddenda::ReceiverLocation(IatLocation {
+                city_and_state: "FRANKFURT*HE\\".to_string(),
+                country_and_postal_code: "DE*60311\\".to_string(),
+            })
+        );
+        let IatAddenda::ForeignCorrespondentBank {
+            bank,
+            addenda_sequence_number,
+        } = &entry.addenda[8]
+        else {
+            panic!("type 18 is a correspondent bank");
+        };
+        assert_eq!(bank.identification, "CORRUS33");
+        assert_eq!(addenda_sequence_number, "0001");
+
+        // Positions count characters, so an accented name keeps the
+        // fields after it in place.
+        let mut addenda = file.batches[0].entries[0].addenda[1].clone();
+        addenda.payment_related_information = addenda
+            .payment_related_information
+            .replacen("ACME CORP", "ACMÉ CORP", 1)
+            .into();
+        let Some(IatAddenda::Originator {
+            name,
+            street_address,
+        }) = addenda.as_iat()
+        else {
+            panic!("type 11 is the originator");
+        };
+        assert_eq!(name, "ACMÉ CORP");
+        let Some(IatAddenda::Originator {
+            street_address: expected,
+            ..
+        }) = file.batches[0].entries[0].addenda[1].as_iat()
+        else {
+            panic!("type 11 is the originator");
+        };
+        assert_eq!(street_address, expected);
+    }
+
+    #[test]
+    fn test_missing_iat_addenda() {
+        let text = fixtures::sample_iat_file();
+        let mut file = AchFile::parse(&text).unwrap();
+        let entry = &mut file.batches[0].entries[0];
+        assert!(entry.missing_iat_addenda().is_empty());
+        entry
+            .addenda
+            .retain(|a| !["12", "15"].contains(&a.addenda_type_code.as_ref()));
+        assert_eq!(entry.missing_iat_addenda(), ["12", "15"]);
+    }
+}
@@ -0,0 +1,613 @@
+//! Conversion between ACH credit batches and ISO 20022 pain.001 messages.
+//!
+//! Available with the `iso20022` feature. Each ACH batch becomes one
+//! payment information block of a `pain.001.001.09` customer credit
+//! transfer initiation:
+//!
+//! - debtor (company name and identification) and debtor agent (ODFI routing
+//!   number) from the batch header
+//! - creditor agent, creditor account, and name from each entry
+//! - end-to-end identification from the trace number
+//! - unstructured remittance information from the entry's addenda
+//!
+//! Only live credit entries can be expressed as credit transfers. Debits,
+//! prenotes, returns, and batches with SEC codes that carry non-domestic or
+//! non-payment data (IAT, COR, ADV, ...) are reported in a
+//! [`ConversionError`] listing everything that couldn't be mapped.
+
+use std::fmt;
+use std::io;
+
+use quick_xml::events::{BytesDecl, BytesText, Event};
+use quick_xml::Writer;
+
+use crate::builder::{BatchBuilder, Direction, SimpleEntry};
+use crate::dates::YearPivot;
+use crate::dollars::dollars;
+use crate::{routing, AchFile, Batch};
+
+/// XML namespace of the generated messages.
+pub const PAIN_001_NAMESPACE: &str = "urn:iso:std:iso:20022:tech:xsd:pain.001.001.09";
+
+/// SEC codes that carry data with no pain.001 equivalent.
+const UNSUPPORTED_SEC_CODES: &[&str] = &["IAT", "COR", "ADV", "ENR", "DNE", "TRC", "TRX"];
+
+/// A `CstmrCdtTrfInitn` message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreditTransferInitiation {
+    /// Message identification (`GrpHdr/MsgId`)
+    pub message_id: String,
+
+    /// Creation timestamp in ISO 8601 form (`GrpHdr/CreDtTm`)
+    pub creation_date_time: String,
+
+    /// Initiating party name (`GrpHdr/InitgPty/Nm`)
+    pub initiating_party_name: String,
+
+    /// One block per ACH batch
+    pub payment_information: Vec<PaymentInformation>,
+}
+
+/// A `PmtInf` block, corresponding to one ACH batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentInformation {
+    /// Payment information identification, taken from the batch number
+    pub payment_information_id: String,
+
+    /// ACH SEC code, emitted as the proprietary local instrument
+    pub standard_entry_class_code: String,
+
+    /// Requested execution date (`YYYY-MM-DD`) from the effective entry date
+    pub requested_execution_date: String,
+
+    /// Debtor name, from the company name
+    pub debtor_name: String,
+
+    /// Debtor identification, from the company identification
+    pub debtor_identification: String,
+
+    /// Debtor agent ABA routing number (9 digits)
+    pub debtor_agent_routing: String,
+
+    /// Individual credit transfers
+    pub transactions: Vec<CreditTransfer>,
+}
+
+/// A `CdtTrfTxInf` block, corresponding to one ACH entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreditTransfer {
+    /// End-to-end identification, from the trace number
+    pub end_to_end_id: String,
+
+    /// Instructed amount in cents (USD)
+    pub amount: u64,
+
+    /// Creditor agent ABA routing number (9 digits)
+    pub creditor_agent_routing: String,
+
+    /// Creditor name
+    pub creditor_name: String,
+
+    /// Creditor account number
+    pub creditor_account: String,
+
+    /// Creditor account type code (`CACC` checking, `SVGS` savings, ...)
+    pub creditor_account_type: &'static str,
+
+    /// Unstructured remittance information from the addenda, if any
+    pub remittance_information: Option<String>,
+}
+
+/// A record that could not be converted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unmapped {
+    /// Batch number of the offending batch
+    pub batch_number: String,
+
+    /// Trace number (or end-to-end id) of the offending entry, if entry-level
+    pub trace_number: Option<String>,
+
+    /// Why the record couldn't be mapped
+    pub reason: String,
+}
+
+/// Conversion failed for one or more records.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversionError {
+    /// Every record that couldn't be mapped, in file order
+    pub unmapped: Vec<Unmapped>,
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} record(s) could not be converted",
+            self.unmapped.len()
+        )?;
+        for item in &self.unmapped {
+            write!(f, "\n  batch {}", item.batch_number)?;
+            if let Some(trace) = &item.trace_number {
+                write!(f, ", trace {trace}")?;
+            }
+            write!(f, ": {}", item.reason)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+impl CreditTransferInitiation {
+    /// Convert every batch of an ACH file into a single pain.001 message.
+    ///
+    /// The message identification is built from the immediate origin, file
+    /// creation date, and file ID modifier. Two-digit years are placed with
+    /// the default [`YearPivot`].
+    pub fn from_ach_file(file: &AchFile) -> Result<Self, ConversionError> {
+        let header = &file.file_header;
+        let mut payment_information = Vec::new();
+        let mut unmapped = Vec::new();
+
+        for batch in &file.batches {
+            match PaymentInformation::from_batch(batch) {
+                Ok(info) => payment_information.push(info),
+                Err(e) => unmapped.extend(e.unmapped),
+            }
+        }
+
+        if !unmapped.is_empty() {
+            return Err(ConversionError { unmapped });
+        }
+
+        let date = &header.file_creation_date;
+        let time = &header.file_creation_time;
+        Ok(CreditTransferInitiation {
+            message_id: format!(
+                "{}{}{}",
+                header.immediate_origin.trim(),
+                date,
+                header.file_id_modifier
+            ),
+            creation_date_time: format!(
+                "{}T{}:{}:00",
+                iso_date(date),
+                time.get(0..2).unwrap_or("00"),
+                time.get(2..4).unwrap_or("00")
+            ),
+            initiating_party_name: header.immediate_origin_name.trim().to_string(),
+            payment_information,
+        })
+    }
+
+    /// Total number of transactions in the message (`GrpHdr/NbOfTxs`).
+    pub fn number_of_transactions(&self) -> usize {
+        self.payment_information
+            .iter()
+            .map(|p| p.transactions.len())
+            .sum()
+    }
+
+    /// Sum of all instructed amounts in cents (`GrpHdr/CtrlSum`).
+    pub fn control_sum(&self) -> u64 {
+        self.payment_information
+            .iter()
+            .map(PaymentInformation::control_sum)
+            .sum()
+    }
+
+    /// Render the message as a pain.001.001.09 XML document.
+    pub fn to_xml(&self) -> String {
+        let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+        self.write_document(&mut writer)
+            .expect("writing XML to a Vec cannot fail");
+        String::from_utf8(writer.into_inner()).expect("generated XML is UTF-8")
+    }
+
+    fn write_document(&self, w: &mut Writer<Vec<u8>>) -> io::Result<()> {
+        w.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+        w.create_element("Document")
+            .with_attribute(("xmlns", PAIN_001_NAMESPACE))
+            .write_inner_content(|w| {
+                w.create_element("CstmrCdtTrfInitn")
+                    .write_inner_content(|w| {
+                        w.create_element("GrpHdr").write_inner_content(|w| {
+                            leaf(w, "MsgId", &self.message_id)?;
+                            leaf(w, "CreDtTm", &self.creation_date_time)?;
+                            leaf(w, "NbOfTxs", &self.number_of_transactions().to_string())?;
+                            leaf(w, "CtrlSum", &dollars(self.control_sum()))?;
+                            w.create_element("InitgPty").write_inner_content(|w| {
+                                leaf(w, "Nm", &self.initiating_party_name)
+                            })?;
+                            Ok(())
+                        })?;
+                        for info in &self.payment_information {
+                            info.write(w)?;
+                        }
+                        Ok(())
+                    })?;
+                Ok(())
+            })?;
+        Ok(())
+    }
+}
+
+impl PaymentInformation {
+    /// Convert a single ACH batch.
+    pub fn from_batch(batch: &Batch) -> Result<Self, ConversionError> {
+        let header = &batch.header;
+        let batch_number = header.batch_number.to_string();
+        let sec = header.standard_entry_class_code.to_string();
+        let mut unmapped = Vec::new();
+
+        if UNSUPPORTED_SEC_CODES.contains(&sec.as_str()) {
+            unmapped.push(Unmapped {
+                batch_number: batch_number.clone(),
+                trace_number: None,
+                reason: format!("SEC code {sec} has no pain.001 equivalent"),
+            });
+            return Err(ConversionError { unmapped });
+        }
+
+        let odfi = header.originating_dfi_identification.as_ref();
+        let debtor_agent_routing = match routing::check_digit(odfi) {
+            Some(digit) => format!("{odfi}{digit}"),
+            None => {
+                unmapped.push(Unmapped {
+                    batch_number: batch_number.clone(),
+                    trace_number: None,
+                    reason: format!("originating DFI '{odfi}' is not 8 digits"),
+                });
+                String::new()
+            }
+        };
+
+        let mut transactions = Vec::new();
+        for entry in &batch.entries {
+            let trace = entry.trace_number.to_string();
+            let fail = |reason: String| Unmapped {
+                batch_number: batch_number.clone(),
+                trace_number: Some(trace.clone()),
+                reason,
+            };
+
+            let account_type = match entry.transaction_code.as_ref() {
+                "22" => "CACC",
+                "32" => "SVGS",
+                "42" => "GL",
+                "52" => "LOAN",
+                code => {
+                    unmapped.push(fail(format!(
+                        "transaction code {code} is not a live credit"
+                    )));
+                    continue;
+                }
+            };
+            if entry.addenda.iter().any(|a| a.addenda_type_code == "99") {
+                unmapped.push(fail("returned entries cannot be converted".to_string()));
+                continue;
+            }
+
+            let remittance = entry
+                .addenda
+                .iter()
+                .map(|a| a.payment_related_information.trim())
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            transactions.push(CreditTransfer {
+                end_to_end_id: trace.clone(),
+                amount: entry.amount,
+                creditor_agent_routing: format!(
+                    "{}{}",
+                    entry.receiving_dfi_identification, entry.check_digit
+                ),
+                creditor_name: entry.individual_name.trim().to_string(),
+                creditor_account: entry.dfi_account_number.trim().to_string(),
+                creditor_account_type: account_type,
+                remittance_information: (!remittance.is_empty()).then_some(remittance),
+            });
+        }
+
+        if !unmapped.is_empty() {
+            return Err(ConversionError { unmapped });
+        }
+
+        Ok(PaymentInformation {
+            payment_information_id: batch_number,
+            standard_entry_class_code: sec,
+            requested_execution_date: iso_date(&header.effective_entry_date),
+            debtor_name: header.company_name.trim().to_string(),
+            debtor_identification: header.company_identification.trim().to_string(),
+            debtor_agent_routing,
+            transactions,
+        })
+    }
+
+    /// Sum of the instructed amounts in cents.
+    pub fn control_sum(&self) -> u64 {
+        self.transactions.iter().map(|t| t.amount).sum()
+    }
+
+    /// Best-effort reverse mapping into a [`BatchBuilder`].
+    ///
+    /// Text fields are truncated to their NACHA widths. Only checking
+    /// account credits can be expressed as [`SimpleEntry`]s; other account
+    /// types and entries the builder rejects are reported as unmapped.
+    pub fn to_batch_builder(&self) -> Result<BatchBuilder, ConversionError> {
+        let batch_number = self.payment_information_id.clone();
+        let fail = |trace: Option<&str>, reason: String| Unmapped {
+            batch_number: batch_number.clone(),
+            trace_number: trace.map(String::from),
+            reason,
+        };
+        let mut unmapped = Vec::new();
+
+        let mut builder = BatchBuilder::new(
+            truncate(&self.debtor_name, 16),
+            truncate(&self.debtor_identification, 10),
+            &self.standard_entry_class_code,
+            truncate("PAYMENT", 10),
+            self.debtor_agent_routing.get(..8).unwrap_or(""),
+        );
+        builder.effective_entry_date(&yymmdd(&self.requested_execution_date));
+        if let Ok(number) = self.payment_information_id.parse::<u64>() {
+            builder.batch_number(number);
+        }
+
+        for tx in &self.transactions {
+            if tx.creditor_account_type != "CACC" {
+                unmapped.push(fail(
+                    Some(&tx.end_to_end_id),
+                    format!("account type {} is not supported", tx.creditor_account_type),
+                ));
+                continue;
+            }
+
+            let entry = SimpleEntry {
+                routing_number: tx.creditor_agent_routing.clone(),
+                account_number: truncate(&tx.creditor_account, 17).to_string(),
+                amount: tx.amount,
+                individual_name: truncate(&tx.creditor_name, 22).to_string(),
+                identification: None,
+                direction: Direction::Credit,
+            };
+            if let Err(e) = builder.add_entry(entry) {
+                unmapped.push(fail(Some(&tx.end_to_end_id), e.to_string()));
+            }
+        }
+
+        if unmapped.is_empty() {
+            Ok(builder)
+        } else {
+            Err(ConversionError { unmapped })
+        }
+    }
+
+    fn write(&self, w: &mut Writer<Vec<u8>>) -> io::Result<()> {
+        w.create_element("PmtInf").write_inner_content(|w| {
+            leaf(w, "PmtInfId", &self.payment_information_id)?;
+            leaf(w, "PmtMtd", "TRF")?;
+            leaf(w, "NbOfTxs", &self.transactions.len().to_string())?;
+            leaf(w, "CtrlSum", &dollars(self.control_sum()))?;
+            w.create_element("PmtTpInf").write_inner_content(|w| {
+                w.create_element("LclInstrm")
+                    .write_inner_content(|w| leaf(w, "Prtry", &self.standard_entry_class_code))?;
+                Ok(())
+            })?;
+            w.create_element("ReqdExctnDt")
+                .write_inner_content(|w| leaf(w, "Dt", &self.requested_execution_date))?;
+            w.create_element("Dbtr").write_inner_content(|w| {
+                leaf(w, "Nm", &self.debtor_name)?;
+                w.create_element("Id").write_inner_content(|w| {
+                    w.create_element("OrgId").write_inner_content(|w| {
+                        w.create_element("Othr")
+                            .write_inner_content(|w| leaf(w, "Id", &self.debtor_identification))?;
+                        Ok(())
+                    })?;
+                    Ok(())
+                })?;
+                Ok(())
+            })?;
+            w.create_element("DbtrAcct").write_inner_content(|w| {
+                account_id(w, &self.debtor_identification)?;
+                Ok(())
+            })?;
+            agent(w, "DbtrAgt", &self.debtor_agent_routing)?;
+            for tx in &self.transactions {
+                tx.write(w)?;
+            }
+            Ok(())
+        })?;
+        Ok(())
+    }
+}
+
+impl CreditTransfer {
+    fn write(&self, w: &mut Writer<Vec<u8>>) -> io::Result<()> {
+        w.create_element("CdtTrfTxInf").write_inner_content(|w| {
+            w.create_element("PmtId")
+                .write_inner_content(|w| leaf(w, "EndToEndId", &self.end_to_end_id))?;
+            w.create_element("Amt").write_inner_content(|w| {
+                w.create_element("InstdAmt")
+                    .with_attribute(("Ccy", "USD"))
+                    .write_text_content(BytesText::new(&dollars(self.amount)))?;
+                Ok(())
+            })?;
+            agent(w, "CdtrAgt", &self.creditor_agent_routing)?;
+            w.create_element("Cdtr")
+                .write_inner_content(|w| leaf(w, "Nm", &self.creditor_name))?;
+            w.create_element("CdtrAcct").write_inner_content(|w| {
+                account_id(w, &self.creditor_account)?;
+                w.create_element("Tp")
+                    .write_inner_content(|w| leaf(w, "Cd", self.creditor_account_type))?;
+                Ok(())
+            })?;
+            if let Some(info) = &self.remittance_information {
+                w.create_element("RmtInf")
+                    .write_inner_content(|w| leaf(w, "Ustrd", info))?;
+            }
+            Ok(())
+        })?;
+        Ok(())
+    }
+}
+
+fn leaf(w: &mut Writer<Vec<u8>>, name: &str, text: &str) -> io::Result<()> {
+    w.create_element(name)
+        .write_text_content(BytesText::new(text))?;
+    Ok(())
+}
+
+fn account_id(w: &mut Writer<Vec<u8>>, id: &str) -> io::Result<()> {
+    w.create_element("Id").write_inner_content(|w| {
+        w.create_element("Othr")
+            .write_inner_content(|w| leaf(w, "Id", id))?;
+        Ok(())
+    })?;
+    Ok(())
+}
+
+fn agent(w: &mut Writer<Vec<u8>>, name: &str, routing: &str) -> io::Result<()> {
+    w.create_element(name).write_inner_content(|w| {
+        w.create_element("FinInstnId").write_inner_content(|w| {
+            w.create_element("ClrSysMmbId").write_inner_content(|w| {
+                w.create_element("ClrSysId")
+                    .write_inner_content(|w| leaf(w, "Cd", "USABA"))?;
+                leaf(w, "MmbId", routing)
+            })?;
+            Ok(())
+        })?;
+        Ok(())
+    })?;
+    Ok(())
+}
+
+/// `YYMMDD` to `YYYY-MM-DD`, with the default [`YearPivot`].
+fn iso_date(yymmdd: &str) -> String {
+    let yy = yymmdd.get(0..2).and_then(|yy| yy.parse().ok());
+    match (yy, yymmdd.get(2..4), yymmdd.get(4..6)) {
+        (Some(yy), Some(mm), Some(dd)) => {
+            format!("{:04}-{mm}-{dd}", YearPivot::default().resolve(yy))
+        }
+        _ => String::new(),
+    }
+}
+
+/// `YYYY-MM-DD` to `YYMMDD`.
+fn yymmdd(iso: &str) -> String {
+    match (iso.get(2..4), iso.get(5..7), iso.get(8..10)) {
+        (Some(yy), Some(mm), Some(dd)) => format!("{yy}{mm}{dd}"),
+        _ => String::new(),
+    }
+}
+
+fn truncate(value: &str, width: usize) -> &str {
+    match value.char_indices().nth(width) {
+        Some((idx, _)) => &value[..idx],
+        None => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CREDIT_FILE: &str = concat!(
+        "101 12345678012345678011409020123A094101YOUR BANK              YOUR COMPANY                   \n",
+        "5220YOUR COMPANY                        1234567890PPDPAYROLL         140903   1123456780000001\n",
+        "62212345678011232132         0000001000               ALICE WANDERDUST        1123456780000001\n",
+        "705INVOICE 1001 & 1002                                                             00010000001\n",
+        "632123232318123123123        0000001213               RACHEL WELCH            0123456780000002\n",
+        "820000000300246913980000000000000000000022131234567890                         123456780000001\n",
+        "9000001000001000000030024691398000000000000000000002213                                       ",
+    );
+
+    #[test]
+    fn test_from_ach_file() {
+        let file = AchFile::parse(CREDIT_FILE).unwrap();
+        let msg = CreditTransferInitiation::from_ach_file(&file).unwrap();
+
+        assert_eq!(msg.message_id, "1234567801140902A");
+        assert_eq!(msg.creation_date_time, "2014-09-02T01:23:00");
+        assert_eq!(msg.number_of_transactions(), 2);
+        assert_eq!(msg.control_sum(), 2213);
+
+        let info = &msg.payment_information[0];
+        assert_eq!(info.payment_information_id, "0000001");
+        assert_eq!(info.requested_execution_date, "2014-09-03");
+        assert_eq!(info.debtor_agent_routing, "123456780");
+
+        let tx = &info.transactions[0];
+        assert_eq!(tx.end_to_end_id, "123456780000001");
+        assert_eq!(tx.creditor_account_type, "CACC");
+        assert_eq!(
+            tx.remittance_information.as_deref(),
+            Some("INVOICE 1001 & 1002")
+        );
+        assert_eq!(info.transactions[1].creditor_account_type, "SVGS");
+    }
+
+    #[test]
+    fn test_to_xml() {
+        let file = AchFile::parse(CREDIT_FILE).unwrap();
+        let xml = CreditTransferInitiation::from_ach_file(&file)
+            .unwrap()
+            .to_xml();
+
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>"));
+        assert!(xml.contains(PAIN_001_NAMESPACE));
+        assert!(xml.contains("<CtrlSum>22.13</CtrlSum>"));
+        assert!(xml.contains("<InstdAmt Ccy=\"USD\">10.00</InstdAmt>"));
+        assert!(xml.contains("<EndToEndId>123456780000001</EndToEndId>"));
+        assert!(xml.contains("<MmbId>123232318</MmbId>"));
+        assert!(xml.contains("<Ustrd>INVOICE 1001 &amp; 1002</Ustrd>"));
+    }
+
+    #[test]
+    fn test_unmappable_entries_are_listed() {
+        let mixed = concat!(
+            "101 12345678012345678011409020123A094101YOUR BANK              YOUR COMPANY                   \n",
+            "5200YOUR COMPANY                        1234567890PPDPAYROLL         140903   1123456780000001\n",
+            "62212345678011232132         0000001000               ALICE WANDERDUST        0123456780000001\n",
+            "627123456780234234234        0000015000               BILLY HOLIDAY           0123456780000002\n",
+            "623123456780234234234        0000000000               BILLY HOLIDAY           0123456780000003\n",
+            "820000000300370145870000000150000000000010001234567890                         123456780000001\n",
+            "5200YOUR COMPANY                        1234567890IATPAYROLL         140903   1123456780000002\n",
+            "820000000000000000000000000000000000000000001234567890                         123456780000002\n",
+            "9000002000001000000030037014587000000015000000000001000                                       ",
+        );
+        let file = AchFile::parse(mixed).unwrap();
+        let err = CreditTransferInitiation::from_ach_file(&file).unwrap_err();
+
+        assert_eq!(err.unmapped.len(), 3);
+        assert_eq!(
+            err.unmapped[0].trace_number.as_deref(),
+            Some("123456780000002")
+        );
+        assert!(err.unmapped[1].reason.contains("23"));
+        assert_eq!(err.unmapped[2].batch_number, "0000002");
+        assert!(err.unmapped[2].reason.contains("IAT"));
+        assert!(err.to_string().contains("3 record(s)"));
+    }
+
+    #[test]
+    fn test_reverse_mapping() {
+        let file = AchFile::parse(CREDIT_FILE).unwrap();
+        let msg = CreditTransferInitiation::from_ach_file(&file).unwrap();
+        let mut info = msg.payment_information[0].clone();
+
+        // The savings credit can't be expressed as a SimpleEntry
+        let err = info.to_batch_builder().unwrap_err();
+        assert_eq!(err.unmapped.len(), 1);
+        assert!(err.unmapped[0].reason.contains("SVGS"));
+
+        info.transactions.truncate(1);
+        let batch = info.to_batch_builder().unwrap().build().unwrap();
+        assert_eq!(batch.header.effective_entry_date, "140903");
+        assert_eq!(batch.header.company_name.trim(), "YOUR COMPANY");
+        assert_eq!(batch.entries[0].amount, 1000);
+        assert_eq!(batch.control.total_credit_amount, 1000);
+    }
+}
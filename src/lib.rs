@@ -29,15 +29,138 @@
 //! }
 //! ```
 
+mod adv;
+mod anonymize;
+#[cfg(feature = "arrow")]
+pub mod arrow_export;
+mod audit;
+pub mod banking_days;
+mod builder;
+mod check_conversion;
+mod controls;
+#[cfg(feature = "csv")]
+pub mod csv_import;
+mod ctx;
+mod dates;
+#[cfg(feature = "decimal")]
+pub mod decimal;
+mod dollars;
+pub mod duplicates;
+mod enrollment;
+mod entry_description;
 mod error;
+mod exposure;
+mod fields;
+mod fingerprint;
+pub mod fixtures;
+pub mod government;
+#[cfg(feature = "gzip")]
+mod gzip;
+mod iat;
+#[cfg(feature = "iso20022")]
+pub mod iso20022;
+pub mod mask;
+mod micro_deposits;
+#[cfg(feature = "moov")]
+mod moov;
+mod naming;
+mod noc;
+mod normalize;
+mod originator_policy;
+mod parse_options;
 mod parser;
+pub mod payroll;
+mod reader;
+mod reassociate;
+mod reconcile;
+mod record_iter;
 mod records;
+mod remittance;
+mod renumber;
+mod report;
+mod resume;
+mod retain;
+pub mod return_stats;
+mod returns;
+pub mod reversals;
+pub mod routing;
+mod rows;
+mod screening;
+mod sec_code;
+mod service_class;
+mod settlement;
+mod summary;
+mod terminal;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod thresholds;
+mod transaction_code;
+pub mod validation;
+mod velocity;
+mod windows;
+mod writer;
 
-pub use error::AchError;
-pub use records::{Addenda, BatchControl, BatchHeader, EntryDetail, FileControl, FileHeader};
+pub use adv::{AdvBatch, AdvBatchControl, AdvEntryDetail, AdvFileControl};
+pub use anonymize::{AmountHandling, AnonymizeOptions};
+pub use builder::{BatchBuilder, Direction, IntoEntryDetail, SimpleEntry, MAX_ENTRY_AMOUNT};
+pub use check_conversion::PopFields;
+pub use ctx::CtxEntryDetail;
+pub use dates::{CalendarDate, CalendarDateTime, CivilDate, YearPivot};
+pub use enrollment::{EnrPayeeInformation, EnrolleeClassification};
+pub use entry_description::EntryDescriptionKind;
+pub use error::{AchError, AchResult, ErrorKind};
+pub use exposure::CompanyExposure;
+pub use fields::{FieldInfo, FieldPadding};
+pub use iat::{IatAddenda, IatBank, IatBatch, IatBatchHeader, IatEntryDetail, IatLocation};
+pub use micro_deposits::{MicroDepositEntry, MicroDepositGroup, MicroEntryCheck};
+pub use naming::{DateFormat, FileNameParts, NamingConvention};
+pub use noc::{ChangeCode, CorrectedData, CorrectedField, NocAddenda};
+pub use normalize::{normalize_to_nacha, normalize_to_nacha_strict};
+pub use originator_policy::{OriginatorPolicy, PolicyRule, PolicyViolation};
+pub use parse_options::{
+    NumberFormat, ParseOptions, ParseProgress, ParseWarning, ParseWarningCode, ProgressHook,
+    DEFAULT_MAX_ERRORS,
+};
+pub use reader::AchReader;
+pub use reassociate::Reassociation;
+pub use reconcile::{ReconciledTrace, Reconciler, ReconciliationReport, TraceStatus, UnknownTrace};
+pub use record_iter::{Record, Records};
+pub use records::layout::{self, FieldClass, FieldLayout, InclusionRequirement};
+pub use records::{
+    Addenda, BatchControl, BatchHeader, EntryDetail, FileControl, FileHeader, UnknownRecord,
+};
+pub use remittance::{RemittanceOptions, RemittanceRecord, RemittanceReference};
+pub use renumber::{RenumberOptions, TraceChange, TraceSequence};
+pub use resume::{Checkpoint, Resumed};
+pub use retain::{RemovedEntry, RetainOptions, Retained};
+pub use returns::{ReturnAddenda, ReturnCategory, ReturnClass, ReturnReasonCode, ReturnedEntry};
+pub use rows::EntryRow;
+pub use screening::{
+    BatchHits, EntryHits, NameScreener, ScreenContext, ScreenHit, ScreenResult, ScreenedParty,
+    ScreeningReport,
+};
+pub use sec_code::SecCode;
+pub use service_class::ServiceClassCode;
+pub use settlement::ScheduleConfig;
+pub use summary::FileSummary;
+pub use terminal::TerminalAddenda;
+pub use thresholds::{Alert, AlertScope, ThresholdConfig, ThresholdKind, Thresholds};
+pub use transaction_code::{AccountType, CodeTotals, TransactionCode};
+pub use validation::{
+    ControlField, ControlMismatch, ControlScope, Severity, ValidationIssue, ValidationOptions,
+    ValidationReport,
+};
+pub use velocity::{VelocityCount, VelocityPolicy, VelocityReport, VelocityViolation};
+pub use windows::{
+    BatchWindow, SameDayEligibility, SameDayReason, WindowClass, WindowConfig, WindowReport, Zone,
+};
+
+use std::sync::Arc;
 
 /// Represents a complete ACH file with file header, batches, and file control.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct AchFile<'a> {
     /// File header record (record type 1)
     pub file_header: FileHeader<'a>,
@@ -47,8 +170,31 @@ pub struct AchFile<'a> {
 
     /// File control record (record type 9)
     pub file_control: FileControl,
+
+    /// Records of unknown type between batches, in file order; always empty
+    /// unless [`ParseOptions::keep_unknown_records`] is set
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub unknown_records: Vec<UnknownRecord<'a>>,
 }
 
+/// An [`AchFile`] that owns all of its data and does not borrow from the
+/// input, as produced by builders and generators.
+///
+/// This is the type that implements [`FromStr`](std::str::FromStr), since
+/// a borrowing `AchFile<'a>` cannot: `content.parse::<AchFileOwned>()` is
+/// [`AchFile::parse`] with the borrowed fields copied.
+///
+/// ```
+/// use rs_ach::{fixtures, AchFileOwned};
+///
+/// let file: AchFileOwned = fixtures::sample_ppd_file(2).parse().unwrap();
+/// assert_eq!(file.batches[0].entries.len(), 2);
+/// ```
+pub type AchFileOwned = AchFile<'static>;
+
 impl<'a> AchFile<'a> {
     /// Parse an ACH file from a string.
     ///
@@ -71,10 +217,155 @@ impl<'a> AchFile<'a> {
     pub fn parse(content: &'a str) -> Result<Self, AchError> {
         parser::parse_ach_file(content)
     }
+
+    /// Parse an ACH file with the given options, returning the file and the
+    /// warnings raised for anything that was tolerated.
+    ///
+    /// A warning whose code is in [`ParseOptions::deny`] fails the parse with
+    /// [`AchError::DeniedWarning`] instead.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile, ParseOptions, ParseWarningCode};
+    ///
+    /// let text = fixtures::sample_ppd_file(1).replace('\n', "  \n");
+    /// let options = ParseOptions {
+    ///     allow_long_lines: true,
+    ///     ..ParseOptions::default()
+    /// };
+    /// let (file, warnings) = AchFile::parse_with(&text, &options).unwrap();
+    /// assert_eq!(file.batches[0].entries.len(), 1);
+    /// assert!(warnings.iter().all(|w| w.code == ParseWarningCode::LongLine));
+    /// ```
+    pub fn parse_with(
+        content: &'a str,
+        options: &ParseOptions,
+    ) -> Result<(Self, Vec<ParseWarning>), AchError> {
+        parser::parse_ach_file_with(content, options)
+    }
+
+    /// Parse an ACH file from bytes, such as a file read with
+    /// [`std::fs::read`], without first checking that all of it is UTF-8.
+    ///
+    /// Valid UTF-8 is parsed as [`AchFile::parse`] parses it, borrowing from
+    /// `bytes`. A byte that is not is [`AchError::InvalidEncoding`], with its
+    /// offset; [`AchFile::parse_bytes_with`] can replace such bytes instead.
+    pub fn parse_bytes(bytes: &'a [u8]) -> Result<Self, AchError> {
+        parser::parse_ach_bytes_with(bytes, &ParseOptions::default()).map(|(file, _)| file)
+    }
+
+    /// Parse an ACH file from bytes with the given options, returning the
+    /// file and the warnings raised.
+    ///
+    /// With [`ParseOptions::replace_invalid_bytes`], each byte that is not
+    /// valid UTF-8, such as a stray Latin-1 letter in a name, is read as `?`
+    /// so that the fields after it keep their positions, with a
+    /// [`ParseWarningCode::InvalidBytes`] warning for its line. The file is
+    /// then parsed from a copy of the input, as only valid UTF-8 can be
+    /// borrowed.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile, ParseOptions, ParseWarningCode};
+    ///
+    /// let text = fixtures::sample_ppd_file(1);
+    /// let entry = text.lines().nth(2).unwrap();
+    /// let at = text.find(entry).unwrap() + entry.find("RECEIVER").unwrap() + 1;
+    /// let mut bytes = text.into_bytes();
+    /// bytes[at] = 0xE9; // `é` in Latin-1
+    /// assert!(AchFile::parse_bytes(&bytes).is_err());
+    ///
+    /// let options = ParseOptions {
+    ///     replace_invalid_bytes: true,
+    ///     ..ParseOptions::default()
+    /// };
+    /// let (file, warnings) = AchFile::parse_bytes_with(&bytes, &options).unwrap();
+    /// let name = &file.batches[0].entries[0].individual_name;
+    /// assert_eq!(name.trim(), "R?CEIVER 1");
+    /// assert_eq!(warnings[0].code, ParseWarningCode::InvalidBytes);
+    /// assert_eq!(warnings[0].line, 3);
+    /// ```
+    pub fn parse_bytes_with(
+        bytes: &'a [u8],
+        options: &ParseOptions,
+    ) -> Result<(Self, Vec<ParseWarning>), AchError> {
+        parser::parse_ach_bytes_with(bytes, options)
+    }
+
+    /// Take the batches out of the file, in file order, without copying
+    /// them.
+    pub fn into_batches(self) -> impl Iterator<Item = Batch<'a>> {
+        self.batches.into_iter()
+    }
+
+    /// Take every entry out of the file, in file order, each with the
+    /// header of its batch. The header is shared by the batch's entries
+    /// rather than copied for each.
+    ///
+    /// The iterator is [`Send`], so it can feed rayon's `par_bridge`.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFileOwned};
+    ///
+    /// let file: AchFileOwned = fixtures::sample_multi_company_file().parse().unwrap();
+    /// let names: Vec<String> = file
+    ///     .into_entries()
+    ///     .map(|(header, entry)| format!("{}: {}", header.company_name.trim(), entry.amount))
+    ///     .collect();
+    /// assert_eq!(names.len(), 7);
+    /// assert_eq!(names[6], "BETA LLC: 12000");
+    /// ```
+    pub fn into_entries(self) -> impl Iterator<Item = (Arc<BatchHeader<'a>>, EntryDetail<'a>)> {
+        self.into_batches().flat_map(|batch| {
+            let header = Arc::new(batch.header);
+            batch
+                .entries
+                .into_iter()
+                .map(move |entry| (Arc::clone(&header), entry))
+        })
+    }
+
+    /// Copy any borrowed fields so the file no longer borrows the input,
+    /// for keeping it after the input is dropped or sending it to another
+    /// thread. Each record type has the same conversion, to its `'static`
+    /// form.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile, AchFileOwned, AchResult};
+    ///
+    /// fn load() -> AchResult<AchFileOwned> {
+    ///     let text = fixtures::sample_ppd_file(2);
+    ///     Ok(AchFile::parse(&text)?.into_owned())
+    /// }
+    ///
+    /// let file = load().unwrap();
+    /// let entries = std::thread::spawn(move || file.batches[0].entries.len());
+    /// assert_eq!(entries.join().unwrap(), 2);
+    /// ```
+    pub fn into_owned(self) -> AchFileOwned {
+        AchFile {
+            file_header: self.file_header.into_owned(),
+            batches: self.batches.into_iter().map(Batch::into_owned).collect(),
+            file_control: self.file_control,
+            unknown_records: self
+                .unknown_records
+                .into_iter()
+                .map(UnknownRecord::into_owned)
+                .collect(),
+        }
+    }
+}
+
+impl std::str::FromStr for AchFileOwned {
+    type Err = AchError;
+
+    fn from_str(content: &str) -> Result<Self, AchError> {
+        AchFile::parse(content).map(AchFile::into_owned)
+    }
 }
 
 /// Represents a batch within an ACH file.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Batch<'a> {
     /// Batch header record (record type 5)
     pub header: BatchHeader<'a>,
@@ -84,4 +375,62 @@ pub struct Batch<'a> {
 
     /// Batch control record (record type 8)
     pub control: BatchControl,
+
+    /// Records of unknown type inside the batch, in file order; always
+    /// empty unless [`ParseOptions::keep_unknown_records`] is set
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub unknown_records: Vec<UnknownRecord<'a>>,
+
+    /// The entries and control read with the ADV layouts, for an ADV batch
+    /// as parsed; `None` for any other batch, and not kept up to date when
+    /// the batch changes
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub adv: Option<AdvBatch>,
+
+    /// The header and entries read with the IAT layouts, for a batch with
+    /// SEC code `IAT` as parsed; `None` for any other batch, and not kept
+    /// up to date when the batch changes
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub iat: Option<IatBatch>,
+}
+
+/// A [`Batch`] that owns all of its data, as from
+/// [`AchFileOwned::into_batches`](AchFile::into_batches).
+pub type BatchOwned = Batch<'static>;
+
+impl<'a> Batch<'a> {
+    /// Take the entries out of the batch, in file order, without copying
+    /// them.
+    pub fn into_entries(self) -> impl Iterator<Item = EntryDetail<'a>> {
+        self.entries.into_iter()
+    }
+
+    /// Copy any borrowed fields so the batch no longer borrows the input.
+    pub fn into_owned(self) -> Batch<'static> {
+        Batch {
+            header: self.header.into_owned(),
+            entries: self
+                .entries
+                .into_iter()
+                .map(EntryDetail::into_owned)
+                .collect(),
+            control: self.control,
+            unknown_records: self
+                .unknown_records
+                .into_iter()
+                .map(UnknownRecord::into_owned)
+                .collect(),
+            adv: self.adv,
+            iat: self.iat,
+        }
+    }
 }
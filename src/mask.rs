@@ -0,0 +1,254 @@
+//! Masking of account numbers and names for logs, reports, and exports.
+//!
+//! [`account`] and [`name`] apply the default rules; a [`MaskingPolicy`]
+//! carries the same rules in a form the report and row APIs accept, so
+//! every surface masks alike.
+//!
+//! An [`EntryDetail`] displays as a masked one-line summary for logs:
+//!
+//! ```
+//! use rs_ach::{fixtures, AchFile};
+//!
+//! let text = fixtures::sample_ppd_file(1);
+//! let file = AchFile::parse(&text).unwrap();
+//! assert_eq!(
+//!     file.batches[0].entries[0].to_string(),
+//!     "credit $12.50 to *****0000 (RECEIVER 1), trace 123456780000001"
+//! );
+//! ```
+
+use std::fmt;
+
+use crate::controls::{is_credit, is_debit};
+use crate::records::EntryDetail;
+use crate::summary::format_cents;
+
+/// How [`MaskingPolicy::name`] masks an individual or company name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum NameMasking {
+    /// The name as it appears, trimmed
+    Unmasked,
+
+    /// The first word's initial and the last word, e.g. `A WANDERDUST`
+    #[default]
+    InitialAndSurname,
+
+    /// The initial of every word, e.g. `A W`
+    Initials,
+
+    /// Nothing of the name
+    Hidden,
+}
+
+/// Masking rules shared by every surface that shows entry data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct MaskingPolicy {
+    /// Trailing characters of an account number left visible, never more
+    /// than half of them; `None` leaves account numbers unmasked
+    pub visible_account_characters: Option<usize>,
+
+    /// How names are masked
+    pub name: NameMasking,
+
+    /// Mask individual identification numbers like account numbers
+    pub mask_identification: bool,
+}
+
+impl Default for MaskingPolicy {
+    /// Last four account characters, first initial and surname, and
+    /// identification numbers masked.
+    fn default() -> Self {
+        MaskingPolicy {
+            visible_account_characters: Some(4),
+            name: NameMasking::InitialAndSurname,
+            mask_identification: true,
+        }
+    }
+}
+
+impl MaskingPolicy {
+    /// A policy that masks nothing.
+    pub fn unmasked() -> Self {
+        MaskingPolicy {
+            visible_account_characters: None,
+            name: NameMasking::Unmasked,
+            mask_identification: false,
+        }
+    }
+
+    /// Mask an account number under this policy.
+    ///
+    /// The result has as many characters as `value`. Spaces, such as
+    /// NACHA padding, stay spaces and are not counted; every other
+    /// character but the last few visible ones becomes `*`.
+    pub fn account(&self, value: &str) -> String {
+        let Some(visible) = self.visible_account_characters else {
+            return value.to_string();
+        };
+        let significant = value.chars().filter(|c| *c != ' ').count();
+        let visible = visible.min(significant / 2);
+        let mut seen = 0;
+        value
+            .chars()
+            .map(|c| {
+                if c == ' ' {
+                    return c;
+                }
+                seen += 1;
+                if seen > significant - visible {
+                    c
+                } else {
+                    '*'
+                }
+            })
+            .collect()
+    }
+
+    /// Mask a name under this policy. Surrounding and repeated spaces are
+    /// removed.
+    pub fn name(&self, value: &str) -> String {
+        let words: Vec<&str> = value.split_whitespace().collect();
+        let initial = |word: &str| word.chars().next().map(String::from).unwrap_or_default();
+        match self.name {
+            NameMasking::Unmasked => words.join(" "),
+            NameMasking::InitialAndSurname => match words.as_slice() {
+                [] => String::new(),
+                [only] => format!("{}{}", initial(only), "*".repeat(only.chars().count() - 1)),
+                [first, .., last] => format!("{} {last}", initial(first)),
+            },
+            NameMasking::Initials => words
+                .iter()
+                .map(|w| initial(w))
+                .collect::<Vec<_>>()
+                .join(" "),
+            NameMasking::Hidden => String::new(),
+        }
+    }
+
+    /// Mask an individual identification number under this policy.
+    pub fn identification(&self, value: &str) -> String {
+        if self.mask_identification {
+            self.account(value)
+        } else {
+            value.to_string()
+        }
+    }
+}
+
+/// Mask an account number with the default policy: the last four
+/// characters stay visible (never more than half of them), the rest become
+/// `*`, and the length is kept.
+pub fn account(value: &str) -> String {
+    MaskingPolicy::default().account(value)
+}
+
+/// Mask a name with the default policy: the first word's initial and the
+/// last word.
+pub fn name(value: &str) -> String {
+    MaskingPolicy::default().name(value)
+}
+
+impl fmt::Display for EntryDetail<'_> {
+    /// `credit $10.00 to ****2132 (ALICE WANDERDUST), trace
+    /// 123456780000001`, with the account masked by the default policy and
+    /// the name trimmed. Debits read `debit $10.00 from …`, prenotes
+    /// `prenote credit …`, and a code that is neither credit nor debit
+    /// `entry …`. The parentheses are left out when the name is blank.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let code = &self.transaction_code;
+        if matches!(code.as_bytes().get(1), Some(b'3' | b'8')) {
+            write!(f, "prenote ")?;
+        }
+        let (kind, preposition) = if is_credit(code) {
+            ("credit", "to")
+        } else if is_debit(code) {
+            ("debit", "from")
+        } else {
+            ("entry", "for")
+        };
+        write!(
+            f,
+            "{kind} ${} {preposition} {}",
+            format_cents(self.amount),
+            account(self.dfi_account_number.trim())
+        )?;
+        let name = MaskingPolicy::unmasked().name(&self.individual_name);
+        if !name.is_empty() {
+            write!(f, " ({name})")?;
+        }
+        write!(f, ", trace {}", self.trace_number.trim())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account() {
+        assert_eq!(account("11232132"), "****2132");
+        assert_eq!(account("000100000        "), "*****0000        ");
+        // Short accounts show at most half their characters.
+        assert_eq!(account("123"), "**3");
+        assert_eq!(account("1"), "*");
+        assert_eq!(account(""), "");
+        assert_eq!(account("     "), "     ");
+
+        let none = MaskingPolicy::unmasked();
+        assert_eq!(none.account("11232132"), "11232132");
+        let hidden = MaskingPolicy {
+            visible_account_characters: Some(0),
+            ..MaskingPolicy::default()
+        };
+        assert_eq!(hidden.account("1123 2132"), "**** ****");
+    }
+
+    #[test]
+    fn test_name() {
+        assert_eq!(name("ALICE WANDERDUST      "), "A WANDERDUST");
+        assert_eq!(name("JOHN Q  PUBLIC"), "J PUBLIC");
+        assert_eq!(name("ACME"), "A***");
+        assert_eq!(name("                      "), "");
+
+        let initials = MaskingPolicy {
+            name: NameMasking::Initials,
+            ..MaskingPolicy::default()
+        };
+        assert_eq!(initials.name("JOHN Q PUBLIC"), "J Q P");
+        assert_eq!(MaskingPolicy::unmasked().name(" JOHN  DOE "), "JOHN DOE");
+    }
+
+    #[test]
+    fn test_entry_display() {
+        let text = crate::fixtures::sample_ppd_file(1);
+        let file = crate::AchFile::parse(&text).unwrap();
+        let mut entry = file.batches[0].entries[0].clone();
+        entry.transaction_code = "22".into();
+        entry.amount = 123_456_789;
+        entry.dfi_account_number = "11232132         ".into();
+        entry.individual_name = "ALICE  WANDERDUST     ".into();
+        assert_eq!(
+            entry.to_string(),
+            "credit $1,234,567.89 to ****2132 (ALICE WANDERDUST), trace 123456780000001"
+        );
+
+        entry.transaction_code = "27".into();
+        entry.amount = 1_000;
+        assert_eq!(
+            entry.to_string(),
+            "debit $10.00 from ****2132 (ALICE WANDERDUST), trace 123456780000001"
+        );
+
+        entry.transaction_code = "38".into();
+        entry.amount = 0;
+        entry.individual_name = " ".repeat(22).into();
+        assert_eq!(
+            entry.to_string(),
+            "prenote debit $0.00 from ****2132, trace 123456780000001"
+        );
+    }
+}
@@ -0,0 +1,297 @@
+//! Detection of micro-deposit account verification activity, and the
+//! NACHA micro-entry rule for batches of it.
+
+use std::collections::HashMap;
+
+use crate::controls::{is_credit, is_debit};
+use crate::{AchFile, Batch, EntryDescriptionKind};
+
+/// Micro-entry credits must be for less than this, in cents.
+const MICRO_ENTRY_LIMIT: u64 = 100;
+
+/// One entry of a [`MicroDepositGroup`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct MicroDepositEntry {
+    /// Batch number of the entry's batch, trimmed
+    pub batch_number: String,
+
+    /// Trace number of the entry
+    pub trace_number: String,
+
+    /// Amount in cents
+    pub amount: u64,
+}
+
+/// Small credits to one account that look like account verification, from
+/// [`AchFile::detect_micro_deposits`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct MicroDepositGroup {
+    /// Receiving routing number, including the check digit
+    pub routing_number: String,
+
+    /// Receiving DFI account number, trimmed
+    pub account_number: String,
+
+    /// The sub-threshold credits, in file order
+    pub credits: Vec<MicroDepositEntry>,
+
+    /// A debit to the same account for the sum of the credits, if any
+    pub claw_back: Option<MicroDepositEntry>,
+}
+
+impl MicroDepositGroup {
+    /// Sum of the credit amounts, in cents.
+    pub fn total_credit_amount(&self) -> u64 {
+        self.credits.iter().map(|c| c.amount).sum()
+    }
+}
+
+/// The NACHA micro-entry rule applied to a batch, from
+/// [`Batch::micro_entry_check`]. Each check is kept so that callers can say
+/// which part of the rule a batch breaks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct MicroEntryCheck {
+    /// Whether the company entry description is `ACCTVERIFY`
+    pub described: bool,
+
+    /// Trace numbers of credits for $1.00 or more, in batch order
+    pub large_credits: Vec<String>,
+
+    /// Sum of the credit amounts, in cents
+    pub total_credit_amount: u64,
+
+    /// Sum of the debit amounts, in cents
+    pub total_debit_amount: u64,
+}
+
+impl MicroEntryCheck {
+    /// Whether the batch's debits total exactly its credits.
+    pub fn is_offset(&self) -> bool {
+        self.total_debit_amount == self.total_credit_amount
+    }
+
+    /// Whether the batch follows every part of the rule.
+    pub fn is_valid(&self) -> bool {
+        self.described && self.large_credits.is_empty() && self.is_offset()
+    }
+}
+
+impl Batch<'_> {
+    /// Check this batch against the micro-entry rule, or `None` if it is
+    /// not a micro-entry batch; see [`Batch::is_micro_entry_batch`].
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile};
+    ///
+    /// let text = fixtures::sample_ppd_file(2);
+    /// let mut file = AchFile::parse(&text).unwrap();
+    /// let batch = &mut file.batches[0];
+    /// assert!(batch.micro_entry_check().is_none());
+    ///
+    /// batch.header.company_entry_description = "ACCTVERIFY".into();
+    /// let check = batch.micro_entry_check().unwrap();
+    /// assert!(check.described);
+    /// assert_eq!(check.large_credits.len(), 1);
+    /// assert!(!check.is_offset());
+    /// ```
+    pub fn micro_entry_check(&self) -> Option<MicroEntryCheck> {
+        let mut check = MicroEntryCheck {
+            described: self.header.entry_description_kind()
+                == EntryDescriptionKind::AccountVerification,
+            large_credits: Vec::new(),
+            total_credit_amount: 0,
+            total_debit_amount: 0,
+        };
+        for entry in &self.entries {
+            if is_credit(&entry.transaction_code) {
+                check.total_credit_amount += entry.amount;
+                if entry.amount >= MICRO_ENTRY_LIMIT {
+                    check.large_credits.push(entry.trace_number.to_string());
+                }
+            } else if is_debit(&entry.transaction_code) {
+                check.total_debit_amount += entry.amount;
+            }
+        }
+        let looks_like =
+            check.total_credit_amount > 0 && check.large_credits.is_empty() && check.is_offset();
+        (check.described || looks_like).then_some(check)
+    }
+
+    /// Whether this is a micro-entry batch: one described `ACCTVERIFY`, or
+    /// one whose nonzero credits are all under $1.00 and offset by debits
+    /// of their total.
+    pub fn is_micro_entry_batch(&self) -> bool {
+        self.micro_entry_check().is_some()
+    }
+}
+
+impl AchFile<'_> {
+    /// Find accounts receiving two or more nonzero credits below
+    /// `threshold_cents`, across all batches of the file.
+    ///
+    /// Groups are keyed by routing and trimmed account number and returned in
+    /// order of their first credit. A debit to the same account equal to the
+    /// sum of the credits is reported as the group's claw-back. Zero-amount
+    /// entries, such as prenotes, are ignored.
+    pub fn detect_micro_deposits(&self, threshold_cents: u64) -> Vec<MicroDepositGroup> {
+        let mut groups: Vec<MicroDepositGroup> = Vec::new();
+        let mut index: HashMap<(String, String), usize> = HashMap::new();
+        let mut debits: Vec<((String, String), MicroDepositEntry)> = Vec::new();
+
+        for batch in &self.batches {
+            for entry in &batch.entries {
+                if entry.amount == 0 {
+                    continue;
+                }
+                let key = (
+                    format!(
+                        "{}{}",
+                        entry.receiving_dfi_identification.trim(),
+                        entry.check_digit.trim()
+                    ),
+                    entry.dfi_account_number.trim().to_string(),
+                );
+                let found = MicroDepositEntry {
+                    batch_number: batch.header.batch_number.trim().to_string(),
+                    trace_number: entry.trace_number.to_string(),
+                    amount: entry.amount,
+                };
+                if is_debit(&entry.transaction_code) {
+                    debits.push((key, found));
+                } else if is_credit(&entry.transaction_code) && entry.amount < threshold_cents {
+                    let i = *index.entry(key.clone()).or_insert_with(|| {
+                        groups.push(MicroDepositGroup {
+                            routing_number: key.0,
+                            account_number: key.1,
+                            credits: Vec::new(),
+                            claw_back: None,
+                        });
+                        groups.len() - 1
+                    });
+                    groups[i].credits.push(found);
+                }
+            }
+        }
+
+        for (key, debit) in debits {
+            if let Some(&i) = index.get(&key) {
+                let group = &mut groups[i];
+                if group.claw_back.is_none() && debit.amount == group.total_credit_amount() {
+                    group.claw_back = Some(debit);
+                }
+            }
+        }
+        groups.retain(|g| g.credits.len() >= 2);
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    /// Point entries at one account with the given transaction codes and
+    /// amounts.
+    fn set_entries(file: &mut AchFile<'_>, batch: usize, entries: &[(&'static str, u64)]) {
+        for (entry, &(code, amount)) in file.batches[batch].entries.iter_mut().zip(entries) {
+            entry.receiving_dfi_identification = "12345678".into();
+            entry.check_digit = "0".into();
+            entry.dfi_account_number = "55501234         ".into();
+            entry.transaction_code = code.into();
+            entry.amount = amount;
+        }
+    }
+
+    #[test]
+    fn test_pair_with_claw_back() {
+        let text = fixtures::sample_ppd_file(4);
+        let mut file = AchFile::parse(&text).unwrap();
+        set_entries(&mut file, 0, &[("22", 32), ("22", 45), ("27", 77)]);
+
+        let groups = file.detect_micro_deposits(100);
+        assert_eq!(groups.len(), 1);
+        let group = &groups[0];
+        assert_eq!(group.routing_number, "123456780");
+        assert_eq!(group.account_number, "55501234");
+        let amounts: Vec<u64> = group.credits.iter().map(|c| c.amount).collect();
+        assert_eq!(amounts, [32, 45]);
+        assert_eq!(group.credits[1].trace_number, "123456780000002");
+        let claw_back = group.claw_back.as_ref().unwrap();
+        assert_eq!(claw_back.amount, 77);
+        assert_eq!(claw_back.trace_number, "123456780000003");
+
+        // Below the threshold only one credit qualifies.
+        assert_eq!(file.detect_micro_deposits(40), []);
+    }
+
+    #[test]
+    fn test_across_batches() {
+        let text = fixtures::sample_multi_company_file();
+        let mut file = AchFile::parse(&text).unwrap();
+        set_entries(&mut file, 0, &[("22", 12)]);
+        set_entries(&mut file, 2, &[("22", 9), ("27", 20)]);
+
+        let groups = file.detect_micro_deposits(100);
+        assert_eq!(groups.len(), 1);
+        let batches: Vec<&str> = groups[0]
+            .credits
+            .iter()
+            .map(|c| c.batch_number.as_str())
+            .collect();
+        assert_eq!(batches, ["0000001", "0000003"]);
+        // The debit does not match the $0.21 total.
+        assert_eq!(groups[0].total_credit_amount(), 21);
+        assert_eq!(groups[0].claw_back, None);
+    }
+
+    #[test]
+    fn test_micro_entry_check() {
+        let text = fixtures::sample_ppd_file(3);
+        let mut file = AchFile::parse(&text).unwrap();
+        set_entries(&mut file, 0, &[("22", 32), ("22", 45), ("27", 77)]);
+        let batch = &mut file.batches[0];
+
+        // Undescribed, but shaped like micro-entries.
+        let check = batch.micro_entry_check().unwrap();
+        assert!(!check.described);
+        assert!(check.large_credits.is_empty());
+        assert_eq!(check.total_credit_amount, 77);
+        assert!(check.is_offset());
+        assert!(!check.is_valid());
+
+        batch.header.company_entry_description = "ACCTVERIFY".into();
+        assert!(batch.micro_entry_check().unwrap().is_valid());
+
+        batch.entries[1].amount = 145;
+        let check = batch.micro_entry_check().unwrap();
+        assert_eq!(check.large_credits, ["123456780000002"]);
+        assert!(!check.is_offset());
+
+        // Without the description, large or unbalanced credits are not
+        // micro-entries.
+        batch.header.company_entry_description = "PAYROLL".into();
+        assert!(!batch.is_micro_entry_batch());
+        batch.entries[1].amount = 45;
+        batch.entries[2].amount = 70;
+        assert!(!batch.is_micro_entry_batch());
+    }
+
+    #[test]
+    fn test_ignores_prenotes_and_other_accounts() {
+        let text = fixtures::sample_ppd_file(4);
+        let mut file = AchFile::parse(&text).unwrap();
+        set_entries(&mut file, 0, &[("23", 0), ("22", 45)]);
+        assert_eq!(file.detect_micro_deposits(100), []);
+
+        // Fixture entries are all to different accounts.
+        let file = AchFile::parse(&text).unwrap();
+        assert_eq!(file.detect_micro_deposits(u64::MAX), []);
+    }
+}
@@ -0,0 +1,510 @@
+//! JSON in the shape of the Go moov-io/ach library's files.
+//!
+//! Available with the `moov` feature. The keys and nesting are moov's:
+//! camelCase field names (with its `ODFIIdentification`,
+//! `RDFIIdentification`, and `DFIAccountNumber` spellings), batches under
+//! `batches` with `batchHeader`, `entryDetails`, and `batchControl`, and
+//! addenda under `addenda02`, `addenda05`, `addenda98`, or `addenda99` by
+//! type. Text is trimmed and codes, counts, and sequence numbers are
+//! numbers. As moov omits empty optional fields, so does this.
+//!
+//! The file creation date and time are RFC 3339 timestamps in UTC, e.g.
+//! `2014-09-02T11:23:00Z`, which moov reads in place of `YYMMDD` and
+//! `HHMM`; when either is invalid, both are written as they are in the
+//! file. Batch dates stay `YYMMDD`, as moov keeps them.
+
+use crate::records::{Addenda, BatchHeader, EntryDetail, FileHeader};
+use crate::{AchError, AchFile, Batch, CivilDate, SecCode};
+
+/// A moov `File`.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MoovFile {
+    id: String,
+    file_header: MoovFileHeader,
+    batches: Vec<MoovBatch>,
+    file_control: MoovFileControl,
+}
+
+/// A moov `FileHeader`.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MoovFileHeader {
+    id: String,
+    immediate_destination: String,
+    immediate_origin: String,
+    file_creation_date: String,
+    file_creation_time: String,
+    #[serde(rename = "fileIDModifier", skip_serializing_if = "String::is_empty")]
+    file_id_modifier: String,
+    immediate_destination_name: String,
+    immediate_origin_name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    reference_code: String,
+}
+
+/// A moov `Batch`.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MoovBatch {
+    id: String,
+    batch_header: MoovBatchHeader,
+    entry_details: Vec<MoovEntryDetail>,
+    batch_control: MoovBatchControl,
+}
+
+/// A moov `BatchHeader`.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MoovBatchHeader {
+    id: String,
+    service_class_code: u32,
+    company_name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    company_discretionary_data: String,
+    company_identification: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    standard_entry_class_code: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    company_entry_description: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    company_descriptive_date: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    effective_entry_date: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    settlement_date: String,
+    #[serde(skip_serializing_if = "is_zero")]
+    originator_status_code: u32,
+    #[serde(rename = "ODFIIdentification")]
+    odfi_identification: String,
+    batch_number: u32,
+}
+
+/// A moov `EntryDetail`.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MoovEntryDetail {
+    id: String,
+    transaction_code: u32,
+    #[serde(rename = "RDFIIdentification")]
+    rdfi_identification: String,
+    check_digit: String,
+    #[serde(rename = "DFIAccountNumber")]
+    dfi_account_number: String,
+    amount: u64,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    identification_number: String,
+    individual_name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    discretionary_data: String,
+    #[serde(skip_serializing_if = "is_zero")]
+    addenda_record_indicator: u32,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    trace_number: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    addenda02: Option<MoovAddenda02>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    addenda05: Vec<MoovAddenda05>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    addenda98: Option<MoovAddenda98>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    addenda99: Option<MoovAddenda99>,
+    category: &'static str,
+}
+
+/// A moov `Addenda02`: a terminal addenda.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MoovAddenda02 {
+    id: String,
+    type_code: String,
+    reference_information_one: String,
+    reference_information_two: String,
+    terminal_identification_code: String,
+    transaction_serial_number: String,
+    transaction_date: String,
+    authorization_code_or_expire_date: String,
+    terminal_location: String,
+    terminal_city: String,
+    terminal_state: String,
+    trace_number: String,
+}
+
+/// A moov `Addenda05`: payment related information.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MoovAddenda05 {
+    id: String,
+    type_code: String,
+    payment_related_information: String,
+    sequence_number: u32,
+    entry_detail_sequence_number: u32,
+}
+
+/// A moov `Addenda98`: a notification of change.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MoovAddenda98 {
+    id: String,
+    type_code: String,
+    change_code: String,
+    original_trace: String,
+    #[serde(rename = "originalDFI")]
+    original_dfi: String,
+    corrected_data: String,
+    trace_number: String,
+}
+
+/// A moov `Addenda99`: a return.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MoovAddenda99 {
+    id: String,
+    type_code: String,
+    return_code: String,
+    original_trace: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    date_of_death: String,
+    #[serde(rename = "originalDFI")]
+    original_dfi: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    addenda_information: String,
+    trace_number: String,
+}
+
+/// A moov `BatchControl`.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MoovBatchControl {
+    id: String,
+    service_class_code: u32,
+    entry_addenda_count: u64,
+    entry_hash: u64,
+    total_debit: u64,
+    total_credit: u64,
+    company_identification: String,
+    #[serde(
+        rename = "messageAuthentication",
+        skip_serializing_if = "String::is_empty"
+    )]
+    message_authentication_code: String,
+    #[serde(rename = "ODFIIdentification")]
+    odfi_identification: String,
+    batch_number: u32,
+}
+
+/// A moov `FileControl`.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MoovFileControl {
+    id: String,
+    batch_count: u64,
+    block_count: u64,
+    entry_addenda_count: u64,
+    entry_hash: u64,
+    total_debit: u64,
+    total_credit: u64,
+}
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
+/// A field trimmed, as moov keeps it.
+fn text(value: &str) -> String {
+    value.trim().to_string()
+}
+
+/// A numeric field as moov reads it, zero when it is not a number.
+fn number(value: &str) -> u32 {
+    value.trim().parse().unwrap_or(0)
+}
+
+/// The file creation date and time as RFC 3339 timestamps, or as they are
+/// when either is invalid.
+fn creation_timestamps(header: &FileHeader<'_>) -> (String, String) {
+    let date = header.creation_date::<CivilDate>();
+    let time = crate::dates::parse_hhmm(&header.file_creation_time);
+    match date.zip(time) {
+        Some((date, (hour, minute))) => {
+            let day = format!("{:04}-{:02}-{:02}", date.year(), date.month(), date.day());
+            (
+                format!("{day}T00:00:00Z"),
+                format!("{day}T{hour:02}:{minute:02}:00Z"),
+            )
+        }
+        None => (
+            text(&header.file_creation_date),
+            text(&header.file_creation_time),
+        ),
+    }
+}
+
+fn header(header: &FileHeader<'_>) -> MoovFileHeader {
+    let (file_creation_date, file_creation_time) = creation_timestamps(header);
+    MoovFileHeader {
+        id: String::new(),
+        immediate_destination: text(&header.immediate_destination),
+        immediate_origin: text(&header.immediate_origin),
+        file_creation_date,
+        file_creation_time,
+        file_id_modifier: text(&header.file_id_modifier),
+        immediate_destination_name: text(&header.immediate_destination_name),
+        immediate_origin_name: text(&header.immediate_origin_name),
+        reference_code: text(&header.reference_code),
+    }
+}
+
+fn batch_header(header: &BatchHeader<'_>) -> MoovBatchHeader {
+    MoovBatchHeader {
+        id: String::new(),
+        service_class_code: number(&header.service_class_code),
+        company_name: text(&header.company_name),
+        company_discretionary_data: text(&header.company_discretionary_data),
+        company_identification: text(&header.company_identification),
+        standard_entry_class_code: text(&header.standard_entry_class_code),
+        company_entry_description: text(&header.company_entry_description),
+        company_descriptive_date: text(&header.company_descriptive_date),
+        effective_entry_date: text(&header.effective_entry_date),
+        settlement_date: text(&header.settlement_date),
+        originator_status_code: number(&header.originator_status_code),
+        odfi_identification: text(&header.originating_dfi_identification),
+        batch_number: number(&header.batch_number),
+    }
+}
+
+/// The entry, or [`AchError::InvalidValue`] for an addenda of a type moov
+/// has no field for.
+fn entry(entry: &EntryDetail<'_>) -> Result<MoovEntryDetail, AchError> {
+    let mut moov = MoovEntryDetail {
+        id: String::new(),
+        transaction_code: number(&entry.transaction_code),
+        rdfi_identification: text(&entry.receiving_dfi_identification),
+        check_digit: text(&entry.check_digit),
+        dfi_account_number: text(&entry.dfi_account_number),
+        amount: entry.amount,
+        identification_number: text(&entry.individual_identification_number),
+        individual_name: text(&entry.individual_name),
+        discretionary_data: text(&entry.discretionary_data),
+        addenda_record_indicator: number(&entry.addenda_record_indicator),
+        trace_number: text(&entry.trace_number),
+        addenda02: None,
+        addenda05: Vec::new(),
+        addenda98: None,
+        addenda99: None,
+        category: "Forward",
+    };
+    for addenda in &entry.addenda {
+        add_addenda(&mut moov, addenda)?;
+    }
+    Ok(moov)
+}
+
+fn add_addenda(moov: &mut MoovEntryDetail, addenda: &Addenda<'_>) -> Result<(), AchError> {
+    let type_code = text(&addenda.addenda_type_code);
+    if let Some(terminal) = addenda.as_terminal() {
+        moov.addenda02 = Some(MoovAddenda02 {
+            id: String::new(),
+            type_code,
+            reference_information_one: terminal.reference_information_1,
+            reference_information_two: terminal.reference_information_2,
+            terminal_identification_code: terminal.terminal_identification_code,
+            transaction_serial_number: terminal.transaction_serial_number,
+            transaction_date: terminal.transaction_date,
+            authorization_code_or_expire_date: terminal.authorization_code_or_card_expiration_date,
+            terminal_location: terminal.terminal_location,
+            terminal_city: terminal.terminal_city,
+            terminal_state: terminal.terminal_state,
+            trace_number: terminal.trace_number,
+        });
+    } else if let Some(noc) = addenda.as_noc() {
+        moov.category = "NOC";
+        moov.addenda98 = Some(MoovAddenda98 {
+            id: String::new(),
+            type_code,
+            change_code: noc.change_code,
+            original_trace: noc.original_entry_trace_number,
+            original_dfi: noc.original_receiving_dfi_identification,
+            corrected_data: noc.corrected_data,
+            trace_number: noc.trace_number,
+        });
+    } else if let Some(returned) = addenda.as_return() {
+        moov.category = "Return";
+        moov.addenda99 = Some(MoovAddenda99 {
+            id: String::new(),
+            type_code,
+            return_code: returned.return_reason_code,
+            original_trace: returned.original_entry_trace_number,
+            date_of_death: returned.date_of_death.unwrap_or_default(),
+            original_dfi: returned.original_receiving_dfi_identification,
+            addenda_information: returned.addenda_information,
+            trace_number: returned.trace_number,
+        });
+    } else if type_code == "05" {
+        moov.addenda05.push(MoovAddenda05 {
+            id: String::new(),
+            type_code,
+            payment_related_information: text(&addenda.payment_related_information),
+            sequence_number: number(&addenda.addenda_sequence_number),
+            entry_detail_sequence_number: number(&addenda.entry_detail_sequence_number),
+        });
+    } else {
+        return Err(AchError::InvalidValue {
+            field: "addenda_type_code",
+            reason: format!("type {type_code} addenda have no moov field"),
+        });
+    }
+    Ok(())
+}
+
+/// The batch, or the error for an entry moov cannot hold or an IAT or ADV
+/// batch, whose layouts moov keeps apart.
+fn batch(index: usize, batch: &Batch<'_>) -> Result<MoovBatch, AchError> {
+    let batch_number = batch.header.batch_number.trim().to_string();
+    let sec = batch.header.sec_code().ok();
+    if sec == Some(SecCode::Iat) || batch.is_adv() {
+        return Err(AchError::InBatch {
+            batch_index: index,
+            batch_number,
+            source: Box::new(AchError::InvalidValue {
+                field: "standard_entry_class_code",
+                reason: format!(
+                    "{} batches have their own moov layout",
+                    batch.header.standard_entry_class_code.trim()
+                ),
+            }),
+        });
+    }
+    let mut entries = Vec::with_capacity(batch.entries.len());
+    for (entry_index, detail) in batch.entries.iter().enumerate() {
+        entries.push(entry(detail).map_err(|source| AchError::InEntry {
+            batch_index: index,
+            batch_number: batch_number.clone(),
+            entry_index,
+            trace_number: Some(detail.trace_number.to_string()),
+            source: Box::new(source),
+        })?);
+    }
+    let control = &batch.control;
+    Ok(MoovBatch {
+        id: String::new(),
+        batch_header: batch_header(&batch.header),
+        entry_details: entries,
+        batch_control: MoovBatchControl {
+            id: String::new(),
+            service_class_code: number(&control.service_class_code),
+            entry_addenda_count: control.entry_addenda_count,
+            entry_hash: control.entry_hash,
+            total_debit: control.total_debit_amount,
+            total_credit: control.total_credit_amount,
+            company_identification: text(&control.company_identification),
+            message_authentication_code: text(&control.message_authentication_code),
+            odfi_identification: text(&control.originating_dfi_identification),
+            batch_number: number(&control.batch_number),
+        },
+    })
+}
+
+impl AchFile<'_> {
+    /// The file as pretty-printed JSON in moov-io/ach's shape.
+    ///
+    /// Keys are moov's camelCase field names, with batches under `batches`
+    /// and addenda under `addenda02`, `addenda05`, `addenda98`, or
+    /// `addenda99` by type. Text is trimmed, codes and counts are numbers,
+    /// and empty optional fields are left out. The file creation date and
+    /// time are one RFC 3339 timestamp in UTC, as moov reads them.
+    ///
+    /// IAT and ADV batches, which moov holds apart with their own layouts,
+    /// and addenda of types moov has no field for are an error naming the
+    /// batch or entry.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile};
+    ///
+    /// let text = fixtures::sample_ppd_file(1);
+    /// let json = AchFile::parse(&text).unwrap().to_moov_json().unwrap();
+    /// let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    /// let entry = &value["batches"][0]["entryDetails"][0];
+    /// assert_eq!(entry["RDFIIdentification"], "12345678");
+    /// assert_eq!(entry["amount"], 1250);
+    /// assert_eq!(value["fileHeader"]["fileCreationDate"], "2014-09-02T00:00:00Z");
+    /// ```
+    pub fn to_moov_json(&self) -> Result<String, AchError> {
+        let batches = self
+            .batches
+            .iter()
+            .enumerate()
+            .map(|(index, b)| batch(index, b))
+            .collect::<Result<_, _>>()?;
+        let control = &self.file_control;
+        let file = MoovFile {
+            id: String::new(),
+            file_header: header(&self.file_header),
+            batches,
+            file_control: MoovFileControl {
+                id: String::new(),
+                batch_count: control.batch_count,
+                block_count: control.block_count,
+                entry_addenda_count: control.entry_addenda_count,
+                entry_hash: control.entry_hash,
+                total_debit: control.total_debit_amount,
+                total_credit: control.total_credit_amount,
+            },
+        };
+        Ok(serde_json::to_string_pretty(&file).expect("moov JSON has only string keys") + "\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use serde_json::Value;
+
+    fn moov(text: &str) -> Value {
+        let json = AchFile::parse(text).unwrap().to_moov_json().unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn test_returns_and_notifications() {
+        let value = moov(&fixtures::sample_returns_file());
+        let entry = &value["batches"][0]["entryDetails"][0];
+        assert_eq!(entry["category"], "Return");
+        assert_eq!(entry["addenda99"]["returnCode"], "R01");
+        assert_eq!(entry["addenda99"]["originalTrace"], "091012980000001");
+
+        let value = moov(&fixtures::sample_noc_file());
+        let entry = &value["batches"][0]["entryDetails"][0];
+        assert_eq!(entry["category"], "NOC");
+        assert_eq!(entry["addenda98"]["typeCode"], "98");
+        assert!(entry.get("addenda99").is_none());
+    }
+
+    #[test]
+    fn test_terminal_addenda() {
+        let value = moov(&fixtures::sample_pos_file());
+        let addenda = &value["batches"][0]["entryDetails"][0]["addenda02"];
+        assert_eq!(addenda["terminalCity"], "SPRINGFIELD");
+        assert_eq!(addenda["typeCode"], "02");
+    }
+
+    #[test]
+    fn test_unmapped_batches() {
+        for text in [fixtures::sample_iat_file(), fixtures::sample_adv_file()] {
+            let err = AchFile::parse(&text).unwrap().to_moov_json().unwrap_err();
+            assert!(matches!(err, AchError::InBatch { batch_index: 0, .. }));
+            assert!(err.to_string().contains("have their own moov layout"));
+        }
+    }
+
+    #[test]
+    fn test_invalid_creation_date() {
+        let text = fixtures::sample_ppd_file(1);
+        let mut file = AchFile::parse(&text).unwrap();
+        file.file_header.file_creation_date = "140231".into();
+        let value: Value = serde_json::from_str(&file.to_moov_json().unwrap()).unwrap();
+        assert_eq!(value["fileHeader"]["fileCreationDate"], "140231");
+        assert_eq!(value["fileHeader"]["fileCreationTime"], "0123");
+    }
+}
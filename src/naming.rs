@@ -0,0 +1,364 @@
+//! File naming conventions for transmitted and received files.
+
+use crate::dates::{CivilDate, YearPivot};
+use crate::records::FileHeader;
+
+/// How the file creation date is written in a file name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum DateFormat {
+    /// `20240902`
+    #[default]
+    Yyyymmdd,
+
+    /// `240902`, as in the file header
+    Yymmdd,
+
+    /// `090224`
+    Mmddyy,
+}
+
+impl DateFormat {
+    fn format(self, date: CivilDate) -> String {
+        let (y, m, d) = (date.year(), date.month(), date.day());
+        match self {
+            DateFormat::Yyyymmdd => format!("{y:04}{m:02}{d:02}"),
+            DateFormat::Yymmdd => format!("{:02}{m:02}{d:02}", y % 100),
+            DateFormat::Mmddyy => format!("{m:02}{d:02}{:02}", y % 100),
+        }
+    }
+
+    fn parse(self, value: &str) -> Option<CivilDate> {
+        if !value.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let number = |range: std::ops::Range<usize>| value.get(range)?.parse::<u32>().ok();
+        let year = |yy: u32| YearPivot::default().resolve(yy);
+        let (y, m, d) = match self {
+            DateFormat::Yyyymmdd if value.len() == 8 => {
+                (number(0..4)? as i32, number(4..6)?, number(6..8)?)
+            }
+            DateFormat::Yymmdd if value.len() == 6 => {
+                (year(number(0..2)?), number(2..4)?, number(4..6)?)
+            }
+            DateFormat::Mmddyy if value.len() == 6 => {
+                (year(number(4..6)?), number(0..2)?, number(2..4)?)
+            }
+            _ => return None,
+        };
+        CivilDate::new(y, m, d)
+    }
+}
+
+/// A file name template of the form
+/// `{prefix}{sep}{origin}{sep}{date}{sep}{modifier}[{sep}{environment}].{extension}`,
+/// e.g. `ACH_1234567801_20240902_A.txt`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct NamingConvention {
+    /// Leading tag; empty to start with the origin
+    pub prefix: String,
+
+    /// Separator between components; names without one cannot be parsed
+    /// back
+    pub separator: String,
+
+    /// How the file creation date is written
+    pub date_format: DateFormat,
+
+    /// Environment tag such as `TEST` or `PROD`, appended after the
+    /// modifier when set
+    pub environment: Option<String>,
+
+    /// Extension without the dot; empty for none
+    pub extension: String,
+
+    /// Upper-case the whole name, extension included
+    pub uppercase: bool,
+}
+
+impl Default for NamingConvention {
+    /// `ACH_{origin}_{YYYYMMDD}_{modifier}.txt`.
+    fn default() -> Self {
+        NamingConvention {
+            prefix: "ACH".to_string(),
+            separator: "_".to_string(),
+            date_format: DateFormat::Yyyymmdd,
+            environment: None,
+            extension: "txt".to_string(),
+            uppercase: false,
+        }
+    }
+}
+
+/// The components of a file name, from [`NamingConvention::parse_filename`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct FileNameParts {
+    /// Immediate origin, trimmed
+    pub immediate_origin: String,
+
+    /// File creation date
+    pub file_creation_date: CivilDate,
+
+    /// File ID modifier
+    pub file_id_modifier: char,
+
+    /// Environment tag, if the name carries one
+    pub environment: Option<String>,
+}
+
+impl FileNameParts {
+    /// Whether `header` has this origin, creation date, and modifier.
+    pub fn matches(&self, header: &FileHeader<'_>) -> bool {
+        header.immediate_origin.trim() == self.immediate_origin
+            && CivilDate::parse(&header.file_creation_date) == Some(self.file_creation_date)
+            && header
+                .file_id_modifier
+                .trim()
+                .eq_ignore_ascii_case(&self.file_id_modifier.to_string())
+    }
+
+    /// Whether both names are for the same origin, creation date, and
+    /// modifier, and so would be taken for the same file.
+    pub fn collides_with(&self, other: &FileNameParts) -> bool {
+        self.immediate_origin == other.immediate_origin
+            && self.file_creation_date == other.file_creation_date
+            && self
+                .file_id_modifier
+                .eq_ignore_ascii_case(&other.file_id_modifier)
+    }
+}
+
+/// File ID modifiers in the order they are used through a day.
+const MODIFIERS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+impl NamingConvention {
+    /// Join the components of a file name.
+    fn filename(&self, origin: &str, date: &str, modifier: &str) -> String {
+        let mut parts: Vec<&str> = Vec::new();
+        if !self.prefix.is_empty() {
+            parts.push(&self.prefix);
+        }
+        parts.extend([origin, date, modifier]);
+        if let Some(environment) = &self.environment {
+            parts.push(environment);
+        }
+        let mut name = parts.join(&self.separator);
+        if !self.extension.is_empty() {
+            name.push('.');
+            name.push_str(&self.extension);
+        }
+        if self.uppercase {
+            name.make_ascii_uppercase();
+        }
+        name
+    }
+
+    /// Read a file name written with this convention, or `None` if it does
+    /// not follow it. The prefix, environment, and extension are compared
+    /// without regard to case.
+    pub fn parse_filename(&self, name: &str) -> Option<FileNameParts> {
+        let name = name.rsplit(['/', '\\']).next().unwrap_or(name);
+        let stem = if self.extension.is_empty() {
+            name
+        } else {
+            let (stem, extension) = name.rsplit_once('.')?;
+            if !extension.eq_ignore_ascii_case(&self.extension) {
+                return None;
+            }
+            stem
+        };
+
+        if self.separator.is_empty() {
+            return None;
+        }
+        let mut parts: Vec<&str> = stem.split(self.separator.as_str()).collect();
+        if !self.prefix.is_empty() {
+            if !parts.first()?.eq_ignore_ascii_case(&self.prefix) {
+                return None;
+            }
+            parts.remove(0);
+        }
+        let environment = match &self.environment {
+            Some(expected) => {
+                let environment = parts.pop()?;
+                if !environment.eq_ignore_ascii_case(expected) {
+                    return None;
+                }
+                Some(environment.to_string())
+            }
+            None => None,
+        };
+
+        let [origin, date, modifier] = parts[..] else {
+            return None;
+        };
+        let mut modifier = modifier.chars();
+        let file_id_modifier = modifier.next().filter(char::is_ascii_alphanumeric)?;
+        if modifier.next().is_some() || origin.is_empty() {
+            return None;
+        }
+        Some(FileNameParts {
+            immediate_origin: origin.to_string(),
+            file_creation_date: self.date_format.parse(date)?,
+            file_id_modifier: file_id_modifier.to_ascii_uppercase(),
+            environment,
+        })
+    }
+
+    /// The first file ID modifier, from `A`-`Z` then `0`-`9`, not used by any
+    /// of `existing` for `header`'s origin and creation date, or `None` when
+    /// all 36 are taken. Names not following this convention are ignored.
+    pub fn next_file_id_modifier(
+        &self,
+        header: &FileHeader<'_>,
+        existing: &[&str],
+    ) -> Option<char> {
+        let origin = header.immediate_origin.trim();
+        let date = CivilDate::parse(&header.file_creation_date);
+        let used: Vec<char> = existing
+            .iter()
+            .filter_map(|name| self.parse_filename(name))
+            .filter(|parts| {
+                parts.immediate_origin == origin && Some(parts.file_creation_date) == date
+            })
+            .map(|parts| parts.file_id_modifier)
+            .collect();
+        MODIFIERS.chars().find(|m| !used.contains(m))
+    }
+}
+
+impl FileHeader<'_> {
+    /// A file name for this file following `convention`, built from the
+    /// immediate origin, creation date, and file ID modifier.
+    ///
+    /// A creation date that is not a valid `YYMMDD` is written as it
+    /// appears.
+    pub fn suggested_filename(&self, convention: &NamingConvention) -> String {
+        let date = CivilDate::parse(&self.file_creation_date).map_or_else(
+            || self.file_creation_date.trim().to_string(),
+            |date| convention.date_format.format(date),
+        );
+        convention.filename(
+            self.immediate_origin.trim(),
+            &date,
+            self.file_id_modifier.trim(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use crate::AchFile;
+
+    #[test]
+    fn test_suggested_filename() {
+        let text = fixtures::sample_ppd_file(1);
+        let file = AchFile::parse(&text).unwrap();
+        let header = &file.file_header;
+        assert_eq!(
+            header.suggested_filename(&NamingConvention::default()),
+            "ACH_1234567801_20140902_A.txt"
+        );
+
+        let convention = NamingConvention {
+            prefix: String::new(),
+            separator: "-".to_string(),
+            date_format: DateFormat::Mmddyy,
+            environment: Some("test".to_string()),
+            extension: "ach".to_string(),
+            uppercase: true,
+        };
+        assert_eq!(
+            header.suggested_filename(&convention),
+            "1234567801-090214-A-TEST.ACH"
+        );
+    }
+
+    #[test]
+    fn test_parse_filename_round_trip() {
+        let text = fixtures::sample_ppd_file(1);
+        let file = AchFile::parse(&text).unwrap();
+        let header = &file.file_header;
+        let convention = NamingConvention {
+            environment: Some("PROD".to_string()),
+            date_format: DateFormat::Yymmdd,
+            ..NamingConvention::default()
+        };
+
+        let name = header.suggested_filename(&convention);
+        let parts = convention
+            .parse_filename(&format!("inbound/{name}"))
+            .unwrap();
+        assert_eq!(parts.immediate_origin, "1234567801");
+        assert_eq!(
+            parts.file_creation_date,
+            CivilDate::new(2014, 9, 2).unwrap()
+        );
+        assert_eq!(parts.file_id_modifier, 'A');
+        assert_eq!(parts.environment.as_deref(), Some("PROD"));
+        assert!(parts.matches(header));
+
+        assert_eq!(
+            convention.parse_filename("ACH_1234567801_140902_A.txt"),
+            None
+        );
+        assert_eq!(
+            convention.parse_filename("ACH_1234567801_141302_A_PROD.txt"),
+            None
+        );
+        assert_eq!(
+            convention.parse_filename("ACH_1234567801_140902_AB_PROD.txt"),
+            None
+        );
+        assert_eq!(
+            convention.parse_filename("ACH_1234567801_140902_A_PROD.csv"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_modifier_rotation() {
+        let text = fixtures::sample_ppd_file(1);
+        let file = AchFile::parse(&text).unwrap();
+        let header = &file.file_header;
+        let convention = NamingConvention::default();
+
+        let existing = [
+            "ACH_1234567801_20140902_A.txt",
+            "ach_1234567801_20140902_b.TXT",
+            // Another day and another origin do not count.
+            "ACH_1234567801_20140903_C.txt",
+            "ACH_9999999999_20140902_C.txt",
+            "notes.txt",
+        ];
+        assert_eq!(
+            convention.next_file_id_modifier(header, &existing),
+            Some('C')
+        );
+
+        let first = convention.parse_filename(existing[0]).unwrap();
+        let second = convention.parse_filename(existing[1]).unwrap();
+        let unrelated = convention.parse_filename(existing[2]).unwrap();
+        assert!(!first.collides_with(&second));
+        assert!(!first.collides_with(&unrelated));
+        assert!(first.collides_with(
+            &convention
+                .parse_filename(&header.suggested_filename(&convention))
+                .unwrap()
+        ));
+
+        let all: Vec<String> = MODIFIERS
+            .chars()
+            .map(|m| format!("ACH_1234567801_20140902_{m}.txt"))
+            .collect();
+        let all: Vec<&str> = all.iter().map(String::as_str).collect();
+        assert_eq!(convention.next_file_id_modifier(header, &all), None);
+    }
+}
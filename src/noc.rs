@@ -0,0 +1,535 @@
+//! Notification of Change (NOC) addenda, change codes, and corrected data.
+
+use std::fmt;
+
+use crate::records::{Addenda, EntryDetail};
+use crate::{routing, AchFile, Batch};
+
+/// Defines [`ChangeCode`] from one row per code: the code, whether it is a
+/// refused NOC, and its description.
+macro_rules! change_codes {
+    ($($code:ident, $refused:literal, $description:literal;)*) => {
+        /// A change code from a Notification of Change addenda (type 98).
+        ///
+        /// Codes not defined by NACHA are kept as [`ChangeCode::Other`].
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+        #[non_exhaustive]
+        pub enum ChangeCode {
+            $(
+                #[doc = $description]
+                $code,
+            )*
+
+            /// A code not in the NACHA table, as it appeared in the file
+            #[cfg_attr(feature = "serde", serde(untagged))]
+            Other(String),
+        }
+
+        impl ChangeCode {
+            /// Read a code such as `C01`. Surrounding spaces are ignored.
+            pub fn from_code(code: &str) -> ChangeCode {
+                match code.trim() {
+                    $(stringify!($code) => ChangeCode::$code,)*
+                    other => ChangeCode::Other(other.to_string()),
+                }
+            }
+
+            /// The code as it appears in the file, e.g. `C01`.
+            pub fn code(&self) -> &str {
+                match self {
+                    $(ChangeCode::$code => stringify!($code),)*
+                    ChangeCode::Other(code) => code,
+                }
+            }
+
+            /// Refused flag and description of a known code.
+            fn row(&self) -> Option<(bool, &'static str)> {
+                match self {
+                    $(ChangeCode::$code => Some(($refused, $description)),)*
+                    ChangeCode::Other(_) => None,
+                }
+            }
+        }
+    };
+}
+
+#[rustfmt::skip]
+change_codes! {
+    C01, false, "Incorrect DFI Account Number";
+    C02, false, "Incorrect Routing Number";
+    C03, false, "Incorrect Routing Number and Incorrect DFI Account Number";
+    C04, false, "Incorrect Individual Name/Receiving Company Name";
+    C05, false, "Incorrect Transaction Code";
+    C06, false, "Incorrect DFI Account Number and Incorrect Transaction Code";
+    C07, false, "Incorrect Routing Number, Incorrect DFI Account Number, and Incorrect Transaction Code";
+    C08, false, "Incorrect Receiving DFI Identification (IAT Only)";
+    C09, false, "Incorrect Individual Identification Number";
+    C10, false, "Incorrect Company Name";
+    C11, false, "Incorrect Company Identification";
+    C12, false, "Incorrect Company Name and Company Identification";
+    C13, false, "Addenda Format Error";
+    C14, false, "Incorrect SEC Code for Outbound International Payment";
+    C61, true, "Misrouted Notification of Change";
+    C62, true, "Incorrect Trace Number";
+    C63, true, "Incorrect Company Identification Number";
+    C64, true, "Incorrect Individual Identification Number/Identification Number";
+    C65, true, "Incorrectly Formatted Corrected Data";
+    C66, true, "Incorrect Discretionary Data";
+    C67, true, "Routing Number Not from Original Entry Detail Record";
+    C68, true, "DFI Account Number Not from Original Entry Detail Record";
+    C69, true, "Incorrect Transaction Code";
+}
+
+/// The corrected data field of a Notification of Change, read according to
+/// its change code.
+///
+/// Text values are trimmed. Routing numbers are the full nine digits with a
+/// valid check digit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub enum CorrectedData {
+    /// C01
+    AccountNumber(String),
+
+    /// C02
+    RoutingNumber(String),
+
+    /// C03
+    RoutingAndAccountNumber {
+        /// Corrected routing number
+        routing_number: String,
+        /// Corrected DFI account number
+        account_number: String,
+    },
+
+    /// C04
+    Name(String),
+
+    /// C05
+    TransactionCode(String),
+
+    /// C06
+    AccountNumberAndTransactionCode {
+        /// Corrected DFI account number
+        account_number: String,
+        /// Corrected transaction code
+        transaction_code: String,
+    },
+
+    /// C07
+    RoutingAccountNumberAndTransactionCode {
+        /// Corrected routing number
+        routing_number: String,
+        /// Corrected DFI account number
+        account_number: String,
+        /// Corrected transaction code
+        transaction_code: String,
+    },
+
+    /// C09
+    IndividualIdentificationNumber(String),
+
+    /// C10
+    CompanyName(String),
+
+    /// C11
+    CompanyIdentification(String),
+
+    /// C12
+    CompanyNameAndIdentification {
+        /// Corrected company name
+        company_name: String,
+        /// Corrected company identification
+        company_identification: String,
+    },
+
+    /// Codes whose corrected data has no fixed layout (C08, C13, C14, and
+    /// refused or unknown codes), trimmed
+    Other(String),
+
+    /// The corrected data does not fit its change code's layout (blank, a
+    /// bad routing number, or a non-numeric transaction code), as it
+    /// appeared
+    Malformed(String),
+}
+
+impl ChangeCode {
+    /// NACHA description, e.g. `Incorrect DFI Account Number`, or `Unknown
+    /// Change Code` for [`ChangeCode::Other`].
+    pub fn description(&self) -> &'static str {
+        self.row().map_or("Unknown Change Code", |(_, d)| d)
+    }
+
+    /// Whether this code refuses a Notification of Change (C61-C69) rather
+    /// than reporting one.
+    pub fn refused(&self) -> bool {
+        self.row().is_some_and(|(refused, _)| refused)
+    }
+
+    /// The fields of the original entry or batch header that the corrected
+    /// data replaces, in the order they appear in it. Empty for codes whose
+    /// corrected data has no fixed layout.
+    ///
+    /// ```
+    /// use rs_ach::{ChangeCode, CorrectedField};
+    ///
+    /// assert_eq!(
+    ///     ChangeCode::C06.corrected_fields(),
+    ///     [CorrectedField::AccountNumber, CorrectedField::TransactionCode]
+    /// );
+    /// assert!(ChangeCode::C13.corrected_fields().is_empty());
+    /// ```
+    pub fn corrected_fields(&self) -> &'static [CorrectedField] {
+        use CorrectedField::*;
+
+        match self {
+            ChangeCode::C01 => &[AccountNumber],
+            ChangeCode::C02 => &[RoutingNumber],
+            ChangeCode::C03 => &[RoutingNumber, AccountNumber],
+            ChangeCode::C04 => &[Name],
+            ChangeCode::C05 => &[TransactionCode],
+            ChangeCode::C06 => &[AccountNumber, TransactionCode],
+            ChangeCode::C07 => &[RoutingNumber, AccountNumber, TransactionCode],
+            ChangeCode::C09 => &[IndividualIdentificationNumber],
+            ChangeCode::C10 => &[CompanyName],
+            ChangeCode::C11 => &[CompanyIdentification],
+            ChangeCode::C12 => &[CompanyName, CompanyIdentification],
+            _ => &[],
+        }
+    }
+
+    /// Read a 29-character corrected data field according to this code's
+    /// layout.
+    pub fn parse_corrected_data(&self, corrected_data: &str) -> CorrectedData {
+        read_corrected_data(self, corrected_data)
+            .unwrap_or_else(|| CorrectedData::Malformed(corrected_data.to_string()))
+    }
+}
+
+/// A field a Notification of Change corrects, from
+/// [`ChangeCode::corrected_fields`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub enum CorrectedField {
+    /// The entry's DFI account number
+    AccountNumber,
+
+    /// The entry's receiving DFI identification and check digit
+    RoutingNumber,
+
+    /// The entry's transaction code
+    TransactionCode,
+
+    /// The entry's individual name, or receiving company name
+    Name,
+
+    /// The entry's individual identification number
+    IndividualIdentificationNumber,
+
+    /// The batch header's company name
+    CompanyName,
+
+    /// The batch header's company identification
+    CompanyIdentification,
+}
+
+/// The fields of a Notification of Change addenda (type 98).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct NocAddenda {
+    /// Change code, e.g. `C01`
+    pub change_code: String,
+
+    /// Trace number of the entry being corrected
+    pub original_entry_trace_number: String,
+
+    /// Receiving DFI identification of the original entry (8 digits)
+    pub original_receiving_dfi_identification: String,
+
+    /// Corrected data, untrimmed; see [`NocAddenda::correction`]
+    pub corrected_data: String,
+
+    /// Trace number of the notification entry
+    pub trace_number: String,
+}
+
+impl NocAddenda {
+    /// The typed change code.
+    pub fn code(&self) -> ChangeCode {
+        ChangeCode::from_code(&self.change_code)
+    }
+
+    /// The corrected data, read according to the change code.
+    pub fn correction(&self) -> CorrectedData {
+        self.code().parse_corrected_data(&self.corrected_data)
+    }
+}
+
+impl Addenda<'_> {
+    /// Read this addenda as a Notification of Change addenda, or `None`
+    /// unless its type code is `98`.
+    pub fn as_noc(&self) -> Option<NocAddenda> {
+        if self.addenda_type_code != "98" {
+            return None;
+        }
+        // As for type 99, reassemble positions 4-94 and slice them again.
+        let rest = format!(
+            "{}{}{}",
+            self.payment_related_information,
+            self.addenda_sequence_number,
+            self.entry_detail_sequence_number
+        );
+        let slice = |from: usize, to: usize| rest.get(from..to).unwrap_or("");
+
+        Some(NocAddenda {
+            change_code: slice(0, 3).trim().to_string(),
+            original_entry_trace_number: slice(3, 18).trim().to_string(),
+            original_receiving_dfi_identification: slice(24, 32).trim().to_string(),
+            corrected_data: slice(32, 61).to_string(),
+            trace_number: slice(76, 91).trim().to_string(),
+        })
+    }
+}
+
+impl EntryDetail<'_> {
+    /// The entry's Notification of Change addenda, or `None` if it has none.
+    pub fn as_noc(&self) -> Option<NocAddenda> {
+        self.addenda.iter().find_map(Addenda::as_noc)
+    }
+}
+
+impl AchFile<'_> {
+    /// Every entry carrying a Notification of Change addenda, with its
+    /// batch, in file order.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile, CorrectedData};
+    ///
+    /// let text = fixtures::sample_noc_file();
+    /// let file = AchFile::parse(&text).unwrap();
+    /// for (batch, entry, noc) in file.notifications_of_change() {
+    ///     assert_eq!(batch.header.standard_entry_class_code, "COR");
+    ///     assert_eq!(entry.amount, 0);
+    ///     if let CorrectedData::AccountNumber(account) = noc.correction() {
+    ///         assert_eq!(account, "1918171614");
+    ///     }
+    /// }
+    /// ```
+    pub fn notifications_of_change(
+        &self,
+    ) -> impl Iterator<Item = (&Batch<'_>, &EntryDetail<'_>, NocAddenda)> {
+        self.batches.iter().flat_map(|batch| {
+            batch
+                .entries
+                .iter()
+                .filter_map(move |entry| Some((batch, entry, entry.as_noc()?)))
+        })
+    }
+}
+
+/// Read `data` with the layout of `code`, or `None` if it does not fit.
+fn read_corrected_data(code: &ChangeCode, data: &str) -> Option<CorrectedData> {
+    use CorrectedData::*;
+
+    Some(match code {
+        ChangeCode::C01 => AccountNumber(text(data, 0, 17)?),
+        ChangeCode::C02 => RoutingNumber(routing_number(data, 0)?),
+        ChangeCode::C03 => RoutingAndAccountNumber {
+            routing_number: routing_number(data, 0)?,
+            account_number: text(data, 12, 29)?,
+        },
+        ChangeCode::C04 => Name(text(data, 0, 22)?),
+        ChangeCode::C05 => TransactionCode(transaction_code(data, 0)?),
+        ChangeCode::C06 => AccountNumberAndTransactionCode {
+            account_number: text(data, 0, 17)?,
+            transaction_code: transaction_code(data, 20)?,
+        },
+        ChangeCode::C07 => RoutingAccountNumberAndTransactionCode {
+            routing_number: routing_number(data, 0)?,
+            account_number: text(data, 9, 26)?,
+            transaction_code: transaction_code(data, 26)?,
+        },
+        ChangeCode::C09 => IndividualIdentificationNumber(text(data, 0, 22)?),
+        ChangeCode::C10 => CompanyName(text(data, 0, 16)?),
+        ChangeCode::C11 => CompanyIdentification(text(data, 0, 10)?),
+        ChangeCode::C12 => CompanyNameAndIdentification {
+            company_name: text(data, 0, 16)?,
+            company_identification: text(data, 16, 26)?,
+        },
+        _ => Other(data.trim().to_string()),
+    })
+}
+
+/// Characters `from..to` of `data`, trimmed, or `None` when blank.
+fn text(data: &str, from: usize, to: usize) -> Option<String> {
+    let value = data.get(from..to.min(data.len()))?.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// A valid nine-digit routing number starting at `from`.
+fn routing_number(data: &str, from: usize) -> Option<String> {
+    text(data, from, from + 9).filter(|r| routing::is_valid(r))
+}
+
+/// A two-digit transaction code starting at `from`.
+fn transaction_code(data: &str, from: usize) -> Option<String> {
+    text(data, from, from + 2).filter(|c| c.len() == 2 && c.bytes().all(|b| b.is_ascii_digit()))
+}
+
+impl fmt::Display for ChangeCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_as_noc() {
+        let text = fixtures::sample_noc_file();
+        let file = AchFile::parse(&text).unwrap();
+        let entry = &file.batches[0].entries[0];
+        let noc = entry.addenda[0].as_noc().unwrap();
+        assert_eq!(
+            noc,
+            NocAddenda {
+                change_code: "C01".to_string(),
+                original_entry_trace_number: "091012980000001".to_string(),
+                original_receiving_dfi_identification: "12345678".to_string(),
+                corrected_data: format!("{:<29}", "1918171614"),
+                trace_number: "123456780000001".to_string(),
+            }
+        );
+        assert_eq!(entry.as_noc(), Some(noc));
+
+        let returns = fixtures::sample_returns_file();
+        let returns = AchFile::parse(&returns).unwrap();
+        assert_eq!(returns.batches[0].entries[0].as_noc(), None);
+        assert_eq!(returns.notifications_of_change().count(), 0);
+    }
+
+    #[test]
+    fn test_notifications_of_change() {
+        let text = fixtures::sample_noc_file();
+        let file = AchFile::parse(&text).unwrap();
+        let notices: Vec<_> = file.notifications_of_change().collect();
+        assert_eq!(notices.len(), 2);
+
+        let (batch, entry, noc) = &notices[1];
+        assert_eq!(batch.header.batch_number, "0000001");
+        assert_eq!(entry.trace_number, "123456780000002");
+        assert_eq!(noc.code(), ChangeCode::C05);
+        assert_eq!(
+            noc.code().corrected_fields(),
+            [CorrectedField::TransactionCode]
+        );
+        assert_eq!(
+            noc.correction(),
+            CorrectedData::TransactionCode("32".to_string())
+        );
+    }
+
+    #[test]
+    fn test_change_code_table() {
+        let code = ChangeCode::from_code("C01");
+        assert_eq!(code, ChangeCode::C01);
+        assert_eq!(code.description(), "Incorrect DFI Account Number");
+        assert!(!code.refused());
+        assert!(ChangeCode::C65.refused());
+        assert_eq!(ChangeCode::C14.to_string(), "C14");
+
+        let unknown = ChangeCode::from_code("C99");
+        assert_eq!(unknown, ChangeCode::Other("C99".to_string()));
+        assert_eq!(unknown.description(), "Unknown Change Code");
+        assert!(!unknown.refused());
+    }
+
+    #[test]
+    fn test_parse_single_field_corrections() {
+        assert_eq!(
+            ChangeCode::C01.parse_corrected_data("1918171614                   "),
+            CorrectedData::AccountNumber("1918171614".to_string())
+        );
+        assert_eq!(
+            ChangeCode::C02.parse_corrected_data("123456780                    "),
+            CorrectedData::RoutingNumber("123456780".to_string())
+        );
+        assert_eq!(
+            ChangeCode::C04.parse_corrected_data("JANE DOE                     "),
+            CorrectedData::Name("JANE DOE".to_string())
+        );
+        assert_eq!(
+            ChangeCode::C05.parse_corrected_data("32                           "),
+            CorrectedData::TransactionCode("32".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_combined_corrections() {
+        assert_eq!(
+            ChangeCode::C03.parse_corrected_data("123456780   1918171614       "),
+            CorrectedData::RoutingAndAccountNumber {
+                routing_number: "123456780".to_string(),
+                account_number: "1918171614".to_string(),
+            }
+        );
+        assert_eq!(
+            ChangeCode::C06.parse_corrected_data("1918171614          27       "),
+            CorrectedData::AccountNumberAndTransactionCode {
+                account_number: "1918171614".to_string(),
+                transaction_code: "27".to_string(),
+            }
+        );
+        assert_eq!(
+            ChangeCode::C07.parse_corrected_data("1234567801918171614       37 "),
+            CorrectedData::RoutingAccountNumberAndTransactionCode {
+                routing_number: "123456780".to_string(),
+                account_number: "1918171614".to_string(),
+                transaction_code: "37".to_string(),
+            }
+        );
+        assert_eq!(
+            ChangeCode::C12.parse_corrected_data("ACME CORP       9876543210   "),
+            CorrectedData::CompanyNameAndIdentification {
+                company_name: "ACME CORP".to_string(),
+                company_identification: "9876543210".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_malformed_and_other() {
+        let bad_check_digit = "123456781                    ";
+        assert_eq!(
+            ChangeCode::C02.parse_corrected_data(bad_check_digit),
+            CorrectedData::Malformed(bad_check_digit.to_string())
+        );
+        let multibyte = "1234567é                    ";
+        assert_eq!(
+            ChangeCode::C02.parse_corrected_data(multibyte),
+            CorrectedData::Malformed(multibyte.to_string())
+        );
+        assert_eq!(
+            ChangeCode::C05.parse_corrected_data("X2"),
+            CorrectedData::Malformed("X2".to_string())
+        );
+        assert_eq!(
+            ChangeCode::C01.parse_corrected_data(""),
+            CorrectedData::Malformed(String::new())
+        );
+        assert_eq!(
+            ChangeCode::C13.parse_corrected_data("SEE ADDENDA   "),
+            CorrectedData::Other("SEE ADDENDA".to_string())
+        );
+    }
+}
@@ -0,0 +1,151 @@
+//! Normalizing free text, such as names from an HR system, to what a NACHA
+//! alphameric field can carry.
+
+use crate::AchError;
+
+/// Normalize `input` for a NACHA alphameric field of `max_len` characters:
+/// upper-case it, transliterate Latin letters with diacritics (`José` is
+/// `JOSE`, `Müller` is `MULLER`, `ß` is `SS`), replace typographic
+/// punctuation with its ASCII form (dashes with `-`, curly quotes with `'`
+/// and `"`), turn every run of whitespace into one space, and truncate.
+///
+/// Characters with no ASCII form, such as emoji, are dropped; see
+/// [`normalize_to_nacha_strict`] to reject them instead. The result is
+/// trimmed and not padded.
+///
+/// ```
+/// use rs_ach::normalize_to_nacha;
+///
+/// assert_eq!(normalize_to_nacha("José  Müller—Smith 🎉", 22), "JOSE MULLER-SMITH");
+/// assert_eq!(normalize_to_nacha("Ærøskøbing Straße", 10), "AEROSKOBIN");
+/// ```
+pub fn normalize_to_nacha(input: &str, max_len: usize) -> String {
+    normalize(input, max_len, |_| {})
+}
+
+/// Like [`normalize_to_nacha`], but fail with [`AchError::InvalidValue`]
+/// naming every character with no ASCII form instead of dropping them.
+///
+/// ```
+/// use rs_ach::normalize_to_nacha_strict;
+///
+/// assert_eq!(normalize_to_nacha_strict("Zoë", 16).unwrap(), "ZOE");
+/// let err = normalize_to_nacha_strict("Café ☕ €", 16).unwrap_err();
+/// assert!(err.to_string().contains("'☕', '€'"));
+/// ```
+pub fn normalize_to_nacha_strict(input: &str, max_len: usize) -> Result<String, AchError> {
+    let mut unmappable = Vec::new();
+    let normalized = normalize(input, max_len, |c| {
+        if !unmappable.contains(&c) {
+            unmappable.push(c);
+        }
+    });
+    if unmappable.is_empty() {
+        return Ok(normalized);
+    }
+    let characters: Vec<String> = unmappable.iter().map(|c| format!("{c:?}")).collect();
+    Err(AchError::InvalidValue {
+        field: "input",
+        reason: format!("no NACHA form for {}", characters.join(", ")),
+    })
+}
+
+/// Normalize `input`, passing each character with no ASCII form to
+/// `unmappable`.
+fn normalize(input: &str, max_len: usize, mut unmappable: impl FnMut(char)) -> String {
+    let mut output = String::with_capacity(input.len().min(max_len));
+    let mut space = false;
+    for c in input.chars() {
+        if c.is_whitespace() {
+            space = !output.is_empty();
+            continue;
+        }
+        let mut mapped = String::new();
+        for upper in c.to_uppercase() {
+            match transliterate(upper) {
+                Some(ascii) => mapped.push_str(ascii),
+                None if (' '..='~').contains(&upper) => mapped.push(upper),
+                None => unmappable(c),
+            }
+        }
+        if mapped.is_empty() {
+            continue;
+        }
+        if space {
+            output.push(' ');
+            space = false;
+        }
+        output.push_str(&mapped);
+    }
+    output.truncate(max_len);
+    output.truncate(output.trim_end().len());
+    output
+}
+
+/// The ASCII form of an upper-case letter or a punctuation mark outside
+/// ASCII.
+fn transliterate(c: char) -> Option<&'static str> {
+    Some(match c {
+        'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' | 'Ā' | 'Ă' | 'Ą' => "A",
+        'Æ' => "AE",
+        'Ç' | 'Ć' | 'Ĉ' | 'Ċ' | 'Č' => "C",
+        'Ð' | 'Ď' | 'Đ' => "D",
+        'È' | 'É' | 'Ê' | 'Ë' | 'Ē' | 'Ĕ' | 'Ė' | 'Ę' | 'Ě' => "E",
+        'Ĝ' | 'Ğ' | 'Ġ' | 'Ģ' => "G",
+        'Ĥ' | 'Ħ' => "H",
+        'Ì' | 'Í' | 'Î' | 'Ï' | 'Ĩ' | 'Ī' | 'Ĭ' | 'Į' | 'İ' => "I",
+        'Ĳ' => "IJ",
+        'Ĵ' => "J",
+        'Ķ' => "K",
+        'Ĺ' | 'Ļ' | 'Ľ' | 'Ŀ' | 'Ł' => "L",
+        'Ñ' | 'Ń' | 'Ņ' | 'Ň' => "N",
+        'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' | 'Ō' | 'Ŏ' | 'Ő' => "O",
+        'Œ' => "OE",
+        'Ŕ' | 'Ŗ' | 'Ř' => "R",
+        'Ś' | 'Ŝ' | 'Ş' | 'Š' | 'Ș' => "S",
+        'ẞ' => "SS",
+        'Ţ' | 'Ť' | 'Ŧ' | 'Ț' => "T",
+        'Þ' => "TH",
+        'Ù' | 'Ú' | 'Û' | 'Ü' | 'Ũ' | 'Ū' | 'Ŭ' | 'Ů' | 'Ű' | 'Ų' => "U",
+        'Ŵ' => "W",
+        'Ý' | 'Ÿ' | 'Ŷ' => "Y",
+        'Ź' | 'Ż' | 'Ž' => "Z",
+        '\u{2010}'..='\u{2015}' | '\u{2212}' => "-",
+        '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{2032}' | '´' => "'",
+        '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{2033}' | '«' | '»' => "\"",
+        '\u{2026}' => "...",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_to_nacha() {
+        assert_eq!(
+            normalize_to_nacha("  o’brien,\tañez\n", 22),
+            "O'BRIEN, ANEZ"
+        );
+        assert_eq!(normalize_to_nacha("“Łódź” … Þór", 22), "\"LODZ\" ... THOR");
+        assert_eq!(normalize_to_nacha("straße", 22), "STRASSE");
+        assert_eq!(normalize_to_nacha("ab 🎉 cd", 22), "AB CD");
+        assert_eq!(normalize_to_nacha("abc def", 4), "ABC");
+        assert_eq!(normalize_to_nacha("", 10), "");
+        assert_eq!(normalize_to_nacha("🎉", 10), "");
+
+        let text = normalize_to_nacha("Ærøskøbing — Ĳssel", 100);
+        assert!(text.chars().all(|c| (' '..='~').contains(&c)));
+    }
+
+    #[test]
+    fn test_strict() {
+        assert_eq!(normalize_to_nacha_strict("Renée", 16).unwrap(), "RENEE");
+        let err = normalize_to_nacha_strict("a\u{7}b 😀 😀", 16).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid value for 'input': no NACHA form for '\\u{7}', '😀'"
+        );
+    }
+}
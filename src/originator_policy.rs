@@ -0,0 +1,208 @@
+//! Checks of batches against each originating company's agreement.
+
+use std::collections::HashMap;
+
+use crate::AchFile;
+
+/// What one originating company is authorized to send. Empty lists allow
+/// any value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct OriginatorPolicy {
+    /// Standard entry class codes allowed, such as `PPD`
+    pub allowed_sec_codes: Vec<String>,
+
+    /// Service class codes allowed, such as `220`
+    pub allowed_service_class_codes: Vec<String>,
+
+    /// Company entry descriptions allowed, compared without surrounding
+    /// spaces or case
+    pub allowed_entry_descriptions: Vec<String>,
+
+    /// Largest amount allowed on one entry, in cents
+    pub max_entry_amount: Option<u64>,
+}
+
+/// The rule of an [`OriginatorPolicy`] a batch or entry breaks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub enum PolicyRule {
+    /// No policy is on file for the company
+    UnknownCompany,
+
+    /// The batch's standard entry class code is not allowed
+    SecCode(String),
+
+    /// The batch's service class code is not allowed
+    ServiceClass(String),
+
+    /// The batch's company entry description is not allowed
+    EntryDescription(String),
+
+    /// The entry's amount is over the limit
+    EntryAmount {
+        /// Amount of the entry, in cents
+        amount: u64,
+
+        /// The policy's limit, in cents
+        limit: u64,
+    },
+}
+
+/// A batch or entry breaking its company's policy, from
+/// [`AchFile::check_policies`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PolicyViolation {
+    /// Company identification of the batch, trimmed
+    pub company_identification: String,
+
+    /// Batch number, trimmed
+    pub batch_number: String,
+
+    /// Trace number of the entry, for entry rules
+    pub trace_number: Option<String>,
+
+    /// The rule broken
+    pub rule: PolicyRule,
+}
+
+impl AchFile<'_> {
+    /// Check every batch against the policy for its company, keyed by
+    /// company identification without surrounding spaces.
+    ///
+    /// A batch whose company has no policy is reported once as
+    /// [`PolicyRule::UnknownCompany`] and not checked further. Violations
+    /// are in file order, each batch's rules before its entries'.
+    pub fn check_policies(
+        &self,
+        policies: &HashMap<String, OriginatorPolicy>,
+    ) -> Vec<PolicyViolation> {
+        let policies: HashMap<&str, &OriginatorPolicy> =
+            policies.iter().map(|(k, v)| (k.trim(), v)).collect();
+        let allowed = |list: &[String], value: &str| {
+            list.is_empty() || list.iter().any(|v| v.trim().eq_ignore_ascii_case(value))
+        };
+
+        let mut violations = Vec::new();
+        for batch in &self.batches {
+            let header = &batch.header;
+            let company = header.company_identification.trim();
+            let violation = |trace_number: Option<String>, rule| PolicyViolation {
+                company_identification: company.to_string(),
+                batch_number: header.batch_number.trim().to_string(),
+                trace_number,
+                rule,
+            };
+            let Some(policy) = policies.get(company) else {
+                violations.push(violation(None, PolicyRule::UnknownCompany));
+                continue;
+            };
+
+            let sec = header.standard_entry_class_code.trim();
+            if !allowed(&policy.allowed_sec_codes, sec) {
+                violations.push(violation(None, PolicyRule::SecCode(sec.to_string())));
+            }
+            let service_class = header.service_class_code.trim();
+            if !allowed(&policy.allowed_service_class_codes, service_class) {
+                violations.push(violation(
+                    None,
+                    PolicyRule::ServiceClass(service_class.to_string()),
+                ));
+            }
+            let description = header.company_entry_description.trim();
+            if !allowed(&policy.allowed_entry_descriptions, description) {
+                violations.push(violation(
+                    None,
+                    PolicyRule::EntryDescription(description.to_string()),
+                ));
+            }
+            if let Some(limit) = policy.max_entry_amount {
+                for entry in batch.entries.iter().filter(|e| e.amount > limit) {
+                    violations.push(violation(
+                        Some(entry.trace_number.to_string()),
+                        PolicyRule::EntryAmount {
+                            amount: entry.amount,
+                            limit,
+                        },
+                    ));
+                }
+            }
+        }
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_check_policies() {
+        let text = fixtures::sample_multi_company_file();
+        let file = AchFile::parse(&text).unwrap();
+        let acme = OriginatorPolicy {
+            allowed_sec_codes: vec!["PPD".to_string()],
+            allowed_entry_descriptions: vec!["payroll".to_string(), "VENDOR PAY".to_string()],
+            max_entry_amount: Some(200_000),
+            ..OriginatorPolicy::default()
+        };
+        let policies = HashMap::from([("1111111111 ".to_string(), acme)]);
+
+        let violations = file.check_policies(&policies);
+        let rules: Vec<(&str, Option<&str>, &PolicyRule)> = violations
+            .iter()
+            .map(|v| (v.batch_number.as_str(), v.trace_number.as_deref(), &v.rule))
+            .collect();
+        let first_trace = file.batches[0].entries[0].trace_number.as_ref();
+        let vendor_trace = file.batches[1].entries[0].trace_number.as_ref();
+        assert_eq!(
+            rules,
+            [
+                (
+                    "0000001",
+                    Some(first_trace),
+                    &PolicyRule::EntryAmount {
+                        amount: 250_000,
+                        limit: 200_000
+                    }
+                ),
+                ("0000002", None, &PolicyRule::SecCode("CCD".to_string())),
+                (
+                    "0000002",
+                    Some(vendor_trace),
+                    &PolicyRule::EntryAmount {
+                        amount: 900_000,
+                        limit: 200_000
+                    }
+                ),
+                ("0000003", None, &PolicyRule::UnknownCompany),
+            ]
+        );
+        assert_eq!(violations[3].company_identification, "2222222222");
+    }
+
+    #[test]
+    fn test_permissive_policy() {
+        let text = fixtures::sample_ppd_file(2);
+        let file = AchFile::parse(&text).unwrap();
+        let mut policies = HashMap::from([("1234567890".to_string(), OriginatorPolicy::default())]);
+        assert_eq!(file.check_policies(&policies), []);
+
+        policies
+            .get_mut("1234567890")
+            .unwrap()
+            .allowed_service_class_codes = vec!["220".to_string()];
+        let violations = file.check_policies(&policies);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].rule,
+            PolicyRule::ServiceClass("200".to_string())
+        );
+    }
+}
@@ -0,0 +1,965 @@
+//! Options for [`AchFile::parse_with`] and the warnings it reports.
+//!
+//! [`AchFile::parse_with`]: crate::AchFile::parse_with
+
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::dates::{self, CivilDate};
+use crate::error::{AchError, ErrorKind};
+use crate::records::{BatchHeader, FileHeader};
+use crate::sec_code::SecCode;
+
+/// Options for [`AchFile::parse_with`](crate::AchFile::parse_with).
+///
+/// The default parses exactly like [`AchFile::parse`](crate::AchFile::parse):
+/// no lenient behavior is enabled and no warning is an error. At most
+/// [`DEFAULT_MAX_ERRORS`] warnings are kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ParseOptions {
+    /// Accept lines longer than 94 characters when the excess is
+    /// whitespace, dropping it ([`ParseWarningCode::LongLine`])
+    pub allow_long_lines: bool,
+
+    /// Pad records shorter than 94 characters with spaces, as when a line
+    /// has lost its trailing spaces ([`ParseWarningCode::ShortLine`])
+    pub pad_short_lines: bool,
+
+    /// Compute the file control from the batches when the file ends
+    /// without one ([`ParseWarningCode::MissingFileControl`])
+    pub allow_missing_file_control: bool,
+
+    /// Read the SEC codes of batch headers and the file ID modifier in
+    /// upper case when they are written in lower case
+    /// ([`ParseWarningCode::LowercaseCode`])
+    pub case_insensitive_record_codes: bool,
+
+    /// Read numeric fields that are all spaces as zero
+    /// ([`ParseWarningCode::BlankNumber`])
+    pub blank_numbers_as_zero: bool,
+
+    /// How strictly numeric fields are read
+    pub number_format: NumberFormat,
+
+    /// Keep records whose type code is none of NACHA's as
+    /// [`UnknownRecord`](crate::UnknownRecord)s instead of failing
+    /// ([`ParseWarningCode::UnknownRecord`])
+    pub keep_unknown_records: bool,
+
+    /// When parsing bytes with [`AchFile::parse_bytes_with`], replace each
+    /// byte that is not valid UTF-8 with `?` instead of failing with
+    /// [`AchError::InvalidEncoding`] ([`ParseWarningCode::InvalidBytes`])
+    ///
+    /// [`AchFile::parse_bytes_with`]: crate::AchFile::parse_bytes_with
+    pub replace_invalid_bytes: bool,
+
+    /// Warnings that fail the parse with [`AchError::DeniedWarning`]
+    /// instead of being reported
+    pub deny: Vec<ParseWarningCode>,
+
+    /// Warnings to keep before the rest are dropped, ending the list with
+    /// one [`ParseWarningCode::TooManyWarnings`]; lossy
+    /// [`Records`](crate::Records) likewise end with
+    /// [`AchError::TooManyErrors`]. `None` keeps them all.
+    pub max_errors: Option<usize>,
+
+    /// Called with the parse's progress as it goes; `None` for no calls.
+    /// Not serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[cfg_attr(feature = "schemars", schemars(skip))]
+    pub progress: Option<ProgressHook>,
+}
+
+/// How numeric fields such as amounts, counts, and the entry hash must be
+/// written, from [`ParseOptions::number_format`]. However strict, a value
+/// cannot exceed its field's width, so amounts stay below 10^10 and batch
+/// counts below 10^6.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum NumberFormat {
+    /// Digits with any surrounding spaces, e.g. `"  1000    "`
+    #[default]
+    Trimmed,
+
+    /// Digits right-aligned in the field, with spaces or zeros before them,
+    /// e.g. `"      1000"`
+    SpacePadded,
+
+    /// Digits filling the field, as NACHA requires, e.g. `"0000001000"`
+    ZeroPadded,
+}
+
+impl NumberFormat {
+    /// Why `value` is not written in this format, if it is not.
+    pub(crate) fn check(self, value: &str) -> Option<String> {
+        let digits = match self {
+            NumberFormat::Trimmed => return None,
+            NumberFormat::SpacePadded => value.trim_start_matches(' '),
+            NumberFormat::ZeroPadded => value,
+        };
+        if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let expected = match self {
+            NumberFormat::SpacePadded => "right-aligned digits",
+            _ => "zero-padded digits",
+        };
+        Some(format!(
+            "expected {} {expected}, got {value:?}",
+            value.len()
+        ))
+    }
+}
+
+/// The default [`ParseOptions::max_errors`] and
+/// [`ValidationOptions::max_errors`](crate::ValidationOptions::max_errors).
+pub const DEFAULT_MAX_ERRORS: usize = 1_000;
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            allow_long_lines: false,
+            pad_short_lines: false,
+            allow_missing_file_control: false,
+            case_insensitive_record_codes: false,
+            blank_numbers_as_zero: false,
+            number_format: NumberFormat::Trimmed,
+            keep_unknown_records: false,
+            replace_invalid_bytes: false,
+            deny: Vec::new(),
+            max_errors: Some(DEFAULT_MAX_ERRORS),
+            progress: None,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Options that fail the parse on any of `codes`, with no lenient
+    /// behavior enabled.
+    pub fn strict(codes: &[ParseWarningCode]) -> Self {
+        ParseOptions {
+            deny: codes.to_vec(),
+            ..ParseOptions::default()
+        }
+    }
+}
+
+/// How far a parse has got, as passed to a [`ProgressHook`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParseProgress {
+    /// Bytes of the content up to the end of the last record parsed
+    pub bytes_processed: usize,
+
+    /// Length of the content in bytes
+    pub total_bytes: usize,
+
+    /// Records parsed so far, not counting padding lines
+    pub records_parsed: usize,
+
+    /// Batches whose control record has been parsed
+    pub batches_completed: usize,
+}
+
+/// A callback reporting the progress of a parse, from
+/// [`ParseOptions::progress`].
+///
+/// It is called by [`AchFile::parse_with`](crate::AchFile::parse_with) and
+/// by [`Records`](crate::Records) iterators each time the given number of
+/// records or bytes have been parsed since the last call, whichever comes
+/// first, and once more when the file control has been parsed. The
+/// callback only sees the counts, never the records themselves.
+///
+/// ```
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::Arc;
+///
+/// use rs_ach::{fixtures, AchFile, ParseOptions, ProgressHook};
+///
+/// let calls = Arc::new(AtomicUsize::new(0));
+/// let counter = Arc::clone(&calls);
+/// let options = ParseOptions {
+///     progress: Some(
+///         ProgressHook::new(move |progress| {
+///             assert!(progress.bytes_processed <= progress.total_bytes);
+///             counter.fetch_add(1, Ordering::Relaxed);
+///         })
+///         .every_records(1),
+///     ),
+///     ..ParseOptions::default()
+/// };
+///
+/// // After the one entry, after the batch control, and at the end.
+/// let text = fixtures::sample_ppd_file(1);
+/// AchFile::parse_with(&text, &options).unwrap();
+/// assert_eq!(calls.load(Ordering::Relaxed), 3);
+/// ```
+#[derive(Clone)]
+pub struct ProgressHook {
+    callback: Arc<dyn Fn(ParseProgress) + Send + Sync>,
+    every_records: usize,
+    every_bytes: Option<usize>,
+}
+
+impl ProgressHook {
+    /// Call `callback` every 1,000 records.
+    pub fn new(callback: impl Fn(ParseProgress) + Send + Sync + 'static) -> Self {
+        ProgressHook {
+            callback: Arc::new(callback),
+            every_records: 1_000,
+            every_bytes: None,
+        }
+    }
+
+    /// Call back every `records` records instead; at least one.
+    pub fn every_records(mut self, records: usize) -> Self {
+        self.every_records = records.max(1);
+        self
+    }
+
+    /// Also call back every `bytes` bytes.
+    pub fn every_bytes(mut self, bytes: usize) -> Self {
+        self.every_bytes = Some(bytes.max(1));
+        self
+    }
+}
+
+impl fmt::Debug for ProgressHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProgressHook")
+            .field("every_records", &self.every_records)
+            .field("every_bytes", &self.every_bytes)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Hooks are equal when they share a callback and call it as often.
+impl PartialEq for ProgressHook {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.callback, &other.callback)
+            && self.every_records == other.every_records
+            && self.every_bytes == other.every_bytes
+    }
+}
+
+impl Eq for ProgressHook {}
+
+/// Counts toward the next [`ProgressHook`] call over one content string.
+#[derive(Debug, Clone)]
+pub(crate) struct ProgressTracker {
+    hook: ProgressHook,
+    /// Address of the content's first byte
+    start: usize,
+    total: usize,
+    next_records: usize,
+    next_bytes: usize,
+}
+
+impl ProgressTracker {
+    /// A tracker over `content`, or `None` when `options` has no hook.
+    pub(crate) fn new(options: &ParseOptions, content: &str) -> Option<Self> {
+        let hook = options.progress.clone()?;
+        Some(ProgressTracker {
+            next_records: hook.every_records,
+            next_bytes: hook.every_bytes.unwrap_or(usize::MAX),
+            hook,
+            start: content.as_ptr() as usize,
+            total: content.len(),
+        })
+    }
+
+    /// Count `line`, a record of the content, as the last parsed, calling
+    /// back if a threshold has been reached.
+    pub(crate) fn advance(&mut self, line: &str, records: usize, batches: usize) {
+        let bytes = line.as_ptr() as usize + line.len() - self.start;
+        if records >= self.next_records || bytes >= self.next_bytes {
+            self.report(bytes, records, batches);
+        }
+    }
+
+    /// Call back with all of the content processed.
+    pub(crate) fn finish(&mut self, records: usize, batches: usize) {
+        self.report(self.total, records, batches);
+    }
+
+    fn report(&mut self, bytes: usize, records: usize, batches: usize) {
+        self.next_records = records.saturating_add(self.hook.every_records);
+        if let Some(every) = self.hook.every_bytes {
+            self.next_bytes = bytes.saturating_add(every);
+        }
+        (self.hook.callback)(ParseProgress {
+            bytes_processed: bytes,
+            total_bytes: self.total,
+            records_parsed: records,
+            batches_completed: batches,
+        });
+    }
+}
+
+/// What a [`ParseWarning`] reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub enum ParseWarningCode {
+    /// A line longer than 94 characters had its trailing whitespace dropped
+    LongLine,
+
+    /// A line shorter than 94 characters was padded with spaces
+    ShortLine,
+
+    /// The file ended without a file control, which was computed from the
+    /// batches
+    MissingFileControl,
+
+    /// A code written in lower case was read in upper case
+    LowercaseCode,
+
+    /// A numeric field of spaces was read as zero
+    BlankNumber,
+
+    /// Block padding (a line of nines) was skipped before further records
+    MidFilePadding,
+
+    /// A record of unknown type was kept as an
+    /// [`UnknownRecord`](crate::UnknownRecord)
+    UnknownRecord,
+
+    /// A batch header's standard entry class code is none of NACHA's, so
+    /// its entries were parsed generically
+    UnknownSecCode,
+
+    /// A date or time field of a header is neither blank nor a valid date
+    /// or time. Denied, it fails as [`AchError::InvalidDate`] with the line
+    InvalidDate,
+
+    /// Bytes of a line that are not valid UTF-8 were replaced with `?`
+    InvalidBytes,
+
+    /// [`ParseOptions::max_errors`] warnings were reported and the rest
+    /// dropped; always the last warning
+    TooManyWarnings,
+}
+
+impl ParseWarningCode {
+    /// The [`ErrorKind`] of the error this warning becomes when denied.
+    pub fn kind(self) -> ErrorKind {
+        match self {
+            ParseWarningCode::LongLine => ErrorKind::Record,
+            ParseWarningCode::ShortLine => ErrorKind::Record,
+            ParseWarningCode::MissingFileControl => ErrorKind::Structure,
+            ParseWarningCode::LowercaseCode => ErrorKind::Field,
+            ParseWarningCode::BlankNumber => ErrorKind::Field,
+            ParseWarningCode::MidFilePadding => ErrorKind::Structure,
+            ParseWarningCode::UnknownRecord => ErrorKind::Structure,
+            ParseWarningCode::UnknownSecCode => ErrorKind::Field,
+            ParseWarningCode::InvalidDate => ErrorKind::Field,
+            ParseWarningCode::InvalidBytes => ErrorKind::Encoding,
+            ParseWarningCode::TooManyWarnings => ErrorKind::Limit,
+        }
+    }
+}
+
+/// Something the parser tolerated that changed or skipped data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ParseWarning {
+    /// What was tolerated
+    pub code: ParseWarningCode,
+
+    /// Description of what was tolerated and how
+    pub message: String,
+
+    /// Line of the file, counting from 1
+    pub line: usize,
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Options and warnings threaded through a parse.
+pub(crate) struct ParseContext<'o> {
+    pub(crate) options: &'o ParseOptions,
+    pub(crate) warnings: Vec<ParseWarning>,
+    pub(crate) progress: Option<ProgressTracker>,
+    /// SEC code of the batch being parsed, for parsing its entries
+    pub(crate) sec_code: Option<SecCode>,
+}
+
+impl<'o> ParseContext<'o> {
+    pub(crate) fn new(options: &'o ParseOptions) -> Self {
+        ParseContext {
+            options,
+            warnings: Vec::new(),
+            progress: None,
+            sec_code: None,
+        }
+    }
+
+    /// Start parsing the entries of the batch with `header`, on `line`,
+    /// warning if its SEC code is none of NACHA's or its effective entry
+    /// date is not a date.
+    pub(crate) fn begin_batch(
+        &mut self,
+        header: &mut BatchHeader<'_>,
+        line: usize,
+    ) -> Result<(), AchError> {
+        self.uppercase_code(
+            &mut header.standard_entry_class_code,
+            "standard_entry_class_code",
+            line,
+        )?;
+        self.check_date(
+            dates::read_field(
+                "effective_entry_date",
+                &header.effective_entry_date,
+                CivilDate::parse,
+            ),
+            line,
+        )?;
+        self.sec_code = SecCode::from_code(&header.standard_entry_class_code);
+        if self.sec_code.is_some() {
+            return Ok(());
+        }
+        self.warn(
+            ParseWarningCode::UnknownSecCode,
+            line,
+            format!(
+                "SEC code {:?} is not a NACHA code, entries parsed generically",
+                header.standard_entry_class_code
+            ),
+        )
+    }
+
+    /// Read the codes of a file header on `line` in upper case, as for
+    /// [`uppercase_code`](Self::uppercase_code), warning if its creation
+    /// date or time is not one.
+    pub(crate) fn begin_file(
+        &mut self,
+        header: &mut FileHeader<'_>,
+        line: usize,
+    ) -> Result<(), AchError> {
+        self.uppercase_code(&mut header.file_id_modifier, "file_id_modifier", line)?;
+        self.check_date(
+            dates::read_field(
+                "file_creation_date",
+                &header.file_creation_date,
+                CivilDate::parse,
+            ),
+            line,
+        )?;
+        self.check_date(
+            dates::read_field(
+                "file_creation_time",
+                &header.file_creation_time,
+                dates::parse_hhmm,
+            ),
+            line,
+        )
+    }
+
+    /// Warn of the [`AchError::InvalidDate`] reading a date or time field
+    /// of the header on `line`, if there was one, or fail with it, at
+    /// `line`, if the warning is denied.
+    fn check_date<T>(&mut self, read: Result<T, AchError>, line: usize) -> Result<(), AchError> {
+        let Err(AchError::InvalidDate { field, value, .. }) = read else {
+            return Ok(());
+        };
+        if self.options.deny.contains(&ParseWarningCode::InvalidDate) {
+            return Err(AchError::InvalidDate {
+                field,
+                value,
+                line: Some(line),
+            });
+        }
+        self.warn(
+            ParseWarningCode::InvalidDate,
+            line,
+            format!("'{field}' {value:?} is not a valid date or time"),
+        )
+    }
+
+    /// Put the code `value` of the record on `line` in upper case when it
+    /// has lower case letters and
+    /// [`ParseOptions::case_insensitive_record_codes`] is set, warning that
+    /// it was.
+    fn uppercase_code(
+        &mut self,
+        value: &mut Cow<'_, str>,
+        field: &str,
+        line: usize,
+    ) -> Result<(), AchError> {
+        if !self.options.case_insensitive_record_codes
+            || !value.bytes().any(|b| b.is_ascii_lowercase())
+        {
+            return Ok(());
+        }
+        let upper = value.to_ascii_uppercase();
+        self.warn(
+            ParseWarningCode::LowercaseCode,
+            line,
+            format!("'{field}' {:?} read as {:?}", value.trim(), upper.trim()),
+        )?;
+        *value = Cow::Owned(upper);
+        Ok(())
+    }
+
+    /// Count `line` toward the progress hook, if there is one; `records`
+    /// and `batches` are the totals parsed through it.
+    pub(crate) fn advance(&mut self, line: &str, records: usize, batches: usize) {
+        if let Some(progress) = &mut self.progress {
+            progress.advance(line, records, batches);
+        }
+    }
+
+    /// Record a warning, or fail if its code is denied. Past
+    /// [`ParseOptions::max_errors`], the warning is dropped.
+    pub(crate) fn warn(
+        &mut self,
+        code: ParseWarningCode,
+        line: usize,
+        message: String,
+    ) -> Result<(), AchError> {
+        let warning = ParseWarning {
+            code,
+            message,
+            line,
+        };
+        if self.options.deny.contains(&code) {
+            return Err(AchError::DeniedWarning(warning));
+        }
+        match self.options.max_errors {
+            Some(max) if self.warnings.len() > max => {}
+            Some(max) if self.warnings.len() == max => self.warnings.push(ParseWarning {
+                code: ParseWarningCode::TooManyWarnings,
+                message: format!("stopped after {max} warnings"),
+                line,
+            }),
+            _ => self.warnings.push(warning),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use crate::{parser, writer, AchFile};
+
+    #[test]
+    fn test_long_lines() {
+        let text = fixtures::sample_ppd_file(1).replacen('\n', "   \n", 1);
+        assert!(AchFile::parse(&text).is_err());
+
+        let options = ParseOptions {
+            allow_long_lines: true,
+            ..ParseOptions::default()
+        };
+        let (file, warnings) = AchFile::parse_with(&text, &options).unwrap();
+        assert_eq!(file.file_header.reference_code.len(), 8);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, ParseWarningCode::LongLine);
+        assert_eq!(warnings[0].line, 1);
+
+        // Only whitespace is dropped.
+        let text = fixtures::sample_ppd_file(1).replacen('\n', "XX\n", 1);
+        assert!(AchFile::parse_with(&text, &options).is_err());
+    }
+
+    #[test]
+    fn test_short_lines() {
+        // The file header and the file control lose their trailing spaces.
+        let sample = fixtures::sample_ppd_file(1);
+        let text: String = sample
+            .lines()
+            .enumerate()
+            .map(|(i, line)| match i {
+                0 | 4 => format!("{}\n", line.trim_end()),
+                _ => format!("{line}\n"),
+            })
+            .collect();
+        assert!(AchFile::parse(&text).is_err());
+
+        let options = ParseOptions {
+            pad_short_lines: true,
+            ..ParseOptions::default()
+        };
+        let (file, warnings) = AchFile::parse_with(&text, &options).unwrap();
+        assert_eq!(file, AchFile::parse(&sample).unwrap());
+        let lines: Vec<(ParseWarningCode, usize)> =
+            warnings.iter().map(|w| (w.code, w.line)).collect();
+        assert_eq!(
+            lines,
+            [
+                (ParseWarningCode::ShortLine, 1),
+                (ParseWarningCode::ShortLine, 5)
+            ]
+        );
+        assert_eq!(
+            warnings[1].message,
+            "55 characters; padded with spaces to 94"
+        );
+
+        let strict = ParseOptions {
+            deny: vec![ParseWarningCode::ShortLine],
+            ..options
+        };
+        let err = AchFile::parse_with(&text, &strict).unwrap_err();
+        assert_eq!(err.line(), Some(1));
+    }
+
+    #[test]
+    fn test_missing_file_control() {
+        let sample = fixtures::sample_multi_company_file();
+        let expected = AchFile::parse(&sample).unwrap();
+        let text = fixtures::corrupt(&sample, fixtures::Corruption::MissingFileControl);
+        assert!(AchFile::parse(&text).is_err());
+
+        let options = ParseOptions {
+            allow_missing_file_control: true,
+            ..ParseOptions::default()
+        };
+        let (file, warnings) = AchFile::parse_with(&text, &options).unwrap();
+        assert_eq!(file.file_control, expected.file_control);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, ParseWarningCode::MissingFileControl);
+        assert_eq!(warnings[0].line, 14);
+        assert!(file.validate().is_valid());
+
+        // A file control that is present is read, not computed.
+        let (file, warnings) = AchFile::parse_with(&sample, &options).unwrap();
+        assert_eq!(file, expected);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_dates() {
+        let sample = fixtures::sample_multi_company_file();
+        let text =
+            sample
+                .replacen("1409020123", "1409021260", 1)
+                .replacen("   140903", "   141332", 1);
+        let (file, warnings) = AchFile::parse_with(&text, &ParseOptions::default()).unwrap();
+        let messages: Vec<(usize, &str)> = warnings
+            .iter()
+            .map(|w| (w.line, w.message.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                (
+                    1,
+                    "'file_creation_time' \"1260\" is not a valid date or time"
+                ),
+                (
+                    2,
+                    "'effective_entry_date' \"141332\" is not a valid date or time"
+                ),
+            ]
+        );
+        assert!(warnings
+            .iter()
+            .all(|w| w.code == ParseWarningCode::InvalidDate));
+        assert!(file.batches[0]
+            .header
+            .try_effective_date::<crate::CivilDate>()
+            .is_err());
+
+        // Blank dates are not warned of.
+        let blank = sample.replacen("   140903", "         ", 1);
+        let (_, warnings) = AchFile::parse_with(&blank, &ParseOptions::default()).unwrap();
+        assert!(warnings.is_empty());
+
+        let strict = ParseOptions::strict(&[ParseWarningCode::InvalidDate]);
+        let err = AchFile::parse_with(&text, &strict).unwrap_err();
+        assert_eq!(err.line(), Some(1));
+        assert_eq!(err.kind(), ErrorKind::Field);
+        assert_eq!(
+            err.to_string(),
+            "Invalid date field 'file_creation_time' at line 1: \"1260\""
+        );
+    }
+
+    #[test]
+    fn test_lowercase_codes() {
+        let sample = fixtures::sample_multi_company_file();
+        let text = sample
+            .replacen("PPD", "ppd", 1)
+            .replacen("0123A094", "0123a094", 1);
+        let (file, warnings) = AchFile::parse_with(&text, &ParseOptions::default()).unwrap();
+        assert_eq!(warnings[0].code, ParseWarningCode::UnknownSecCode);
+        assert!(file.batches[0].header.sec_code().is_err());
+
+        let options = ParseOptions {
+            case_insensitive_record_codes: true,
+            ..ParseOptions::default()
+        };
+        let (file, warnings) = AchFile::parse_with(&text, &options).unwrap();
+        assert_eq!(file, AchFile::parse(&sample).unwrap());
+        let messages: Vec<(usize, &str)> = warnings
+            .iter()
+            .map(|w| (w.line, w.message.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                (1, "'file_id_modifier' \"a\" read as \"A\""),
+                (2, "'standard_entry_class_code' \"ppd\" read as \"PPD\""),
+            ]
+        );
+        assert!(warnings
+            .iter()
+            .all(|w| w.code == ParseWarningCode::LowercaseCode));
+    }
+
+    #[test]
+    fn test_blank_numbers_and_padding() {
+        let sample = fixtures::sample_ppd_file(1);
+        let mut lines: Vec<String> = sample.lines().map(String::from).collect();
+        // Blank the batch control's entry hash (positions 11-20), and put a
+        // padding line between the batch and the file control.
+        let control = lines.iter().position(|l| l.starts_with('8')).unwrap();
+        lines[control].replace_range(10..20, &" ".repeat(10));
+        lines.insert(control + 1, "9".repeat(94));
+        let text = lines.join("\n");
+        assert!(AchFile::parse(&text).is_err());
+
+        let options = ParseOptions {
+            blank_numbers_as_zero: true,
+            ..ParseOptions::default()
+        };
+        let (file, warnings) = AchFile::parse_with(&text, &options).unwrap();
+        assert_eq!(file.batches[0].control.entry_hash, 0);
+        let codes: Vec<(ParseWarningCode, usize)> =
+            warnings.iter().map(|w| (w.code, w.line)).collect();
+        assert_eq!(
+            codes,
+            [
+                (ParseWarningCode::MidFilePadding, control + 2),
+                (ParseWarningCode::BlankNumber, control + 1),
+            ]
+        );
+        assert!(warnings[1].message.contains("entry_hash"));
+    }
+
+    #[test]
+    fn test_number_format() {
+        let sample = fixtures::sample_ppd_file(1);
+        let mut lines: Vec<String> = sample.lines().map(String::from).collect();
+        let amount = lines[2][29..39].trim_start_matches('0').to_string();
+        let parse = |text: &str, number_format| {
+            let options = ParseOptions {
+                number_format,
+                ..ParseOptions::default()
+            };
+            AchFile::parse_with(text, &options).map(|(file, _)| file.batches[0].entries[0].amount)
+        };
+
+        lines[2].replace_range(29..39, &format!("{amount:>10}"));
+        let text = lines.join("\n");
+        assert!(parse(&text, NumberFormat::Trimmed).is_ok());
+        assert_eq!(
+            parse(&text, NumberFormat::SpacePadded).unwrap(),
+            amount.parse::<u64>().unwrap()
+        );
+        let err = parse(&text, NumberFormat::ZeroPadded).unwrap_err();
+        match err.root_cause() {
+            AchError::InvalidField { field, reason, .. } => {
+                assert_eq!(*field, "amount");
+                assert_eq!(
+                    *reason,
+                    format!("expected 10 zero-padded digits, got \"{amount:>10}\"")
+                );
+            }
+            other => panic!("unexpected {other}"),
+        }
+
+        for value in [format!("{amount:<10}"), format!("+{amount:0>9}")] {
+            lines[2].replace_range(29..39, &value);
+            let text = lines.join("\n");
+            assert!(parse(&text, NumberFormat::Trimmed).is_ok(), "{value}");
+            assert!(parse(&text, NumberFormat::SpacePadded).is_err(), "{value}");
+            assert!(parse(&text, NumberFormat::ZeroPadded).is_err(), "{value}");
+        }
+    }
+
+    #[test]
+    fn test_keep_unknown_records() {
+        let sample = fixtures::sample_ppd_file(2);
+        let mut lines: Vec<String> = sample.lines().map(String::from).collect();
+        let unknown = |code: char| format!("{code}{:<93}", "PROPRIETARY");
+        // One between the file header and the batch, one after the first
+        // entry, one before the batch control.
+        lines.insert(4, unknown('3'));
+        lines.insert(3, unknown('Z'));
+        lines.insert(1, unknown('0'));
+        let text = lines.join("\n");
+        assert!(AchFile::parse(&text).is_err());
+
+        let options = ParseOptions {
+            keep_unknown_records: true,
+            ..ParseOptions::default()
+        };
+        let (file, warnings) = AchFile::parse_with(&text, &options).unwrap();
+        assert_eq!(file.unknown_records.len(), 1);
+        assert_eq!(file.unknown_records[0].type_code, "0");
+        assert_eq!(file.unknown_records[0].line_number, 2);
+        assert_eq!(file.unknown_records[0].preceding, 0);
+        let batch = &file.batches[0];
+        let kept: Vec<(&str, usize)> = batch
+            .unknown_records
+            .iter()
+            .map(|r| (&*r.type_code, r.preceding))
+            .collect();
+        assert_eq!(kept, [("Z", 1), ("3", 2)]);
+        assert_eq!(batch.entries.len(), 2);
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings
+            .iter()
+            .all(|w| w.code == ParseWarningCode::UnknownRecord));
+        assert!(file.validate().is_valid());
+
+        // Written back in place, character for character.
+        let unpadded: Vec<&str> = lines
+            .iter()
+            .map(String::as_str)
+            .filter(|l| !parser::is_filler(l))
+            .collect();
+        assert_eq!(writer::render_lines(&file).unwrap(), unpadded);
+
+        // An addenda after an unknown record is out of place.
+        lines.insert(5, format!("705{:<80}00010000001", ""));
+        let text = lines.join("\n");
+        assert!(AchFile::parse_with(&text, &options).is_err());
+    }
+
+    /// Options calling back into the returned list.
+    fn recording(
+        hook: impl FnOnce(ProgressHook) -> ProgressHook,
+    ) -> (ParseOptions, Arc<std::sync::Mutex<Vec<ParseProgress>>>) {
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = Arc::clone(&calls);
+        let options = ParseOptions {
+            progress: Some(hook(ProgressHook::new(move |progress| {
+                sink.lock().unwrap().push(progress)
+            }))),
+            ..ParseOptions::default()
+        };
+        (options, calls)
+    }
+
+    #[test]
+    fn test_progress() {
+        let text = fixtures::sample_multi_company_file();
+        let total = text.len();
+
+        let (options, calls) = recording(|hook| hook.every_records(4));
+        AchFile::parse_with(&text, &options).unwrap();
+        let parsed = calls.lock().unwrap().clone();
+        let counts: Vec<(usize, usize)> = parsed
+            .iter()
+            .map(|p| (p.records_parsed, p.batches_completed))
+            .collect();
+        // Three batches of 2, 3, and 2 entries, between a header and a control.
+        assert_eq!(counts, [(4, 0), (8, 1), (12, 2), (15, 3)]);
+        assert!(parsed.iter().all(|p| p.total_bytes == total));
+        assert_eq!(parsed[0].bytes_processed, 4 * 95 - 1);
+        assert_eq!(parsed.last().unwrap().bytes_processed, total);
+
+        // Iterating records reports the same way.
+        let records = AchFile::records_with(&text, &options).count();
+        assert_eq!(records, 15);
+        assert_eq!(calls.lock().unwrap()[parsed.len()..], parsed[..]);
+
+        // A byte threshold can come first.
+        let (options, calls) = recording(|hook| hook.every_bytes(95 * 6));
+        AchFile::parse_with(&text, &options).unwrap();
+        let records: Vec<usize> = calls
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|p| p.records_parsed)
+            .collect();
+        assert_eq!(records, [7, 13, 15]);
+
+        // Nothing is reported of a parse that fails.
+        let (options, calls) = recording(|hook| hook);
+        let truncated = &text[..text.find("\n9").unwrap()];
+        assert!(AchFile::parse_with(truncated, &options).is_err());
+        assert!(calls.lock().unwrap().is_empty());
+
+        // Options compare equal only with the same hook.
+        assert_eq!(options.clone(), options);
+        assert_ne!(recording(|hook| hook).0, options);
+    }
+
+    #[test]
+    fn test_max_errors() {
+        let sample = fixtures::sample_ppd_file(1);
+        let mut lines: Vec<&str> = sample.lines().collect();
+        let padding = "9".repeat(94);
+        for at in [4, 3, 2] {
+            lines.insert(at, &padding);
+        }
+        let text = lines.join("\n");
+
+        let options = ParseOptions {
+            max_errors: Some(1),
+            ..ParseOptions::default()
+        };
+        let (_, warnings) = AchFile::parse_with(&text, &options).unwrap();
+        let codes: Vec<ParseWarningCode> = warnings.iter().map(|w| w.code).collect();
+        assert_eq!(
+            codes,
+            [
+                ParseWarningCode::MidFilePadding,
+                ParseWarningCode::TooManyWarnings
+            ]
+        );
+        assert_eq!(warnings[1].to_string(), "line 5: stopped after 1 warnings");
+
+        let options = ParseOptions {
+            max_errors: None,
+            ..ParseOptions::default()
+        };
+        let (_, warnings) = AchFile::parse_with(&text, &options).unwrap();
+        assert_eq!(warnings.len(), 3);
+    }
+
+    #[test]
+    fn test_denied_warnings() {
+        let sample = fixtures::sample_ppd_file(1);
+        let mut lines: Vec<&str> = sample.lines().collect();
+        let padding = "9".repeat(94);
+        lines.insert(2, &padding);
+        let text = lines.join("\n");
+
+        // Tolerated by default, and by parse.
+        assert!(AchFile::parse(&text).is_ok());
+        let (_, warnings) = AchFile::parse_with(&text, &ParseOptions::default()).unwrap();
+        assert_eq!(warnings[0].code, ParseWarningCode::MidFilePadding);
+
+        let strict = ParseOptions::strict(&[ParseWarningCode::MidFilePadding]);
+        let err = AchFile::parse_with(&text, &strict).unwrap_err();
+        match &err {
+            AchError::DeniedWarning(warning) => {
+                assert_eq!(warning.code, ParseWarningCode::MidFilePadding);
+                assert_eq!(warning.line, 3);
+            }
+            other => panic!("unexpected {other}"),
+        }
+        assert_eq!(err.kind(), ErrorKind::Structure);
+        assert_eq!(
+            err.to_string(),
+            "Denied warning at line 3: padding line 3 skipped"
+        );
+    }
+}
@@ -1,6 +1,13 @@
 //! ACH file parser implementation.
 
+use std::borrow::Cow;
+use std::ops::Range;
+
 use crate::error::AchError;
+use crate::parse_options::{
+    ParseContext, ParseOptions, ParseWarning, ParseWarningCode, ProgressTracker,
+};
+use crate::records::layout::{self, FieldLayout};
 use crate::records::*;
 use crate::{AchFile, Batch};
 
@@ -14,10 +21,157 @@ use crate::{AchFile, Batch};
 ///
 /// Returns a parsed `AchFile` or an error if parsing fails.
 pub fn parse_ach_file<'a>(content: &'a str) -> Result<AchFile<'a>, AchError> {
-    let lines: Vec<&'a str> = content
-        .lines()
-        .filter(|line| !line.chars().all(|c| c == '9'))
-        .collect();
+    parse_ach_file_with(content, &ParseOptions::default()).map(|(file, _)| file)
+}
+
+/// Parse a complete ACH file with the given options, returning the warnings
+/// raised along the way.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(name = "parse_file", level = "debug", skip_all, fields(bytes = content.len()))
+)]
+pub fn parse_ach_file_with<'a>(
+    content: &'a str,
+    options: &ParseOptions,
+) -> Result<(AchFile<'a>, Vec<ParseWarning>), AchError> {
+    let mut ctx = ParseContext::new(options);
+    let file = parse_padded(content, &mut ctx)?;
+    Ok((file, ctx.warnings))
+}
+
+/// Parse a complete ACH file from bytes that may not be valid UTF-8,
+/// returning the warnings raised along the way.
+///
+/// Valid UTF-8 is parsed as [`parse_ach_file_with`] parses it, borrowing
+/// from `bytes`. Otherwise each byte of an invalid sequence is an error or,
+/// with [`ParseOptions::replace_invalid_bytes`], replaced with `?` so that
+/// every field keeps its position, and the file is parsed from the
+/// replaced copy.
+pub fn parse_ach_bytes_with<'a>(
+    bytes: &'a [u8],
+    options: &ParseOptions,
+) -> Result<(AchFile<'a>, Vec<ParseWarning>), AchError> {
+    if let Ok(content) = std::str::from_utf8(bytes) {
+        return parse_ach_file_with(content, options);
+    }
+    let mut ctx = ParseContext::new(options);
+    let content = replace_invalid_bytes(bytes, &mut ctx)?;
+    let file = parse_padded(&content, &mut ctx)?.into_owned();
+    Ok((file, ctx.warnings))
+}
+
+/// `bytes` with each byte of its invalid UTF-8 sequences replaced with `?`,
+/// warning once for each line with any, or [`AchError::InvalidEncoding`]
+/// at the first unless [`ParseOptions::replace_invalid_bytes`] is set.
+/// Lines are numbered as [`split_lines`] splits the replaced content.
+fn replace_invalid_bytes(bytes: &[u8], ctx: &mut ParseContext<'_>) -> Result<String, AchError> {
+    let mut content = String::with_capacity(bytes.len());
+    // Offset in `content` and length of each run of `?` put in.
+    let mut runs = Vec::new();
+    for chunk in bytes.utf8_chunks() {
+        content.push_str(chunk.valid());
+        let invalid = chunk.invalid().len();
+        if invalid == 0 {
+            continue;
+        }
+        if !ctx.options.replace_invalid_bytes {
+            // Each byte so far is in `content` as itself or as a `?`.
+            return Err(AchError::InvalidEncoding {
+                offset: content.len(),
+            });
+        }
+        runs.push((content.len(), invalid));
+        content.extend(std::iter::repeat_n('?', invalid));
+    }
+
+    // A run never holds a line ending, but may cross into the next record
+    // of unblocked content.
+    let mut pending = &runs[..];
+    for (index, line) in split_lines(&content).enumerate() {
+        if pending.is_empty() {
+            break;
+        }
+        let start = line.as_ptr() as usize - content.as_ptr() as usize;
+        let end = start + line.len();
+        let mut replaced = 0;
+        for &(at, len) in pending.iter().take_while(|&&(at, _)| at < end) {
+            replaced += (at + len).min(end).saturating_sub(at.max(start));
+        }
+        while let [(at, len), rest @ ..] = pending {
+            if at + len > end {
+                break;
+            }
+            pending = rest;
+        }
+        if replaced > 0 {
+            ctx.warn(
+                ParseWarningCode::InvalidBytes,
+                index + 1,
+                format!(
+                    "{replaced} byte{} not valid UTF-8 replaced with '?'",
+                    if replaced == 1 { "" } else { "s" }
+                ),
+            )?;
+        }
+    }
+    Ok(content)
+}
+
+/// Parse `content` as [`parse_content`] does, after padding its short lines
+/// when [`ParseOptions::pad_short_lines`] is set. A file with any is parsed
+/// from the padded copy.
+fn parse_padded<'a>(content: &'a str, ctx: &mut ParseContext<'_>) -> Result<AchFile<'a>, AchError> {
+    if !ctx.options.pad_short_lines {
+        return parse_content(content, ctx);
+    }
+    let mut padded = String::with_capacity(content.len());
+    let mut any = false;
+    for (idx, line) in split_lines(content).enumerate() {
+        match pad_short_line(ctx, idx + 1, line)? {
+            Some(line) => {
+                padded.push_str(&line);
+                any = true;
+            }
+            None => padded.push_str(line),
+        }
+        padded.push('\n');
+    }
+    if !any {
+        return parse_content(content, ctx);
+    }
+    Ok(parse_content(&padded, ctx)?.into_owned())
+}
+
+/// `line` padded with spaces to 94 characters, warning that it was, if it
+/// is a record shorter than that and [`ParseOptions::pad_short_lines`] is
+/// set.
+pub(crate) fn pad_short_line(
+    ctx: &mut ParseContext<'_>,
+    number: usize,
+    line: &str,
+) -> Result<Option<String>, AchError> {
+    let len = line.chars().count();
+    if !ctx.options.pad_short_lines || len >= 94 || line.is_empty() || is_filler(line) {
+        return Ok(None);
+    }
+    ctx.warn(
+        ParseWarningCode::ShortLine,
+        number,
+        format!("{len} characters; padded with spaces to 94"),
+    )?;
+    Ok(Some(format!("{line:<94}")))
+}
+
+/// Parse `content` as a complete ACH file, raising warnings in `ctx`.
+fn parse_content<'a>(
+    content: &'a str,
+    ctx: &mut ParseContext<'_>,
+) -> Result<AchFile<'a>, AchError> {
+    ctx.progress = ProgressTracker::new(ctx.options, content);
+    let lines = record_lines(content, ctx)?;
+
+    #[cfg(feature = "tracing")]
+    trace_mid_file_filler(content);
 
     if lines.is_empty() {
         return Err(AchError::EmptyFile);
@@ -26,62 +180,280 @@ pub fn parse_ach_file<'a>(content: &'a str) -> Result<AchFile<'a>, AchError> {
     let mut line_idx = 0;
 
     // Parse file header (must be first)
-    let file_header = parse_file_header(lines[line_idx])?;
+    let mut file_header = parse_file_header(lines[line_idx])?;
+    ctx.begin_file(&mut file_header, lines[line_idx].0)?;
     line_idx += 1;
 
     // Parse batches
     let mut batches = Vec::new();
+    let mut unknown_records = Vec::new();
     while line_idx < lines.len() {
-        let record_type = get_record_type(lines[line_idx])?;
+        let record_type = get_record_type(lines[line_idx].1)?;
 
         if record_type == "5" {
-            let batch = parse_batch(&lines, &mut line_idx)?;
+            let batch = parse_batch(ctx, &lines, &mut line_idx, batches.len())?;
             batches.push(batch);
         } else if record_type == "9" {
             break;
+        } else if keeps_unknown(ctx, record_type) {
+            unknown_records.push(parse_unknown(ctx, lines[line_idx], batches.len())?);
+            line_idx += 1;
         } else {
-            return Err(AchError::InvalidStructure(format!(
-                "Unexpected record type '{record_type}' at line {line_idx}"
-            )));
+            return Err(unexpected_record(record_type, lines[line_idx].0, false));
         }
     }
 
     // Parse file control (must be last)
-    if line_idx >= lines.len() {
+    let missing_control = line_idx >= lines.len();
+    let file_control = if !missing_control {
+        parse_file_control(ctx, lines[line_idx])?
+    } else if ctx.options.allow_missing_file_control {
+        let last = lines[lines.len() - 1].0;
+        ctx.warn(
+            ParseWarningCode::MissingFileControl,
+            last,
+            format!("no file control after line {last}; computed from the batches"),
+        )?;
+        blank_file_control()
+    } else {
         return Err(AchError::InvalidStructure(
             "Missing file control record".to_string(),
         ));
+    };
+    if let Some(progress) = &mut ctx.progress {
+        progress.finish(lines.len().min(line_idx + 1), batches.len());
     }
 
-    let file_control = parse_file_control(lines[line_idx])?;
+    #[cfg(feature = "tracing")]
+    tracing::debug!(batches = batches.len(), "parsed file");
 
-    Ok(AchFile {
+    let mut file = AchFile {
         file_header,
         batches,
         file_control,
-    })
+        unknown_records,
+    };
+    if missing_control {
+        file.recompute_file_control();
+    }
+    Ok(file)
+}
+
+/// A file control of zeros, for computing from a file's batches.
+pub(crate) fn blank_file_control() -> FileControl {
+    FileControl {
+        record_type: "9".to_string(),
+        batch_count: 0,
+        block_count: 0,
+        entry_addenda_count: 0,
+        entry_hash: 0,
+        total_debit_amount: 0,
+        total_credit_amount: 0,
+        reserved: " ".repeat(39),
+    }
+}
+
+/// The record lines of `content` with their line numbers, counting from 1,
+/// as [`LineFilter`] passes them.
+fn record_lines<'a>(
+    content: &'a str,
+    ctx: &mut ParseContext<'_>,
+) -> Result<Vec<(usize, &'a str)>, AchError> {
+    let mut lines = Vec::new();
+    let mut filter = LineFilter::default();
+    for (idx, line) in split_lines(content).enumerate() {
+        let number = idx + 1;
+        if let Some(line) = filter.record(ctx, number, line)? {
+            lines.push((number, line));
+        }
+    }
+    Ok(lines)
+}
+
+/// The lines of `content`, after the UTF-8 byte order mark it may start
+/// with. A line ends at a `\n`, a `\r\n`, or a bare `\r`; the last needs
+/// none.
+///
+/// Content that is unblocked, its records of 94 characters one after
+/// another with no line endings except perhaps a final one, is split into
+/// its records.
+pub(crate) fn split_lines(content: &str) -> SplitLines<'_> {
+    let rest = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let records = rest
+        .strip_suffix('\n')
+        .map(|rest| rest.strip_suffix('\r').unwrap_or(rest))
+        .or_else(|| rest.strip_suffix('\r'))
+        .unwrap_or(rest);
+    let len = records.chars().count();
+    if len > 94 && len.is_multiple_of(94) && !records.contains(['\n', '\r']) {
+        return SplitLines {
+            rest: records,
+            unblocked: true,
+        };
+    }
+    SplitLines {
+        rest,
+        unblocked: false,
+    }
+}
+
+/// The iterator returned by [`split_lines`].
+#[derive(Debug, Clone)]
+pub(crate) struct SplitLines<'a> {
+    rest: &'a str,
+    unblocked: bool,
+}
+
+impl<'a> Iterator for SplitLines<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        if self.unblocked {
+            let end = self
+                .rest
+                .char_indices()
+                .nth(94)
+                .map_or(self.rest.len(), |(at, _)| at);
+            let line = &self.rest[..end];
+            self.rest = &self.rest[end..];
+            return Some(line);
+        }
+        let Some(end) = self.rest.find(['\n', '\r']) else {
+            return Some(std::mem::take(&mut self.rest));
+        };
+        let line = &self.rest[..end];
+        let terminator = if self.rest[end..].starts_with("\r\n") {
+            2
+        } else {
+            1
+        };
+        self.rest = &self.rest[end + terminator..];
+        Some(line)
+    }
+}
+
+/// Picks the records out of a file's lines, one line at a time: padding
+/// lines are dropped, with a warning when records follow them, and long
+/// lines are shortened when the options allow it.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LineFilter {
+    padding_start: Option<usize>,
+}
+
+impl LineFilter {
+    /// The record on line `number`, or `None` if it is padding.
+    pub(crate) fn record<'a>(
+        &mut self,
+        ctx: &mut ParseContext<'_>,
+        number: usize,
+        line: &'a str,
+    ) -> Result<Option<&'a str>, AchError> {
+        if is_filler(line) {
+            self.padding_start.get_or_insert(number);
+            return Ok(None);
+        }
+        if let Some(start) = self.padding_start.take() {
+            let message = if start == number - 1 {
+                format!("padding line {start} skipped")
+            } else {
+                format!("padding lines {start}-{} skipped", number - 1)
+            };
+            ctx.warn(ParseWarningCode::MidFilePadding, start, message)?;
+        }
+
+        let mut line = line;
+        let excess = line.char_indices().nth(94).map(|(at, _)| at);
+        if let Some(at) =
+            excess.filter(|&at| ctx.options.allow_long_lines && line[at..].trim().is_empty())
+        {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(line = number, "lenient-mode recovery: long line");
+            ctx.warn(
+                ParseWarningCode::LongLine,
+                number,
+                format!(
+                    "{} characters; trailing whitespace after position 94 dropped",
+                    line.chars().count()
+                ),
+            )?;
+            line = &line[..at];
+        }
+        Ok(Some(line))
+    }
+}
+
+/// Block padding lines are all nines.
+pub(crate) fn is_filler(line: &str) -> bool {
+    line.chars().all(|c| c == '9')
+}
+
+/// Emit an event for each run of filler lines followed by further records.
+#[cfg(feature = "tracing")]
+fn trace_mid_file_filler(content: &str) {
+    let mut filler_line = None;
+    for (idx, line) in split_lines(content).enumerate() {
+        if is_filler(line) {
+            filler_line.get_or_insert(idx + 1);
+        } else if let Some(line) = filler_line.take() {
+            tracing::debug!(line, "filler line found mid-file");
+        }
+    }
 }
 
 /// Parse a single batch including header, entries, and control.
-fn parse_batch<'a>(lines: &[&'a str], line_idx: &mut usize) -> Result<Batch<'a>, AchError> {
+///
+/// Errors are wrapped with the batch's index and number, and within an
+/// entry also with the entry's index and trace number.
+pub(crate) fn parse_batch<'a>(
+    ctx: &mut ParseContext<'_>,
+    lines: &[(usize, &'a str)],
+    line_idx: &mut usize,
+    batch_index: usize,
+) -> Result<Batch<'a>, AchError> {
+    let (_, header_line) = lines[*line_idx];
+    let in_batch = |source: AchError| AchError::InBatch {
+        batch_index,
+        batch_number: header_line.get(87..94).unwrap_or("").trim().to_string(),
+        source: Box::new(source),
+    };
+
     // Parse batch header
-    let header = parse_batch_header(lines[*line_idx])?;
+    let mut header = parse_batch_header(lines[*line_idx]).map_err(in_batch)?;
+    ctx.begin_batch(&mut header, lines[*line_idx].0)
+        .map_err(in_batch)?;
     *line_idx += 1;
 
+    #[cfg(feature = "tracing")]
+    let _span =
+        tracing::debug_span!("parse_batch", batch_number = header.batch_number.trim()).entered();
+
     // Parse entries
     let mut entries = Vec::new();
+    let mut unknown_records = Vec::new();
     while *line_idx < lines.len() {
-        let record_type = get_record_type(lines[*line_idx])?;
+        let record_type = get_record_type(lines[*line_idx].1).map_err(in_batch)?;
 
         if record_type == "6" {
-            let mut entry = parse_entry_detail(lines[*line_idx])?;
+            let entry_line = lines[*line_idx].1;
+            let in_entry = |source: AchError| AchError::InEntry {
+                batch_index,
+                batch_number: header.batch_number.trim().to_string(),
+                entry_index: entries.len(),
+                trace_number: entry_line.get(79..94).map(|t| t.trim().to_string()),
+                source: Box::new(source),
+            };
+
+            let mut entry = parse_entry_detail(ctx, lines[*line_idx]).map_err(in_entry)?;
             *line_idx += 1;
 
             // Check for addenda records
             while *line_idx < lines.len() {
-                let next_record_type = get_record_type(lines[*line_idx])?;
+                let next_record_type = get_record_type(lines[*line_idx].1).map_err(in_entry)?;
                 if next_record_type == "7" {
-                    let addenda = parse_addenda(lines[*line_idx])?;
+                    let addenda = parse_addenda(lines[*line_idx]).map_err(in_entry)?;
                     entry.addenda.push(addenda);
                     *line_idx += 1;
                 } else {
@@ -90,222 +462,428 @@ fn parse_batch<'a>(lines: &[&'a str], line_idx: &mut usize) -> Result<Batch<'a>,
             }
 
             entries.push(entry);
+            ctx.advance(lines[*line_idx - 1].1, *line_idx, batch_index);
         } else if record_type == "8" {
             break;
+        } else if keeps_unknown(ctx, record_type) {
+            // Ends any addenda run: addenda after it are out of place.
+            let unknown = parse_unknown(ctx, lines[*line_idx], entries.len()).map_err(in_batch)?;
+            unknown_records.push(unknown);
+            *line_idx += 1;
         } else {
-            return Err(AchError::InvalidStructure(format!(
-                "Unexpected record type '{record_type}' in batch at line {line_idx}"
+            return Err(in_batch(unexpected_record(
+                record_type,
+                lines[*line_idx].0,
+                true,
             )));
         }
     }
 
     // Parse batch control
     if *line_idx >= lines.len() {
-        return Err(AchError::IncompleteBatch(
+        return Err(in_batch(AchError::IncompleteBatch(
             "Missing batch control record".to_string(),
-        ));
+        )));
     }
 
-    let control = parse_batch_control(lines[*line_idx])?;
+    let control = parse_batch_control(ctx, lines[*line_idx]).map_err(in_batch)?;
     *line_idx += 1;
+    ctx.advance(lines[*line_idx - 1].1, *line_idx, batch_index + 1);
+
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        entries = entries.len(),
+        addenda = entries.iter().map(|e| e.addenda.len()).sum::<usize>(),
+        "parsed batch"
+    );
 
-    Ok(Batch {
+    let mut batch = Batch {
         header,
         entries,
         control,
+        unknown_records,
+        adv: None,
+        iat: None,
+    };
+    batch.adv = batch.read_adv().map_err(in_batch)?;
+    batch.iat = batch.read_iat();
+    Ok(batch)
+}
+
+/// Whether a record of `record_type` is kept as an [`UnknownRecord`]:
+/// the type is none of NACHA's and the options keep such records.
+pub(crate) fn keeps_unknown(ctx: &ParseContext<'_>, record_type: &str) -> bool {
+    ctx.options.keep_unknown_records && record_name(record_type).is_none()
+}
+
+/// Keep a record of unknown type as it is, warning that it was kept.
+/// `preceding` is the number of batches or entries before it.
+pub(crate) fn parse_unknown<'a>(
+    ctx: &mut ParseContext<'_>,
+    (line_number, line): (usize, &'a str),
+    preceding: usize,
+) -> Result<UnknownRecord<'a>, AchError> {
+    let in_record = |source| AchError::InRecord {
+        record_type: "unknown",
+        line: line_number,
+        source: Box::new(source),
+    };
+    check_characters(line).map_err(in_record)?;
+    validate_line_length(line).map_err(in_record)?;
+    let type_code = get_record_type(line)?;
+    ctx.warn(
+        ParseWarningCode::UnknownRecord,
+        line_number,
+        format!("record type '{type_code}' kept as is"),
+    )?;
+    Ok(UnknownRecord {
+        type_code: Cow::Borrowed(type_code),
+        raw_line: Cow::Borrowed(line),
+        line_number,
+        preceding,
     })
 }
 
 /// Get the record type (first character) from a line.
-fn get_record_type(line: &str) -> Result<&str, AchError> {
-    if line.is_empty() {
-        return Err(AchError::InvalidLineLength(0));
+pub(crate) fn get_record_type(line: &str) -> Result<&str, AchError> {
+    match line.chars().next() {
+        Some(first) => Ok(&line[..first.len_utf8()]),
+        None => Err(AchError::InvalidLineLength(0)),
     }
-    Ok(&line[0..1])
 }
 
-/// Validate that a line is exactly 94 characters.
-fn validate_line_length(line: &str) -> Result<(), AchError> {
-    if line.len() != 94 {
-        return Err(AchError::InvalidLineLength(line.len()));
+/// The name of the record with this record type code, as used in errors.
+pub(crate) fn record_name(record_type: &str) -> Option<&'static str> {
+    Some(match record_type {
+        "1" => "file header",
+        "5" => "batch header",
+        "6" => "entry detail",
+        "7" => "addenda",
+        "8" => "batch control",
+        "9" => "file control",
+        _ => return None,
+    })
+}
+
+/// The error for a record of `record_type` where none may appear.
+pub(crate) fn unexpected_record(record_type: &str, line: usize, in_batch: bool) -> AchError {
+    AchError::UnexpectedRecord {
+        record_type: record_type.to_string(),
+        line,
+        in_batch,
+    }
+}
+
+/// Check that a line is a whole record of the expected type, naming the
+/// record and line in the error.
+fn check_record((line_number, line): (usize, &str), record_type: &str) -> Result<(), AchError> {
+    let in_record = |source| AchError::InRecord {
+        record_type: record_name(record_type).unwrap_or("unknown"),
+        line: line_number,
+        source: Box::new(source),
+    };
+    check_characters(line).map_err(in_record)?;
+    validate_line_length(line).map_err(in_record)?;
+    let found = get_record_type(line).map_err(in_record)?;
+    if found != record_type {
+        return Err(in_record(AchError::InvalidRecordType(found.to_string())));
     }
     Ok(())
 }
 
-/// Parse a file header record (type 1).
-fn parse_file_header(line: &str) -> Result<FileHeader, AchError> {
-    validate_line_length(line)?;
+/// Check that a line has no control characters, such as a tab or a stray
+/// carriage return, which would otherwise shift the fields after them.
+fn check_characters(line: &str) -> Result<(), AchError> {
+    match line.chars().enumerate().find(|(_, c)| c.is_control()) {
+        Some((index, character)) => Err(AchError::ControlCharacter {
+            character,
+            position: index + 1,
+        }),
+        None => Ok(()),
+    }
+}
 
-    let record_type = &line[0..1];
-    if record_type != "1" {
-        return Err(AchError::InvalidRecordType(record_type.to_string()));
+/// Validate that a line is exactly 94 characters. Characters, not bytes,
+/// are counted, as they are by the writer, so a field may hold a multibyte
+/// character such as an accented letter.
+fn validate_line_length(line: &str) -> Result<(), AchError> {
+    let len = line.chars().count();
+    if len != 94 {
+        return Err(AchError::InvalidLineLength(len));
     }
+    Ok(())
+}
 
+/// Parse a file header record (type 1).
+pub(crate) fn parse_file_header<'a>(
+    (line_number, line): (usize, &'a str),
+) -> Result<FileHeader<'a>, AchError> {
+    check_record((line_number, line), "1")?;
+
+    let [record_type, priority_code, immediate_destination, immediate_origin, file_creation_date, file_creation_time, file_id_modifier, record_size, blocking_factor, format_code, immediate_destination_name, immediate_origin_name, reference_code] =
+        &layout::FILE_HEADER;
     Ok(FileHeader {
-        record_type,
-        priority_code: &line[1..3],
-        immediate_destination: &line[3..13],
-        immediate_origin: &line[13..23],
-        file_creation_date: &line[23..29],
-        file_creation_time: &line[29..33],
-        file_id_modifier: &line[33..34],
-        record_size: &line[34..37],
-        blocking_factor: &line[37..39],
-        format_code: &line[39..40],
-        immediate_destination_name: &line[40..63],
-        immediate_origin_name: &line[63..86],
-        reference_code: &line[86..94],
+        record_type: field(line, record_type),
+        priority_code: field(line, priority_code),
+        immediate_destination: field(line, immediate_destination),
+        immediate_origin: field(line, immediate_origin),
+        file_creation_date: field(line, file_creation_date),
+        file_creation_time: field(line, file_creation_time),
+        file_id_modifier: field(line, file_id_modifier),
+        record_size: field(line, record_size),
+        blocking_factor: field(line, blocking_factor),
+        format_code: field(line, format_code),
+        immediate_destination_name: field(line, immediate_destination_name),
+        immediate_origin_name: field(line, immediate_origin_name),
+        reference_code: field(line, reference_code),
     })
 }
 
 /// Parse a batch header record (type 5).
-fn parse_batch_header(line: &str) -> Result<BatchHeader, AchError> {
-    validate_line_length(line)?;
-
-    let record_type = &line[0..1];
-    if record_type != "5" {
-        return Err(AchError::InvalidRecordType(record_type.to_string()));
-    }
+pub(crate) fn parse_batch_header<'a>(
+    (line_number, line): (usize, &'a str),
+) -> Result<BatchHeader<'a>, AchError> {
+    check_record((line_number, line), "5")?;
 
+    let [record_type, service_class_code, company_name, company_discretionary_data, company_identification, standard_entry_class_code, company_entry_description, company_descriptive_date, effective_entry_date, settlement_date, originator_status_code, originating_dfi_identification, batch_number] =
+        &layout::BATCH_HEADER;
     Ok(BatchHeader {
-        record_type,
-        service_class_code: &line[1..4],
-        company_name: &line[4..20],
-        company_discretionary_data: &line[20..40],
-        company_identification: &line[40..50],
-        standard_entry_class_code: &line[50..53],
-        company_entry_description: &line[53..63],
-        company_descriptive_date: &line[63..69],
-        effective_entry_date: &line[69..75],
-        settlement_date: &line[75..78],
-        originator_status_code: &line[78..79],
-        originating_dfi_identification: &line[79..87],
-        batch_number: &line[87..94],
+        record_type: field(line, record_type),
+        service_class_code: field(line, service_class_code),
+        company_name: field(line, company_name),
+        company_discretionary_data: field(line, company_discretionary_data),
+        company_identification: field(line, company_identification),
+        standard_entry_class_code: field(line, standard_entry_class_code),
+        company_entry_description: field(line, company_entry_description),
+        company_descriptive_date: field(line, company_descriptive_date),
+        effective_entry_date: field(line, effective_entry_date),
+        settlement_date: field(line, settlement_date),
+        originator_status_code: field(line, originator_status_code),
+        originating_dfi_identification: field(line, originating_dfi_identification),
+        batch_number: field(line, batch_number),
     })
 }
 
 /// Parse an entry detail record (type 6).
-fn parse_entry_detail(line: &str) -> Result<EntryDetail, AchError> {
-    validate_line_length(line)?;
-
-    let record_type = &line[0..1];
-    if record_type != "6" {
-        return Err(AchError::InvalidRecordType(record_type.to_string()));
-    }
+pub(crate) fn parse_entry_detail<'a>(
+    ctx: &mut ParseContext<'_>,
+    (line_number, line): (usize, &'a str),
+) -> Result<EntryDetail<'a>, AchError> {
+    check_record((line_number, line), "6")?;
 
-    // Parse amount field (positions 29-39, 10 characters)
-    let amount_str = line[29..39].trim();
-    let amount = amount_str
-        .parse::<u64>()
-        .map_err(|e| AchError::InvalidNumber {
-            field: "amount",
-            source: e,
-        })?;
+    let [record_type, transaction_code, receiving_dfi_identification, check_digit, dfi_account_number, amount, individual_identification_number, individual_name, discretionary_data, addenda_record_indicator, trace_number] =
+        &layout::ENTRY_DETAIL;
+    let amount = parse_u64(ctx, line, amount, "entry detail", line_number)?;
 
     Ok(EntryDetail {
-        record_type,
-        transaction_code: &line[1..3],
-        receiving_dfi_identification: &line[3..11],
-        check_digit: &line[11..12],
-        dfi_account_number: &line[12..29],
+        record_type: field(line, record_type),
+        transaction_code: field(line, transaction_code),
+        receiving_dfi_identification: field(line, receiving_dfi_identification),
+        check_digit: field(line, check_digit),
+        dfi_account_number: field(line, dfi_account_number),
         amount,
-        individual_identification_number: &line[39..54],
-        individual_name: &line[54..76],
-        discretionary_data: &line[76..78],
-        addenda_record_indicator: &line[78..79],
-        trace_number: &line[79..94],
+        individual_identification_number: field(line, individual_identification_number),
+        individual_name: field(line, individual_name),
+        discretionary_data: field(line, discretionary_data),
+        addenda_record_indicator: field(line, addenda_record_indicator),
+        trace_number: field(line, trace_number),
         addenda: Vec::new(),
     })
 }
 
 /// Parse an addenda record (type 7).
-fn parse_addenda(line: &str) -> Result<Addenda, AchError> {
-    validate_line_length(line)?;
-
-    let record_type = &line[0..1];
-    if record_type != "7" {
-        return Err(AchError::InvalidRecordType(record_type.to_string()));
-    }
+pub(crate) fn parse_addenda<'a>(
+    (line_number, line): (usize, &'a str),
+) -> Result<Addenda<'a>, AchError> {
+    check_record((line_number, line), "7")?;
 
+    let [record_type, addenda_type_code, payment_related_information, addenda_sequence_number, entry_detail_sequence_number] =
+        &layout::ADDENDA;
     Ok(Addenda {
-        record_type,
-        addenda_type_code: &line[1..3],
-        payment_related_information: &line[3..83],
-        addenda_sequence_number: &line[83..87],
-        entry_detail_sequence_number: &line[87..94],
+        record_type: field(line, record_type),
+        addenda_type_code: field(line, addenda_type_code),
+        payment_related_information: field(line, payment_related_information),
+        addenda_sequence_number: field(line, addenda_sequence_number),
+        entry_detail_sequence_number: field(line, entry_detail_sequence_number),
     })
 }
 
 /// Parse a batch control record (type 8).
-fn parse_batch_control(line: &str) -> Result<BatchControl, AchError> {
-    validate_line_length(line)?;
-
-    let record_type = &line[0..1];
-    if record_type != "8" {
-        return Err(AchError::InvalidRecordType(record_type.to_string()));
-    }
+pub(crate) fn parse_batch_control(
+    ctx: &mut ParseContext<'_>,
+    (line_number, line): (usize, &str),
+) -> Result<BatchControl, AchError> {
+    check_record((line_number, line), "8")?;
 
+    let [record_type, service_class_code, entry_addenda_count, entry_hash, total_debit_amount, total_credit_amount, company_identification, message_authentication_code, reserved, originating_dfi_identification, batch_number] =
+        &layout::BATCH_CONTROL;
+    let mut number = |spec| parse_u64(ctx, line, spec, "batch control", line_number);
     Ok(BatchControl {
-        record_type: line[0..1].to_string(),
-        service_class_code: line[1..4].to_string(),
-        entry_addenda_count: parse_u64(&line[4..10], "entry_addenda_count")?,
-        entry_hash: parse_u64(&line[10..20], "entry_hash")?,
-        total_debit_amount: parse_u64(&line[20..32], "total_debit_amount")?,
-        total_credit_amount: parse_u64(&line[32..44], "total_credit_amount")?,
-        company_identification: line[44..54].to_string(),
-        message_authentication_code: line[54..73].to_string(),
-        reserved: line[73..79].to_string(),
-        originating_dfi_identification: line[79..87].to_string(),
-        batch_number: line[87..94].to_string(),
+        record_type: text(line, record_type),
+        service_class_code: text(line, service_class_code),
+        entry_addenda_count: number(entry_addenda_count)?,
+        entry_hash: number(entry_hash)?,
+        total_debit_amount: number(total_debit_amount)?,
+        total_credit_amount: number(total_credit_amount)?,
+        company_identification: text(line, company_identification),
+        message_authentication_code: text(line, message_authentication_code),
+        reserved: text(line, reserved),
+        originating_dfi_identification: text(line, originating_dfi_identification),
+        batch_number: text(line, batch_number),
     })
 }
 
 /// Parse a file control record (type 9).
-fn parse_file_control(line: &str) -> Result<FileControl, AchError> {
-    validate_line_length(line)?;
-
-    let record_type = &line[0..1];
-    if record_type != "9" {
-        return Err(AchError::InvalidRecordType(record_type.to_string()));
-    }
+pub(crate) fn parse_file_control(
+    ctx: &mut ParseContext<'_>,
+    (line_number, line): (usize, &str),
+) -> Result<FileControl, AchError> {
+    check_record((line_number, line), "9")?;
 
+    let [record_type, batch_count, block_count, entry_addenda_count, entry_hash, total_debit_amount, total_credit_amount, reserved] =
+        &layout::FILE_CONTROL;
+    let mut number = |spec| parse_u64(ctx, line, spec, "file control", line_number);
     Ok(FileControl {
-        record_type: line[0..1].to_string(),
-        batch_count: parse_u64(&line[1..7], "batch_count")?,
-        block_count: parse_u64(&line[7..13], "block_count")?,
-        entry_addenda_count: parse_u64(&line[13..21], "entry_addenda_count")?,
-        entry_hash: parse_u64(&line[21..31], "entry_hash")?,
-        total_debit_amount: parse_u64(&line[31..43], "total_debit_amount")?,
-        total_credit_amount: parse_u64(&line[43..55], "total_credit_amount")?,
-        reserved: line[55..94].to_string(),
+        record_type: text(line, record_type),
+        batch_count: number(batch_count)?,
+        block_count: number(block_count)?,
+        entry_addenda_count: number(entry_addenda_count)?,
+        entry_hash: number(entry_hash)?,
+        total_debit_amount: number(total_debit_amount)?,
+        total_credit_amount: number(total_credit_amount)?,
+        reserved: text(line, reserved),
     })
 }
 
-/// Helper function to parse a u64 from a string slice.
-fn parse_u64(s: &str, field_name: &'static str) -> Result<u64, AchError> {
-    s.trim()
+/// The characters of `line` at `range`, which counts characters. Records
+/// are ASCII, where characters are bytes, but may have multibyte characters
+/// in text fields.
+fn slice(line: &str, range: Range<usize>) -> &str {
+    if line.is_ascii() {
+        return line.get(range).unwrap_or("");
+    }
+    let byte = |at: usize| line.char_indices().nth(at).map_or(line.len(), |(i, _)| i);
+    &line[byte(range.start)..byte(range.end)]
+}
+
+/// Borrow a fixed-width field from a record line.
+fn field<'a>(line: &'a str, spec: &FieldLayout) -> Cow<'a, str> {
+    Cow::Borrowed(slice(line, spec.range()))
+}
+
+/// Copy a fixed-width field from a record line.
+fn text(line: &str, spec: &FieldLayout) -> String {
+    slice(line, spec.range()).to_string()
+}
+
+/// Parse a numeric field of a record line.
+fn parse_u64(
+    ctx: &mut ParseContext<'_>,
+    line: &str,
+    spec: &FieldLayout,
+    record_type: &'static str,
+    line_number: usize,
+) -> Result<u64, AchError> {
+    let (field, range) = (spec.name, spec.range());
+    let value = slice(line, range.clone());
+    if ctx.options.blank_numbers_as_zero && value.trim().is_empty() {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            line = line_number,
+            field,
+            "lenient-mode recovery: blank number"
+        );
+        ctx.warn(
+            ParseWarningCode::BlankNumber,
+            line_number,
+            format!("blank '{field}' in {record_type} record read as zero"),
+        )?;
+        return Ok(0);
+    }
+    if let Some(reason) = ctx.options.number_format.check(value) {
+        return Err(AchError::InvalidField {
+            record_type,
+            field,
+            line: line_number,
+            range,
+            reason,
+        });
+    }
+    value
+        .trim()
         .parse::<u64>()
-        .map_err(|e| AchError::InvalidNumber {
-            field: field_name,
-            source: e,
+        .map_err(|e| AchError::InvalidField {
+            record_type,
+            field,
+            line: line_number,
+            range,
+            reason: e.to_string(),
         })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parse_options::NumberFormat;
+    use crate::records::layout::{FieldClass, InclusionRequirement};
+
+    fn ctx() -> ParseContext<'static> {
+        static DEFAULT: ParseOptions = ParseOptions {
+            allow_long_lines: false,
+            pad_short_lines: false,
+            allow_missing_file_control: false,
+            case_insensitive_record_codes: false,
+            blank_numbers_as_zero: false,
+            number_format: NumberFormat::Trimmed,
+            keep_unknown_records: false,
+            replace_invalid_bytes: false,
+            deny: Vec::new(),
+            max_errors: None,
+            progress: None,
+        };
+        ParseContext::new(&DEFAULT)
+    }
+
+    /// A numeric field at `range`.
+    fn spec(name: &'static str, range: Range<usize>) -> FieldLayout {
+        FieldLayout {
+            name,
+            label: name,
+            start: range.start + 1,
+            end: range.end,
+            class: FieldClass::Numeric,
+            inclusion: InclusionRequirement::Mandatory,
+        }
+    }
 
     #[test]
     fn test_parse_u64_valid() {
-        assert_eq!(parse_u64("12345", "test").unwrap(), 12345);
-        assert_eq!(parse_u64("  12345  ", "test").unwrap(), 12345);
-        assert_eq!(parse_u64("0000012345", "test").unwrap(), 12345);
+        let parse = |s: &str| parse_u64(&mut ctx(), s, &spec("test", 0..s.len()), "test", 1);
+        assert_eq!(parse("12345").unwrap(), 12345);
+        assert_eq!(parse("  12345  ").unwrap(), 12345);
+        assert_eq!(parse("0000012345").unwrap(), 12345);
     }
 
     #[test]
     fn test_parse_u64_invalid() {
-        assert!(parse_u64("abc", "test").is_err());
-        assert!(parse_u64("12.34", "test").is_err());
+        assert!(parse_u64(&mut ctx(), "abc", &spec("test", 0..3), "test", 1).is_err());
+        assert!(parse_u64(&mut ctx(), "12.34", &spec("test", 0..5), "test", 1).is_err());
+        match parse_u64(
+            &mut ctx(),
+            "9 12.34",
+            &spec("entry_hash", 2..7),
+            "batch control",
+            4,
+        ) {
+            Err(AchError::InvalidField {
+                record_type: "batch control",
+                field: "entry_hash",
+                line: 4,
+                range,
+                ..
+            }) => assert_eq!(range, 2..7),
+            other => panic!("unexpected {other:?}"),
+        }
     }
 
     #[test]
@@ -316,6 +894,23 @@ mod tests {
         assert!(get_record_type("").is_err());
     }
 
+    #[test]
+    fn test_split_lines() {
+        let split = |content| split_lines(content).collect::<Vec<_>>();
+        assert_eq!(split("a\nb\r\nc\rd"), ["a", "b", "c", "d"]);
+        assert_eq!(split("a\r\r\nb\n\n"), ["a", "", "b", ""]);
+        assert_eq!(split("\u{feff}a\r\n"), ["a"]);
+        assert_eq!(split("a\u{feff}"), ["a\u{feff}"]);
+        assert!(split("\u{feff}").is_empty());
+
+        let records = format!("{}{}", "1".repeat(94), "9".repeat(94));
+        assert_eq!(split(&records), ["1".repeat(94), "9".repeat(94)]);
+        let ended = format!("{records}\n");
+        assert_eq!(split(&ended).len(), 2);
+        assert_eq!(split(&records[1..]).len(), 1);
+        assert_eq!(split(&records[..94]).len(), 1);
+    }
+
     #[test]
     fn test_validate_line_length() {
         let valid = "1".repeat(94);
@@ -331,7 +926,7 @@ mod tests {
     #[test]
     fn test_parse_file_header() {
         let header = "101 12345678012345678011409020123A094101YOUR BANK              YOUR COMPANY                   ";
-        let result = parse_file_header(header);
+        let result = parse_file_header((1, header));
         assert!(result.is_ok());
 
         let fh = result.unwrap();
@@ -347,7 +942,7 @@ mod tests {
     #[test]
     fn test_parse_batch_header() {
         let header = "5200YOUR COMPANY                        1234567890PPDPAYROLL         140903   1123456780000001";
-        let result = parse_batch_header(header);
+        let result = parse_batch_header((1, header));
         assert!(result.is_ok());
 
         let bh = result.unwrap();
@@ -362,7 +957,7 @@ mod tests {
     #[test]
     fn test_parse_entry_detail() {
         let entry = "62212345678011232132         0000001000               ALICE WANDERDUST        1123456780000001";
-        let result = parse_entry_detail(entry);
+        let result = parse_entry_detail(&mut ctx(), (1, entry));
         assert!(result.is_ok());
 
         let ed = result.unwrap();
@@ -378,22 +973,21 @@ mod tests {
     #[test]
     fn test_parse_addenda() {
         let addenda = "705HERE IS SOME ADDITIONAL INFORMATION                                             00000000001";
-        let result = parse_addenda(addenda);
+        let result = parse_addenda((1, addenda));
         assert!(result.is_ok());
 
         let add = result.unwrap();
         assert_eq!(add.record_type, "7");
         assert_eq!(add.addenda_type_code, "05");
-        assert!(
-            add.payment_related_information
-                .starts_with("HERE IS SOME ADDITIONAL")
-        );
+        assert!(add
+            .payment_related_information
+            .starts_with("HERE IS SOME ADDITIONAL"));
     }
 
     #[test]
     fn test_parse_batch_control() {
         let control = "820000000400370145870000000150000000000022131234567890                         123456780000001";
-        let result = parse_batch_control(control);
+        let result = parse_batch_control(&mut ctx(), (1, control));
         assert!(result.is_ok());
 
         let bc = result.unwrap();
@@ -405,7 +999,7 @@ mod tests {
     #[test]
     fn test_parse_file_control() {
         let control = "9000001000001000000040037014587000000015000000000002213                                       ";
-        let result = parse_file_control(control);
+        let result = parse_file_control(&mut ctx(), (1, control));
         assert!(result.is_ok());
 
         let fc = result.unwrap();
@@ -413,4 +1007,186 @@ mod tests {
         assert_eq!(fc.batch_count, 1);
         assert_eq!(fc.block_count, 1);
     }
+
+    #[test]
+    fn test_multibyte_characters() {
+        let text = crate::fixtures::sample_ppd_file(1);
+        let name = "JOSÉ GARCÍA           ";
+        let named = text.replacen("RECEIVER 1            ", name, 1);
+        let file = parse_ach_file(&named).unwrap();
+        let entry = &file.batches[0].entries[0];
+        assert_eq!(entry.individual_name, name);
+        assert_eq!(entry.discretionary_data, "  ");
+        assert_eq!(entry.trace_number, "123456780000001");
+        assert_eq!(
+            parse_ach_file(&file.to_ach_string().unwrap()).unwrap(),
+            file
+        );
+
+        // 94 bytes but 93 characters, with a multibyte character spanning
+        // positions 94 and 95, and as the record type.
+        let lines: Vec<&str> = text.lines().collect();
+        let entry = lines[2];
+        for broken in [
+            format!("{}É", &entry[..92]),
+            format!("{}É{}", &entry[..54], &entry[56..]),
+            format!("É{}", &entry[2..]),
+        ] {
+            let broken = text.replacen(entry, &broken, 1);
+            assert!(parse_ach_file(&broken).is_err());
+            assert!(AchFile::records(&broken).any(|r| r.is_err()));
+        }
+        let broken = text.replacen(entry, &format!("É{}", &entry[1..]), 1);
+        let err = parse_ach_file(&broken).unwrap_err();
+        assert!(matches!(
+            err.root_cause(),
+            AchError::UnexpectedRecord { line: 3, .. }
+        ));
+    }
+
+    #[test]
+    fn test_parse_bytes() {
+        let text = crate::fixtures::sample_multi_company_file().replace('\n', "\r\n");
+        let (file, _) = parse_ach_bytes_with(text.as_bytes(), &ParseOptions::default()).unwrap();
+        assert_eq!(file, parse_ach_file(&text).unwrap());
+        assert!(matches!(
+            file.batches[0].entries[0].individual_name,
+            Cow::Borrowed(_)
+        ));
+
+        // Two bytes in the first entry's name, and a truncated sequence at
+        // the end of the second's.
+        let entry = text.match_indices("\r\n6").map(|(at, _)| at + 2);
+        let at: Vec<usize> = entry.take(2).collect();
+        let mut bytes = text.into_bytes();
+        bytes[at[0] + 54] = 0xC9;
+        bytes[at[0] + 55] = 0xFF;
+        bytes[at[1] + 75] = 0xE2;
+        let err = parse_ach_bytes_with(&bytes, &ParseOptions::default()).unwrap_err();
+        assert!(matches!(err, AchError::InvalidEncoding { offset } if offset == at[0] + 54));
+
+        let options = ParseOptions {
+            replace_invalid_bytes: true,
+            ..ParseOptions::default()
+        };
+        let (file, warnings) = parse_ach_bytes_with(&bytes, &options).unwrap();
+        let entries = &file.batches[0].entries;
+        assert!(entries[0].individual_name.starts_with("??"));
+        assert!(entries[1].individual_name.ends_with('?'));
+        assert_eq!(entries[1].trace_number.len(), 15);
+        let lines: Vec<(usize, &str)> = warnings
+            .iter()
+            .map(|w| (w.line, w.message.as_str()))
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                (3, "2 bytes not valid UTF-8 replaced with '?'"),
+                (4, "1 byte not valid UTF-8 replaced with '?'"),
+            ]
+        );
+
+        let strict = ParseOptions {
+            deny: vec![ParseWarningCode::InvalidBytes],
+            ..options.clone()
+        };
+        let err = parse_ach_bytes_with(&bytes, &strict).unwrap_err();
+        assert!(matches!(err, AchError::DeniedWarning(w) if w.line == 3));
+
+        // Lines end as split_lines ends them: at bare CRs, or nowhere in
+        // unblocked content.
+        let sample = crate::fixtures::sample_multi_company_file();
+        for (ending, step) in [("\r", 95), ("", 94)] {
+            let mut bytes = sample.replace('\n', ending).into_bytes();
+            bytes[2 * step + 54] = 0xC9;
+            bytes[3 * step + 75] = 0xE2;
+            let (_, warnings) = parse_ach_bytes_with(&bytes, &options).unwrap();
+            let lines: Vec<usize> = warnings.iter().map(|w| w.line).collect();
+            assert_eq!(lines, [3, 4], "{ending:?}");
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_tracing_spans_and_events() {
+        use std::fmt::Debug;
+        use std::sync::atomic::{AtomicU64, Ordering};
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        #[derive(Default)]
+        struct Fields(Vec<String>);
+
+        impl Visit for Fields {
+            fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+                self.0.push(format!("{}={value:?}", field.name()));
+            }
+        }
+
+        /// Records every span, span update, and event as one line.
+        #[derive(Default)]
+        struct Capture {
+            log: Arc<Mutex<Vec<String>>>,
+            next_id: AtomicU64,
+        }
+
+        impl Capture {
+            fn push(&self, kind: &str, name: &str, fields: Fields) {
+                let line = format!("{kind} {name} {}", fields.0.join(" "));
+                self.log.lock().unwrap().push(line.trim_end().to_string());
+            }
+        }
+
+        impl Subscriber for Capture {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                let mut fields = Fields::default();
+                span.record(&mut fields);
+                self.push("span", span.metadata().name(), fields);
+                Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+            }
+
+            fn record(&self, _: &Id, values: &Record<'_>) {
+                let mut fields = Fields::default();
+                values.record(&mut fields);
+                self.push("record", "", fields);
+            }
+
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+            fn event(&self, event: &Event<'_>) {
+                let mut fields = Fields::default();
+                event.record(&mut fields);
+                self.push("event", "", fields);
+            }
+
+            fn enter(&self, _: &Id) {}
+
+            fn exit(&self, _: &Id) {}
+        }
+
+        let text = crate::fixtures::sample_ppd_file(2);
+        let (header, rest) = text.split_once('\n').unwrap();
+        let text = format!("{header}\n{}\n{rest}", "9".repeat(94));
+
+        let capture = Capture::default();
+        let log = Arc::clone(&capture.log);
+        tracing::subscriber::with_default(capture, || parse_ach_file(&text).unwrap());
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            [
+                format!("span parse_file bytes={}", text.len()),
+                "event  message=filler line found mid-file line=2".to_string(),
+                "span parse_batch batch_number=\"0000001\"".to_string(),
+                "event  message=parsed batch entries=2 addenda=0".to_string(),
+                "event  message=parsed file batches=1".to_string(),
+            ]
+        );
+    }
 }
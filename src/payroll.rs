@@ -0,0 +1,256 @@
+//! Grouping of entries by payee across several files.
+//!
+//! Payroll is reconciled per employee over a regular run plus any off-cycle
+//! corrections, so [`group_by_payee`] takes any number of files and keys each
+//! entry by a [`PayeeKey`].
+
+use std::collections::HashMap;
+
+use crate::controls::{is_credit, is_debit};
+use crate::dates::CivilDate;
+use crate::records::EntryDetail;
+use crate::AchFile;
+
+/// Number of letters and digits of a normalized name compared by
+/// [`normalize_name`]. Names are cut at 22 characters including spaces and
+/// punctuation, so two spellings of a long name can differ near the end.
+pub const NAME_KEY_LENGTH: usize = 18;
+
+/// How entries are matched to a payee.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum PayeeKey {
+    /// The individual identification number when present, otherwise the
+    /// normalized name, routing number, and account number
+    #[default]
+    IdentificationOrAccount,
+
+    /// Normalized name, routing number, and account number, ignoring the
+    /// identification number
+    NameAndAccount,
+
+    /// The individual identification number only; entries without one are
+    /// skipped
+    IdentificationOnly,
+}
+
+/// One entry paid to or collected from a payee.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PayeeOccurrence {
+    /// Index of the entry's file in the slice given to [`group_by_payee`]
+    pub file_index: usize,
+
+    /// Trace number of the entry
+    pub trace_number: String,
+
+    /// Amount in cents
+    pub amount: u64,
+
+    /// Whether the entry is a debit rather than a credit
+    pub debit: bool,
+
+    /// Effective entry date of the entry's batch, or `None` when blank or
+    /// invalid
+    pub effective_entry_date: Option<CivilDate>,
+}
+
+/// Totals for one payee, from [`group_by_payee`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PayeeSummary {
+    /// The key entries were grouped by
+    pub key: String,
+
+    /// Individual name of the payee's first entry, trimmed
+    pub individual_name: String,
+
+    /// Receiving routing number of the payee's first entry
+    pub routing_number: String,
+
+    /// Account number of the payee's first entry, trimmed
+    pub account_number: String,
+
+    /// Individual identification number of the payee's first entry that
+    /// has one
+    pub identification_number: Option<String>,
+
+    /// Sum of credit amounts, in cents
+    pub total_credit_amount: u64,
+
+    /// Sum of debit amounts, in cents
+    pub total_debit_amount: u64,
+
+    /// Every entry for the payee, in file order
+    pub occurrences: Vec<PayeeOccurrence>,
+}
+
+impl PayeeSummary {
+    /// Number of entries for the payee.
+    pub fn entry_count(&self) -> usize {
+        self.occurrences.len()
+    }
+}
+
+/// Upper-case letters and digits of `name`, at most [`NAME_KEY_LENGTH`] of
+/// them.
+pub fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_uppercase())
+        .take(NAME_KEY_LENGTH)
+        .collect()
+}
+
+/// Group the credit and debit entries of `files` by payee, in order of
+/// each payee's first entry.
+pub fn group_by_payee(files: &[&AchFile<'_>], key: PayeeKey) -> Vec<PayeeSummary> {
+    let mut payees: Vec<PayeeSummary> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for (file_index, file) in files.iter().enumerate() {
+        for batch in &file.batches {
+            let effective_entry_date = CivilDate::parse(&batch.header.effective_entry_date);
+            for entry in &batch.entries {
+                let debit = is_debit(&entry.transaction_code);
+                if !debit && !is_credit(&entry.transaction_code) {
+                    continue;
+                }
+                let Some(payee_key) = payee_key(entry, key) else {
+                    continue;
+                };
+
+                let i = *index.entry(payee_key.clone()).or_insert_with(|| {
+                    payees.push(PayeeSummary {
+                        key: payee_key,
+                        individual_name: entry.individual_name.trim().to_string(),
+                        routing_number: routing_number(entry),
+                        account_number: entry.dfi_account_number.trim().to_string(),
+                        identification_number: None,
+                        total_credit_amount: 0,
+                        total_debit_amount: 0,
+                        occurrences: Vec::new(),
+                    });
+                    payees.len() - 1
+                });
+                let payee = &mut payees[i];
+                if payee.identification_number.is_none() {
+                    payee.identification_number = identification(entry).map(str::to_string);
+                }
+                if debit {
+                    payee.total_debit_amount += entry.amount;
+                } else {
+                    payee.total_credit_amount += entry.amount;
+                }
+                payee.occurrences.push(PayeeOccurrence {
+                    file_index,
+                    trace_number: entry.trace_number.to_string(),
+                    amount: entry.amount,
+                    debit,
+                    effective_entry_date,
+                });
+            }
+        }
+    }
+    payees
+}
+
+fn payee_key(entry: &EntryDetail<'_>, key: PayeeKey) -> Option<String> {
+    let account = || {
+        format!(
+            "{}/{}/{}",
+            normalize_name(&entry.individual_name),
+            routing_number(entry),
+            entry.dfi_account_number.trim()
+        )
+    };
+    match key {
+        PayeeKey::IdentificationOrAccount => {
+            Some(identification(entry).map_or_else(account, |id| format!("ID/{id}")))
+        }
+        PayeeKey::NameAndAccount => Some(account()),
+        PayeeKey::IdentificationOnly => identification(entry).map(|id| format!("ID/{id}")),
+    }
+}
+
+fn routing_number(entry: &EntryDetail<'_>) -> String {
+    format!(
+        "{}{}",
+        entry.receiving_dfi_identification.trim(),
+        entry.check_digit.trim()
+    )
+}
+
+fn identification<'e>(entry: &'e EntryDetail<'_>) -> Option<&'e str> {
+    let id = entry.individual_identification_number.trim();
+    (!id.is_empty()).then_some(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_normalize_name() {
+        assert_eq!(normalize_name("  Mary O'Connor  "), "MARYOCONNOR");
+        // The same long name, cut at 22 characters with and without
+        // punctuation.
+        assert_eq!(
+            normalize_name("MARY O'CONNOR-WHITEHEA"),
+            normalize_name("MARY OCONNOR WHITEHEAD")
+        );
+    }
+
+    #[test]
+    fn test_group_across_files() {
+        let regular = fixtures::sample_ppd_file(3);
+        let regular = AchFile::parse(&regular).unwrap();
+        // An off-cycle correction to the first receiver, spelled differently.
+        let text = fixtures::sample_ppd_file(1);
+        let mut correction = AchFile::parse(&text).unwrap();
+        correction.batches[0].entries[0].individual_name = "Receiver-1".into();
+        correction.batches[0].entries[0].amount = 300;
+
+        let payees = group_by_payee(&[&regular, &correction], PayeeKey::default());
+        assert_eq!(payees.len(), 3);
+        let first = &payees[0];
+        assert_eq!(first.key, "RECEIVER1/123456780/000100000");
+        assert_eq!(first.individual_name, "RECEIVER 1");
+        assert_eq!(first.entry_count(), 2);
+        assert_eq!(first.total_credit_amount, 1_250 + 300);
+        assert_eq!(first.occurrences[1].file_index, 1);
+        assert_eq!(
+            first.occurrences[1].effective_entry_date,
+            CivilDate::new(2014, 9, 3)
+        );
+        // The second receiver is debited.
+        assert_eq!(payees[1].total_debit_amount, 2_500);
+        assert!(payees[1].occurrences[0].debit);
+    }
+
+    #[test]
+    fn test_identification_keys() {
+        let text = fixtures::sample_ppd_file(3);
+        let mut file = AchFile::parse(&text).unwrap();
+        // One employee paid to two accounts under the same ID.
+        for entry in &mut file.batches[0].entries[..2] {
+            entry.individual_identification_number = "EMP0042        ".into();
+        }
+
+        let payees = group_by_payee(&[&file], PayeeKey::IdentificationOrAccount);
+        assert_eq!(payees.len(), 2);
+        assert_eq!(payees[0].key, "ID/EMP0042");
+        assert_eq!(payees[0].identification_number.as_deref(), Some("EMP0042"));
+        assert_eq!(payees[0].entry_count(), 2);
+
+        let payees = group_by_payee(&[&file], PayeeKey::IdentificationOnly);
+        assert_eq!(payees.len(), 1);
+
+        let payees = group_by_payee(&[&file], PayeeKey::NameAndAccount);
+        assert_eq!(payees.len(), 3);
+    }
+}
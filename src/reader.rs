@@ -0,0 +1,461 @@
+//! Reading a file batch by batch from an [`io::BufRead`](BufRead), for files too
+//! large to hold in memory.
+
+use std::io::BufRead;
+use std::iter::FusedIterator;
+use std::sync::Arc;
+
+use crate::controls::{self, FileTotals};
+use crate::error::{AchError, AchResult};
+use crate::parse_options::{ParseContext, ParseOptions, ParseWarning, ParseWarningCode};
+use crate::parser::{self, LineFilter};
+use crate::records::{FileControl, FileHeader, UnknownRecord};
+use crate::writer::RECORD_LENGTH;
+use crate::{Batch, BatchOwned};
+
+/// The record lines of the input, with their line numbers.
+#[derive(Debug)]
+struct Lines<R> {
+    reader: R,
+    filter: LineFilter,
+    line_number: usize,
+    offset: usize,
+    buffer: Vec<u8>,
+    after_cr: bool,
+    unblocked: bool,
+}
+
+impl<R: BufRead> Lines<R> {
+    /// The next record line, skipping padding as [`AchFile::parse`] does,
+    /// or `None` at the end of the input.
+    ///
+    /// [`AchFile::parse`]: crate::AchFile::parse
+    fn next(&mut self, ctx: &mut ParseContext<'_>) -> AchResult<Option<(usize, String)>> {
+        loop {
+            let Some(mut start) = self.read_line()? else {
+                return Ok(None);
+            };
+            self.line_number += 1;
+
+            let mut bytes = &self.buffer[..];
+            if self.line_number == 1 {
+                if let Some(rest) = bytes.strip_prefix(b"\xEF\xBB\xBF") {
+                    bytes = rest;
+                    start += 3;
+                }
+            }
+            let line = std::str::from_utf8(bytes).map_err(|err| AchError::InvalidEncoding {
+                offset: start + err.valid_up_to(),
+            })?;
+            let padded = parser::pad_short_line(ctx, self.line_number, line)?;
+            let line = padded.as_deref().unwrap_or(line);
+            if let Some(record) = self.filter.record(ctx, self.line_number, line)? {
+                return Ok(Some((self.line_number, record.to_string())));
+            }
+        }
+    }
+
+    /// Read the next line into the buffer, without the `\n`, `\r\n`, or
+    /// bare `\r` ending it, as [`parser::split_lines`] splits lines.
+    /// Returns the offset of the line, or `None` at the end of the input.
+    ///
+    /// A first line running on past 94 characters into one that is not
+    /// whitespace is taken to start an unblocked file, whose lines are then
+    /// its records of 94 characters.
+    fn read_line(&mut self) -> AchResult<Option<usize>> {
+        self.buffer.clear();
+        let mut start = None;
+        let mut chars = 0;
+        loop {
+            let available = match self.reader.fill_buf() {
+                Ok(available) => available,
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err.into()),
+            };
+            if available.is_empty() {
+                return Ok(start);
+            }
+            // The `\n` of a `\r\n`, whose `\r` ended the last line.
+            if std::mem::take(&mut self.after_cr) && available[0] == b'\n' {
+                self.reader.consume(1);
+                self.offset += 1;
+                continue;
+            }
+            start.get_or_insert(self.offset);
+
+            let mut end = None;
+            for (at, &byte) in available.iter().enumerate() {
+                if byte == b'\n' || byte == b'\r' {
+                    self.after_cr = byte == b'\r';
+                    end = Some((at, at + 1));
+                    break;
+                }
+                // Count characters by their first bytes, leaving out the
+                // byte order mark.
+                if byte & 0xC0 == 0x80 || (self.offset + at == 0 && byte == 0xEF) {
+                    continue;
+                }
+                if chars == RECORD_LENGTH
+                    && (self.unblocked || (self.line_number == 0 && !byte.is_ascii_whitespace()))
+                {
+                    self.unblocked = true;
+                    end = Some((at, at));
+                    break;
+                }
+                chars += 1;
+            }
+            let (used, ended) = match end {
+                Some((at, used)) => {
+                    self.buffer.extend_from_slice(&available[..at]);
+                    (used, true)
+                }
+                None => {
+                    self.buffer.extend_from_slice(available);
+                    (available.len(), false)
+                }
+            };
+            self.reader.consume(used);
+            self.offset += used;
+            if ended {
+                return Ok(start);
+            }
+        }
+    }
+}
+
+/// A reader of ACH files that parses one batch at a time from an
+/// [`BufRead`], so no more than one batch is held in memory.
+///
+/// The file header is read when the reader is created; the reader then
+/// yields the batches in file order. Records are checked as the in-memory
+/// parser checks them, with the same errors, and the first error ends the
+/// iteration. The iteration also ends at the file control, which is then
+/// available from [`file_control`](AchReader::file_control); nothing after
+/// it is read. Batches are not validated: see
+/// [`Batch::validate`](crate::Batch::validate). A file header running on
+/// into another record, with no line ending, starts an unblocked file,
+/// which is read record by record.
+///
+/// Warnings are collected in [`warnings`](AchReader::warnings) and denied
+/// warnings are errors, as with [`AchFile::parse_with`]. A
+/// [`ProgressHook`](crate::ProgressHook) is not called, as the length of
+/// the input is not known.
+///
+/// ```
+/// use rs_ach::{fixtures, AchReader};
+///
+/// let text = fixtures::sample_multi_company_file();
+/// let mut reader = AchReader::new(text.as_bytes()).unwrap();
+/// assert_eq!(reader.file_header().immediate_origin, "1234567801");
+///
+/// let mut entries = 0;
+/// for batch in &mut reader {
+///     entries += batch.unwrap().entries.len();
+/// }
+/// assert_eq!(entries, 7);
+/// assert_eq!(reader.file_control().unwrap().batch_count, 3);
+/// ```
+///
+/// [`AchFile::parse_with`]: crate::AchFile::parse_with
+#[derive(Debug)]
+pub struct AchReader<R> {
+    lines: Lines<R>,
+    options: Arc<ParseOptions>,
+    file_header: FileHeader<'static>,
+    file_control: Option<FileControl>,
+    unknown_records: Vec<UnknownRecord<'static>>,
+    warnings: Vec<ParseWarning>,
+    batches: usize,
+    totals: FileTotals,
+    last_line: usize,
+    done: bool,
+}
+
+impl<R: BufRead> AchReader<R> {
+    /// Start reading `reader`, parsing its file header.
+    pub fn new(reader: R) -> AchResult<Self> {
+        AchReader::with_options(reader, &ParseOptions::default())
+    }
+
+    /// Start reading `reader`, parsing with `options`.
+    pub fn with_options(reader: R, options: &ParseOptions) -> AchResult<Self> {
+        let mut lines = Lines {
+            reader,
+            filter: LineFilter::default(),
+            line_number: 0,
+            offset: 0,
+            buffer: Vec::new(),
+            after_cr: false,
+            unblocked: false,
+        };
+        let mut ctx = ParseContext::new(options);
+        let Some((number, line)) = lines.next(&mut ctx)? else {
+            return Err(AchError::EmptyFile);
+        };
+        let mut file_header = parser::parse_file_header((number, &line))?.into_owned();
+        ctx.begin_file(&mut file_header, number)?;
+        let warnings = ctx.warnings;
+        Ok(AchReader {
+            lines,
+            options: Arc::new(options.clone()),
+            file_header,
+            file_control: None,
+            unknown_records: Vec::new(),
+            warnings,
+            batches: 0,
+            totals: FileTotals::default(),
+            last_line: number,
+            done: false,
+        })
+    }
+
+    /// The file header.
+    pub fn file_header(&self) -> &FileHeader<'static> {
+        &self.file_header
+    }
+
+    /// The file control, once the iteration has reached it.
+    pub fn file_control(&self) -> Option<&FileControl> {
+        self.file_control.as_ref()
+    }
+
+    /// The records of unknown type read so far outside any batch; always
+    /// empty unless [`ParseOptions::keep_unknown_records`] is set.
+    pub fn unknown_records(&self) -> &[UnknownRecord<'static>] {
+        &self.unknown_records
+    }
+
+    /// The warnings raised so far.
+    pub fn warnings(&self) -> &[ParseWarning] {
+        &self.warnings
+    }
+
+    /// The next batch, `None` at the file control, or the error that ends
+    /// the iteration.
+    fn read(&mut self, ctx: &mut ParseContext<'_>) -> AchResult<Option<BatchOwned>> {
+        while let Some((number, line)) = self.lines.next(ctx)? {
+            self.last_line = number;
+            let record_type = parser::get_record_type(&line)?;
+            if record_type == "5" {
+                let batch = read_batch(&mut self.lines, ctx, (number, line), self.batches)?;
+                self.last_line = self.lines.line_number;
+                self.totals
+                    .add_batch(&batch.control, batch.unknown_records.len());
+                return Ok(Some(batch));
+            } else if record_type == "9" {
+                self.file_control = Some(parser::parse_file_control(ctx, (number, &line))?);
+                return Ok(None);
+            } else if parser::keeps_unknown(ctx, record_type) {
+                let unknown = parser::parse_unknown(ctx, (number, &line), self.batches)?;
+                self.unknown_records.push(unknown.into_owned());
+                self.totals.add_unknown(1);
+            } else {
+                return Err(parser::unexpected_record(record_type, number, false));
+            }
+        }
+        if !ctx.options.allow_missing_file_control {
+            return Err(AchError::InvalidStructure(
+                "Missing file control record".to_string(),
+            ));
+        }
+        let last = self.last_line;
+        ctx.warn(
+            ParseWarningCode::MissingFileControl,
+            last,
+            format!("no file control after line {last}; computed from the batches"),
+        )?;
+        let mut control = parser::blank_file_control();
+        let blocking_factor = controls::blocking_factor(&self.file_header);
+        self.totals.apply(blocking_factor, &mut control);
+        self.file_control = Some(control);
+        Ok(None)
+    }
+}
+
+/// Read the batch whose header is `header`: its lines up to the batch
+/// control, or up to the first that cannot be in a batch, parsed together.
+fn read_batch<R: BufRead>(
+    lines: &mut Lines<R>,
+    ctx: &mut ParseContext<'_>,
+    header: (usize, String),
+    batch_index: usize,
+) -> AchResult<BatchOwned> {
+    let mut batch_lines = vec![header];
+    while let Some((number, line)) = lines.next(ctx)? {
+        let record_type = parser::get_record_type(&line)?;
+        let more = matches!(record_type, "6" | "7") || parser::keeps_unknown(ctx, record_type);
+        batch_lines.push((number, line));
+        if !more {
+            break;
+        }
+    }
+    let borrowed: Vec<(usize, &str)> = batch_lines
+        .iter()
+        .map(|(number, line)| (*number, line.as_str()))
+        .collect();
+    parser::parse_batch(ctx, &borrowed, &mut 0, batch_index).map(Batch::into_owned)
+}
+
+impl<R: BufRead> Iterator for AchReader<R> {
+    type Item = AchResult<BatchOwned>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let options = Arc::clone(&self.options);
+        let mut ctx = ParseContext::new(&options);
+        let result = self.read(&mut ctx);
+        self.warnings.append(&mut ctx.warnings);
+        match result {
+            Ok(Some(batch)) => {
+                self.batches += 1;
+                Some(Ok(batch))
+            }
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(error) => {
+                self.done = true;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+impl<R: BufRead> FusedIterator for AchReader<R> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, AchFile, ParseWarningCode};
+
+    /// Read all of `text`, returning the batches and the error ending the
+    /// iteration, if any.
+    fn read_all<'t>(
+        text: &'t str,
+        options: &ParseOptions,
+    ) -> AchResult<(AchReader<&'t [u8]>, Vec<BatchOwned>)> {
+        let mut reader = AchReader::with_options(text.as_bytes(), options)?;
+        let batches = reader.by_ref().collect::<AchResult<Vec<_>>>()?;
+        Ok((reader, batches))
+    }
+
+    #[test]
+    fn test_reads_as_parse() {
+        let options = ParseOptions::default();
+        for text in [
+            fixtures::sample_multi_company_file(),
+            fixtures::sample_ctx_with_addenda(),
+            fixtures::sample_empty_file(),
+            // No trailing newline, and Windows line endings.
+            fixtures::sample_ppd_file(3).trim_end().to_string(),
+            fixtures::sample_ppd_file(2).replace('\n', "\r\n"),
+        ] {
+            let file = AchFile::parse(&text).unwrap();
+            let (reader, batches) = read_all(&text, &options).unwrap();
+            assert_eq!(reader.file_header(), &file.file_header);
+            assert_eq!(batches, file.batches);
+            assert_eq!(reader.file_control(), Some(&file.file_control));
+        }
+    }
+
+    #[test]
+    fn test_errors_as_parse() {
+        let sample = fixtures::sample_multi_company_file();
+        let lines: Vec<&str> = sample.lines().collect();
+        let without = |skip: usize| -> String {
+            let mut kept = lines.clone();
+            kept.remove(skip);
+            kept.join("\n")
+        };
+        let options = ParseOptions::default();
+        for text in [
+            // The second batch's control, the end of the third batch, and
+            // the file control.
+            without(8),
+            lines[..12].join("\n"),
+            lines[..14].join("\n"),
+            // An entry record type in the first batch.
+            sample.replacen("\n6", "\nX", 1),
+            String::new(),
+        ] {
+            let expected = AchFile::parse(&text).unwrap_err().to_string();
+            let err = read_all(&text, &options).err().unwrap();
+            assert_eq!(err.to_string(), expected);
+        }
+
+        let mut bytes = sample.clone().into_bytes();
+        bytes[200] = 0xff;
+        let err = AchReader::new(&bytes[..])
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap_err();
+        assert!(matches!(err, AchError::InvalidEncoding { offset: 200 }));
+    }
+
+    #[test]
+    fn test_ends_after_error() {
+        let sample = fixtures::sample_multi_company_file();
+        let mut lines: Vec<String> = sample.lines().map(String::from).collect();
+        // The second batch's control.
+        lines[8].replace_range(..1, "X");
+        let text = lines.join("\n");
+        let mut reader = AchReader::new(text.as_bytes()).unwrap();
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().unwrap().is_err());
+        assert!(reader.next().is_none());
+        assert_eq!(reader.file_control(), None);
+    }
+
+    #[test]
+    fn test_warnings() {
+        let sample = fixtures::sample_ppd_file(2);
+        let mut lines: Vec<String> = sample.lines().map(String::from).collect();
+        lines.insert(1, "9".repeat(94));
+        lines.insert(3, format!("Q{:<93}", ""));
+        let text = lines.join("\n");
+        let options = ParseOptions {
+            keep_unknown_records: true,
+            ..ParseOptions::default()
+        };
+        let (file, warnings) = AchFile::parse_with(&text, &options).unwrap();
+        let (reader, batches) = read_all(&text, &options).unwrap();
+        assert_eq!(reader.warnings(), warnings);
+        assert_eq!(batches, file.batches);
+        assert_eq!(reader.warnings()[0].code, ParseWarningCode::MidFilePadding);
+
+        let strict = ParseOptions::strict(&[ParseWarningCode::MidFilePadding]);
+        let expected = AchFile::parse_with(&text, &strict).unwrap_err().to_string();
+        assert_eq!(
+            read_all(&text, &strict).err().unwrap().to_string(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_lenient_options() {
+        let sample = fixtures::sample_multi_company_file().replacen("PPD", "ppd", 1);
+        let text = fixtures::corrupt(&sample, fixtures::Corruption::MissingFileControl)
+            .lines()
+            .map(|line| format!("{}\n", line.trim_end()))
+            .collect::<String>();
+        let options = ParseOptions {
+            pad_short_lines: true,
+            allow_missing_file_control: true,
+            case_insensitive_record_codes: true,
+            ..ParseOptions::default()
+        };
+        let (file, warnings) = AchFile::parse_with(&text, &options).unwrap();
+        let (reader, batches) = read_all(&text, &options).unwrap();
+        assert_eq!(reader.warnings(), warnings);
+        assert_eq!(reader.file_header(), &file.file_header);
+        assert_eq!(batches, file.batches);
+        assert_eq!(reader.file_control(), Some(&file.file_control));
+        assert!(warnings
+            .iter()
+            .any(|w| w.code == ParseWarningCode::MissingFileControl));
+    }
+}
@@ -0,0 +1,188 @@
+//! Moving addenda back to the entries their sequence numbers name.
+
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap};
+
+use crate::Batch;
+
+/// An addenda whose entry detail sequence number did not match the trace
+/// number of the entry it followed, found by [`Batch::reassociate_addenda`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Reassociation {
+    /// The addenda's entry detail sequence number
+    pub entry_detail_sequence_number: String,
+
+    /// The addenda's addenda sequence number
+    pub addenda_sequence_number: String,
+
+    /// Index of the entry the addenda was attached to, counting from 0
+    pub from_entry: usize,
+
+    /// Index of the entry it was moved to, or `None` if no entry, or more
+    /// than one, in the batch has a matching trace number, in which case
+    /// the addenda was left in place
+    pub to_entry: Option<usize>,
+}
+
+impl Reassociation {
+    /// Whether the addenda was left in place for want of a single matching
+    /// entry.
+    pub fn is_unresolvable(&self) -> bool {
+        self.to_entry.is_none()
+    }
+}
+
+impl Batch<'_> {
+    /// Move each addenda whose entry detail sequence number differs from the
+    /// last 7 digits of its entry's trace number to the entry in the batch
+    /// whose trace number does end in it, and report every addenda found.
+    ///
+    /// Moved addenda follow the addenda the entry already had, in addenda
+    /// sequence number order, and the addenda record indicator of every
+    /// entry that gained or lost one is set to match. An addenda that
+    /// matches no single entry is left where it was and reported with
+    /// [`to_entry`](Reassociation::to_entry) of `None`. The batch control
+    /// is unaffected, since the batch keeps the same records.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile};
+    ///
+    /// let text = fixtures::sample_ctx_with_addenda();
+    /// let mut file = AchFile::parse(&text).unwrap();
+    /// let batch = &mut file.batches[0];
+    /// // A generator that wrote the addenda after the next entry.
+    /// let mut next = batch.entries[0].clone();
+    /// next.trace_number = "123456780000002".into();
+    /// next.addenda_record_indicator = "1".into();
+    /// batch.entries[0].addenda_record_indicator = "0".into();
+    /// next.addenda = std::mem::take(&mut batch.entries[0].addenda);
+    /// batch.entries.push(next);
+    ///
+    /// let moves = batch.reassociate_addenda();
+    /// assert_eq!(moves.len(), 3);
+    /// assert!(moves.iter().all(|m| (m.from_entry, m.to_entry) == (1, Some(0))));
+    /// assert_eq!(batch.entries[0].addenda.len(), 3);
+    /// assert_eq!(batch.entries[1].addenda_record_indicator, "0");
+    /// ```
+    pub fn reassociate_addenda(&mut self) -> Vec<Reassociation> {
+        let mut by_sequence: HashMap<String, Option<usize>> = HashMap::new();
+        for (i, entry) in self.entries.iter().enumerate() {
+            by_sequence
+                .entry(trace_sequence(&entry.trace_number))
+                .and_modify(|found| *found = None)
+                .or_insert(Some(i));
+        }
+
+        let mut reassociations = Vec::new();
+        let mut moves = Vec::new();
+        for (from_entry, entry) in self.entries.iter_mut().enumerate() {
+            let sequence = trace_sequence(&entry.trace_number);
+            let addenda = std::mem::take(&mut entry.addenda);
+            for addenda in addenda {
+                let named = addenda.entry_detail_sequence_number.trim();
+                if named == sequence {
+                    entry.addenda.push(addenda);
+                    continue;
+                }
+                let to_entry = by_sequence.get(named).copied().flatten();
+                reassociations.push(Reassociation {
+                    entry_detail_sequence_number: named.to_string(),
+                    addenda_sequence_number: addenda.addenda_sequence_number.to_string(),
+                    from_entry,
+                    to_entry,
+                });
+                match to_entry {
+                    Some(to_entry) => moves.push((from_entry, to_entry, addenda)),
+                    None => entry.addenda.push(addenda),
+                }
+            }
+        }
+
+        let mut touched = BTreeSet::new();
+        for (from_entry, to_entry, addenda) in moves {
+            self.entries[to_entry].addenda.push(addenda);
+            touched.extend([from_entry, to_entry]);
+        }
+        for i in touched {
+            let entry = &mut self.entries[i];
+            entry
+                .addenda
+                .sort_by_key(|a| a.addenda_sequence_number.trim().parse::<u32>().ok());
+            let indicator = if entry.addenda.is_empty() { "0" } else { "1" };
+            entry.addenda_record_indicator = Cow::Borrowed(indicator);
+        }
+        reassociations
+    }
+}
+
+/// The last 7 characters of a trace number, which an addenda's entry detail
+/// sequence number repeats.
+fn trace_sequence(trace_number: &str) -> String {
+    let trace = trace_number.trim();
+    let start = trace.char_indices().rev().nth(6).map_or(0, |(i, _)| i);
+    trace[start..].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, AchFile};
+
+    #[test]
+    fn test_reassociate_addenda() {
+        let text = fixtures::sample_ctx_with_addenda();
+        let file = AchFile::parse(&text).unwrap();
+        let mut batch = file.batches[0].clone();
+        let mut second = batch.entries[0].clone();
+        second.trace_number = "123456780000002".into();
+        for addenda in &mut second.addenda {
+            addenda.entry_detail_sequence_number = "0000002".into();
+        }
+        // The first entry's last addenda was written after the second entry.
+        let stray = batch.entries[0].addenda.pop().unwrap();
+        second.addenda.push(stray.clone());
+        batch.entries.push(second);
+        let control = batch.control.clone();
+
+        let moves = batch.reassociate_addenda();
+        assert_eq!(
+            moves,
+            [Reassociation {
+                entry_detail_sequence_number: "0000001".to_string(),
+                addenda_sequence_number: stray.addenda_sequence_number.to_string(),
+                from_entry: 1,
+                to_entry: Some(0),
+            }]
+        );
+        assert_eq!(batch.entries[0].addenda, file.batches[0].entries[0].addenda);
+        assert_eq!(batch.entries[1].addenda.len(), 3);
+        assert_eq!(batch.entries[1].addenda_record_indicator, "1");
+        assert_eq!(batch.control, control);
+        assert!(batch.reassociate_addenda().is_empty());
+    }
+
+    #[test]
+    fn test_unresolvable_addenda() {
+        let text = fixtures::sample_ctx_with_addenda();
+        let file = AchFile::parse(&text).unwrap();
+        let mut batch = file.batches[0].clone();
+        batch.entries[0].addenda[1].entry_detail_sequence_number = "0000009".into();
+
+        let moves = batch.reassociate_addenda();
+        assert_eq!(moves.len(), 1);
+        assert!(moves[0].is_unresolvable());
+        assert_eq!(moves[0].entry_detail_sequence_number, "0000009");
+        assert_eq!(batch.entries[0].addenda.len(), 3);
+        assert_eq!(batch.entries[0].addenda_record_indicator, "1");
+
+        // Two entries ending in the same sequence number are ambiguous.
+        let mut copy = batch.entries[0].clone();
+        copy.addenda.clear();
+        copy.trace_number = "876543210000009".into();
+        batch.entries.push(copy.clone());
+        batch.entries.push(copy);
+        assert!(batch.reassociate_addenda()[0].is_unresolvable());
+    }
+}
@@ -0,0 +1,329 @@
+//! Reconciliation of originated entries against returns, Notifications of
+//! Change, and acknowledgments received back.
+
+use std::collections::HashMap;
+
+use crate::noc::ChangeCode;
+use crate::returns::ReturnReasonCode;
+use crate::AchFile;
+
+/// What is known about an originated entry, from [`Reconciler::report`].
+///
+/// When several received entries refer to one trace number, a return
+/// outranks a correction, which outranks an acknowledgment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum TraceStatus {
+    /// Nothing was received for the entry, so it is presumed settled
+    SettledPresumed,
+
+    /// The entry was returned with this reason code
+    Returned(ReturnReasonCode),
+
+    /// A Notification of Change with this change code was received
+    Corrected(ChangeCode),
+
+    /// An acknowledgment entry (ACK or ATX) was received
+    Acknowledged,
+}
+
+impl TraceStatus {
+    fn rank(&self) -> u8 {
+        match self {
+            TraceStatus::SettledPresumed => 0,
+            TraceStatus::Acknowledged => 1,
+            TraceStatus::Corrected(_) => 2,
+            TraceStatus::Returned(_) => 3,
+        }
+    }
+}
+
+/// An originated entry and its status.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ReconciledTrace {
+    /// Trace number of the originated entry
+    pub trace_number: String,
+
+    /// Index of the entry's file, counting originated files in the order
+    /// added
+    pub file_index: usize,
+
+    /// Batch number of the entry's batch, trimmed
+    pub batch_number: String,
+
+    /// Amount in cents
+    pub amount: u64,
+
+    /// The entry's status
+    pub status: TraceStatus,
+}
+
+/// A received entry referring to a trace number that was not originated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct UnknownTrace {
+    /// The trace number referred to
+    pub original_trace_number: String,
+
+    /// Index of the received file, counting received files in the order
+    /// added
+    pub received_file_index: usize,
+
+    /// Trace number of the received entry
+    pub trace_number: String,
+
+    /// What the received entry reports
+    pub status: TraceStatus,
+}
+
+/// Result of [`Reconciler::report`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ReconciliationReport {
+    /// Every originated entry, in the order added
+    pub traces: Vec<ReconciledTrace>,
+
+    /// Received entries matching no originated entry, in the order added
+    pub unknown: Vec<UnknownTrace>,
+}
+
+impl ReconciliationReport {
+    /// The status of the originated entry with `trace_number`, if any.
+    pub fn status(&self, trace_number: &str) -> Option<&TraceStatus> {
+        self.traces
+            .iter()
+            .find(|t| t.trace_number == trace_number.trim())
+            .map(|t| &t.status)
+    }
+
+    /// Originated entries that were returned, in the order added.
+    pub fn returned(&self) -> impl Iterator<Item = &ReconciledTrace> {
+        self.traces
+            .iter()
+            .filter(|t| matches!(t.status, TraceStatus::Returned(_)))
+    }
+}
+
+/// Collects originated files and the return, NOC, and acknowledgment files
+/// received for them, and reports the status of each originated trace
+/// number.
+///
+/// Files are read when added, so they need not outlive the reconciler.
+///
+/// ```
+/// use rs_ach::{fixtures, AchFile, Reconciler, TraceStatus};
+///
+/// let originated = fixtures::sample_ppd_file(2);
+/// let returns = fixtures::sample_returns_file();
+///
+/// let report = Reconciler::new()
+///     .add_originated(&AchFile::parse(&originated).unwrap())
+///     .add_received(&AchFile::parse(&returns).unwrap())
+///     .report();
+/// assert_eq!(report.traces[0].status, TraceStatus::SettledPresumed);
+/// assert_eq!(report.unknown.len(), 2);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Reconciler {
+    originated: Vec<ReconciledTrace>,
+    originated_files: usize,
+    received_files: usize,
+    received: Vec<UnknownTrace>,
+}
+
+impl Reconciler {
+    /// An empty reconciler.
+    pub fn new() -> Self {
+        Reconciler::default()
+    }
+
+    /// Add the entries of a file that was sent.
+    pub fn add_originated(&mut self, file: &AchFile<'_>) -> &mut Self {
+        let file_index = self.originated_files;
+        for batch in &file.batches {
+            for entry in &batch.entries {
+                self.originated.push(ReconciledTrace {
+                    trace_number: entry.trace_number.trim().to_string(),
+                    file_index,
+                    batch_number: batch.header.batch_number.trim().to_string(),
+                    amount: entry.amount,
+                    status: TraceStatus::SettledPresumed,
+                });
+            }
+        }
+        self.originated_files += 1;
+        self
+    }
+
+    /// Add the returns, Notifications of Change, and acknowledgments in a
+    /// file that was received. Other entries are ignored.
+    pub fn add_received(&mut self, file: &AchFile<'_>) -> &mut Self {
+        let received_file_index = self.received_files;
+        for batch in &file.batches {
+            let sec = batch.header.standard_entry_class_code.trim();
+            for entry in &batch.entries {
+                let found = if let Some(addenda) = entry.as_return() {
+                    Some((
+                        addenda.original_entry_trace_number.clone(),
+                        TraceStatus::Returned(addenda.reason_code()),
+                    ))
+                } else if let Some(addenda) = entry.as_noc() {
+                    let code = addenda.code();
+                    Some((
+                        addenda.original_entry_trace_number,
+                        TraceStatus::Corrected(code),
+                    ))
+                } else if sec == "ACK" || sec == "ATX" {
+                    // Positions 40-54 carry the original entry trace number.
+                    Some((
+                        entry.individual_identification_number.trim().to_string(),
+                        TraceStatus::Acknowledged,
+                    ))
+                } else {
+                    None
+                };
+                if let Some((original_trace_number, status)) = found {
+                    self.received.push(UnknownTrace {
+                        original_trace_number,
+                        received_file_index,
+                        trace_number: entry.trace_number.trim().to_string(),
+                        status,
+                    });
+                }
+            }
+        }
+        self.received_files += 1;
+        self
+    }
+
+    /// The status of every originated entry, and the received entries that
+    /// match none of them.
+    pub fn report(&self) -> ReconciliationReport {
+        let mut report = ReconciliationReport {
+            traces: self.originated.clone(),
+            unknown: Vec::new(),
+        };
+        let index: HashMap<&str, usize> = self
+            .originated
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (t.trace_number.as_str(), i))
+            .rev()
+            .collect();
+        for received in &self.received {
+            match index.get(received.original_trace_number.as_str()) {
+                Some(&i) => {
+                    let trace = &mut report.traces[i];
+                    if received.status.rank() > trace.status.rank() {
+                        trace.status = received.status.clone();
+                    }
+                }
+                None => report.unknown.push(received.clone()),
+            }
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    /// The PPD fixture with trace numbers matching the originals named in
+    /// the returns fixture.
+    fn originated(text: &str) -> AchFile<'_> {
+        let mut file = AchFile::parse(text).unwrap();
+        for (i, entry) in file.batches[0].entries.iter_mut().enumerate() {
+            entry.trace_number = format!("09101298{:07}", i + 1).into();
+        }
+        file
+    }
+
+    #[test]
+    fn test_returns_and_unknown_traces() {
+        let sent = fixtures::sample_ppd_file(3);
+        let received = fixtures::sample_returns_file();
+        let mut received = AchFile::parse(&received).unwrap();
+        // The second return refers to an entry we never sent.
+        received.batches[0].entries[1].addenda[0].payment_related_information =
+            received.batches[0].entries[1].addenda[0]
+                .payment_related_information
+                .replacen("091012980000002", "999999990000009", 1)
+                .into();
+
+        let report = Reconciler::new()
+            .add_originated(&originated(&sent))
+            .add_received(&received)
+            .report();
+        assert_eq!(report.traces.len(), 3);
+        assert_eq!(
+            report.status("091012980000001"),
+            Some(&TraceStatus::Returned(ReturnReasonCode::R01))
+        );
+        assert_eq!(
+            report.status("091012980000002"),
+            Some(&TraceStatus::SettledPresumed)
+        );
+        assert_eq!(report.returned().count(), 1);
+        assert_eq!(report.unknown.len(), 1);
+        let unknown = &report.unknown[0];
+        assert_eq!(unknown.original_trace_number, "999999990000009");
+        assert_eq!(unknown.received_file_index, 0);
+        assert_eq!(unknown.status, TraceStatus::Returned(ReturnReasonCode::R03));
+    }
+
+    #[test]
+    fn test_corrections_and_acknowledgments() {
+        let sent = fixtures::sample_ppd_file(3);
+        let text = fixtures::sample_returns_file();
+        // The returns fixture, turned into a COR file of C01 and C02 notices.
+        let mut noc = AchFile::parse(&text).unwrap();
+        noc.batches[0].header.standard_entry_class_code = "COR".into();
+        for (entry, code) in noc.batches[0].entries.iter_mut().zip(["C01", "C02"]) {
+            let addenda = &mut entry.addenda[0];
+            addenda.addenda_type_code = "98".into();
+            addenda.payment_related_information =
+                format!("{code}{}", &addenda.payment_related_information[3..]).into();
+        }
+        // An acknowledgment of the third entry.
+        let ack_text = fixtures::sample_ppd_file(1);
+        let mut ack = AchFile::parse(&ack_text).unwrap();
+        ack.batches[0].header.standard_entry_class_code = "ACK".into();
+        ack.batches[0].entries[0].individual_identification_number = "091012980000003".into();
+
+        let returns = AchFile::parse(&text).unwrap();
+
+        let report = Reconciler::new()
+            .add_originated(&originated(&sent))
+            .add_received(&ack)
+            .add_received(&noc)
+            .add_received(&returns)
+            .report();
+        let statuses: Vec<&TraceStatus> = report.traces.iter().map(|t| &t.status).collect();
+        // Returns outrank the corrections received before them.
+        assert_eq!(
+            statuses,
+            [
+                &TraceStatus::Returned(ReturnReasonCode::R01),
+                &TraceStatus::Returned(ReturnReasonCode::R03),
+                &TraceStatus::Acknowledged,
+            ]
+        );
+        assert!(report.unknown.is_empty());
+
+        let report = Reconciler::new()
+            .add_originated(&originated(&sent))
+            .add_received(&noc)
+            .report();
+        assert_eq!(
+            report.status("091012980000002"),
+            Some(&TraceStatus::Corrected(ChangeCode::C02))
+        );
+    }
+}
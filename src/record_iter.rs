@@ -0,0 +1,436 @@
+//! Record-by-record iteration over ACH content.
+
+use std::iter::{Enumerate, FusedIterator};
+
+use crate::error::{AchError, AchResult};
+use crate::parse_options::{ParseContext, ParseOptions, ProgressTracker};
+use crate::parser::{self, SplitLines};
+use crate::records::*;
+use crate::sec_code::SecCode;
+use crate::AchFile;
+
+/// One record of an ACH file, as yielded by [`Records`].
+///
+/// Entries are yielded with no addenda; their addenda follow as
+/// [`Record::Addenda`] items.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum Record<'a> {
+    /// File header record (record type 1)
+    FileHeader(FileHeader<'a>),
+
+    /// Batch header record (record type 5)
+    BatchHeader(BatchHeader<'a>),
+
+    /// Entry detail record (record type 6)
+    EntryDetail(EntryDetail<'a>),
+
+    /// Addenda record (record type 7)
+    Addenda(Addenda<'a>),
+
+    /// Batch control record (record type 8)
+    BatchControl(BatchControl),
+
+    /// File control record (record type 9)
+    FileControl(FileControl),
+
+    /// Record of unknown type, yielded only when
+    /// [`ParseOptions::keep_unknown_records`] is set
+    Unknown(UnknownRecord<'a>),
+}
+
+/// Where the iterator is in the file's structure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Position {
+    /// Before the file header
+    Start,
+    /// Between batches
+    File,
+    /// After a batch header, before any entry
+    Batch,
+    /// After an entry or one of its addenda
+    Entry,
+    /// After the file control, or after a fatal error
+    Done,
+}
+
+/// An iterator over the records of ACH content, from [`AchFile::records`].
+///
+/// Padding lines are skipped and nothing after the file control is read, as
+/// in [`AchFile::parse`]. Records out of order, such as an entry before any
+/// batch header or a missing file control, end the iteration with an error:
+/// the next call returns `None`.
+///
+/// A record that cannot be parsed also ends the iteration, unless the
+/// iterator is [lossy](Records::lossy). Then the record is yielded as an
+/// `Err` and iteration continues as though it had parsed, so the structure
+/// is still followed by record type. After
+/// [`max_errors`](ParseOptions::max_errors) such errors, the next ends the
+/// iteration as [`AchError::TooManyErrors`].
+///
+/// ```
+/// use rs_ach::fixtures::{self, Corruption};
+/// use rs_ach::{AchFile, Record};
+///
+/// let text = fixtures::corrupt(&fixtures::sample_ppd_file(2), Corruption::BadAmount);
+///
+/// // The first entry's bad amount ends strict iteration...
+/// let strict: Vec<_> = AchFile::records(&text).collect();
+/// assert_eq!(strict.len(), 3);
+/// assert!(strict[2].is_err());
+///
+/// // ...but lossy iteration goes on to the second entry and the controls.
+/// let lossy: Vec<_> = AchFile::records(&text).lossy().collect();
+/// assert_eq!(lossy.len(), 6);
+/// assert!(matches!(lossy[3], Ok(Record::EntryDetail(_))));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Records<'a> {
+    lines: Enumerate<SplitLines<'a>>,
+    position: Position,
+    lossy: bool,
+    options: ParseOptions,
+    errors: usize,
+    batches: usize,
+    entries: usize,
+    parsed: usize,
+    progress: Option<ProgressTracker>,
+    sec_code: Option<SecCode>,
+}
+
+impl<'a> Records<'a> {
+    /// Iterate over the records of `content`.
+    pub fn new(content: &'a str) -> Self {
+        Records::with_options(content, &ParseOptions::default())
+    }
+
+    /// Iterate over the records of `content`, parsing them with `options`.
+    /// Warnings are not reported, but denied warnings are errors. A
+    /// [`ProgressHook`](crate::ProgressHook) is called as records are
+    /// yielded, counting those that fail to parse.
+    pub fn with_options(content: &'a str, options: &ParseOptions) -> Self {
+        Records {
+            lines: parser::split_lines(content).enumerate(),
+            position: Position::Start,
+            lossy: false,
+            options: options.clone(),
+            errors: 0,
+            batches: 0,
+            entries: 0,
+            parsed: 0,
+            progress: ProgressTracker::new(options, content),
+            sec_code: None,
+        }
+    }
+
+    /// Continue past records that cannot be parsed, yielding each as an
+    /// `Err`. Records out of order still end the iteration.
+    pub fn lossy(mut self) -> Self {
+        self.lossy = true;
+        self
+    }
+
+    /// End the iteration with `error`.
+    fn fail(&mut self, error: AchError) -> Option<AchResult<Record<'a>>> {
+        self.position = Position::Done;
+        Some(Err(error))
+    }
+
+    /// Yield `error` and go on, unless it is one too many.
+    fn skip(&mut self, error: AchError) -> Option<AchResult<Record<'a>>> {
+        if self.options.max_errors == Some(self.errors) {
+            return self.fail(AchError::TooManyErrors {
+                reported: self.errors,
+                truncated: true,
+            });
+        }
+        self.errors += 1;
+        Some(Err(error))
+    }
+}
+
+impl<'a> Iterator for Records<'a> {
+    type Item = AchResult<Record<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.position == Position::Done {
+            return None;
+        }
+
+        let Some((idx, line)) = self
+            .lines
+            .by_ref()
+            .find(|(_, line)| !parser::is_filler(line))
+        else {
+            return match self.position {
+                Position::Start => self.fail(AchError::EmptyFile),
+                Position::Batch | Position::Entry => self.fail(AchError::IncompleteBatch(
+                    "Missing batch control record".to_string(),
+                )),
+                _ => self.fail(AchError::InvalidStructure(
+                    "Missing file control record".to_string(),
+                )),
+            };
+        };
+        let number = idx + 1;
+        self.parsed += 1;
+
+        let record_type = line.as_bytes()[0];
+        let mut ctx = ParseContext::new(&self.options);
+        ctx.sec_code = self.sec_code;
+        let code = parser::get_record_type(line).unwrap_or_default();
+        if self.position != Position::Start && parser::keeps_unknown(&ctx, code) {
+            let (preceding, next) = match self.position {
+                Position::File => (self.batches, Position::File),
+                // Any addenda after it are out of place, as in a parse.
+                _ => (self.entries, Position::Batch),
+            };
+            return match parser::parse_unknown(&mut ctx, (number, line), preceding) {
+                Ok(record) => {
+                    self.position = next;
+                    Some(Ok(Record::Unknown(record)))
+                }
+                Err(error) if self.lossy => self.skip(error),
+                Err(error) => self.fail(error),
+            };
+        }
+
+        let next = match (self.position, record_type) {
+            (Position::Start, _) => Position::File,
+            (Position::File, b'5') => Position::Batch,
+            (Position::File, b'9') => Position::Done,
+            (Position::Batch | Position::Entry, b'6') => Position::Entry,
+            (Position::Entry, b'7') => Position::Entry,
+            (Position::Batch | Position::Entry, b'8') => Position::File,
+            (position, b'1' | b'5' | b'6' | b'7' | b'8' | b'9') => {
+                let in_batch = position != Position::File;
+                return self.fail(parser::unexpected_record(&line[..1], number, in_batch));
+            }
+            _ => {
+                let first = line.chars().next().unwrap_or_default();
+                let error = AchError::InvalidRecordType(first.to_string());
+                if !self.lossy {
+                    return self.fail(error);
+                }
+                // Skipped, leaving the structure as it was.
+                return self.skip(error);
+            }
+        };
+
+        let record = match (self.position, record_type) {
+            (Position::Start, _) => {
+                parser::parse_file_header((number, line)).and_then(|mut header| {
+                    ctx.begin_file(&mut header, number)?;
+                    Ok(Record::FileHeader(header))
+                })
+            }
+            (_, b'5') => parser::parse_batch_header((number, line)).and_then(|mut header| {
+                ctx.begin_batch(&mut header, number)?;
+                Ok(Record::BatchHeader(header))
+            }),
+            (_, b'6') => {
+                parser::parse_entry_detail(&mut ctx, (number, line)).map(Record::EntryDetail)
+            }
+            (_, b'7') => parser::parse_addenda((number, line)).map(Record::Addenda),
+            (_, b'8') => {
+                parser::parse_batch_control(&mut ctx, (number, line)).map(Record::BatchControl)
+            }
+            _ => parser::parse_file_control(&mut ctx, (number, line)).map(Record::FileControl),
+        };
+
+        match (self.position, record_type) {
+            (Position::Start, _) => {}
+            (_, b'5') => {
+                self.entries = 0;
+                self.sec_code = ctx.sec_code;
+            }
+            (_, b'6') => self.entries += 1,
+            (_, b'8') => self.batches += 1,
+            _ => {}
+        }
+        if let Some(progress) = &mut self.progress {
+            match next {
+                Position::Done if record.is_ok() => progress.finish(self.parsed, self.batches),
+                _ => progress.advance(line, self.parsed, self.batches),
+            }
+        }
+        match record {
+            Ok(record) => {
+                self.position = next;
+                Some(Ok(record))
+            }
+            // Content that does not start with a file header is not an ACH
+            // file, lossy or not.
+            Err(error)
+                if !self.lossy || (self.position == Position::Start && record_type != b'1') =>
+            {
+                self.fail(error)
+            }
+            Err(error) => {
+                self.position = next;
+                self.skip(error)
+            }
+        }
+    }
+}
+
+impl FusedIterator for Records<'_> {}
+
+impl<'a> AchFile<'a> {
+    /// Iterate over the records of `content` without building an
+    /// [`AchFile`]; see [`Records`].
+    pub fn records(content: &'a str) -> Records<'a> {
+        Records::new(content)
+    }
+
+    /// Like [`records`](AchFile::records), parsing with `options`.
+    pub fn records_with(content: &'a str, options: &ParseOptions) -> Records<'a> {
+        Records::with_options(content, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    fn kinds(records: &[AchResult<Record<'_>>]) -> String {
+        records
+            .iter()
+            .map(|r| match r {
+                Ok(Record::FileHeader(_)) => '1',
+                Ok(Record::BatchHeader(_)) => '5',
+                Ok(Record::EntryDetail(_)) => '6',
+                Ok(Record::Addenda(_)) => '7',
+                Ok(Record::BatchControl(_)) => '8',
+                Ok(Record::FileControl(_)) => '9',
+                Ok(Record::Unknown(_)) => 'U',
+                Err(_) => 'E',
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_records_of_valid_file() {
+        let text = fixtures::sample_ctx_with_addenda();
+        let records: Vec<_> = AchFile::records(&text).collect();
+        assert_eq!(kinds(&records), "15677789");
+        let file = AchFile::parse(&text).unwrap();
+        match &records[2] {
+            Ok(Record::EntryDetail(entry)) => {
+                assert!(entry.addenda.is_empty());
+                assert_eq!(entry.trace_number, file.batches[0].entries[0].trace_number);
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+        assert!(matches!(
+            AchFile::records("").collect::<Vec<_>>()[..],
+            [Err(AchError::EmptyFile)]
+        ));
+    }
+
+    #[test]
+    fn test_error_on_first_line() {
+        let sample = fixtures::sample_ppd_file(1);
+        let text = sample.replacen(&sample[..94], &sample[..90], 1);
+        let mut strict = AchFile::records(&text);
+        let err = strict.next().unwrap().unwrap_err();
+        assert!(matches!(err.root_cause(), AchError::InvalidLineLength(90)));
+        assert!(err
+            .to_string()
+            .starts_with("file header record at line 1: "));
+        assert!(strict.next().is_none());
+        assert!(strict.next().is_none());
+
+        // Lossy iteration takes the short line as the file header.
+        let lossy: Vec<_> = AchFile::records(&text).lossy().collect();
+        assert_eq!(kinds(&lossy), "E5689");
+
+        // Content that does not start with a header ends even lossy
+        // iteration.
+        let headless = sample.split_once('\n').unwrap().1;
+        let lossy: Vec<_> = AchFile::records(headless).lossy().collect();
+        assert_eq!(lossy.len(), 1);
+        let err = lossy[0].as_ref().unwrap_err();
+        assert!(matches!(err.root_cause(), AchError::InvalidRecordType(_)));
+    }
+
+    #[test]
+    fn test_error_inside_addenda_run() {
+        let sample = fixtures::sample_ctx_with_addenda();
+        let mut lines: Vec<String> = sample.lines().map(String::from).collect();
+        lines[3].truncate(93);
+        let text = lines.join("\n");
+
+        let strict: Vec<_> = AchFile::records(&text).collect();
+        assert_eq!(kinds(&strict), "156E");
+        let lossy: Vec<_> = AchFile::records(&text).lossy().collect();
+        assert_eq!(kinds(&lossy), "156E7789");
+
+        // An addenda after the batch control is out of order, lossy or not.
+        lines.swap(3, 6);
+        let text = lines.join("\n");
+        let lossy: Vec<_> = AchFile::records(&text).lossy().collect();
+        assert_eq!(kinds(&lossy), "1568E");
+        assert!(matches!(
+            lossy.last(),
+            Some(Err(AchError::UnexpectedRecord { line: 5, .. }))
+        ));
+    }
+
+    #[test]
+    fn test_unknown_records() {
+        let sample = fixtures::sample_ctx_with_addenda();
+        let mut lines: Vec<String> = sample.lines().map(String::from).collect();
+        lines.insert(6, format!("Q{:<93}", ""));
+        lines.insert(1, format!("Q{:<93}", ""));
+        let text = lines.join("\n");
+        assert_eq!(kinds(&AchFile::records(&text).collect::<Vec<_>>()), "1E");
+
+        let options = ParseOptions {
+            keep_unknown_records: true,
+            ..ParseOptions::default()
+        };
+        let records: Vec<_> = AchFile::records_with(&text, &options).collect();
+        assert_eq!(kinds(&records), "1U56777U89");
+        let (file, _) = AchFile::parse_with(&text, &options).unwrap();
+        for (record, kept) in [
+            (&records[1], &file.unknown_records[0]),
+            (&records[7], &file.batches[0].unknown_records[0]),
+        ] {
+            assert!(matches!(record, Ok(Record::Unknown(r)) if r == kept));
+        }
+    }
+
+    #[test]
+    fn test_lossy_error_limit() {
+        let sample = fixtures::sample_ppd_file(3);
+        let mut lines: Vec<String> = sample.lines().map(String::from).collect();
+        for line in &mut lines[2..5] {
+            line.truncate(93);
+        }
+        let text = lines.join("\n");
+
+        let options = ParseOptions {
+            max_errors: Some(2),
+            ..ParseOptions::default()
+        };
+        let limited: Vec<_> = AchFile::records_with(&text, &options).lossy().collect();
+        assert_eq!(kinds(&limited), "15EEE");
+        assert!(matches!(
+            limited[4],
+            Err(AchError::TooManyErrors {
+                reported: 2,
+                truncated: true
+            })
+        ));
+
+        let unlimited = ParseOptions {
+            max_errors: None,
+            ..ParseOptions::default()
+        };
+        let all: Vec<_> = AchFile::records_with(&text, &unlimited).lossy().collect();
+        assert_eq!(kinds(&all), "15EEE89");
+    }
+}
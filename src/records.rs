@@ -2,139 +2,152 @@
 //!
 //! Each record type represents a specific line in an ACH file.
 //! All ACH records are exactly 94 characters long.
+//!
+//! Text fields are stored as `Cow<'a, str>`: parsed records borrow
+//! from the input, while records built in code own their data.
+
+use std::borrow::Cow;
+
+pub mod layout;
 
 /// File Header Record (Record Type 1)
 ///
 /// The file header record designates physical file characteristics and
 /// identifies the immediate destination and origin of the entries within the file.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct FileHeader<'a> {
     /// Record Type Code (always "1")
-    pub record_type: &'a str,
+    pub record_type: Cow<'a, str>,
 
     /// Priority Code (01-99)
-    pub priority_code: &'a str,
+    pub priority_code: Cow<'a, str>,
 
     /// Immediate Destination (10 characters) - Routing number with leading space
-    pub immediate_destination: &'a str,
+    pub immediate_destination: Cow<'a, str>,
 
     /// Immediate Origin (10 characters) - Company ID with leading space
-    pub immediate_origin: &'a str,
+    pub immediate_origin: Cow<'a, str>,
 
     /// File Creation Date (YYMMDD)
-    pub file_creation_date: &'a str,
+    pub file_creation_date: Cow<'a, str>,
 
     /// File Creation Time (HHMM)
-    pub file_creation_time: &'a str,
+    pub file_creation_time: Cow<'a, str>,
 
     /// File ID Modifier (A-Z, 0-9)
-    pub file_id_modifier: &'a str,
+    pub file_id_modifier: Cow<'a, str>,
 
     /// Record Size (always "094")
-    pub record_size: &'a str,
+    pub record_size: Cow<'a, str>,
 
     /// Blocking Factor (always "10")
-    pub blocking_factor: &'a str,
+    pub blocking_factor: Cow<'a, str>,
 
     /// Format Code (always "1")
-    pub format_code: &'a str,
+    pub format_code: Cow<'a, str>,
 
     /// Immediate Destination Name (23 characters)
-    pub immediate_destination_name: &'a str,
+    pub immediate_destination_name: Cow<'a, str>,
 
     /// Immediate Origin Name (23 characters)
-    pub immediate_origin_name: &'a str,
+    pub immediate_origin_name: Cow<'a, str>,
 
     /// Reference Code (8 characters)
-    pub reference_code: &'a str,
+    pub reference_code: Cow<'a, str>,
 }
 
 /// Batch Header Record (Record Type 5)
 ///
 /// The batch header record identifies the batch and provides summary
 /// information about the entries in the batch.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct BatchHeader<'a> {
     /// Record Type Code (always "5")
-    pub record_type: &'a str,
+    pub record_type: Cow<'a, str>,
 
     /// Service Class Code (200, 220, 225)
     /// - 200: Mixed debits and credits
     /// - 220: Credits only
     /// - 225: Debits only
-    pub service_class_code: &'a str,
+    pub service_class_code: Cow<'a, str>,
 
     /// Company Name (16 characters)
-    pub company_name: &'a str,
+    pub company_name: Cow<'a, str>,
 
     /// Company Discretionary Data (20 characters)
-    pub company_discretionary_data: &'a str,
+    pub company_discretionary_data: Cow<'a, str>,
 
     /// Company Identification (10 characters) - Tax ID
-    pub company_identification: &'a str,
+    pub company_identification: Cow<'a, str>,
 
     /// Standard Entry Class Code (3 characters) - PPD, CCD, WEB, etc.
-    pub standard_entry_class_code: &'a str,
+    pub standard_entry_class_code: Cow<'a, str>,
 
     /// Company Entry Description (10 characters)
-    pub company_entry_description: &'a str,
+    pub company_entry_description: Cow<'a, str>,
 
     /// Company Descriptive Date (6 characters)
-    pub company_descriptive_date: &'a str,
+    pub company_descriptive_date: Cow<'a, str>,
 
     /// Effective Entry Date (YYMMDD)
-    pub effective_entry_date: &'a str,
+    pub effective_entry_date: Cow<'a, str>,
 
     /// Settlement Date (Julian, 3 characters)
-    pub settlement_date: &'a str,
+    pub settlement_date: Cow<'a, str>,
 
     /// Originator Status Code (1 character)
-    pub originator_status_code: &'a str,
+    pub originator_status_code: Cow<'a, str>,
 
     /// Originating DFI Identification (8 characters) - First 8 digits of routing number
-    pub originating_dfi_identification: &'a str,
+    pub originating_dfi_identification: Cow<'a, str>,
 
     /// Batch Number (7 characters)
-    pub batch_number: &'a str,
+    pub batch_number: Cow<'a, str>,
 }
 
 /// Entry Detail Record (Record Type 6)
 ///
 /// Contains the details of individual transactions within a batch.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct EntryDetail<'a> {
     /// Record Type Code (always "6")
-    pub record_type: &'a str,
+    pub record_type: Cow<'a, str>,
 
     /// Transaction Code (22, 23, 27, 28, 32, 33, 37, 38)
-    pub transaction_code: &'a str,
+    pub transaction_code: Cow<'a, str>,
 
     /// Receiving DFI Identification (8 characters) - First 8 digits of routing number
-    pub receiving_dfi_identification: &'a str,
+    pub receiving_dfi_identification: Cow<'a, str>,
 
     /// Check Digit (1 character) - 9th digit of routing number
-    pub check_digit: &'a str,
+    pub check_digit: Cow<'a, str>,
 
     /// DFI Account Number (17 characters)
-    pub dfi_account_number: &'a str,
+    pub dfi_account_number: Cow<'a, str>,
 
     /// Amount (10 characters) - In cents, no decimal
     pub amount: u64,
 
     /// Individual Identification Number (15 characters)
-    pub individual_identification_number: &'a str,
+    pub individual_identification_number: Cow<'a, str>,
 
     /// Individual Name (22 characters)
-    pub individual_name: &'a str,
+    pub individual_name: Cow<'a, str>,
 
     /// Discretionary Data (2 characters)
-    pub discretionary_data: &'a str,
+    pub discretionary_data: Cow<'a, str>,
 
     /// Addenda Record Indicator (0 or 1)
-    pub addenda_record_indicator: &'a str,
+    pub addenda_record_indicator: Cow<'a, str>,
 
     /// Trace Number (15 characters)
-    pub trace_number: &'a str,
+    pub trace_number: Cow<'a, str>,
 
     /// Optional addenda records
     pub addenda: Vec<Addenda<'a>>,
@@ -143,28 +156,57 @@ pub struct EntryDetail<'a> {
 /// Addenda Record (Record Type 7)
 ///
 /// Provides additional information for an entry detail record.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Addenda<'a> {
     /// Record Type Code (always "7")
-    pub record_type: &'a str,
+    pub record_type: Cow<'a, str>,
 
     /// Addenda Type Code (05 for most types)
-    pub addenda_type_code: &'a str,
+    pub addenda_type_code: Cow<'a, str>,
 
     /// Payment Related Information (80 characters)
-    pub payment_related_information: &'a str,
+    pub payment_related_information: Cow<'a, str>,
 
     /// Addenda Sequence Number (4 characters)
-    pub addenda_sequence_number: &'a str,
+    pub addenda_sequence_number: Cow<'a, str>,
 
     /// Entry Detail Sequence Number (7 characters)
-    pub entry_detail_sequence_number: &'a str,
+    pub entry_detail_sequence_number: Cow<'a, str>,
+}
+
+/// A record whose type code is none of NACHA's, kept as it was read when
+/// [`ParseOptions::keep_unknown_records`](crate::ParseOptions::keep_unknown_records)
+/// is set.
+///
+/// Records between batches are kept on the [`AchFile`](crate::AchFile) and
+/// those inside a batch on the [`Batch`](crate::Batch), each with its place
+/// among the other records, and the writer puts them back there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct UnknownRecord<'a> {
+    /// Record Type Code (the first character)
+    pub type_code: Cow<'a, str>,
+
+    /// The whole record, exactly as read
+    pub raw_line: Cow<'a, str>,
+
+    /// Line of the record in the file, counting from 1
+    pub line_number: usize,
+
+    /// Batches before the record, for one kept on the file, or entries
+    /// before it, for one kept on a batch
+    pub preceding: usize,
 }
 
 /// Batch Control Record (Record Type 8)
 ///
 /// Contains totals and counts for the entries in the batch.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct BatchControl {
     /// Record Type Code (always "8")
     pub record_type: String,
@@ -203,7 +245,9 @@ pub struct BatchControl {
 /// File Control Record (Record Type 9)
 ///
 /// Contains totals and counts for the entire file.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct FileControl {
     /// Record Type Code (always "9")
     pub record_type: String,
@@ -229,3 +273,94 @@ pub struct FileControl {
     /// Reserved (39 characters)
     pub reserved: String,
 }
+
+fn owned(value: Cow<'_, str>) -> Cow<'static, str> {
+    Cow::Owned(value.into_owned())
+}
+
+impl FileHeader<'_> {
+    /// Copy any borrowed fields so the record no longer borrows the input.
+    pub fn into_owned(self) -> FileHeader<'static> {
+        FileHeader {
+            record_type: owned(self.record_type),
+            priority_code: owned(self.priority_code),
+            immediate_destination: owned(self.immediate_destination),
+            immediate_origin: owned(self.immediate_origin),
+            file_creation_date: owned(self.file_creation_date),
+            file_creation_time: owned(self.file_creation_time),
+            file_id_modifier: owned(self.file_id_modifier),
+            record_size: owned(self.record_size),
+            blocking_factor: owned(self.blocking_factor),
+            format_code: owned(self.format_code),
+            immediate_destination_name: owned(self.immediate_destination_name),
+            immediate_origin_name: owned(self.immediate_origin_name),
+            reference_code: owned(self.reference_code),
+        }
+    }
+}
+
+impl BatchHeader<'_> {
+    /// Copy any borrowed fields so the record no longer borrows the input.
+    pub fn into_owned(self) -> BatchHeader<'static> {
+        BatchHeader {
+            record_type: owned(self.record_type),
+            service_class_code: owned(self.service_class_code),
+            company_name: owned(self.company_name),
+            company_discretionary_data: owned(self.company_discretionary_data),
+            company_identification: owned(self.company_identification),
+            standard_entry_class_code: owned(self.standard_entry_class_code),
+            company_entry_description: owned(self.company_entry_description),
+            company_descriptive_date: owned(self.company_descriptive_date),
+            effective_entry_date: owned(self.effective_entry_date),
+            settlement_date: owned(self.settlement_date),
+            originator_status_code: owned(self.originator_status_code),
+            originating_dfi_identification: owned(self.originating_dfi_identification),
+            batch_number: owned(self.batch_number),
+        }
+    }
+}
+
+impl EntryDetail<'_> {
+    /// Copy any borrowed fields so the record no longer borrows the input.
+    pub fn into_owned(self) -> EntryDetail<'static> {
+        EntryDetail {
+            record_type: owned(self.record_type),
+            transaction_code: owned(self.transaction_code),
+            receiving_dfi_identification: owned(self.receiving_dfi_identification),
+            check_digit: owned(self.check_digit),
+            dfi_account_number: owned(self.dfi_account_number),
+            amount: self.amount,
+            individual_identification_number: owned(self.individual_identification_number),
+            individual_name: owned(self.individual_name),
+            discretionary_data: owned(self.discretionary_data),
+            addenda_record_indicator: owned(self.addenda_record_indicator),
+            trace_number: owned(self.trace_number),
+            addenda: self.addenda.into_iter().map(Addenda::into_owned).collect(),
+        }
+    }
+}
+
+impl Addenda<'_> {
+    /// Copy any borrowed fields so the record no longer borrows the input.
+    pub fn into_owned(self) -> Addenda<'static> {
+        Addenda {
+            record_type: owned(self.record_type),
+            addenda_type_code: owned(self.addenda_type_code),
+            payment_related_information: owned(self.payment_related_information),
+            addenda_sequence_number: owned(self.addenda_sequence_number),
+            entry_detail_sequence_number: owned(self.entry_detail_sequence_number),
+        }
+    }
+}
+
+impl UnknownRecord<'_> {
+    /// Copy any borrowed fields so the record no longer borrows the input.
+    pub fn into_owned(self) -> UnknownRecord<'static> {
+        UnknownRecord {
+            type_code: owned(self.type_code),
+            raw_line: owned(self.raw_line),
+            line_number: self.line_number,
+            preceding: self.preceding,
+        }
+    }
+}
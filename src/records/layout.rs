@@ -0,0 +1,227 @@
+//! Field layout of each record type: the one definition of where every
+//! field sits, used by the parser, the writer, and `fields()`.
+//!
+//! Each table lists a record's fields in position order, covering all 94
+//! positions without gaps.
+//!
+//! ```
+//! use rs_ach::layout::{self, FieldClass};
+//!
+//! let amount = &layout::ENTRY_DETAIL[5];
+//! assert_eq!((amount.name, amount.start, amount.end), ("amount", 30, 39));
+//! assert_eq!(amount.class, FieldClass::Numeric);
+//! assert_eq!(amount.range(), 29..39);
+//! ```
+
+use std::ops::Range;
+
+/// NACHA inclusion requirement of a field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InclusionRequirement {
+    /// Must contain valid data or the file is rejected (M).
+    Mandatory,
+
+    /// Must be present, but an invalid value doesn't reject the file (R).
+    Required,
+
+    /// May be left blank (O). Also used for fields inserted by the ACH operator.
+    Optional,
+
+    /// Reserved for future use; must be blank.
+    Reserved,
+}
+
+/// NACHA character class of a field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FieldClass {
+    /// Digits only, right-justified and zero-filled (N).
+    Numeric,
+
+    /// Letters, digits, spaces, and special characters, left-justified and
+    /// space-filled (A).
+    Alphameric,
+}
+
+/// Where one field sits in a record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FieldLayout {
+    /// Field identifier, matching the struct field name (e.g. `individual_name`)
+    pub name: &'static str,
+
+    /// NACHA field name (e.g. `Individual Name`)
+    pub label: &'static str,
+
+    /// First position of the field, 1-based
+    pub start: usize,
+
+    /// Last position of the field, 1-based and inclusive
+    pub end: usize,
+
+    /// Character class
+    pub class: FieldClass,
+
+    /// Inclusion requirement
+    pub inclusion: InclusionRequirement,
+}
+
+impl FieldLayout {
+    /// Byte offsets of the field within a record, counting from 0.
+    pub const fn range(&self) -> Range<usize> {
+        self.start - 1..self.end
+    }
+
+    /// Field width in characters.
+    pub const fn width(&self) -> usize {
+        self.end - self.start + 1
+    }
+}
+
+/// The layout of the record with this record type code, e.g. `"6"`.
+pub fn for_record_type(record_type: &str) -> Option<&'static [FieldLayout]> {
+    Some(match record_type {
+        "1" => &FILE_HEADER,
+        "5" => &BATCH_HEADER,
+        "6" => &ENTRY_DETAIL,
+        "7" => &ADDENDA,
+        "8" => &BATCH_CONTROL,
+        "9" => &FILE_CONTROL,
+        _ => return None,
+    })
+}
+
+use FieldClass::*;
+use InclusionRequirement::*;
+
+const fn f(
+    name: &'static str,
+    label: &'static str,
+    start: usize,
+    end: usize,
+    class: FieldClass,
+    inclusion: InclusionRequirement,
+) -> FieldLayout {
+    FieldLayout {
+        name,
+        label,
+        start,
+        end,
+        class,
+        inclusion,
+    }
+}
+
+/// File header record (type 1).
+#[rustfmt::skip]
+pub const FILE_HEADER: [FieldLayout; 13] = [
+    f("record_type", "Record Type Code", 1, 1, Numeric, Mandatory),
+    f("priority_code", "Priority Code", 2, 3, Numeric, Required),
+    f("immediate_destination", "Immediate Destination", 4, 13, Alphameric, Mandatory),
+    f("immediate_origin", "Immediate Origin", 14, 23, Alphameric, Mandatory),
+    f("file_creation_date", "File Creation Date", 24, 29, Numeric, Mandatory),
+    f("file_creation_time", "File Creation Time", 30, 33, Numeric, Optional),
+    f("file_id_modifier", "File ID Modifier", 34, 34, Alphameric, Mandatory),
+    f("record_size", "Record Size", 35, 37, Numeric, Mandatory),
+    f("blocking_factor", "Blocking Factor", 38, 39, Numeric, Mandatory),
+    f("format_code", "Format Code", 40, 40, Numeric, Mandatory),
+    f("immediate_destination_name", "Immediate Destination Name", 41, 63, Alphameric, Optional),
+    f("immediate_origin_name", "Immediate Origin Name", 64, 86, Alphameric, Optional),
+    f("reference_code", "Reference Code", 87, 94, Alphameric, Optional),
+];
+
+/// Batch header record (type 5).
+#[rustfmt::skip]
+pub const BATCH_HEADER: [FieldLayout; 13] = [
+    f("record_type", "Record Type Code", 1, 1, Numeric, Mandatory),
+    f("service_class_code", "Service Class Code", 2, 4, Numeric, Mandatory),
+    f("company_name", "Company Name", 5, 20, Alphameric, Mandatory),
+    f("company_discretionary_data", "Company Discretionary Data", 21, 40, Alphameric, Optional),
+    f("company_identification", "Company Identification", 41, 50, Alphameric, Mandatory),
+    f("standard_entry_class_code", "Standard Entry Class Code", 51, 53, Alphameric, Mandatory),
+    f("company_entry_description", "Company Entry Description", 54, 63, Alphameric, Mandatory),
+    f("company_descriptive_date", "Company Descriptive Date", 64, 69, Alphameric, Optional),
+    f("effective_entry_date", "Effective Entry Date", 70, 75, Numeric, Required),
+    f("settlement_date", "Settlement Date (Julian)", 76, 78, Numeric, Optional),
+    f("originator_status_code", "Originator Status Code", 79, 79, Alphameric, Mandatory),
+    f("originating_dfi_identification", "Originating DFI Identification", 80, 87, Numeric, Mandatory),
+    f("batch_number", "Batch Number", 88, 94, Numeric, Mandatory),
+];
+
+/// Entry detail record (type 6).
+#[rustfmt::skip]
+pub const ENTRY_DETAIL: [FieldLayout; 11] = [
+    f("record_type", "Record Type Code", 1, 1, Numeric, Mandatory),
+    f("transaction_code", "Transaction Code", 2, 3, Numeric, Mandatory),
+    f("receiving_dfi_identification", "Receiving DFI Identification", 4, 11, Numeric, Mandatory),
+    f("check_digit", "Check Digit", 12, 12, Numeric, Mandatory),
+    f("dfi_account_number", "DFI Account Number", 13, 29, Alphameric, Required),
+    f("amount", "Amount", 30, 39, Numeric, Mandatory),
+    f("individual_identification_number", "Individual Identification Number", 40, 54, Alphameric, Optional),
+    f("individual_name", "Individual Name", 55, 76, Alphameric, Required),
+    f("discretionary_data", "Discretionary Data", 77, 78, Alphameric, Optional),
+    f("addenda_record_indicator", "Addenda Record Indicator", 79, 79, Numeric, Mandatory),
+    f("trace_number", "Trace Number", 80, 94, Numeric, Mandatory),
+];
+
+/// Addenda record (type 7).
+#[rustfmt::skip]
+pub const ADDENDA: [FieldLayout; 5] = [
+    f("record_type", "Record Type Code", 1, 1, Numeric, Mandatory),
+    f("addenda_type_code", "Addenda Type Code", 2, 3, Numeric, Mandatory),
+    f("payment_related_information", "Payment Related Information", 4, 83, Alphameric, Optional),
+    f("addenda_sequence_number", "Addenda Sequence Number", 84, 87, Numeric, Mandatory),
+    f("entry_detail_sequence_number", "Entry Detail Sequence Number", 88, 94, Numeric, Mandatory),
+];
+
+/// Batch control record (type 8).
+#[rustfmt::skip]
+pub const BATCH_CONTROL: [FieldLayout; 11] = [
+    f("record_type", "Record Type Code", 1, 1, Numeric, Mandatory),
+    f("service_class_code", "Service Class Code", 2, 4, Numeric, Mandatory),
+    f("entry_addenda_count", "Entry/Addenda Count", 5, 10, Numeric, Mandatory),
+    f("entry_hash", "Entry Hash", 11, 20, Numeric, Mandatory),
+    f("total_debit_amount", "Total Debit Entry Dollar Amount", 21, 32, Numeric, Mandatory),
+    f("total_credit_amount", "Total Credit Entry Dollar Amount", 33, 44, Numeric, Mandatory),
+    f("company_identification", "Company Identification", 45, 54, Alphameric, Required),
+    f("message_authentication_code", "Message Authentication Code", 55, 73, Alphameric, Optional),
+    f("reserved", "Reserved", 74, 79, Alphameric, Reserved),
+    f("originating_dfi_identification", "Originating DFI Identification", 80, 87, Numeric, Mandatory),
+    f("batch_number", "Batch Number", 88, 94, Numeric, Mandatory),
+];
+
+/// File control record (type 9).
+#[rustfmt::skip]
+pub const FILE_CONTROL: [FieldLayout; 8] = [
+    f("record_type", "Record Type Code", 1, 1, Numeric, Mandatory),
+    f("batch_count", "Batch Count", 2, 7, Numeric, Mandatory),
+    f("block_count", "Block Count", 8, 13, Numeric, Mandatory),
+    f("entry_addenda_count", "Entry/Addenda Count", 14, 21, Numeric, Mandatory),
+    f("entry_hash", "Entry Hash", 22, 31, Numeric, Mandatory),
+    f("total_debit_amount", "Total Debit Entry Dollar Amount in File", 32, 43, Numeric, Mandatory),
+    f("total_credit_amount", "Total Credit Entry Dollar Amount in File", 44, 55, Numeric, Mandatory),
+    f("reserved", "Reserved", 56, 94, Alphameric, Reserved),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layouts_cover_each_record() {
+        for code in ["1", "5", "6", "7", "8", "9"] {
+            let layout = for_record_type(code).unwrap();
+            let mut next = 1;
+            for field in layout {
+                assert_eq!(
+                    field.start, next,
+                    "gap before {} in type {code}",
+                    field.name
+                );
+                assert!(field.end >= field.start);
+                next = field.end + 1;
+            }
+            assert_eq!(next, 95, "type {code}");
+            assert_eq!(layout[0].name, "record_type");
+        }
+        assert_eq!(for_record_type("4"), None);
+    }
+}
@@ -0,0 +1,254 @@
+//! Remittance information pulled from entry addenda.
+
+use crate::records::{Addenda, EntryDetail};
+use crate::AchFile;
+
+/// An invoice or other reference found in X12 remittance data: an `RMR`
+/// or `REF` segment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct RemittanceReference {
+    /// Segment the reference came from, `RMR` or `REF`
+    pub segment: String,
+
+    /// Reference qualifier, such as `IV` for an invoice or `PO` for a
+    /// purchase order
+    pub qualifier: String,
+
+    /// The reference itself, such as the invoice number
+    pub reference: String,
+
+    /// Amount paid against the reference (`RMR04`), as it appears
+    pub amount: Option<String>,
+}
+
+/// One entry's remittance information, from [`AchFile::remittance_report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct RemittanceRecord {
+    /// Company identification of the payer's batch, trimmed
+    pub company_identification: String,
+
+    /// Company name of the payer's batch, trimmed
+    pub company_name: String,
+
+    /// Standard entry class code of the batch
+    pub standard_entry_class_code: String,
+
+    /// Trace number of the entry
+    pub trace_number: String,
+
+    /// Amount in cents
+    pub amount: u64,
+
+    /// Payment related information of the entry's addenda in sequence
+    /// number order, concatenated, with trailing padding removed
+    pub text: String,
+
+    /// References read from the text as X12 segments, for CTX and CCD
+    /// entries; empty otherwise
+    pub references: Vec<RemittanceReference>,
+}
+
+/// Options for [`AchFile::remittance_report_with`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct RemittanceOptions {
+    /// Include entries without addenda, with empty text
+    pub include_entries_without_addenda: bool,
+}
+
+impl AchFile<'_> {
+    /// The remittance information of every entry with addenda, in file
+    /// order.
+    pub fn remittance_report(&self) -> Vec<RemittanceRecord> {
+        self.remittance_report_with(&RemittanceOptions::default())
+    }
+
+    /// Like [`remittance_report`](AchFile::remittance_report), with the
+    /// given options.
+    ///
+    /// Return and Notification of Change addenda (types 99 and 98) are not
+    /// remittance information and are left out.
+    pub fn remittance_report_with(&self, options: &RemittanceOptions) -> Vec<RemittanceRecord> {
+        let mut records = Vec::new();
+        for batch in &self.batches {
+            let header = &batch.header;
+            let sec = header.standard_entry_class_code.trim();
+            for entry in &batch.entries {
+                let has_addenda = entry.addenda.iter().any(is_remittance);
+                if !has_addenda && !options.include_entries_without_addenda {
+                    continue;
+                }
+                let text = entry.payment_related_data();
+
+                records.push(RemittanceRecord {
+                    company_identification: header.company_identification.trim().to_string(),
+                    company_name: header.company_name.trim().to_string(),
+                    standard_entry_class_code: sec.to_string(),
+                    trace_number: entry.trace_number.to_string(),
+                    amount: entry.amount,
+                    references: if sec == "CTX" || sec == "CCD" {
+                        x12_references(&text)
+                    } else {
+                        Vec::new()
+                    },
+                    text,
+                });
+            }
+        }
+        records
+    }
+}
+
+impl EntryDetail<'_> {
+    /// The payment related information of the entry's addenda in sequence
+    /// number order, concatenated, with trailing padding removed: for a CTX
+    /// entry, its whole X12 payload. Return and Notification of Change
+    /// addenda (types 99 and 98) are left out.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile};
+    ///
+    /// let text = fixtures::sample_ctx_with_addenda();
+    /// let file = AchFile::parse(&text).unwrap();
+    /// let payload = file.batches[0].entries[0].payment_related_data();
+    /// assert!(payload.starts_with("ISA*00*"));
+    /// assert!(payload.ends_with("SE*5*0001~"));
+    /// ```
+    pub fn payment_related_data(&self) -> String {
+        let mut addenda: Vec<&Addenda<'_>> =
+            self.addenda.iter().filter(|a| is_remittance(a)).collect();
+        // Stable, so addenda with unreadable numbers keep file order.
+        addenda.sort_by_key(|a| {
+            a.addenda_sequence_number
+                .trim()
+                .parse::<u32>()
+                .unwrap_or(u32::MAX)
+        });
+        let text: String = addenda
+            .iter()
+            .map(|a| a.payment_related_information.as_ref())
+            .collect();
+        text.trim_end().to_string()
+    }
+}
+
+/// Whether an addenda may carry remittance information: it is neither a
+/// return nor a Notification of Change addenda.
+fn is_remittance(addenda: &Addenda<'_>) -> bool {
+    addenda.addenda_type_code != "98" && addenda.addenda_type_code != "99"
+}
+
+/// The `RMR` and `REF` segments of X12 data, whose segments end in `~` or
+/// `\` and whose elements are separated by `*`.
+fn x12_references(text: &str) -> Vec<RemittanceReference> {
+    text.split(['~', '\\'])
+        .filter_map(|segment| {
+            let elements: Vec<&str> = segment.trim().split('*').map(str::trim).collect();
+            let (&id, rest) = elements.split_first()?;
+            if id != "RMR" && id != "REF" {
+                return None;
+            }
+            let element = |i: usize| rest.get(i).copied().filter(|e| !e.is_empty());
+            Some(RemittanceReference {
+                segment: id.to_string(),
+                qualifier: element(0)?.to_string(),
+                reference: element(1)?.to_string(),
+                amount: if id == "RMR" {
+                    element(3).map(str::to_string)
+                } else {
+                    None
+                },
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_ctx_remittance() {
+        let text = fixtures::sample_ctx_with_addenda();
+        let mut file = AchFile::parse(&text).unwrap();
+        // Sequence numbers, not file order, decide the text order.
+        file.batches[0].entries[0].addenda.swap(0, 2);
+
+        let records = file.remittance_report();
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record.standard_entry_class_code, "CTX");
+        assert_eq!(record.amount, 125_000);
+        assert!(record.text.starts_with("ISA*00*"));
+        assert!(record.text.ends_with("SE*5*0001~"));
+        assert_eq!(
+            record.references,
+            [RemittanceReference {
+                segment: "RMR".to_string(),
+                qualifier: "IV".to_string(),
+                reference: "INV-1001".to_string(),
+                amount: Some("1250.00".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_payment_related_data() {
+        let text = fixtures::sample_ctx_with_addenda();
+        let mut file = AchFile::parse(&text).unwrap();
+        let entry = &mut file.batches[0].entries[0];
+        let payload = entry.payment_related_data();
+        entry.addenda.reverse();
+        assert_eq!(entry.payment_related_data(), payload);
+        assert_eq!(
+            payload.len(),
+            80 * 2 + "RMR*IV*INV-1001**1250.00~SE*5*0001~".len()
+        );
+
+        let returns = fixtures::sample_returns_file();
+        let returns = AchFile::parse(&returns).unwrap();
+        assert_eq!(returns.batches[0].entries[0].payment_related_data(), "");
+    }
+
+    #[test]
+    fn test_entries_without_addenda() {
+        let text = fixtures::sample_ppd_file(2);
+        let file = AchFile::parse(&text).unwrap();
+        assert_eq!(file.remittance_report(), []);
+
+        let options = RemittanceOptions {
+            include_entries_without_addenda: true,
+        };
+        let records = file.remittance_report_with(&options);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].company_identification, "1234567890");
+        assert_eq!(records[1].text, "");
+    }
+
+    #[test]
+    fn test_ccd_plus_references() {
+        assert_eq!(
+            x12_references("RMR*PO*4500012**99.50\\REF*IV*A-17\\"),
+            [
+                RemittanceReference {
+                    segment: "RMR".to_string(),
+                    qualifier: "PO".to_string(),
+                    reference: "4500012".to_string(),
+                    amount: Some("99.50".to_string()),
+                },
+                RemittanceReference {
+                    segment: "REF".to_string(),
+                    qualifier: "IV".to_string(),
+                    reference: "A-17".to_string(),
+                    amount: None,
+                },
+            ]
+        );
+    }
+}
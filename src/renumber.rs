@@ -0,0 +1,198 @@
+//! Reassigning batch numbers and trace numbers.
+
+use std::borrow::Cow;
+
+use crate::AchFile;
+
+/// How [`AchFile::renumber`] reassigns trace numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum TraceSequence {
+    /// Keep every trace number as is.
+    Keep,
+
+    /// Number each batch's entries from 1, as [`BatchBuilder`] does.
+    ///
+    /// [`BatchBuilder`]: crate::BatchBuilder
+    #[default]
+    PerBatch,
+
+    /// Number the entries from 1 across the whole file, so no two share a
+    /// trace number even if their batches have the same ODFI.
+    PerFile,
+}
+
+/// Options for [`AchFile::renumber`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct RenumberOptions {
+    /// Number of the first batch; the rest follow in order
+    pub first_batch_number: u64,
+
+    /// How trace numbers are reassigned
+    pub traces: TraceSequence,
+
+    /// Return the old and new trace number of every entry
+    pub map_traces: bool,
+}
+
+impl Default for RenumberOptions {
+    fn default() -> Self {
+        RenumberOptions {
+            first_batch_number: 1,
+            traces: TraceSequence::PerBatch,
+            map_traces: false,
+        }
+    }
+}
+
+/// An entry's trace number before and after [`AchFile::renumber`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct TraceChange {
+    /// Index of the entry's batch, counting from 0
+    pub batch_index: usize,
+
+    /// Index of the entry in its batch, counting from 0
+    pub entry_index: usize,
+
+    /// Trace number before renumbering
+    pub old_trace: String,
+
+    /// Trace number after renumbering
+    pub new_trace: String,
+}
+
+impl AchFile<'_> {
+    /// Number the batches in order from
+    /// [`first_batch_number`](RenumberOptions::first_batch_number) and give
+    /// each entry a trace number of its batch's ODFI followed by a 7-digit
+    /// sequence number. Each addenda's entry detail sequence number is set
+    /// to the sequence number of its entry.
+    ///
+    /// Neither number enters the controls, which stay valid. With
+    /// [`map_traces`](RenumberOptions::map_traces) set, the old and new trace
+    /// of every entry are returned in file order, for records kept under the
+    /// original traces; otherwise the result is empty.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile, RenumberOptions, TraceSequence};
+    ///
+    /// let text = fixtures::sample_multi_company_file();
+    /// let mut file = AchFile::parse(&text).unwrap();
+    /// let options = RenumberOptions {
+    ///     first_batch_number: 41,
+    ///     traces: TraceSequence::PerFile,
+    ///     map_traces: true,
+    /// };
+    /// let changes = file.renumber(&options);
+    /// assert_eq!(file.batches[2].header.batch_number, "0000043");
+    /// assert_eq!(changes[2].old_trace, "123456780000001");
+    /// assert_eq!(changes[2].new_trace, "123456780000003");
+    /// assert!(file.validate().is_valid());
+    /// ```
+    pub fn renumber(&mut self, options: &RenumberOptions) -> Vec<TraceChange> {
+        let mut changes = Vec::new();
+        let mut sequence = 0;
+        for (batch_index, batch) in self.batches.iter_mut().enumerate() {
+            let batch_number = format!("{:07}", options.first_batch_number + batch_index as u64);
+            batch.header.batch_number = Cow::Owned(batch_number.clone());
+            batch.control.batch_number = batch_number;
+
+            if options.traces == TraceSequence::Keep {
+                continue;
+            }
+            if options.traces == TraceSequence::PerBatch {
+                sequence = 0;
+            }
+            let odfi = batch.header.originating_dfi_identification.to_string();
+            for (entry_index, entry) in batch.entries.iter_mut().enumerate() {
+                sequence += 1;
+                let entry_sequence = format!("{sequence:07}");
+                let new_trace = format!("{odfi}{entry_sequence}");
+                for addenda in &mut entry.addenda {
+                    addenda.entry_detail_sequence_number = Cow::Owned(entry_sequence.clone());
+                }
+                let old_trace = std::mem::replace(&mut entry.trace_number, Cow::Owned(new_trace));
+                if options.map_traces {
+                    changes.push(TraceChange {
+                        batch_index,
+                        entry_index,
+                        old_trace: old_trace.into_owned(),
+                        new_trace: entry.trace_number.to_string(),
+                    });
+                }
+            }
+        }
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_renumber_per_batch() {
+        let text = fixtures::sample_ctx_with_addenda();
+        let mut file = AchFile::parse(&text).unwrap();
+        file.batches[0].entries[0].trace_number = "876543210000042".into();
+        for addenda in &mut file.batches[0].entries[0].addenda {
+            addenda.entry_detail_sequence_number = "0000042".into();
+        }
+
+        let changes = file.renumber(&RenumberOptions::default());
+        assert!(changes.is_empty());
+        let entry = &file.batches[0].entries[0];
+        assert_eq!(entry.trace_number, "123456780000001");
+        assert!(entry
+            .addenda
+            .iter()
+            .all(|a| a.entry_detail_sequence_number == "0000001"));
+        assert_eq!(file.batches[0].control.batch_number, "0000001");
+        assert!(file.validate().is_valid());
+    }
+
+    #[test]
+    fn test_renumber_per_file() {
+        let text = fixtures::sample_multi_company_file();
+        let mut file = AchFile::parse(&text).unwrap();
+        let before = file.clone();
+        let options = RenumberOptions {
+            traces: TraceSequence::PerFile,
+            map_traces: true,
+            ..RenumberOptions::default()
+        };
+        let changes = file.renumber(&options);
+        assert_eq!(changes.len(), 7);
+        let traces: Vec<&str> = changes.iter().map(|c| c.new_trace.as_str()).collect();
+        assert_eq!(traces[6], "123456780000007");
+        for change in &changes {
+            let original = &before.batches[change.batch_index].entries[change.entry_index];
+            assert_eq!(change.old_trace, original.trace_number);
+            let entry = &file.batches[change.batch_index].entries[change.entry_index];
+            assert_eq!(change.new_trace, entry.trace_number);
+        }
+        assert_eq!(file.file_control, before.file_control);
+    }
+
+    #[test]
+    fn test_renumber_keep_traces() {
+        let text = fixtures::sample_multi_company_file();
+        let mut file = AchFile::parse(&text).unwrap();
+        let before = file.clone();
+        let options = RenumberOptions {
+            first_batch_number: 9,
+            traces: TraceSequence::Keep,
+            map_traces: true,
+        };
+        assert!(file.renumber(&options).is_empty());
+        assert_eq!(file.batches[1].header.batch_number, "0000010");
+        assert_eq!(file.batches[1].control.batch_number, "0000010");
+        assert_eq!(file.batches[1].entries, before.batches[1].entries);
+    }
+}
@@ -0,0 +1,309 @@
+//! Plain-text report rendering.
+
+use std::fmt::Write;
+
+use crate::mask::{MaskingPolicy, NameMasking};
+use crate::records::{BatchControl, EntryDetail, FileControl};
+use crate::routing::RoutingDirectory;
+use crate::summary::format_cents;
+use crate::transaction_code::format_breakdown;
+use crate::{AchFile, Batch};
+
+impl AchFile<'_> {
+    /// Render the file as an aligned plain-text report for humans.
+    ///
+    /// The report opens with the [`FileSummary`](crate::FileSummary), then
+    /// lists each batch with a table of its entries (account numbers masked
+    /// to their last four characters), and closes every batch and the file
+    /// with computed versus declared control totals. Mismatched totals are
+    /// flagged with `MISMATCH`.
+    pub fn render_report(&self) -> String {
+        self.render_report_with(&MaskingPolicy {
+            name: NameMasking::Unmasked,
+            ..MaskingPolicy::default()
+        })
+    }
+
+    /// Like [`render_report`](AchFile::render_report), with account numbers
+    /// and names in the entry tables masked by `policy`.
+    pub fn render_report_with(&self, policy: &MaskingPolicy) -> String {
+        self.render(policy, None)
+    }
+
+    /// Like [`render_report_with`](AchFile::render_report_with), with an
+    /// `INSTITUTION` column naming each receiving institution found in
+    /// `directory`.
+    pub fn render_report_with_directory(
+        &self,
+        policy: &MaskingPolicy,
+        directory: &dyn RoutingDirectory,
+    ) -> String {
+        self.render(policy, Some(directory))
+    }
+
+    fn render(&self, policy: &MaskingPolicy, directory: Option<&dyn RoutingDirectory>) -> String {
+        let mut out = String::new();
+        out.push_str("ACH FILE\n");
+        for line in self.summary().to_string().lines() {
+            let _ = writeln!(out, "  {line}");
+        }
+
+        for batch in &self.batches {
+            out.push('\n');
+            render_batch(&mut out, batch, policy, directory);
+        }
+
+        let mut computed = self.clone();
+        for batch in &mut computed.batches {
+            batch.recompute_control();
+        }
+        computed.recompute_file_control();
+
+        out.push_str("\nFILE CONTROL\n");
+        render_totals(
+            &mut out,
+            &file_totals(&computed.file_control),
+            &file_totals(&self.file_control),
+        );
+        out
+    }
+}
+
+fn render_batch(
+    out: &mut String,
+    batch: &Batch<'_>,
+    policy: &MaskingPolicy,
+    directory: Option<&dyn RoutingDirectory>,
+) {
+    let header = &batch.header;
+    let _ = writeln!(
+        out,
+        "BATCH {}  {} ({})  SEC {}  EFFECTIVE {}  SERVICE CLASS {}",
+        header.batch_number,
+        header.company_name.trim(),
+        header.company_identification.trim(),
+        header.standard_entry_class_code,
+        header.effective_entry_date,
+        header.service_class_code,
+    );
+
+    let rows: Vec<Vec<String>> = batch
+        .entries
+        .iter()
+        .map(|e| entry_row(e, policy, directory))
+        .collect();
+    let mut headings = vec![
+        "TRACE", "TC", "ROUTING", "ACCOUNT", "AMOUNT", "NAME", "ADDENDA",
+    ];
+    if directory.is_some() {
+        headings.push("INSTITUTION");
+    }
+    let widths: Vec<usize> = (0..headings.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain([headings[i].len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let render_row = |out: &mut String, cells: &[&str]| {
+        let mut line = String::from(" ");
+        for (i, cell) in cells.iter().enumerate() {
+            let width = widths[i];
+            // Amounts and counts are right-aligned.
+            if i == 4 || i == 6 {
+                let _ = write!(line, " {cell:>width$}");
+            } else {
+                let _ = write!(line, " {cell:<width$}");
+            }
+        }
+        let _ = writeln!(out, "{}", line.trim_end());
+    };
+
+    render_row(out, &headings);
+    for row in &rows {
+        let cells: Vec<&str> = row.iter().map(String::as_str).collect();
+        render_row(out, &cells);
+    }
+
+    let breakdown = batch.breakdown_by_transaction_code();
+    if !breakdown.is_empty() {
+        let _ = writeln!(out, "  By code: {}", format_breakdown(&breakdown));
+    }
+
+    let mut computed = batch.clone();
+    computed.recompute_control();
+    render_totals(
+        out,
+        &batch_totals(&computed.control),
+        &batch_totals(&batch.control),
+    );
+}
+
+fn entry_row(
+    entry: &EntryDetail<'_>,
+    policy: &MaskingPolicy,
+    directory: Option<&dyn RoutingDirectory>,
+) -> Vec<String> {
+    let routing = format!(
+        "{}{}",
+        entry.receiving_dfi_identification, entry.check_digit
+    );
+    let mut row = vec![
+        entry.trace_number.to_string(),
+        entry.transaction_code.to_string(),
+        routing.clone(),
+        policy.account(entry.dfi_account_number.trim()),
+        format_cents(entry.amount),
+        policy.name(&entry.individual_name),
+        entry.addenda.len().to_string(),
+    ];
+    if let Some(directory) = directory {
+        row.push(
+            directory
+                .lookup(&routing)
+                .map_or_else(String::new, |i| i.name),
+        );
+    }
+    row
+}
+
+fn batch_totals(control: &BatchControl) -> Vec<(&'static str, String)> {
+    vec![
+        (
+            "Entry/addenda count",
+            control.entry_addenda_count.to_string(),
+        ),
+        ("Entry hash", format!("{:010}", control.entry_hash)),
+        ("Total debits", format_cents(control.total_debit_amount)),
+        ("Total credits", format_cents(control.total_credit_amount)),
+    ]
+}
+
+fn file_totals(control: &FileControl) -> Vec<(&'static str, String)> {
+    vec![
+        ("Batch count", control.batch_count.to_string()),
+        (
+            "Entry/addenda count",
+            control.entry_addenda_count.to_string(),
+        ),
+        ("Entry hash", format!("{:010}", control.entry_hash)),
+        ("Total debits", format_cents(control.total_debit_amount)),
+        ("Total credits", format_cents(control.total_credit_amount)),
+    ]
+}
+
+fn render_totals(
+    out: &mut String,
+    computed: &[(&'static str, String)],
+    declared: &[(&'static str, String)],
+) {
+    let label_width = computed.iter().map(|(l, _)| l.len()).max().unwrap_or(0);
+    let value_width = computed
+        .iter()
+        .chain(declared)
+        .map(|(_, v)| v.len())
+        .chain(["COMPUTED".len()])
+        .max()
+        .unwrap_or(0);
+
+    let _ = writeln!(
+        out,
+        "  {:label_width$}  {:>value_width$}  {:>value_width$}",
+        "", "COMPUTED", "DECLARED"
+    );
+    for ((label, ours), (_, theirs)) in computed.iter().zip(declared) {
+        let flag = if ours == theirs { "" } else { "  MISMATCH" };
+        let _ = writeln!(
+            out,
+            "  {label:label_width$}  {ours:>value_width$}  {theirs:>value_width$}{flag}"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{self, Corruption};
+
+    #[test]
+    fn test_render_report_snapshot() {
+        let text = fixtures::sample_ppd_file(3);
+        let report = AchFile::parse(&text).unwrap().render_report();
+        assert_eq!(
+            report,
+            "\
+ACH FILE
+  Destination: 123456780 YOUR BANK
+  Origin:      1234567801 YOUR COMPANY
+  Created:     140902 0123 (file ID A)
+  Batches: 1  Entries: 3  Addenda: 0
+  Debits: 25.00  Credits: 50.00
+  By code: 22: 2 for 50.00, 27: 1 for 25.00
+
+BATCH 0000001  YOUR COMPANY (1234567890)  SEC PPD  EFFECTIVE 140903  SERVICE CLASS 200
+  TRACE           TC ROUTING   ACCOUNT   AMOUNT NAME       ADDENDA
+  123456780000001 22 123456780 *****0000  12.50 RECEIVER 1       0
+  123456780000002 27 123232315 *****0001  25.00 RECEIVER 2       0
+  123456780000003 22 021000021 *****0002  37.50 RECEIVER 3       0
+  By code: 22: 2 for 50.00, 27: 1 for 25.00
+                         COMPUTED    DECLARED
+  Entry/addenda count           3           3
+  Entry hash           0026768911  0026768911
+  Total debits              25.00       25.00
+  Total credits             50.00       50.00
+
+FILE CONTROL
+                         COMPUTED    DECLARED
+  Batch count                   1           1
+  Entry/addenda count           3           3
+  Entry hash           0026768911  0026768911
+  Total debits              25.00       25.00
+  Total credits             50.00       50.00
+"
+        );
+    }
+
+    #[test]
+    fn test_render_report_with_policy() {
+        let text = fixtures::sample_ppd_file(1);
+        let file = AchFile::parse(&text).unwrap();
+        let report = file.render_report_with(&MaskingPolicy::default());
+        assert!(report.contains(" *****0000  12.50 R 1 "));
+        let report = file.render_report_with(&MaskingPolicy::unmasked());
+        assert!(report.contains(" 000100000  12.50 RECEIVER 1 "));
+    }
+
+    #[test]
+    fn test_render_report_with_directory() {
+        use crate::routing::InstitutionInfo;
+        use std::collections::HashMap;
+
+        let text = fixtures::sample_ppd_file(2);
+        let file = AchFile::parse(&text).unwrap();
+        let bank = InstitutionInfo {
+            routing_number: "123456780".to_string(),
+            name: "FIRST BANK".to_string(),
+            active: true,
+        };
+        let directory = HashMap::from([(bank.routing_number.clone(), bank)]);
+        let report = file.render_report_with_directory(&MaskingPolicy::default(), &directory);
+        let lines: Vec<&str> = report.lines().collect();
+        assert!(lines[9].ends_with(" ADDENDA INSTITUTION"));
+        assert!(lines[10].ends_with(" 0 FIRST BANK"));
+        assert!(lines[11].ends_with(" 0"));
+    }
+
+    #[test]
+    fn test_render_report_flags_mismatch() {
+        let text = fixtures::corrupt(&fixtures::sample_ppd_file(1), Corruption::TotalsMismatch);
+        let report = AchFile::parse(&text).unwrap().render_report();
+        let line = report
+            .lines()
+            .find(|l| l.contains("Total credits") && l.contains("MISMATCH"))
+            .unwrap();
+        assert!(line.contains("12.50") && line.contains("12.51"));
+    }
+}
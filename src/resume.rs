@@ -0,0 +1,257 @@
+//! Resumable parsing of a file that is still being written.
+
+use crate::error::{AchError, AchResult};
+use crate::parse_options::{ParseContext, ParseOptions};
+use crate::parser;
+use crate::records::{FileControl, FileHeader};
+use crate::writer::RECORD_LENGTH;
+use crate::{AchFile, Batch};
+
+/// How far [`AchFile::resume`] has read a file: a byte offset and where in
+/// the file's structure it is.
+///
+/// A checkpoint only ever falls after a complete record, so the next call
+/// starts at a record boundary. With the `serde` feature it can be stored
+/// between runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Checkpoint {
+    offset: usize,
+    line: usize,
+    stage: Stage,
+}
+
+/// Where a [`Checkpoint`] is in the file's structure.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+enum Stage {
+    /// Before the file header
+    #[default]
+    Start,
+    /// Between batches, after this many
+    Batches(usize),
+    /// After the file control, with this many batches
+    Done(usize),
+}
+
+impl Checkpoint {
+    /// The checkpoint at the start of a file.
+    pub fn start() -> Self {
+        Checkpoint::default()
+    }
+
+    /// Bytes of the file read so far.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Batches read so far.
+    pub fn batch_count(&self) -> usize {
+        match self.stage {
+            Stage::Start => 0,
+            Stage::Batches(count) | Stage::Done(count) => count,
+        }
+    }
+
+    /// Whether the file control has been read, so nothing more will be.
+    pub fn is_complete(&self) -> bool {
+        matches!(self.stage, Stage::Done(_))
+    }
+}
+
+/// What [`AchFile::resume`] read past its checkpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Resumed<'a> {
+    /// The file header, if read by this call
+    pub file_header: Option<FileHeader<'a>>,
+
+    /// Batches completed since the checkpoint, in file order
+    pub batches: Vec<Batch<'a>>,
+
+    /// The file control, if read by this call
+    pub file_control: Option<FileControl>,
+
+    /// Where to resume from next
+    pub checkpoint: Checkpoint,
+}
+
+impl<'a> AchFile<'a> {
+    /// Continue parsing `content`, the whole of a file still being
+    /// appended to, from `checkpoint`.
+    ///
+    /// Only whole batches are read: a batch whose control record has not
+    /// arrived yet is left for the next call, as is a last line shorter than
+    /// a record and without a line ending. The file control is read when it
+    /// appears, and after it nothing more is. Records as they should not be,
+    /// such as an entry outside a batch, are errors, and the checkpoint to
+    /// retry from is the one passed in.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile, Checkpoint};
+    ///
+    /// let text = fixtures::sample_multi_company_file();
+    /// let (mut batches, mut checkpoint) = (0, Checkpoint::start());
+    /// for written in [500, 1_000, text.len()] {
+    ///     let resumed = AchFile::resume(&text[..written], &checkpoint).unwrap();
+    ///     batches += resumed.batches.len();
+    ///     checkpoint = resumed.checkpoint;
+    /// }
+    /// assert_eq!(batches, 3);
+    /// assert!(checkpoint.is_complete());
+    /// ```
+    pub fn resume(content: &'a str, checkpoint: &Checkpoint) -> AchResult<Resumed<'a>> {
+        let rest = content.get(checkpoint.offset..).ok_or_else(|| {
+            AchError::InvalidStructure(format!(
+                "content is shorter than the checkpoint at byte {}",
+                checkpoint.offset
+            ))
+        })?;
+
+        // Complete record lines past the checkpoint, each with the offset
+        // just after it.
+        let mut records = Vec::new();
+        let mut ends = Vec::new();
+        let (mut offset, mut number) = (checkpoint.offset, checkpoint.line);
+        for piece in rest.split_inclusive('\n') {
+            let line = match piece.strip_suffix('\n') {
+                Some(line) => line.strip_suffix('\r').unwrap_or(line),
+                None if piece.trim_end_matches('\r').len() >= RECORD_LENGTH => {
+                    piece.trim_end_matches('\r')
+                }
+                // Still being written.
+                None => break,
+            };
+            offset += piece.len();
+            number += 1;
+            if !parser::is_filler(line) {
+                records.push((number, line));
+                ends.push(offset);
+            }
+        }
+
+        let options = ParseOptions::default();
+        let mut ctx = ParseContext::new(&options);
+        let mut resumed = Resumed {
+            file_header: None,
+            batches: Vec::new(),
+            file_control: None,
+            checkpoint: checkpoint.clone(),
+        };
+        let mut idx = 0;
+        while idx < records.len() {
+            match resumed.checkpoint.stage {
+                Stage::Start => {
+                    resumed.file_header = Some(parser::parse_file_header(records[idx])?);
+                    idx += 1;
+                    resumed.checkpoint.stage = Stage::Batches(0);
+                }
+                Stage::Batches(count) => match records[idx].1.as_bytes()[0] {
+                    b'5' => {
+                        // The batch is over once a record other than an
+                        // entry or addenda follows it.
+                        let ended = records[idx + 1..]
+                            .iter()
+                            .any(|(_, line)| !matches!(line.as_bytes()[0], b'6' | b'7'));
+                        if !ended {
+                            break;
+                        }
+                        let batch = parser::parse_batch(&mut ctx, &records, &mut idx, count)?;
+                        resumed.batches.push(batch);
+                        resumed.checkpoint.stage = Stage::Batches(count + 1);
+                    }
+                    b'9' => {
+                        resumed.file_control =
+                            Some(parser::parse_file_control(&mut ctx, records[idx])?);
+                        idx += 1;
+                        resumed.checkpoint.stage = Stage::Done(count);
+                    }
+                    _ => {
+                        let (number, line) = records[idx];
+                        let first = line.chars().next().unwrap_or_default();
+                        return Err(parser::unexpected_record(&first.to_string(), number, false));
+                    }
+                },
+                Stage::Done(_) => break,
+            }
+            resumed.checkpoint.offset = ends[idx - 1];
+            resumed.checkpoint.line = records[idx - 1].0;
+        }
+        Ok(resumed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_resume_as_file_grows() {
+        for text in [
+            fixtures::sample_multi_company_file(),
+            fixtures::sample_ctx_with_addenda().replace('\n', "\r\n"),
+        ] {
+            let file = AchFile::parse(&text).unwrap();
+            let mut checkpoint = Checkpoint::start();
+            let mut header = None;
+            let mut batches = Vec::new();
+            let mut control = None;
+            for written in (0..text.len()).step_by(37).chain([text.len()]) {
+                let resumed = AchFile::resume(&text[..written], &checkpoint).unwrap();
+                header = header.or(resumed.file_header);
+                batches.extend(resumed.batches);
+                control = control.or(resumed.file_control);
+                checkpoint = resumed.checkpoint;
+                assert_eq!(checkpoint.batch_count(), batches.len());
+            }
+            assert!(checkpoint.is_complete());
+            assert_eq!(header.as_ref(), Some(&file.file_header));
+            assert_eq!(batches, file.batches);
+            assert_eq!(control.as_ref(), Some(&file.file_control));
+
+            // Nothing is read after the file control.
+            let again = AchFile::resume(&text, &checkpoint).unwrap();
+            assert!(again.batches.is_empty() && again.file_control.is_none());
+        }
+    }
+
+    #[test]
+    fn test_resume_waits_for_batch_control() {
+        let text = fixtures::sample_ppd_file(2);
+        let lines: Vec<&str> = text.lines().collect();
+
+        // Header, batch header, entries, and half of the batch control.
+        let written = format!("{}\n{}", lines[..4].join("\n"), &lines[4][..40]);
+        let resumed = AchFile::resume(&written, &Checkpoint::start()).unwrap();
+        assert!(resumed.file_header.is_some());
+        assert!(resumed.batches.is_empty());
+        assert_eq!(resumed.checkpoint.offset(), lines[0].len() + 1);
+
+        let resumed = AchFile::resume(&text, &resumed.checkpoint).unwrap();
+        assert_eq!(resumed.batches.len(), 1);
+        assert!(resumed.checkpoint.is_complete());
+    }
+
+    #[test]
+    fn test_resume_errors() {
+        let text = fixtures::sample_ppd_file(1);
+        let header = AchFile::resume(&text[..95], &Checkpoint::start()).unwrap();
+        assert_eq!(header.checkpoint.offset(), 95);
+
+        // An entry outside any batch, with its line in the file.
+        let lines: Vec<&str> = text.lines().collect();
+        let misplaced = format!("{}\n{}\n{}\n", lines[0], lines[2], lines[3]);
+        let err = AchFile::resume(&misplaced, &header.checkpoint).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid file structure: Unexpected entry detail record at line 2"
+        );
+
+        let err = AchFile::resume(&text[..50], &header.checkpoint).unwrap_err();
+        assert!(matches!(err, AchError::InvalidStructure(_)));
+    }
+}
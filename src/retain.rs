@@ -0,0 +1,294 @@
+//! Filtering a file down to some of its entries or batches.
+
+use crate::records::{BatchHeader, EntryDetail, UnknownRecord};
+use crate::{AchFile, AchFileOwned, Batch, BatchOwned, RenumberOptions, TraceSequence};
+
+/// Options for [`AchFile::retain_entries_with`] and
+/// [`AchFile::retain_batches_with`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct RetainOptions {
+    /// Keep batches left with no entries instead of dropping them
+    pub keep_empty_batches: bool,
+
+    /// Give the kept entries new trace numbers, numbering each batch's
+    /// entries from 1 as [`AchFile::renumber`] does with
+    /// [`TraceSequence::PerBatch`]
+    pub renumber_traces: bool,
+}
+
+/// The file left by [`AchFile::retain_entries`] or
+/// [`AchFile::retain_batches`], and the entries taken out of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Retained {
+    /// The kept entries, with batches renumbered and controls recomputed
+    pub file: AchFileOwned,
+
+    /// The entries left out, in file order, as they were
+    pub removed: Vec<RemovedEntry>,
+}
+
+/// An entry left out by [`AchFile::retain_entries`] or
+/// [`AchFile::retain_batches`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct RemovedEntry {
+    /// Index of its batch in the original file, counting from 0
+    pub batch_index: usize,
+
+    /// Header of its batch in the original file
+    pub batch_header: BatchHeader<'static>,
+
+    /// The entry, with its addenda
+    pub entry: EntryDetail<'static>,
+}
+
+impl AchFile<'_> {
+    /// A copy of the file with only the entries for which `keep` returns
+    /// `true`, with the default [`RetainOptions`].
+    ///
+    /// Batches left empty are dropped and the rest are numbered from 1.
+    /// Batch and file controls, and so the block count and padding, are
+    /// recomputed, so the copy of a file that passes
+    /// [`validate`](AchFile::validate) passes it too.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile};
+    ///
+    /// let text = fixtures::sample_multi_company_file();
+    /// let file = AchFile::parse(&text).unwrap();
+    /// // Hold entries over the limit for review.
+    /// let retained = file.retain_entries(|e| e.amount <= 500_000);
+    /// assert_eq!(retained.removed.len(), 1);
+    /// assert_eq!(retained.removed[0].entry.amount, 900_000);
+    /// assert_eq!(retained.file.batches[1].entries.len(), 1);
+    /// assert!(retained.file.validate().is_valid());
+    /// ```
+    pub fn retain_entries(&self, keep: impl FnMut(&EntryDetail<'_>) -> bool) -> Retained {
+        self.retain_entries_with(keep, &RetainOptions::default())
+    }
+
+    /// Like [`retain_entries`](AchFile::retain_entries), with the given
+    /// options.
+    pub fn retain_entries_with(
+        &self,
+        mut keep: impl FnMut(&EntryDetail<'_>) -> bool,
+        options: &RetainOptions,
+    ) -> Retained {
+        let mut removed = Vec::new();
+        let mut batches = Vec::with_capacity(self.batches.len());
+        for (batch_index, batch) in self.batches.iter().enumerate() {
+            let mut kept = batch.clone().into_owned();
+            // Entries kept before each original one, so unknown records
+            // stay after the entries they followed.
+            let mut kept_before = vec![0];
+            let mut entries = Vec::with_capacity(kept.entries.len());
+            for entry in kept.entries.drain(..) {
+                if keep(&entry) {
+                    entries.push(entry);
+                } else {
+                    removed.push(RemovedEntry {
+                        batch_index,
+                        batch_header: kept.header.clone(),
+                        entry,
+                    });
+                }
+                kept_before.push(entries.len());
+            }
+            kept.entries = entries;
+            for unknown in &mut kept.unknown_records {
+                unknown.preceding = kept_before[unknown.preceding.min(kept_before.len() - 1)];
+            }
+            let empty = kept.entries.is_empty() && !batch.entries.is_empty();
+            batches.push((!empty || options.keep_empty_batches).then_some(kept));
+        }
+        Retained {
+            file: self.rebuild(batches, options),
+            removed,
+        }
+    }
+
+    /// A copy of the file with only the batches for which `keep` returns
+    /// `true`, with the default [`RetainOptions`]. The entries of the other
+    /// batches are returned as removed; otherwise this is like
+    /// [`retain_entries`](AchFile::retain_entries).
+    pub fn retain_batches(&self, keep: impl FnMut(&Batch<'_>) -> bool) -> Retained {
+        self.retain_batches_with(keep, &RetainOptions::default())
+    }
+
+    /// Like [`retain_batches`](AchFile::retain_batches), with the given
+    /// options. [`RetainOptions::keep_empty_batches`] has no effect, since
+    /// no kept batch loses entries.
+    pub fn retain_batches_with(
+        &self,
+        mut keep: impl FnMut(&Batch<'_>) -> bool,
+        options: &RetainOptions,
+    ) -> Retained {
+        let mut removed = Vec::new();
+        let mut batches = Vec::with_capacity(self.batches.len());
+        for (batch_index, batch) in self.batches.iter().enumerate() {
+            if keep(batch) {
+                batches.push(Some(batch.clone().into_owned()));
+                continue;
+            }
+            let batch_header = batch.header.clone().into_owned();
+            removed.extend(batch.entries.iter().map(|entry| RemovedEntry {
+                batch_index,
+                batch_header: batch_header.clone(),
+                entry: entry.clone().into_owned(),
+            }));
+            batches.push(None);
+        }
+        Retained {
+            file: self.rebuild(batches, options),
+            removed,
+        }
+    }
+
+    /// The file with the given batches, one per original batch or `None`
+    /// for one dropped, numbered from 1 with controls recomputed.
+    fn rebuild(&self, batches: Vec<Option<BatchOwned>>, options: &RetainOptions) -> AchFileOwned {
+        let mut file = AchFile {
+            file_header: self.file_header.clone().into_owned(),
+            batches: Vec::with_capacity(batches.len()),
+            file_control: self.file_control.clone(),
+            unknown_records: Vec::new(),
+        };
+        // Batches kept before each original one, so unknown records stay
+        // after the batches they followed.
+        let mut kept_before = vec![0];
+        for batch in batches {
+            file.batches.extend(batch);
+            kept_before.push(file.batches.len());
+        }
+        file.unknown_records = self
+            .unknown_records
+            .iter()
+            .map(|unknown| UnknownRecord {
+                preceding: kept_before[unknown.preceding.min(kept_before.len() - 1)],
+                ..unknown.clone().into_owned()
+            })
+            .collect();
+
+        let traces = if options.renumber_traces {
+            TraceSequence::PerBatch
+        } else {
+            TraceSequence::Keep
+        };
+        file.renumber(&RenumberOptions {
+            traces,
+            ..RenumberOptions::default()
+        });
+        for batch in &mut file.batches {
+            batch.recompute_control();
+        }
+        file.recompute_file_control();
+        file
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_retain_entries() {
+        let text = fixtures::sample_multi_company_file();
+        let file = AchFile::parse(&text).unwrap();
+        let dropped = file.batches[1].entries.clone();
+        let retained = file.retain_entries(|e| !dropped.contains(e));
+
+        assert_eq!(retained.file.batches.len(), 2);
+        assert_eq!(retained.removed.len(), 2);
+        assert!(retained.removed.iter().all(|r| r.batch_index == 1));
+        assert_eq!(retained.removed[0].batch_header, file.batches[1].header);
+        assert_eq!(retained.removed[1].entry, dropped[1]);
+        let numbers: Vec<&str> = retained
+            .file
+            .batches
+            .iter()
+            .map(|b| b.control.batch_number.as_str())
+            .collect();
+        assert_eq!(numbers, ["0000001", "0000002"]);
+        assert_eq!(retained.file.batches[1].header.batch_number, "0000002");
+        assert_eq!(retained.file.file_control.batch_count, 2);
+        assert!(retained.file.validate().is_valid());
+
+        let text = crate::writer::render_lines(&retained.file)
+            .unwrap()
+            .join("\n");
+        let reparsed = AchFile::parse(&text).unwrap();
+        assert_eq!(reparsed, retained.file);
+    }
+
+    #[test]
+    fn test_keep_empty_batches() {
+        let text = fixtures::sample_multi_company_file();
+        let file = AchFile::parse(&text).unwrap();
+        let options = RetainOptions {
+            keep_empty_batches: true,
+            ..RetainOptions::default()
+        };
+        let retained = file.retain_entries_with(|e| e.amount > 1_000_000_000, &options);
+        assert_eq!(retained.file.batches.len(), 3);
+        assert!(retained.file.batches.iter().all(|b| b.entries.is_empty()));
+        assert_eq!(retained.file.file_control.entry_addenda_count, 0);
+
+        let retained = file.retain_entries(|_| false);
+        assert!(retained.file.batches.is_empty());
+        assert_eq!(retained.removed.len(), 7);
+    }
+
+    #[test]
+    fn test_renumber_traces() {
+        let ctx = fixtures::sample_ctx_with_addenda();
+        let ctx = AchFile::parse(&ctx).unwrap();
+        let text = fixtures::sample_multi_company_file();
+        let mut file = AchFile::parse(&text).unwrap();
+        let entry = &mut file.batches[0].entries[1];
+        entry.addenda_record_indicator = "1".into();
+        entry.addenda = ctx.batches[0].entries[0].addenda.clone();
+        file.batches[0].recompute_control();
+        file.recompute_file_control();
+        let first = file.batches[0].entries[0].trace_number.clone();
+        let options = RetainOptions {
+            renumber_traces: true,
+            ..RetainOptions::default()
+        };
+        let retained = file.retain_entries_with(|e| e.trace_number != first, &options);
+        let batch = &retained.file.batches[0];
+        assert_eq!(batch.entries[0].addenda.len(), 3);
+        let odfi = &batch.header.originating_dfi_identification;
+        for (n, entry) in batch.entries.iter().enumerate() {
+            assert_eq!(entry.trace_number, format!("{odfi}{:07}", n + 1));
+            for addenda in &entry.addenda {
+                assert_eq!(
+                    addenda.entry_detail_sequence_number,
+                    format!("{:07}", n + 1)
+                );
+            }
+        }
+        assert!(retained.file.validate().is_valid());
+    }
+
+    #[test]
+    fn test_retain_batches() {
+        let text = fixtures::sample_multi_company_file();
+        let file = AchFile::parse(&text).unwrap();
+        let retained = file.retain_batches(|b| b.header.batch_number != "0000001");
+        assert_eq!(retained.file.batches.len(), 2);
+        assert_eq!(retained.file.batches[0].header, {
+            let mut header = file.batches[1].header.clone();
+            header.batch_number = "0000001".into();
+            header
+        });
+        assert_eq!(retained.removed.len(), file.batches[0].entries.len());
+        assert!(retained.removed.iter().all(|r| r.batch_index == 0));
+        assert!(retained.file.validate().is_valid());
+    }
+}
@@ -0,0 +1,208 @@
+//! Return rates per originating company, as monitored by NACHA.
+//!
+//! The rules set three thresholds on an originator's debit returns, each a
+//! share of the debit entries it originated over the same period:
+//! unauthorized returns (0.5%), administrative returns (3%), and all
+//! returns (15%). [`return_statistics`] computes these rates, plus counts
+//! by [`ReturnCategory`], from the files originated and the returns
+//! received for them.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::controls::is_debit;
+use crate::records::{Addenda, BatchHeader};
+use crate::returns::ReturnCategory;
+use crate::AchFile;
+
+/// Return counts and rates for one company, from [`return_statistics`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct CompanyReturnStats {
+    /// Company identification, trimmed
+    pub company_identification: String,
+
+    /// Company name of the company's first batch, trimmed
+    pub company_name: String,
+
+    /// Entries originated, prenotes excluded
+    pub originated_entry_count: usize,
+
+    /// Debit entries originated, prenotes excluded: the denominator of
+    /// every rate
+    pub originated_debit_count: usize,
+
+    /// Returns received, of credits and debits
+    pub returned_count: usize,
+
+    /// Returns received of debit entries
+    pub returned_debit_count: usize,
+
+    /// Returns received by reason code category, of credits and debits
+    pub category_counts: BTreeMap<ReturnCategory, usize>,
+
+    /// Unauthorized debit returns per debit originated, or `None` when no
+    /// debits were originated
+    pub unauthorized_return_rate: Option<f64>,
+
+    /// Administrative debit returns per debit originated
+    pub administrative_return_rate: Option<f64>,
+
+    /// All debit returns per debit originated
+    pub overall_return_rate: Option<f64>,
+}
+
+/// Result of [`return_statistics`].
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ReturnStats {
+    /// One entry per company, in order of first appearance in the
+    /// originated files and then in the returns
+    pub companies: Vec<CompanyReturnStats>,
+}
+
+impl ReturnStats {
+    /// The statistics for `company_identification`, if it appears.
+    pub fn company(&self, company_identification: &str) -> Option<&CompanyReturnStats> {
+        self.companies
+            .iter()
+            .find(|c| c.company_identification == company_identification.trim())
+    }
+}
+
+/// Whether `transaction_code` is a prenote (second digit 3 or 8).
+fn is_prenote(transaction_code: &str) -> bool {
+    matches!(transaction_code.as_bytes().get(1), Some(b'3' | b'8'))
+}
+
+/// Count entries originated in `originated` and returns received in
+/// `returns` per company identification.
+///
+/// Returns are attributed by the company identification of the batch
+/// carrying them, which a returning RDFI copies from the original batch.
+/// A return is of a debit when its own transaction code is a debit code,
+/// such as `26` for a returned `27`.
+pub fn return_statistics(originated: &[&AchFile<'_>], returns: &[&AchFile<'_>]) -> ReturnStats {
+    let mut stats = ReturnStats::default();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut company = |stats: &mut ReturnStats, header: &BatchHeader<'_>| {
+        let id = header.company_identification.trim().to_string();
+        *index.entry(id.clone()).or_insert_with(|| {
+            stats.companies.push(CompanyReturnStats {
+                company_identification: id,
+                company_name: header.company_name.trim().to_string(),
+                originated_entry_count: 0,
+                originated_debit_count: 0,
+                returned_count: 0,
+                returned_debit_count: 0,
+                category_counts: BTreeMap::new(),
+                unauthorized_return_rate: None,
+                administrative_return_rate: None,
+                overall_return_rate: None,
+            });
+            stats.companies.len() - 1
+        })
+    };
+
+    // Debit returns by company: unauthorized, administrative.
+    let mut debit_returns: HashMap<usize, (usize, usize)> = HashMap::new();
+    for file in originated {
+        for batch in &file.batches {
+            let i = company(&mut stats, &batch.header);
+            let entries = batch
+                .entries
+                .iter()
+                .filter(|e| !is_prenote(&e.transaction_code));
+            for entry in entries {
+                stats.companies[i].originated_entry_count += 1;
+                if is_debit(&entry.transaction_code) {
+                    stats.companies[i].originated_debit_count += 1;
+                }
+            }
+        }
+    }
+    for file in returns {
+        for batch in &file.batches {
+            for returned in &batch.entries {
+                let Some(addenda) = returned.addenda.iter().find_map(Addenda::as_return) else {
+                    continue;
+                };
+                let i = company(&mut stats, &batch.header);
+                let category = addenda.reason_code().category();
+                let entry = &mut stats.companies[i];
+                entry.returned_count += 1;
+                *entry.category_counts.entry(category).or_default() += 1;
+                if is_debit(&returned.transaction_code) {
+                    entry.returned_debit_count += 1;
+                    let counts = debit_returns.entry(i).or_default();
+                    match category {
+                        ReturnCategory::Unauthorized => counts.0 += 1,
+                        ReturnCategory::Administrative => counts.1 += 1,
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    for (i, entry) in stats.companies.iter_mut().enumerate() {
+        let debits = entry.originated_debit_count;
+        let rate = |count: usize| (debits > 0).then(|| count as f64 / debits as f64);
+        let (unauthorized, administrative) = debit_returns.get(&i).copied().unwrap_or((0, 0));
+        entry.unauthorized_return_rate = rate(unauthorized);
+        entry.administrative_return_rate = rate(administrative);
+        entry.overall_return_rate = rate(entry.returned_debit_count);
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_return_statistics() {
+        // Two credits and two debits, plus a debit prenote.
+        let text = fixtures::sample_ppd_file(5);
+        let mut originated = AchFile::parse(&text).unwrap();
+        originated.batches[0].entries[4].transaction_code = "28".into();
+        originated.batches[0].entries[4].amount = 0;
+        // An R01 debit return and an R03 credit return.
+        let returns_text = fixtures::sample_returns_file();
+        let mut returns = AchFile::parse(&returns_text).unwrap();
+        returns.batches[0].header.company_identification = "1234567890".into();
+
+        let stats = return_statistics(&[&originated], &[&returns]);
+        assert_eq!(stats.companies.len(), 1);
+        let company = stats.company("1234567890").unwrap();
+        assert_eq!(company.originated_entry_count, 4);
+        assert_eq!(company.originated_debit_count, 2);
+        assert_eq!(company.returned_count, 2);
+        assert_eq!(company.returned_debit_count, 1);
+        assert_eq!(
+            company.category_counts,
+            BTreeMap::from([
+                (ReturnCategory::Administrative, 1),
+                (ReturnCategory::AccountRelated, 1),
+            ])
+        );
+        // The administrative return is of a credit, so only the overall
+        // rate counts a return.
+        assert_eq!(company.unauthorized_return_rate, Some(0.0));
+        assert_eq!(company.administrative_return_rate, Some(0.0));
+        assert_eq!(company.overall_return_rate, Some(0.5));
+    }
+
+    #[test]
+    fn test_returns_for_unknown_company() {
+        let text = fixtures::sample_returns_file();
+        let returns = AchFile::parse(&text).unwrap();
+        let stats = return_statistics(&[], &[&returns]);
+        let company = &stats.companies[0];
+        assert_eq!(company.returned_count, 2);
+        assert_eq!(company.originated_debit_count, 0);
+        assert_eq!(company.overall_return_rate, None);
+    }
+}
@@ -0,0 +1,479 @@
+//! Returned entries, their type 99 addenda, and return reason codes.
+
+use std::fmt;
+
+use crate::records::{Addenda, EntryDetail};
+use crate::{AchFile, Batch};
+
+/// The group a return reason code belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub enum ReturnCategory {
+    /// Account closed, not found, or malformed (R02, R03, R04)
+    Administrative,
+
+    /// The receiver did not authorize the entry (R05, R07, R10, R11, R29)
+    Unauthorized,
+
+    /// The account cannot take the entry: funds, status, or holder
+    AccountRelated,
+
+    /// An ODFI dishonoring a return (R61, R62, R67-R70)
+    Dishonored,
+
+    /// An RDFI contesting a dishonored return (R71-R77)
+    Contested,
+
+    /// International ACH Transaction returns (R80-R85)
+    Iat,
+
+    /// Anything else: formatting errors, program participation, entry-type
+    /// specific returns, and unknown codes
+    Other,
+}
+
+/// Defines [`ReturnReasonCode`] from one row per code: the code, its
+/// category, whether it has the extended (60-day) return window, and its
+/// description.
+macro_rules! return_reason_codes {
+    ($($code:ident, $category:ident, $extended:literal, $description:literal;)*) => {
+        /// A return reason code from a return addenda (type 99).
+        ///
+        /// Codes not defined by NACHA are kept as [`ReturnReasonCode::Other`].
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+        #[non_exhaustive]
+        pub enum ReturnReasonCode {
+            $(
+                #[doc = $description]
+                $code,
+            )*
+
+            /// A code not in the NACHA table, as it appeared in the file
+            #[cfg_attr(feature = "serde", serde(untagged))]
+            Other(String),
+        }
+
+        impl ReturnReasonCode {
+            /// Read a code such as `R01`. Surrounding spaces are ignored.
+            pub fn from_code(code: &str) -> ReturnReasonCode {
+                match code.trim() {
+                    $(stringify!($code) => ReturnReasonCode::$code,)*
+                    other => ReturnReasonCode::Other(other.to_string()),
+                }
+            }
+
+            /// The code as it appears in the file, e.g. `R01`.
+            pub fn code(&self) -> &str {
+                match self {
+                    $(ReturnReasonCode::$code => stringify!($code),)*
+                    ReturnReasonCode::Other(code) => code,
+                }
+            }
+
+            /// Category, extended window, and description of a known code.
+            fn row(&self) -> Option<(ReturnCategory, bool, &'static str)> {
+                match self {
+                    $(ReturnReasonCode::$code => {
+                        Some((ReturnCategory::$category, $extended, $description))
+                    })*
+                    ReturnReasonCode::Other(_) => None,
+                }
+            }
+        }
+    };
+}
+
+#[rustfmt::skip]
+return_reason_codes! {
+    R01, AccountRelated, false, "Insufficient Funds";
+    R02, Administrative, false, "Account Closed";
+    R03, Administrative, false, "No Account/Unable to Locate Account";
+    R04, Administrative, false, "Invalid Account Number Structure";
+    R05, Unauthorized, true, "Unauthorized Debit to Consumer Account Using Corporate SEC Code";
+    R06, Other, false, "Returned per ODFI's Request";
+    R07, Unauthorized, true, "Authorization Revoked by Customer";
+    R08, AccountRelated, false, "Payment Stopped";
+    R09, AccountRelated, false, "Uncollected Funds";
+    R10, Unauthorized, true, "Customer Advises Originator is Not Known to Receiver and/or Is Not Authorized to Debit";
+    R11, Unauthorized, true, "Customer Advises Entry Not in Accordance with the Terms of the Authorization";
+    R12, AccountRelated, false, "Account Sold to Another DFI";
+    R13, Other, false, "Invalid ACH Routing Number";
+    R14, AccountRelated, false, "Representative Payee Deceased or Unable to Continue in That Capacity";
+    R15, AccountRelated, false, "Beneficiary or Account Holder (Other Than a Representative Payee) Deceased";
+    R16, AccountRelated, false, "Account Frozen/Entry Returned per OFAC Instruction";
+    R17, Other, false, "File Record Edit Criteria";
+    R18, Other, false, "Improper Effective Entry Date";
+    R19, Other, false, "Amount Field Error";
+    R20, AccountRelated, false, "Non-Transaction Account";
+    R21, Other, false, "Invalid Company Identification";
+    R22, Other, false, "Invalid Individual ID Number";
+    R23, Other, false, "Credit Entry Refused by Receiver";
+    R24, Other, false, "Duplicate Entry";
+    R25, Other, false, "Addenda Error";
+    R26, Other, false, "Mandatory Field Error";
+    R27, Other, false, "Trace Number Error";
+    R28, Other, false, "Routing Number Check Digit Error";
+    R29, Unauthorized, false, "Corporate Customer Advises Not Authorized";
+    R30, Other, false, "RDFI Not Participant in Check Truncation Program";
+    R31, Other, false, "Permissible Return Entry (CCD and CTX Only)";
+    R32, Other, false, "RDFI Non-Settlement";
+    R33, Other, false, "Return of XCK Entry";
+    R34, Other, false, "Limited Participation DFI";
+    R35, Other, false, "Return of Improper Debit Entry";
+    R36, Other, false, "Return of Improper Credit Entry";
+    R37, Other, true, "Source Document Presented for Payment";
+    R38, Other, true, "Stop Payment on Source Document";
+    R39, Other, false, "Improper Source Document/Source Document Presented for Payment";
+    R40, Other, false, "Return of ENR Entry by Federal Government Agency";
+    R41, Other, false, "Invalid Transaction Code (ENR)";
+    R42, Other, false, "Routing Number/Check Digit Error (ENR)";
+    R43, Other, false, "Invalid DFI Account Number (ENR)";
+    R44, Other, false, "Invalid Individual ID Number/Identification Number (ENR)";
+    R45, Other, false, "Invalid Individual Name/Company Name (ENR)";
+    R46, Other, false, "Invalid Representative Payee Indicator (ENR)";
+    R47, Other, false, "Duplicate Enrollment (ENR)";
+    R50, Other, false, "State Law Affecting RCK Acceptance";
+    R51, Other, true, "Item Related to RCK Entry Is Ineligible or RCK Entry Is Improper";
+    R52, Other, true, "Stop Payment on Item Related to RCK Entry";
+    R53, Other, true, "Item and RCK Entry Presented for Payment";
+    R61, Dishonored, false, "Misrouted Return";
+    R62, Dishonored, false, "Return of Erroneous or Reversing Debit";
+    R67, Dishonored, false, "Duplicate Return";
+    R68, Dishonored, false, "Untimely Return";
+    R69, Dishonored, false, "Field Error(s)";
+    R70, Dishonored, false, "Permissible Return Entry Not Accepted/Return Not Requested by ODFI";
+    R71, Contested, false, "Misrouted Dishonored Return";
+    R72, Contested, false, "Untimely Dishonored Return";
+    R73, Contested, false, "Timely Original Return";
+    R74, Contested, false, "Corrected Return";
+    R75, Contested, false, "Return Not a Duplicate";
+    R76, Contested, false, "No Errors Found";
+    R77, Contested, false, "Non-Acceptance of R62 Dishonored Return";
+    R80, Iat, false, "IAT Entry Coding Error";
+    R81, Iat, false, "Non-Participant in IAT Program";
+    R82, Iat, false, "Invalid Foreign Receiving DFI Identification";
+    R83, Iat, false, "Foreign Receiving DFI Unable to Settle";
+    R84, Iat, false, "Entry Not Processed by Gateway";
+    R85, Iat, false, "Incorrectly Coded Outbound International Payment";
+}
+
+impl ReturnReasonCode {
+    /// NACHA description, e.g. `Insufficient Funds`, or `Unknown Return
+    /// Reason` for [`ReturnReasonCode::Other`].
+    pub fn description(&self) -> &'static str {
+        self.row().map_or("Unknown Return Reason", |(_, _, d)| d)
+    }
+
+    /// The category this code is grouped under.
+    pub fn category(&self) -> ReturnCategory {
+        self.row().map_or(ReturnCategory::Other, |(c, _, _)| c)
+    }
+
+    /// Whether the RDFI may return the entry up to 60 days after settlement
+    /// instead of within two banking days.
+    pub fn has_extended_return_window(&self) -> bool {
+        self.row().is_some_and(|(_, extended, _)| extended)
+    }
+
+    /// Whether the originator may reinitiate the entry. Only returns for
+    /// insufficient or uncollected funds (R01, R09) are retried as-is.
+    pub fn allows_reinitiation(&self) -> bool {
+        matches!(self, ReturnReasonCode::R01 | ReturnReasonCode::R09)
+    }
+}
+
+impl fmt::Display for ReturnReasonCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// The structured fields of a return addenda (type 99).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ReturnAddenda {
+    /// Return reason code, e.g. `R01`
+    pub return_reason_code: String,
+
+    /// Trace number of the entry being returned
+    pub original_entry_trace_number: String,
+
+    /// Date of death (YYMMDD), present on R14 and R15 returns
+    pub date_of_death: Option<String>,
+
+    /// Receiving DFI identification of the original entry (8 digits)
+    pub original_receiving_dfi_identification: String,
+
+    /// Addenda information, trimmed
+    pub addenda_information: String,
+
+    /// Trace number of the return entry
+    pub trace_number: String,
+}
+
+impl Addenda<'_> {
+    /// Read this addenda as a return addenda, or `None` unless its type code
+    /// is `99`.
+    pub fn as_return(&self) -> Option<ReturnAddenda> {
+        if self.addenda_type_code != "99" {
+            return None;
+        }
+        // The type 99 layout does not follow the type 05 field boundaries,
+        // so reassemble positions 4-94 and slice them again.
+        let rest = format!(
+            "{}{}{}",
+            self.payment_related_information,
+            self.addenda_sequence_number,
+            self.entry_detail_sequence_number
+        );
+        let slice = |from: usize, to: usize| rest.get(from..to).unwrap_or("").trim().to_string();
+
+        let date_of_death = slice(18, 24);
+        Some(ReturnAddenda {
+            return_reason_code: slice(0, 3),
+            original_entry_trace_number: slice(3, 18),
+            date_of_death: Some(date_of_death).filter(|d| !d.is_empty()),
+            original_receiving_dfi_identification: slice(24, 32),
+            addenda_information: slice(32, 76),
+            trace_number: slice(76, 91),
+        })
+    }
+}
+
+impl EntryDetail<'_> {
+    /// The entry's return addenda, or `None` if it has none, for deciding
+    /// what to do with the original payment.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile, ReturnReasonCode};
+    ///
+    /// let text = fixtures::sample_returns_file();
+    /// let file = AchFile::parse(&text).unwrap();
+    /// let entry = &file.batches[0].entries[0];
+    /// let returned = entry.as_return().unwrap();
+    /// assert_eq!(returned.reason_code(), ReturnReasonCode::R01);
+    /// assert_eq!(returned.original_entry_trace_number, "091012980000001");
+    /// ```
+    pub fn as_return(&self) -> Option<ReturnAddenda> {
+        self.addenda.iter().find_map(Addenda::as_return)
+    }
+}
+
+impl ReturnAddenda {
+    /// The typed return reason code.
+    pub fn reason_code(&self) -> ReturnReasonCode {
+        ReturnReasonCode::from_code(&self.return_reason_code)
+    }
+}
+
+/// How a return relates to the original entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum ReturnClass {
+    /// A return of an original entry by the RDFI
+    Return,
+
+    /// A return dishonored by the ODFI (R61, R62, R67-R70)
+    Dishonored,
+
+    /// A dishonored return contested by the RDFI (R71-R77)
+    Contested,
+}
+
+impl ReturnClass {
+    fn of(reason_code: &ReturnReasonCode) -> ReturnClass {
+        match reason_code.category() {
+            ReturnCategory::Dishonored => ReturnClass::Dishonored,
+            ReturnCategory::Contested => ReturnClass::Contested,
+            _ => ReturnClass::Return,
+        }
+    }
+}
+
+/// A returned entry joined with its return addenda.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReturnedEntry<'a> {
+    /// The batch containing the return
+    pub batch: &'a Batch<'a>,
+
+    /// The return entry
+    pub entry: &'a EntryDetail<'a>,
+
+    /// The entry's return addenda
+    pub addenda: ReturnAddenda,
+
+    /// Whether this is a return, a dishonored return, or a contested
+    /// dishonored return
+    pub class: ReturnClass,
+}
+
+impl ReturnedEntry<'_> {
+    /// The return reason code.
+    pub fn reason_code(&self) -> ReturnReasonCode {
+        self.addenda.reason_code()
+    }
+
+    /// Trace number of the entry being returned.
+    pub fn original_trace_number(&self) -> &str {
+        &self.addenda.original_entry_trace_number
+    }
+
+    /// Whether this is an administrative return (R02, R03, R04).
+    pub fn is_administrative(&self) -> bool {
+        self.reason_code().category() == ReturnCategory::Administrative
+    }
+
+    /// Whether this is a return for an unauthorized entry (R05, R07, R10,
+    /// R11, R29).
+    pub fn is_unauthorized(&self) -> bool {
+        self.reason_code().category() == ReturnCategory::Unauthorized
+    }
+}
+
+impl AchFile<'_> {
+    /// Every entry carrying a return addenda, in file order.
+    pub fn returns(&self) -> Vec<ReturnedEntry<'_>> {
+        let mut returns = Vec::new();
+        for batch in &self.batches {
+            for entry in &batch.entries {
+                if let Some(addenda) = entry.as_return() {
+                    let class = ReturnClass::of(&addenda.reason_code());
+                    returns.push(ReturnedEntry {
+                        batch,
+                        entry,
+                        addenda,
+                        class,
+                    });
+                }
+            }
+        }
+        returns
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_as_return() {
+        let text = fixtures::sample_returns_file();
+        let file = AchFile::parse(&text).unwrap();
+        let addenda = file.batches[0].entries[0].addenda[0].as_return().unwrap();
+
+        assert_eq!(
+            addenda,
+            ReturnAddenda {
+                return_reason_code: "R01".to_string(),
+                original_entry_trace_number: "091012980000001".to_string(),
+                date_of_death: None,
+                original_receiving_dfi_identification: "12345678".to_string(),
+                addenda_information: String::new(),
+                trace_number: "123456780000001".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_entry_as_return() {
+        let text = fixtures::sample_returns_file();
+        let mut file = AchFile::parse(&text).unwrap();
+        let entry = &mut file.batches[0].entries[1];
+        assert_eq!(entry.as_return(), entry.addenda[0].as_return());
+        assert_eq!(
+            entry.as_return().unwrap().reason_code(),
+            ReturnReasonCode::R03
+        );
+
+        entry.addenda.clear();
+        assert_eq!(entry.as_return(), None);
+    }
+
+    #[test]
+    fn test_as_return_ignores_other_types() {
+        let text = fixtures::sample_ctx_with_addenda();
+        let file = AchFile::parse(&text).unwrap();
+        assert_eq!(file.batches[0].entries[0].addenda[0].as_return(), None);
+        assert_eq!(file.batches[0].entries[0].as_return(), None);
+        assert!(file.returns().is_empty());
+    }
+
+    #[test]
+    fn test_returns() {
+        let text = fixtures::sample_returns_file();
+        let file = AchFile::parse(&text).unwrap();
+        let returns = file.returns();
+
+        assert_eq!(returns.len(), 2);
+        assert_eq!(returns[0].reason_code(), ReturnReasonCode::R01);
+        assert_eq!(returns[0].entry.amount, 15_000);
+        assert_eq!(returns[0].class, ReturnClass::Return);
+        assert!(!returns[0].is_administrative());
+        assert_eq!(returns[1].reason_code(), ReturnReasonCode::R03);
+        assert_eq!(returns[1].original_trace_number(), "091012980000002");
+        assert!(returns[1].is_administrative());
+        assert!(!returns[1].is_unauthorized());
+    }
+
+    #[test]
+    fn test_return_class() {
+        assert_eq!(ReturnClass::of(&ReturnReasonCode::R10), ReturnClass::Return);
+        assert_eq!(
+            ReturnClass::of(&ReturnReasonCode::R69),
+            ReturnClass::Dishonored
+        );
+        assert_eq!(
+            ReturnClass::of(&ReturnReasonCode::R74),
+            ReturnClass::Contested
+        );
+    }
+
+    #[test]
+    fn test_reason_code_table() {
+        let code = ReturnReasonCode::from_code(" R01 ");
+        assert_eq!(code, ReturnReasonCode::R01);
+        assert_eq!(code.code(), "R01");
+        assert_eq!(code.description(), "Insufficient Funds");
+        assert_eq!(code.category(), ReturnCategory::AccountRelated);
+        assert!(code.allows_reinitiation());
+        assert!(!code.has_extended_return_window());
+
+        let code = ReturnReasonCode::from_code("R10");
+        assert_eq!(code.category(), ReturnCategory::Unauthorized);
+        assert!(code.has_extended_return_window());
+        assert!(!code.allows_reinitiation());
+
+        assert_eq!(ReturnReasonCode::R83.category(), ReturnCategory::Iat);
+        assert_eq!(ReturnReasonCode::R85.to_string(), "R85");
+    }
+
+    #[test]
+    fn test_unknown_reason_code() {
+        let code = ReturnReasonCode::from_code("R99");
+        assert_eq!(code, ReturnReasonCode::Other("R99".to_string()));
+        assert_eq!(code.code(), "R99");
+        assert_eq!(code.description(), "Unknown Return Reason");
+        assert_eq!(code.category(), ReturnCategory::Other);
+        assert!(!code.has_extended_return_window());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_reason_code_serializes_as_string() {
+        let json = serde_json::to_string(&[
+            ReturnReasonCode::R01,
+            ReturnReasonCode::Other("R99".to_string()),
+        ])
+        .unwrap();
+        assert_eq!(json, r#"["R01","R99"]"#);
+        let back: Vec<ReturnReasonCode> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back[1], ReturnReasonCode::Other("R99".to_string()));
+    }
+}
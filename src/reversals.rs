@@ -0,0 +1,335 @@
+//! Pairing of reversing entries with the originals they reverse.
+//!
+//! A reversal must be described `REVERSAL` and reach the receiver within
+//! five banking days of the erroneous entry's settlement.
+//! [`detect_reversals`] matches each reversing entry to an original in
+//! earlier files and reports what could not be accounted for.
+
+use std::collections::HashMap;
+
+use crate::banking_days::{FederalReserve, HolidayCalendar};
+use crate::controls::{is_credit, is_debit};
+use crate::dates::CivilDate;
+use crate::entry_description::EntryDescriptionKind;
+use crate::records::{BatchHeader, EntryDetail};
+use crate::AchFile;
+
+/// Most banking days allowed between an original and its reversal.
+pub const REVERSAL_WINDOW_BANKING_DAYS: u32 = 5;
+
+/// One entry on either side of a reversal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ReversalEntry {
+    /// Batch number of the entry's batch, trimmed
+    pub batch_number: String,
+
+    /// Trace number of the entry
+    pub trace_number: String,
+
+    /// Transaction code of the entry
+    pub transaction_code: String,
+
+    /// Amount in cents
+    pub amount: u64,
+
+    /// Effective entry date of the entry's batch, or `None` when blank or
+    /// invalid
+    pub effective_entry_date: Option<CivilDate>,
+}
+
+/// A reversing entry and the original it reverses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ReversalPair {
+    /// The reversing entry
+    pub reversal: ReversalEntry,
+
+    /// Index of the original's file in the `originals` slice
+    pub original_file_index: usize,
+
+    /// The reversed entry
+    pub original: ReversalEntry,
+
+    /// Banking days from the original's effective date to the reversal's,
+    /// or `None` when either date is unreadable or the reversal is dated
+    /// first
+    pub banking_days: Option<u32>,
+
+    /// Whether the reversal is dated more than
+    /// [`REVERSAL_WINDOW_BANKING_DAYS`] banking days after the original
+    pub late: bool,
+}
+
+/// An original matched by more than one reversing entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ReversedOriginal {
+    /// Index of the original's file in the `originals` slice
+    pub file_index: usize,
+
+    /// The original entry
+    pub original: ReversalEntry,
+
+    /// Number of reversing entries matched to it
+    pub reversal_count: usize,
+}
+
+/// Result of [`detect_reversals`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ReversalReport {
+    /// Matched reversals, in reversal file order
+    pub pairs: Vec<ReversalPair>,
+
+    /// Reversing entries with no matching original, in file order
+    pub unmatched: Vec<ReversalEntry>,
+
+    /// Originals reversed more than once, in the order first reversed
+    pub reversed_more_than_once: Vec<ReversedOriginal>,
+}
+
+impl ReversalReport {
+    /// Returns `true` if every reversal matched an original once, on time.
+    pub fn is_clean(&self) -> bool {
+        self.unmatched.is_empty()
+            && self.reversed_more_than_once.is_empty()
+            && self.pairs.iter().all(|p| !p.late)
+    }
+}
+
+/// What a reversing entry and its original have in common.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Key {
+    company_identification: String,
+    routing_number: String,
+    account_number: String,
+    amount: u64,
+    account_type: u8,
+}
+
+impl Key {
+    fn of(header: &BatchHeader<'_>, entry: &EntryDetail<'_>) -> Key {
+        Key {
+            company_identification: header.company_identification.trim().to_string(),
+            routing_number: format!(
+                "{}{}",
+                entry.receiving_dfi_identification.trim(),
+                entry.check_digit.trim()
+            ),
+            account_number: entry.dfi_account_number.trim().to_string(),
+            amount: entry.amount,
+            account_type: entry
+                .transaction_code
+                .as_bytes()
+                .first()
+                .copied()
+                .unwrap_or(0),
+        }
+    }
+}
+
+fn reversal_entry(header: &BatchHeader<'_>, entry: &EntryDetail<'_>) -> ReversalEntry {
+    ReversalEntry {
+        batch_number: header.batch_number.trim().to_string(),
+        trace_number: entry.trace_number.to_string(),
+        transaction_code: entry.transaction_code.to_string(),
+        amount: entry.amount,
+        effective_entry_date: CivilDate::parse(&header.effective_entry_date),
+    }
+}
+
+/// Match the entries of `REVERSAL` batches in `reversal_file` to the
+/// entries they reverse in `originals`.
+///
+/// An original matches a reversing entry with the same company
+/// identification, routing and account number, amount, and account type,
+/// and the opposite direction: a credit reverses a debit and vice versa.
+/// Originals not yet reversed are preferred, in file order; once all are
+/// used, the first is matched again and reported as reversed more than
+/// once. `REVERSAL` batches in `originals` are not candidates.
+pub fn detect_reversals(reversal_file: &AchFile<'_>, originals: &[&AchFile<'_>]) -> ReversalReport {
+    // Candidates by key and direction (true for debits).
+    let mut candidates: HashMap<(Key, bool), Vec<(usize, ReversalEntry)>> = HashMap::new();
+    for (file_index, file) in originals.iter().enumerate() {
+        for batch in &file.batches {
+            if batch.header.entry_description_kind() == EntryDescriptionKind::Reversal {
+                continue;
+            }
+            for entry in &batch.entries {
+                let debit = is_debit(&entry.transaction_code);
+                if debit || is_credit(&entry.transaction_code) {
+                    candidates
+                        .entry((Key::of(&batch.header, entry), debit))
+                        .or_default()
+                        .push((file_index, reversal_entry(&batch.header, entry)));
+                }
+            }
+        }
+    }
+
+    let mut report = ReversalReport::default();
+    // Reversal counts of each candidate, and the order of first reversals.
+    let mut counts: HashMap<(usize, String), usize> = HashMap::new();
+    let mut first_reversed: Vec<(usize, ReversalEntry)> = Vec::new();
+    for batch in &reversal_file.batches {
+        if batch.header.entry_description_kind() != EntryDescriptionKind::Reversal {
+            continue;
+        }
+        for entry in &batch.entries {
+            let reversal = reversal_entry(&batch.header, entry);
+            let debit = is_debit(&entry.transaction_code);
+            if !debit && !is_credit(&entry.transaction_code) {
+                report.unmatched.push(reversal);
+                continue;
+            }
+            let Some(found) = candidates.get(&(Key::of(&batch.header, entry), !debit)) else {
+                report.unmatched.push(reversal);
+                continue;
+            };
+            let count = |(file_index, original): &(usize, ReversalEntry)| {
+                counts
+                    .get(&(*file_index, original.trace_number.clone()))
+                    .copied()
+                    .unwrap_or(0)
+            };
+            let (file_index, original) = found
+                .iter()
+                .find(|candidate| count(candidate) == 0)
+                .unwrap_or(&found[0])
+                .clone();
+
+            let times = counts
+                .entry((file_index, original.trace_number.clone()))
+                .or_default();
+            *times += 1;
+            if *times == 1 {
+                first_reversed.push((file_index, original.clone()));
+            }
+
+            let banking_days =
+                banking_days_between(original.effective_entry_date, reversal.effective_entry_date);
+            report.pairs.push(ReversalPair {
+                late: banking_days.is_some_and(|d| d > REVERSAL_WINDOW_BANKING_DAYS),
+                reversal,
+                original_file_index: file_index,
+                original,
+                banking_days,
+            });
+        }
+    }
+
+    for (file_index, original) in first_reversed {
+        let reversal_count = counts[&(file_index, original.trace_number.clone())];
+        if reversal_count > 1 {
+            report.reversed_more_than_once.push(ReversedOriginal {
+                file_index,
+                original,
+                reversal_count,
+            });
+        }
+    }
+    report
+}
+
+/// Number of banking days after `from` up to and including `to`.
+fn banking_days_between(from: Option<CivilDate>, to: Option<CivilDate>) -> Option<u32> {
+    let (mut date, to) = (from?, to?);
+    if to < date {
+        return None;
+    }
+    let mut days = 0;
+    loop {
+        date = FederalReserve.next_banking_day(date)?;
+        if date > to {
+            return Some(days);
+        }
+        days += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    /// The PPD fixture with directions swapped, described `REVERSAL`, and
+    /// effective on `effective`.
+    fn reversing<'a>(text: &'a str, effective: &'static str) -> AchFile<'a> {
+        let mut file = AchFile::parse(text).unwrap();
+        let batch = &mut file.batches[0];
+        batch.header.company_entry_description = "REVERSAL  ".into();
+        batch.header.effective_entry_date = effective.into();
+        for entry in &mut batch.entries {
+            entry.transaction_code = match entry.transaction_code.as_ref() {
+                "22" => "27".into(),
+                _ => "22".into(),
+            };
+        }
+        file
+    }
+
+    #[test]
+    fn test_matched_reversals() {
+        let text = fixtures::sample_ppd_file(2);
+        let original = AchFile::parse(&text).unwrap();
+        // Original effective Wednesday, September 3, 2014.
+        let reversal = reversing(&text, "140908");
+
+        let report = detect_reversals(&reversal, &[&original]);
+        assert!(report.is_clean(), "{report:?}");
+        assert_eq!(report.pairs.len(), 2);
+        let pair = &report.pairs[0];
+        assert_eq!(pair.reversal.transaction_code, "27");
+        assert_eq!(pair.original.transaction_code, "22");
+        assert_eq!(pair.original.trace_number, "123456780000001");
+        assert_eq!(pair.original_file_index, 0);
+        assert_eq!(pair.banking_days, Some(3));
+    }
+
+    #[test]
+    fn test_late_unmatched_and_repeated() {
+        let text = fixtures::sample_ppd_file(2);
+        let original = AchFile::parse(&text).unwrap();
+        // September 4, 5, 8, 9, 10, and 11: six banking days.
+        let mut reversal = reversing(&text, "140911");
+        reversal.batches[0].entries[1].amount += 1;
+        let duplicate = reversal.batches[0].entries[0].clone();
+        reversal.batches[0].entries.push(duplicate);
+
+        let report = detect_reversals(&reversal, &[&original]);
+        assert!(!report.is_clean());
+        assert_eq!(report.pairs.len(), 2);
+        assert_eq!(report.pairs[0].banking_days, Some(6));
+        assert!(report.pairs[0].late);
+        assert_eq!(report.unmatched.len(), 1);
+        assert_eq!(report.unmatched[0].amount, 2_501);
+        let repeated = &report.reversed_more_than_once[0];
+        assert_eq!(repeated.original.trace_number, "123456780000001");
+        assert_eq!(repeated.reversal_count, 2);
+    }
+
+    #[test]
+    fn test_only_reversal_batches() {
+        let text = fixtures::sample_ppd_file(2);
+        let original = AchFile::parse(&text).unwrap();
+        let mut not_described = reversing(&text, "140905");
+        not_described.batches[0].header.company_entry_description = "PAYROLL".into();
+        assert_eq!(
+            detect_reversals(&not_described, &[&original]),
+            ReversalReport::default()
+        );
+
+        // Same direction is not a reversal.
+        let mut described = AchFile::parse(&text).unwrap();
+        described.batches[0].header.company_entry_description = "REVERSAL".into();
+        assert_eq!(
+            detect_reversals(&described, &[&original]).unmatched.len(),
+            2
+        );
+    }
+}
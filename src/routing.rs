@@ -0,0 +1,108 @@
+//! ABA routing number helpers.
+//!
+//! A routing number is nine digits: the eight-digit DFI identification
+//! followed by a check digit computed from weights 3, 7, 1.
+//!
+//! A [`RoutingDirectory`], such as one loaded from the FedACH directory,
+//! lets validation and reports look up the institution behind a routing
+//! number.
+
+use std::collections::HashMap;
+
+const WEIGHTS: [u32; 8] = [3, 7, 1, 3, 7, 1, 3, 7];
+
+/// Compute the check digit for an eight-digit DFI identification.
+///
+/// Returns `None` if `dfi` is not exactly eight ASCII digits.
+pub fn check_digit(dfi: &str) -> Option<u8> {
+    if dfi.len() != 8 || !dfi.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let sum: u32 = dfi
+        .bytes()
+        .zip(WEIGHTS)
+        .map(|(b, w)| u32::from(b - b'0') * w)
+        .sum();
+
+    Some(((10 - sum % 10) % 10) as u8)
+}
+
+/// Returns `true` if `routing` is nine digits with a correct check digit.
+pub fn is_valid(routing: &str) -> bool {
+    if routing.len() != 9 || !routing.bytes().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+    match (check_digit(&routing[..8]), routing.as_bytes()[8]) {
+        (Some(expected), last) if last.is_ascii_digit() => expected == last - b'0',
+        _ => false,
+    }
+}
+
+/// A financial institution's directory entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct InstitutionInfo {
+    /// Nine-digit routing number
+    pub routing_number: String,
+
+    /// Institution name
+    pub name: String,
+
+    /// Whether the routing number currently receives ACH entries
+    pub active: bool,
+}
+
+/// Looks up institutions by routing number.
+///
+/// Loading the directory is left to the implementation; a `HashMap` keyed
+/// by routing number works as an in-memory directory.
+pub trait RoutingDirectory {
+    /// The institution for a nine-digit `routing` number, or `None` if it
+    /// is not in the directory.
+    fn lookup(&self, routing: &str) -> Option<InstitutionInfo>;
+}
+
+impl RoutingDirectory for HashMap<String, InstitutionInfo> {
+    fn lookup(&self, routing: &str) -> Option<InstitutionInfo> {
+        self.get(routing.trim()).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_digit() {
+        assert_eq!(check_digit("12345678"), Some(0));
+        assert_eq!(check_digit("02100002"), Some(1));
+        assert_eq!(check_digit("1234567"), None);
+        assert_eq!(check_digit("1234567X"), None);
+    }
+
+    #[test]
+    fn test_is_valid() {
+        assert!(is_valid("123456780"));
+        assert!(is_valid("021000021"));
+        assert!(!is_valid("123456781"));
+        assert!(!is_valid("12345678"));
+        assert!(!is_valid("12345678X"));
+        // Nine bytes, but not nine characters.
+        assert!(!is_valid("1234567é"));
+        assert_eq!(check_digit("123456é"), None);
+    }
+
+    #[test]
+    fn test_hash_map_directory() {
+        let bank = InstitutionInfo {
+            routing_number: "021000021".to_string(),
+            name: "JPMORGAN CHASE".to_string(),
+            active: true,
+        };
+        let directory = HashMap::from([(bank.routing_number.clone(), bank.clone())]);
+        assert_eq!(directory.lookup(" 021000021 "), Some(bank));
+        assert_eq!(directory.lookup("123456780"), None);
+    }
+}
@@ -0,0 +1,260 @@
+//! Flat, owned entry rows for bulk loading into databases.
+
+use crate::dates::CivilDate;
+use crate::mask::MaskingPolicy;
+use crate::routing::RoutingDirectory;
+use crate::AchFile;
+
+/// One entry with its file and batch context, flattened for a table row.
+///
+/// Text fields are trimmed; optional fields are `None` when blank. Dates are
+/// ISO `YYYY-MM-DD` strings (two-digit years read as 20YY) and `None` when
+/// blank or invalid. Fields are only ever appended, never renamed or retyped.
+/// With the `serde` feature this type implements `Serialize` and
+/// `Deserialize`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct EntryRow {
+    /// Immediate origin from the file header
+    pub immediate_origin: String,
+
+    /// Immediate destination from the file header
+    pub immediate_destination: String,
+
+    /// File creation date
+    pub file_creation_date: Option<String>,
+
+    /// File ID modifier
+    pub file_id_modifier: String,
+
+    /// Batch number
+    pub batch_number: i64,
+
+    /// Service class code of the batch
+    pub service_class_code: String,
+
+    /// Company identification of the batch
+    pub company_identification: String,
+
+    /// Company name of the batch
+    pub company_name: String,
+
+    /// Standard entry class code of the batch
+    pub standard_entry_class_code: String,
+
+    /// Company entry description of the batch
+    pub company_entry_description: String,
+
+    /// Effective entry date of the batch
+    pub effective_entry_date: Option<String>,
+
+    /// Transaction code
+    pub transaction_code: String,
+
+    /// Full 9-digit routing number (RDFI identification and check digit)
+    pub routing_number: String,
+
+    /// DFI account number
+    pub dfi_account_number: String,
+
+    /// Amount in cents
+    pub amount: i64,
+
+    /// Individual identification number
+    pub individual_identification_number: Option<String>,
+
+    /// Individual name
+    pub individual_name: Option<String>,
+
+    /// Discretionary data
+    pub discretionary_data: Option<String>,
+
+    /// Trace number
+    pub trace_number: String,
+
+    /// Number of addenda records
+    pub addenda_count: i32,
+
+    /// Payment related information of every addenda, concatenated in order
+    /// with trailing padding removed
+    pub addenda_text: Option<String>,
+
+    /// Name of the receiving institution, when rows are looked up in a
+    /// [`RoutingDirectory`] that knows the routing number
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub institution_name: Option<String>,
+}
+
+impl AchFile<'_> {
+    /// Flatten every entry into an [`EntryRow`], in file order.
+    pub fn to_rows(&self) -> Vec<EntryRow> {
+        let header = &self.file_header;
+        let optional = |value: &str| Some(value.trim().to_string()).filter(|s| !s.is_empty());
+        let iso = |value: &str| CivilDate::parse(value).map(CivilDate::to_iso);
+
+        let mut rows = Vec::new();
+        for batch in &self.batches {
+            let batch_header = &batch.header;
+            for entry in &batch.entries {
+                let addenda_text: String = entry
+                    .addenda
+                    .iter()
+                    .map(|a| a.payment_related_information.as_ref())
+                    .collect();
+
+                rows.push(EntryRow {
+                    immediate_origin: header.immediate_origin.trim().to_string(),
+                    immediate_destination: header.immediate_destination.trim().to_string(),
+                    file_creation_date: iso(&header.file_creation_date),
+                    file_id_modifier: header.file_id_modifier.trim().to_string(),
+                    batch_number: batch_header.batch_number.trim().parse().unwrap_or(0),
+                    service_class_code: batch_header.service_class_code.to_string(),
+                    company_identification: batch_header.company_identification.trim().to_string(),
+                    company_name: batch_header.company_name.trim().to_string(),
+                    standard_entry_class_code: batch_header.standard_entry_class_code.to_string(),
+                    company_entry_description: batch_header
+                        .company_entry_description
+                        .trim()
+                        .to_string(),
+                    effective_entry_date: iso(&batch_header.effective_entry_date),
+                    transaction_code: entry.transaction_code.to_string(),
+                    routing_number: format!(
+                        "{}{}",
+                        entry.receiving_dfi_identification, entry.check_digit
+                    ),
+                    dfi_account_number: entry.dfi_account_number.trim().to_string(),
+                    amount: entry.amount as i64,
+                    individual_identification_number: optional(
+                        &entry.individual_identification_number,
+                    ),
+                    individual_name: optional(&entry.individual_name),
+                    discretionary_data: optional(&entry.discretionary_data),
+                    trace_number: entry.trace_number.to_string(),
+                    addenda_count: entry.addenda.len() as i32,
+                    addenda_text: Some(addenda_text.trim_end().to_string())
+                        .filter(|s| !s.is_empty()),
+                    institution_name: None,
+                });
+            }
+        }
+        rows
+    }
+
+    /// Like [`to_rows`](AchFile::to_rows), with
+    /// [`institution_name`](EntryRow::institution_name) looked up in
+    /// `directory`.
+    pub fn to_rows_with_directory(&self, directory: &dyn RoutingDirectory) -> Vec<EntryRow> {
+        let mut rows = self.to_rows();
+        for row in &mut rows {
+            row.institution_name = directory.lookup(&row.routing_number).map(|i| i.name);
+        }
+        rows
+    }
+
+    /// Like [`to_rows`](AchFile::to_rows), with account numbers, names, and
+    /// identification numbers masked by `policy`.
+    pub fn to_rows_masked(&self, policy: &MaskingPolicy) -> Vec<EntryRow> {
+        let mut rows = self.to_rows();
+        for row in &mut rows {
+            row.dfi_account_number = policy.account(&row.dfi_account_number);
+            row.individual_name = row.individual_name.as_deref().map(|n| policy.name(n));
+            row.individual_identification_number = row
+                .individual_identification_number
+                .as_deref()
+                .map(|id| policy.identification(id));
+        }
+        rows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_to_rows() {
+        let text = fixtures::sample_ppd_file(2);
+        let rows = AchFile::parse(&text).unwrap().to_rows();
+        assert_eq!(rows.len(), 2);
+
+        let row = &rows[1];
+        assert_eq!(row.immediate_destination, "123456780");
+        assert_eq!(row.file_creation_date.as_deref(), Some("2014-09-02"));
+        assert_eq!(row.batch_number, 1);
+        assert_eq!(row.effective_entry_date.as_deref(), Some("2014-09-03"));
+        assert_eq!(row.transaction_code, "27");
+        assert_eq!(row.routing_number, "123232315");
+        assert_eq!(row.dfi_account_number, "000100001");
+        assert_eq!(row.amount, 2_500);
+        assert_eq!(row.individual_name.as_deref(), Some("RECEIVER 2"));
+        assert_eq!(row.individual_identification_number, None);
+        assert_eq!(row.trace_number, "123456780000002");
+        assert_eq!(row.addenda_text, None);
+    }
+
+    #[test]
+    fn test_to_rows_masked() {
+        let text = fixtures::sample_ppd_file(1);
+        let mut file = AchFile::parse(&text).unwrap();
+        file.batches[0].entries[0].individual_identification_number = "EMP0042        ".into();
+        let row = file.to_rows_masked(&MaskingPolicy::default()).remove(0);
+        assert_eq!(row.dfi_account_number, "*****0000");
+        assert_eq!(row.individual_name.as_deref(), Some("R 1"));
+        assert_eq!(
+            row.individual_identification_number.as_deref(),
+            Some("****042")
+        );
+        assert_eq!(
+            file.to_rows_masked(&MaskingPolicy::unmasked()),
+            file.to_rows()
+        );
+    }
+
+    #[test]
+    fn test_to_rows_with_directory() {
+        use crate::routing::InstitutionInfo;
+        use std::collections::HashMap;
+
+        let text = fixtures::sample_ppd_file(2);
+        let file = AchFile::parse(&text).unwrap();
+        let bank = InstitutionInfo {
+            routing_number: "123232315".to_string(),
+            name: "SECOND BANK".to_string(),
+            active: true,
+        };
+        let directory = HashMap::from([(bank.routing_number.clone(), bank)]);
+        let rows = file.to_rows_with_directory(&directory);
+        assert_eq!(rows[0].institution_name, None);
+        assert_eq!(rows[1].institution_name.as_deref(), Some("SECOND BANK"));
+    }
+
+    #[test]
+    fn test_addenda_text_is_concatenated() {
+        let text = fixtures::sample_ctx_with_addenda();
+        let rows = AchFile::parse(&text).unwrap().to_rows();
+        let addenda_text = rows[0].addenda_text.as_deref().unwrap();
+
+        assert_eq!(rows[0].addenda_count, 3);
+        assert!(addenda_text.starts_with("ISA*00*"));
+        assert!(addenda_text.contains("BPR*C*1250.00"));
+        assert!(addenda_text.ends_with("SE*5*0001~"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_row_serializes() {
+        let text = fixtures::sample_ppd_file(1);
+        let row = AchFile::parse(&text).unwrap().to_rows().remove(0);
+        let json = serde_json::to_value(&row).unwrap();
+        assert_eq!(json["amount"], 1_250);
+        assert_eq!(
+            json["individual_identification_number"],
+            serde_json::Value::Null
+        );
+
+        let back: EntryRow = serde_json::from_value(json).unwrap();
+        assert_eq!(back, row);
+    }
+}
@@ -0,0 +1,385 @@
+//! Screening the names in a file against a sanctions or watch list.
+
+use crate::records::{BatchHeader, EntryDetail};
+use crate::{AchFile, IatAddenda};
+
+/// Whose name is being screened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub enum ScreenedParty {
+    /// The originating company named in a batch header, or in an IAT
+    /// entry's type 11 addenda
+    Originator,
+
+    /// The receiver of an entry: the individual name, the receiving company
+    /// name for CTX and CIE entries, or the receiver name in an IAT entry's
+    /// type 10 addenda
+    Receiver,
+
+    /// The originating DFI named in an IAT entry's type 13 addenda
+    OriginatingDfi,
+
+    /// The receiving DFI named in an IAT entry's type 14 addenda
+    ReceivingDfi,
+
+    /// A foreign correspondent bank named in an IAT entry's type 18 addenda
+    ForeignCorrespondentBank,
+}
+
+/// Where a name passed to [`NameScreener::screen`] comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ScreenContext<'a> {
+    /// Whose name it is
+    pub party: ScreenedParty,
+
+    /// Batch number of the batch, trimmed
+    pub batch_number: &'a str,
+
+    /// Standard entry class code of the batch
+    pub standard_entry_class_code: &'a str,
+
+    /// Trace number of the entry, for a name from an entry
+    pub trace_number: Option<&'a str>,
+}
+
+/// The outcome of screening one name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScreenResult {
+    /// The name matches nothing on the list
+    Clear,
+
+    /// The name matches a list entry
+    Match {
+        /// The list entry it matches, as the screener names it
+        matched_name: String,
+    },
+}
+
+/// A screening engine, such as an OFAC SDN list lookup, called by
+/// [`AchFile::screen`] for each name in a file.
+///
+/// Closures taking a name and a [`ScreenContext`] are screeners.
+pub trait NameScreener {
+    /// Screen one name, trimmed and never blank.
+    fn screen(&self, name: &str, context: ScreenContext<'_>) -> ScreenResult;
+}
+
+impl<F> NameScreener for F
+where
+    F: Fn(&str, ScreenContext<'_>) -> ScreenResult,
+{
+    fn screen(&self, name: &str, context: ScreenContext<'_>) -> ScreenResult {
+        self(name, context)
+    }
+}
+
+/// A name that matched, from [`AchFile::screen`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ScreenHit {
+    /// Whose name it is
+    pub party: ScreenedParty,
+
+    /// The name as screened
+    pub name: String,
+
+    /// The list entry it matched
+    pub matched_name: String,
+}
+
+/// The hits on a batch's originator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct BatchHits {
+    /// Batch number of the batch, trimmed
+    pub batch_number: String,
+
+    /// The hits
+    pub hits: Vec<ScreenHit>,
+}
+
+/// The hits on one entry's parties.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct EntryHits {
+    /// Batch number of the entry's batch, trimmed
+    pub batch_number: String,
+
+    /// Trace number of the entry
+    pub trace_number: String,
+
+    /// The hits
+    pub hits: Vec<ScreenHit>,
+}
+
+/// Result of [`AchFile::screen`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ScreeningReport {
+    /// Number of names passed to the screener
+    pub names_screened: usize,
+
+    /// Batches whose originator matched, in file order
+    pub batches: Vec<BatchHits>,
+
+    /// Entries with a party that matched, in file order
+    pub entries: Vec<EntryHits>,
+}
+
+impl ScreeningReport {
+    /// Whether no name matched.
+    pub fn is_clear(&self) -> bool {
+        self.batches.is_empty() && self.entries.is_empty()
+    }
+}
+
+impl AchFile<'_> {
+    /// Pass every name in the file to `screener`: each batch's company name
+    /// and each entry's receiver name. Blank names are skipped.
+    ///
+    /// IAT batch headers carry no company name; instead each IAT entry's
+    /// originator, receiver, originating and receiving DFIs, and foreign
+    /// correspondent banks are screened from its addenda.
+    ///
+    /// Run it before the file is released, since a hit on an originator
+    /// holds the whole batch and a hit on a receiver holds the entry.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile, ScreenContext, ScreenResult, ScreenedParty};
+    ///
+    /// let text = fixtures::sample_multi_company_file();
+    /// let file = AchFile::parse(&text).unwrap();
+    /// let list = ["RECEIVER 2", "BETA LLC"];
+    /// let screener = |name: &str, _: ScreenContext<'_>| {
+    ///     if list.contains(&name) {
+    ///         ScreenResult::Match { matched_name: name.to_string() }
+    ///     } else {
+    ///         ScreenResult::Clear
+    ///     }
+    /// };
+    ///
+    /// let report = file.screen(&screener);
+    /// assert_eq!(report.names_screened, 10);
+    /// assert_eq!(report.batches[0].batch_number, "0000003");
+    /// assert_eq!(report.entries.len(), 3);
+    /// assert_eq!(report.entries[0].trace_number, "123456780000002");
+    /// assert_eq!(report.entries[0].hits[0].party, ScreenedParty::Receiver);
+    /// ```
+    pub fn screen(&self, screener: &dyn NameScreener) -> ScreeningReport {
+        let mut report = ScreeningReport::default();
+        for batch in &self.batches {
+            let header = &batch.header;
+            let batch_number = header.batch_number.trim();
+            let context = ScreenContext {
+                party: ScreenedParty::Originator,
+                batch_number,
+                standard_entry_class_code: &header.standard_entry_class_code,
+                trace_number: None,
+            };
+            let iat = header.as_iat().is_some();
+            let company_name = if iat { "" } else { &*header.company_name };
+            let hits = screen_names(screener, &mut report, [(company_name, context)]);
+            if !hits.is_empty() {
+                report.batches.push(BatchHits {
+                    batch_number: batch_number.to_string(),
+                    hits,
+                });
+            }
+
+            for entry in &batch.entries {
+                let context = ScreenContext {
+                    party: ScreenedParty::Receiver,
+                    trace_number: Some(&entry.trace_number),
+                    ..context
+                };
+                let hits = if iat {
+                    let names = iat_names(entry);
+                    let names = names.iter().map(|(party, name)| {
+                        let context = ScreenContext {
+                            party: *party,
+                            ..context
+                        };
+                        (name.as_str(), context)
+                    });
+                    screen_names(screener, &mut report, names)
+                } else {
+                    screen_names(
+                        screener,
+                        &mut report,
+                        [(receiver_name(header, entry), context)],
+                    )
+                };
+                if !hits.is_empty() {
+                    report.entries.push(EntryHits {
+                        batch_number: batch_number.to_string(),
+                        trace_number: entry.trace_number.to_string(),
+                        hits,
+                    });
+                }
+            }
+        }
+        report
+    }
+}
+
+/// Screen each nonblank name, counting it in the report, and return the
+/// hits.
+fn screen_names<'a>(
+    screener: &dyn NameScreener,
+    report: &mut ScreeningReport,
+    names: impl IntoIterator<Item = (&'a str, ScreenContext<'a>)>,
+) -> Vec<ScreenHit> {
+    let mut hits = Vec::new();
+    for (name, context) in names {
+        let name = name.trim();
+        if name.is_empty() {
+            continue;
+        }
+        report.names_screened += 1;
+        if let ScreenResult::Match { matched_name } = screener.screen(name, context) {
+            hits.push(ScreenHit {
+                party: context.party,
+                name: name.to_string(),
+                matched_name,
+            });
+        }
+    }
+    hits
+}
+
+/// The receiver's name. CTX entries carry the receiving company name in
+/// positions 59-74, after the addenda count; CIE entries carry the
+/// individual name in the identification number's positions 40-54.
+fn receiver_name<'a>(header: &BatchHeader<'_>, entry: &'a EntryDetail<'_>) -> &'a str {
+    match header.standard_entry_class_code.trim() {
+        "CTX" => entry.individual_name.get(4..20).unwrap_or_default(),
+        "CIE" => &entry.individual_identification_number,
+        _ => &entry.individual_name,
+    }
+}
+
+/// The parties named in an IAT entry's addenda, in addenda order.
+fn iat_names(entry: &EntryDetail<'_>) -> Vec<(ScreenedParty, String)> {
+    entry
+        .as_iat()
+        .addenda
+        .into_iter()
+        .filter_map(|addenda| match addenda {
+            IatAddenda::Transaction { receiver_name, .. } => {
+                Some((ScreenedParty::Receiver, receiver_name))
+            }
+            IatAddenda::Originator { name, .. } => Some((ScreenedParty::Originator, name)),
+            IatAddenda::OriginatingDfi(bank) => Some((ScreenedParty::OriginatingDfi, bank.name)),
+            IatAddenda::ReceivingDfi(bank) => Some((ScreenedParty::ReceivingDfi, bank.name)),
+            IatAddenda::ForeignCorrespondentBank { bank, .. } => {
+                Some((ScreenedParty::ForeignCorrespondentBank, bank.name))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_screen() {
+        let text = fixtures::sample_multi_company_file();
+        let mut file = AchFile::parse(&text).unwrap();
+        file.batches[0].entries[1].individual_name = " ".repeat(22).into();
+
+        let seen = RefCell::new(Vec::new());
+        let screener = |name: &str, context: ScreenContext<'_>| {
+            seen.borrow_mut().push((name.to_string(), context.party));
+            if name.starts_with("ACME") || name == "RECEIVER 3" {
+                ScreenResult::Match {
+                    matched_name: "ACME CORPORATION".to_string(),
+                }
+            } else {
+                ScreenResult::Clear
+            }
+        };
+        let report = file.screen(&screener);
+
+        assert_eq!(report.names_screened, 9);
+        assert_eq!(seen.borrow().len(), 9);
+        assert_eq!(
+            seen.borrow()[0],
+            ("ACME CORP".to_string(), ScreenedParty::Originator)
+        );
+        let batches: Vec<&str> = report
+            .batches
+            .iter()
+            .map(|b| b.batch_number.as_str())
+            .collect();
+        assert_eq!(batches, ["0000001", "0000002"]);
+        assert_eq!(report.batches[0].hits[0].matched_name, "ACME CORPORATION");
+        assert_eq!(report.entries.len(), 1);
+        assert_eq!(report.entries[0].batch_number, "0000003");
+        assert_eq!(report.entries[0].trace_number, "123456780000003");
+        assert_eq!(report.entries[0].hits[0].name, "RECEIVER 3");
+        assert!(!report.is_clear());
+    }
+
+    #[test]
+    fn test_screen_ctx_receiver() {
+        let text = fixtures::sample_ctx_with_addenda();
+        let file = AchFile::parse(&text).unwrap();
+        let names = RefCell::new(Vec::new());
+        let report = file.screen(&|name: &str, context: ScreenContext<'_>| {
+            names.borrow_mut().push(name.to_string());
+            assert_eq!(context.standard_entry_class_code, "CTX");
+            ScreenResult::Clear
+        });
+        assert!(report.is_clear());
+        assert_eq!(names.borrow()[1], "ACME CORP");
+    }
+
+    #[test]
+    fn test_screen_iat_parties() {
+        let text = fixtures::sample_iat_file();
+        let file = AchFile::parse(&text).unwrap();
+        let parties = RefCell::new(Vec::new());
+        let report = file.screen(&|name: &str, context: ScreenContext<'_>| {
+            parties.borrow_mut().push(context.party);
+            if name == "HANS MUELLER" || name == "CORRESPONDENT BANK" {
+                ScreenResult::Match {
+                    matched_name: name.to_string(),
+                }
+            } else {
+                ScreenResult::Clear
+            }
+        });
+
+        assert_eq!(
+            *parties.borrow(),
+            [
+                ScreenedParty::Receiver,
+                ScreenedParty::Originator,
+                ScreenedParty::OriginatingDfi,
+                ScreenedParty::ReceivingDfi,
+                ScreenedParty::ForeignCorrespondentBank,
+            ]
+        );
+        assert!(report.batches.is_empty());
+        let hits: Vec<ScreenedParty> = report.entries[0].hits.iter().map(|h| h.party).collect();
+        assert_eq!(
+            hits,
+            [
+                ScreenedParty::Receiver,
+                ScreenedParty::ForeignCorrespondentBank
+            ]
+        );
+    }
+}
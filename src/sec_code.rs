@@ -0,0 +1,211 @@
+//! Standard entry class codes.
+
+use std::fmt;
+
+use crate::records::BatchHeader;
+use crate::AchError;
+
+/// A batch's standard entry class code, from [`BatchHeader::sec_code`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
+pub enum SecCode {
+    /// `ACK`: acknowledgment of a CCD entry
+    Ack,
+
+    /// `ADV`: automated accounting advice
+    Adv,
+
+    /// `ARC`: accounts receivable check conversion
+    Arc,
+
+    /// `ATX`: acknowledgment of a CTX entry
+    Atx,
+
+    /// `BOC`: back office check conversion
+    Boc,
+
+    /// `CCD`: corporate credit or debit
+    Ccd,
+
+    /// `CIE`: customer initiated entry
+    Cie,
+
+    /// `COR`: notification of change or refused notification of change
+    Cor,
+
+    /// `CTX`: corporate trade exchange
+    Ctx,
+
+    /// `DNE`: death notification
+    Dne,
+
+    /// `ENR`: automated enrollment
+    Enr,
+
+    /// `IAT`: international ACH transaction
+    Iat,
+
+    /// `MTE`: machine transfer entry
+    Mte,
+
+    /// `POP`: point of purchase check conversion
+    Pop,
+
+    /// `POS`: point of sale
+    Pos,
+
+    /// `PPD`: prearranged payment and deposit
+    Ppd,
+
+    /// `RCK`: re-presented check
+    Rck,
+
+    /// `SHR`: shared network transaction
+    Shr,
+
+    /// `TEL`: telephone-initiated entry
+    Tel,
+
+    /// `TRC`: truncated check
+    Trc,
+
+    /// `TRX`: truncated checks exchange
+    Trx,
+
+    /// `WEB`: internet-initiated or mobile entry
+    Web,
+
+    /// `XCK`: destroyed check
+    Xck,
+}
+
+/// Every code, as it appears in the field.
+const SEC_CODES: &[(&str, SecCode)] = &[
+    ("ACK", SecCode::Ack),
+    ("ADV", SecCode::Adv),
+    ("ARC", SecCode::Arc),
+    ("ATX", SecCode::Atx),
+    ("BOC", SecCode::Boc),
+    ("CCD", SecCode::Ccd),
+    ("CIE", SecCode::Cie),
+    ("COR", SecCode::Cor),
+    ("CTX", SecCode::Ctx),
+    ("DNE", SecCode::Dne),
+    ("ENR", SecCode::Enr),
+    ("IAT", SecCode::Iat),
+    ("MTE", SecCode::Mte),
+    ("POP", SecCode::Pop),
+    ("POS", SecCode::Pos),
+    ("PPD", SecCode::Ppd),
+    ("RCK", SecCode::Rck),
+    ("SHR", SecCode::Shr),
+    ("TEL", SecCode::Tel),
+    ("TRC", SecCode::Trc),
+    ("TRX", SecCode::Trx),
+    ("WEB", SecCode::Web),
+    ("XCK", SecCode::Xck),
+];
+
+impl SecCode {
+    /// Read a code such as `PPD`, or `None` if NACHA does not define it.
+    /// Surrounding spaces are ignored.
+    pub fn from_code(code: &str) -> Option<SecCode> {
+        let code = code.trim();
+        SEC_CODES
+            .iter()
+            .find(|(known, _)| *known == code)
+            .map(|&(_, sec)| sec)
+    }
+
+    /// The code as it appears in the field, e.g. `PPD`.
+    pub fn code(self) -> &'static str {
+        SEC_CODES
+            .iter()
+            .find(|(_, sec)| *sec == self)
+            .map_or("", |(code, _)| code)
+    }
+
+    /// Whether the crate reads this code's entries and addenda field by
+    /// field, with the standard layouts or, for IAT and ADV, the views of
+    /// [`BatchHeader::as_iat`] and
+    /// [`EntryDetail::as_adv`](crate::EntryDetail::as_adv). Others have their
+    /// own entry or addenda layouts, which are parsed as the standard ones
+    /// and only handled generically: the truncated check codes TRC and TRX,
+    /// and the card codes MTE, POS, and SHR, whose type 02 addenda
+    /// [`Addenda::as_terminal`](crate::Addenda::as_terminal) reads.
+    pub fn is_supported(self) -> bool {
+        !matches!(
+            self,
+            SecCode::Mte | SecCode::Pos | SecCode::Shr | SecCode::Trc | SecCode::Trx
+        )
+    }
+}
+
+impl fmt::Display for SecCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl BatchHeader<'_> {
+    /// The batch's standard entry class code, or [`AchError::InvalidValue`]
+    /// if NACHA does not define it.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile, SecCode};
+    ///
+    /// let text = fixtures::sample_ctx_with_addenda();
+    /// let file = AchFile::parse(&text).unwrap();
+    /// let sec = file.batches[0].header.sec_code().unwrap();
+    /// assert_eq!(sec, SecCode::Ctx);
+    /// assert!(sec.is_supported());
+    /// ```
+    pub fn sec_code(&self) -> Result<SecCode, AchError> {
+        SecCode::from_code(&self.standard_entry_class_code).ok_or_else(|| AchError::InvalidValue {
+            field: "standard_entry_class_code",
+            reason: format!("{:?} is not a NACHA code", self.standard_entry_class_code),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use crate::{AchFile, ParseOptions, ParseWarningCode};
+
+    #[test]
+    fn test_sec_code_table() {
+        for &(code, sec) in SEC_CODES {
+            assert_eq!(SecCode::from_code(code), Some(sec));
+            assert_eq!(sec.to_string(), code);
+        }
+        assert_eq!(SecCode::from_code(" WEB "), Some(SecCode::Web));
+        assert_eq!(SecCode::from_code("web"), None);
+        assert!(SecCode::Ppd.is_supported());
+        assert!(SecCode::Iat.is_supported());
+        assert!(SecCode::Adv.is_supported());
+        assert!(!SecCode::Trc.is_supported());
+    }
+
+    #[test]
+    fn test_unknown_sec_code() {
+        let text = fixtures::sample_multi_company_file().replacen("PPD", "XYZ", 1);
+        let (file, warnings) = AchFile::parse_with(&text, &ParseOptions::default()).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, ParseWarningCode::UnknownSecCode);
+        assert_eq!(warnings[0].line, 2);
+        let err = file.batches[0].header.sec_code().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid value for 'standard_entry_class_code': \"XYZ\" is not a NACHA code"
+        );
+
+        let strict = ParseOptions::strict(&[ParseWarningCode::UnknownSecCode]);
+        let err = AchFile::parse_with(&text, &strict).unwrap_err();
+        assert!(matches!(err, AchError::InBatch { batch_index: 0, .. }));
+        assert!(AchFile::records_with(&text, &strict).any(|r| r.is_err()));
+    }
+}
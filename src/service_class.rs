@@ -0,0 +1,170 @@
+//! Service class codes.
+
+use std::fmt;
+
+use crate::records::{BatchControl, BatchHeader};
+use crate::AchError;
+
+/// Which kinds of entry a batch holds, from its service class code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum ServiceClassCode {
+    /// `200`: debits and credits
+    Mixed,
+
+    /// `220`: credits only
+    CreditsOnly,
+
+    /// `225`: debits only
+    DebitsOnly,
+
+    /// `280`: automated accounting advices (ADV)
+    AutomatedAccountingAdvices,
+}
+
+impl ServiceClassCode {
+    /// Read a code such as `220`, or `None` if NACHA does not define it.
+    /// Surrounding spaces are ignored.
+    pub fn from_code(code: &str) -> Option<ServiceClassCode> {
+        match code.trim() {
+            "200" => Some(ServiceClassCode::Mixed),
+            "220" => Some(ServiceClassCode::CreditsOnly),
+            "225" => Some(ServiceClassCode::DebitsOnly),
+            "280" => Some(ServiceClassCode::AutomatedAccountingAdvices),
+            _ => None,
+        }
+    }
+
+    /// The code as it appears in the field, e.g. `220`.
+    pub fn code(self) -> &'static str {
+        match self {
+            ServiceClassCode::Mixed => "200",
+            ServiceClassCode::CreditsOnly => "220",
+            ServiceClassCode::DebitsOnly => "225",
+            ServiceClassCode::AutomatedAccountingAdvices => "280",
+        }
+    }
+}
+
+impl fmt::Display for ServiceClassCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// Read `code`, or fail with [`AchError::InvalidValue`].
+fn service_class_code(code: &str) -> Result<ServiceClassCode, AchError> {
+    ServiceClassCode::from_code(code).ok_or_else(|| AchError::InvalidValue {
+        field: "service_class_code",
+        reason: format!("{code:?} is not a NACHA code"),
+    })
+}
+
+impl BatchHeader<'_> {
+    /// The header's service class code, or [`AchError::InvalidValue`] if
+    /// NACHA does not define it.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile, ServiceClassCode};
+    ///
+    /// let text = fixtures::sample_ppd_file(2);
+    /// let file = AchFile::parse(&text).unwrap();
+    /// let batch = &file.batches[0];
+    /// assert_eq!(batch.header.service_class_code().unwrap(), ServiceClassCode::Mixed);
+    /// assert_eq!(batch.control.service_class_code().unwrap(), ServiceClassCode::Mixed);
+    /// ```
+    pub fn service_class_code(&self) -> Result<ServiceClassCode, AchError> {
+        service_class_code(&self.service_class_code)
+    }
+}
+
+impl BatchControl {
+    /// The control's service class code, as
+    /// [`BatchHeader::service_class_code`].
+    pub fn service_class_code(&self) -> Result<ServiceClassCode, AchError> {
+        service_class_code(&self.service_class_code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, AchFile};
+
+    #[test]
+    fn test_service_class_codes() {
+        for code in ["200", "220", "225", "280"] {
+            let class = ServiceClassCode::from_code(code).unwrap();
+            assert_eq!(class.to_string(), code);
+        }
+        assert_eq!(
+            ServiceClassCode::from_code(" 225"),
+            Some(ServiceClassCode::DebitsOnly)
+        );
+        assert_eq!(ServiceClassCode::from_code("210"), None);
+
+        let err = service_class_code("2 0").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid value for 'service_class_code': \"2 0\" is not a NACHA code"
+        );
+    }
+
+    #[test]
+    fn test_header_and_control_codes() {
+        let text = fixtures::sample_ppd_file(1);
+        let mut file = AchFile::parse(&text).unwrap();
+        let batch = &mut file.batches[0];
+        batch.header.service_class_code = "220".into();
+        batch.control.service_class_code = "225".to_string();
+        assert_eq!(
+            batch.header.service_class_code().unwrap(),
+            ServiceClassCode::CreditsOnly
+        );
+        assert_eq!(
+            batch.control.service_class_code().unwrap(),
+            ServiceClassCode::DebitsOnly
+        );
+
+        batch.header.service_class_code = "999".into();
+        batch.control.service_class_code = "   ".to_string();
+        assert_eq!(
+            batch.header.service_class_code().unwrap_err().to_string(),
+            "Invalid value for 'service_class_code': \"999\" is not a NACHA code"
+        );
+        assert!(batch.control.service_class_code().is_err());
+    }
+
+    #[test]
+    fn test_entries_on_the_wrong_side() {
+        // The fixture's only entry is a credit.
+        let text = fixtures::sample_ppd_file(1);
+        let mut file = AchFile::parse(&text).unwrap();
+        file.batches[0].header.service_class_code = "225".into();
+        file.batches[0].control.service_class_code = "225".to_string();
+        let errors: Vec<String> = file.validate().errors().map(|i| i.to_string()).collect();
+        assert_eq!(
+            errors,
+            [
+                "error: batch 0000001: entry 123456780000001: transaction_code: \
+              service class 225 allows only debits, transaction code 22 is not one"
+            ]
+        );
+
+        file.batches[0].header.service_class_code = "220".into();
+        file.batches[0].control.service_class_code = "220".to_string();
+        assert!(file.validate().is_valid());
+        file.batches[0].entries[0].transaction_code = "27".into();
+        file.batches[0].recompute_control();
+        file.recompute_file_control();
+        let errors: Vec<String> = file.validate().errors().map(|i| i.to_string()).collect();
+        assert_eq!(
+            errors,
+            [
+                "error: batch 0000001: entry 123456780000001: transaction_code: \
+              service class 220 allows only credits, transaction code 27 is not one"
+            ]
+        );
+    }
+}
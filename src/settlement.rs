@@ -0,0 +1,301 @@
+//! Expected settlement dates from effective dates and processing windows.
+
+use crate::banking_days::{FederalReserve, HolidayCalendar, BANKING_DAY_SEARCH_DAYS};
+use crate::dates::{parse_hhmm, CalendarDate, CivilDate, YearPivot};
+use crate::error::AchError;
+use crate::records::FileHeader;
+use crate::Batch;
+
+/// ODFI processing deadlines used to estimate settlement.
+///
+/// Times are `(hour, minute)` on a 24-hour clock in the time zone of the
+/// file creation time. A file created at or before a cutoff makes it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleConfig {
+    /// Same-day submission deadlines, in any order. Empty if the ODFI does
+    /// not offer same-day processing.
+    pub same_day_cutoffs: Vec<(u32, u32)>,
+
+    /// Deadline for next-day settlement. Later files settle a banking day
+    /// later still.
+    pub next_day_cutoff: (u32, u32),
+
+    /// How two-digit years in the effective entry and file creation dates
+    /// are placed
+    pub year_pivot: YearPivot,
+}
+
+impl Default for ScheduleConfig {
+    /// The Federal Reserve same-day deadlines (10:30, 14:45, and 16:45), a
+    /// 17:00 next-day cutoff, and the default [`YearPivot`].
+    fn default() -> Self {
+        ScheduleConfig {
+            same_day_cutoffs: vec![(10, 30), (14, 45), (16, 45)],
+            next_day_cutoff: (17, 0),
+            year_pivot: YearPivot::default(),
+        }
+    }
+}
+
+impl ScheduleConfig {
+    /// The first date a file created at `created` (date and `(hour,
+    /// minute)`) can settle, or `None` if `calendar` has no banking day to
+    /// settle on.
+    fn earliest_settlement(
+        &self,
+        date: CivilDate,
+        time: (u32, u32),
+        calendar: &dyn HolidayCalendar,
+    ) -> Option<CivilDate> {
+        if !calendar.is_banking_day(date) {
+            // Created on a closed day: processed at the start of the next
+            // banking day, in time for its first window.
+            let next = calendar.next_banking_day(date)?;
+            return if self.same_day_cutoffs.is_empty() {
+                calendar.next_banking_day(next)
+            } else {
+                Some(next)
+            };
+        }
+        if self.same_day_cutoffs.iter().any(|&cutoff| time <= cutoff) {
+            Some(date)
+        } else if time <= self.next_day_cutoff {
+            calendar.next_banking_day(date)
+        } else {
+            calendar.add_banking_days(date, 2)
+        }
+    }
+}
+
+impl Batch<'_> {
+    /// Estimate the date this batch settles when submitted in the file with
+    /// `file_header`.
+    ///
+    /// The effective entry date rolls forward to a banking day, and the
+    /// batch cannot settle before the file's creation time allows under
+    /// `config`. A blank file creation time is read as midnight. Fails if
+    /// the effective entry date or file creation date is blank or invalid,
+    /// or if the calendar has no banking day to settle on.
+    pub fn expected_settlement_date<D: CalendarDate>(
+        &self,
+        file_header: &FileHeader<'_>,
+        config: &ScheduleConfig,
+    ) -> Result<D, AchError> {
+        self.expected_settlement_date_with(file_header, config, &FederalReserve)
+    }
+
+    /// Like [`expected_settlement_date`](Batch::expected_settlement_date),
+    /// counting banking days with `calendar`.
+    pub fn expected_settlement_date_with<D: CalendarDate>(
+        &self,
+        file_header: &FileHeader<'_>,
+        config: &ScheduleConfig,
+        calendar: &dyn HolidayCalendar,
+    ) -> Result<D, AchError> {
+        let expected = self.expected_settlement(file_header, config, calendar)?;
+        expected.to_date().ok_or_else(|| AchError::InvalidValue {
+            field: "effective_entry_date",
+            reason: format!("{} is out of range", expected.to_iso()),
+        })
+    }
+
+    /// Whether the operator-stamped Julian settlement date matches the
+    /// expected settlement date, or `None` when there is no stamped date or
+    /// no expectation can be computed.
+    pub fn settlement_date_matches(
+        &self,
+        file_header: &FileHeader<'_>,
+        config: &ScheduleConfig,
+    ) -> Option<bool> {
+        self.settlement_date_matches_with(file_header, config, &FederalReserve)
+    }
+
+    /// Like [`settlement_date_matches`](Batch::settlement_date_matches),
+    /// counting banking days with `calendar`.
+    pub fn settlement_date_matches_with(
+        &self,
+        file_header: &FileHeader<'_>,
+        config: &ScheduleConfig,
+        calendar: &dyn HolidayCalendar,
+    ) -> Option<bool> {
+        let stamped: CivilDate = self.header.settlement_date_with(config.year_pivot)?;
+        let expected = self
+            .expected_settlement(file_header, config, calendar)
+            .ok()?;
+        Some(stamped == expected)
+    }
+
+    fn expected_settlement(
+        &self,
+        file_header: &FileHeader<'_>,
+        config: &ScheduleConfig,
+        calendar: &dyn HolidayCalendar,
+    ) -> Result<CivilDate, AchError> {
+        let date = |field: &'static str, value: &str| {
+            CivilDate::parse_with(value, config.year_pivot).ok_or_else(|| AchError::InvalidValue {
+                field,
+                reason: format!("{value:?} is not a YYMMDD date"),
+            })
+        };
+        let effective = date("effective_entry_date", &self.header.effective_entry_date)?;
+        let created = date("file_creation_date", &file_header.file_creation_date)?;
+        let time = parse_hhmm(&file_header.file_creation_time).unwrap_or((0, 0));
+
+        let effective = if calendar.is_banking_day(effective) {
+            Some(effective)
+        } else {
+            calendar.next_banking_day(effective)
+        };
+        effective
+            .zip(config.earliest_settlement(created, time, calendar))
+            .map(|(effective, earliest)| effective.max(earliest))
+            .ok_or_else(|| AchError::InvalidValue {
+                field: "effective_entry_date",
+                reason: format!(
+                    "the calendar has no banking day within {BANKING_DAY_SEARCH_DAYS} days"
+                ),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, AchFile};
+
+    /// Parse a fixture with the given file creation date and time and
+    /// effective entry date.
+    fn expected(created: &str, time: &str, effective: &str) -> CivilDate {
+        let text = fixtures::sample_ppd_file(1);
+        let mut file = AchFile::parse(&text).unwrap();
+        file.file_header.file_creation_date = created.to_string().into();
+        file.file_header.file_creation_time = time.to_string().into();
+        file.batches[0].header.effective_entry_date = effective.to_string().into();
+        file.batches[0]
+            .expected_settlement_date(&file.file_header, &ScheduleConfig::default())
+            .unwrap()
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> CivilDate {
+        CivilDate::new(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_future_effective_date() {
+        // Created Monday, effective Wednesday.
+        assert_eq!(expected("250602", "0900", "250604"), date(2025, 6, 4));
+        // Effective Saturday rolls to Monday.
+        assert_eq!(expected("250602", "0900", "250607"), date(2025, 6, 9));
+    }
+
+    #[test]
+    fn test_processing_windows() {
+        // Same-day windows, including exactly at the last cutoff.
+        assert_eq!(expected("250602", "1030", "250602"), date(2025, 6, 2));
+        assert_eq!(expected("250602", "1645", "250602"), date(2025, 6, 2));
+        // Past the same-day windows: next day. Past the next-day cutoff too.
+        assert_eq!(expected("250602", "1646", "250602"), date(2025, 6, 3));
+        assert_eq!(expected("250602", "1701", "250602"), date(2025, 6, 4));
+        // Created on Saturday, effective Friday: Monday's first window.
+        assert_eq!(expected("250607", "1200", "250606"), date(2025, 6, 9));
+    }
+
+    #[test]
+    fn test_without_same_day() {
+        let text = fixtures::sample_ppd_file(1);
+        let file = AchFile::parse(&text).unwrap();
+        let config = ScheduleConfig {
+            same_day_cutoffs: Vec::new(),
+            ..ScheduleConfig::default()
+        };
+        // Created Tuesday, September 2, 2014 at 01:23 for Wednesday.
+        let settles: CivilDate = file.batches[0]
+            .expected_settlement_date(&file.file_header, &config)
+            .unwrap();
+        assert_eq!(settles, date(2014, 9, 3));
+    }
+
+    #[test]
+    fn test_custom_calendar() {
+        let text = fixtures::sample_ppd_file(1);
+        let file = AchFile::parse(&text).unwrap();
+        // The processor is also closed Wednesday, September 3, 2014.
+        let calendar = vec![date(2014, 9, 3)];
+        let settles: CivilDate = file.batches[0]
+            .expected_settlement_date_with(&file.file_header, &ScheduleConfig::default(), &calendar)
+            .unwrap();
+        assert_eq!(settles, date(2014, 9, 4));
+
+        struct AlwaysClosed;
+
+        impl HolidayCalendar for AlwaysClosed {
+            fn is_holiday(&self, _: CivilDate) -> bool {
+                true
+            }
+        }
+
+        let result: Result<CivilDate, _> = file.batches[0].expected_settlement_date_with(
+            &file.file_header,
+            &ScheduleConfig::default(),
+            &AlwaysClosed,
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Invalid value for 'effective_entry_date': \
+             the calendar has no banking day within 366 days"
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono_settlement_date() {
+        let text = fixtures::sample_ppd_file(1);
+        let file = AchFile::parse(&text).unwrap();
+        let settles: chrono::NaiveDate = file.batches[0]
+            .expected_settlement_date(&file.file_header, &ScheduleConfig::default())
+            .unwrap();
+        assert_eq!(
+            settles,
+            chrono::NaiveDate::from_ymd_opt(2014, 9, 3).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_invalid_effective_date() {
+        let text = fixtures::sample_ppd_file(1);
+        let mut file = AchFile::parse(&text).unwrap();
+        file.batches[0].header.effective_entry_date = "      ".into();
+        let result: Result<CivilDate, _> =
+            file.batches[0].expected_settlement_date(&file.file_header, &ScheduleConfig::default());
+        assert!(matches!(
+            result,
+            Err(AchError::InvalidValue {
+                field: "effective_entry_date",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_settlement_date_matches() {
+        let text = fixtures::sample_ppd_file(1);
+        let mut file = AchFile::parse(&text).unwrap();
+        let config = ScheduleConfig::default();
+        assert_eq!(
+            file.batches[0].settlement_date_matches(&file.file_header, &config),
+            None
+        );
+
+        // September 3, 2014 is day 246.
+        file.batches[0].header.settlement_date = "246".into();
+        assert_eq!(
+            file.batches[0].settlement_date_matches(&file.file_header, &config),
+            Some(true)
+        );
+        file.batches[0].header.settlement_date = "247".into();
+        assert_eq!(
+            file.batches[0].settlement_date_matches(&file.file_header, &config),
+            Some(false)
+        );
+    }
+}
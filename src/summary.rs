@@ -0,0 +1,221 @@
+//! File-level summary totals.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::controls::{is_credit, is_debit};
+use crate::government::{classify_government, GovPaymentKind};
+use crate::transaction_code::{format_breakdown, CodeTotals, TransactionCode};
+use crate::AchFile;
+
+/// Headline figures of a file, computed from its entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct FileSummary {
+    /// Immediate destination routing number, trimmed
+    pub immediate_destination: String,
+
+    /// Immediate destination name, trimmed
+    pub immediate_destination_name: String,
+
+    /// Immediate origin, trimmed
+    pub immediate_origin: String,
+
+    /// Immediate origin name, trimmed
+    pub immediate_origin_name: String,
+
+    /// File creation date (YYMMDD)
+    pub file_creation_date: String,
+
+    /// File creation time (HHMM), trimmed; may be empty
+    pub file_creation_time: String,
+
+    /// File ID modifier
+    pub file_id_modifier: String,
+
+    /// Number of batches
+    pub batch_count: usize,
+
+    /// Number of entry detail records
+    pub entry_count: usize,
+
+    /// Number of addenda records
+    pub addenda_count: usize,
+
+    /// Sum of debit entry amounts, in cents
+    pub total_debit_amount: u64,
+
+    /// Sum of credit entry amounts, in cents
+    pub total_credit_amount: u64,
+
+    /// Number of entries in batches classified as federal government
+    /// payments, by kind
+    pub government_entry_counts: BTreeMap<GovPaymentKind, usize>,
+
+    /// Number and total of entries under each transaction code; see
+    /// [`AchFile::breakdown_by_transaction_code`]
+    pub transaction_codes: BTreeMap<TransactionCode, CodeTotals>,
+}
+
+impl AchFile<'_> {
+    /// Summarize the file from its header and entries.
+    ///
+    /// Totals are computed from the entries, not read from the control
+    /// records, so they reflect what the file actually contains.
+    pub fn summary(&self) -> FileSummary {
+        let entries = || self.batches.iter().flat_map(|b| &b.entries);
+        let total = |keep: fn(&str) -> bool| -> u64 {
+            entries()
+                .filter(|e| keep(&e.transaction_code))
+                .map(|e| e.amount)
+                .sum()
+        };
+        let header = &self.file_header;
+        let mut government_entry_counts = BTreeMap::new();
+        for batch in &self.batches {
+            if let Some(kind) = classify_government(&batch.header) {
+                *government_entry_counts.entry(kind).or_default() += batch.entries.len();
+            }
+        }
+
+        FileSummary {
+            immediate_destination: header.immediate_destination.trim().to_string(),
+            immediate_destination_name: header.immediate_destination_name.trim().to_string(),
+            immediate_origin: header.immediate_origin.trim().to_string(),
+            immediate_origin_name: header.immediate_origin_name.trim().to_string(),
+            file_creation_date: header.file_creation_date.to_string(),
+            file_creation_time: header.file_creation_time.trim().to_string(),
+            file_id_modifier: header.file_id_modifier.to_string(),
+            batch_count: self.batches.len(),
+            entry_count: entries().count(),
+            addenda_count: entries().map(|e| e.addenda.len()).sum(),
+            total_debit_amount: total(is_debit),
+            total_credit_amount: total(is_credit),
+            government_entry_counts,
+            transaction_codes: self.breakdown_by_transaction_code(),
+        }
+    }
+}
+
+impl fmt::Display for FileSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Destination: {} {}",
+            self.immediate_destination, self.immediate_destination_name
+        )?;
+        writeln!(
+            f,
+            "Origin:      {} {}",
+            self.immediate_origin, self.immediate_origin_name
+        )?;
+        writeln!(
+            f,
+            "Created:     {} {} (file ID {})",
+            self.file_creation_date, self.file_creation_time, self.file_id_modifier
+        )?;
+        writeln!(
+            f,
+            "Batches: {}  Entries: {}  Addenda: {}",
+            self.batch_count, self.entry_count, self.addenda_count
+        )?;
+        write!(
+            f,
+            "Debits: {}  Credits: {}",
+            format_cents(self.total_debit_amount),
+            format_cents(self.total_credit_amount)
+        )?;
+        if !self.transaction_codes.is_empty() {
+            write!(
+                f,
+                "\nBy code: {}",
+                format_breakdown(&self.transaction_codes)
+            )?;
+        }
+        if !self.government_entry_counts.is_empty() {
+            write!(f, "\nGovernment:")?;
+            for (kind, count) in &self.government_entry_counts {
+                write!(f, " {kind:?} {count}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Render cents as dollars with thousands separators, e.g. `1,234.56`.
+pub(crate) fn format_cents(cents: u64) -> String {
+    let dollars = (cents / 100).to_string();
+    let mut grouped = String::with_capacity(dollars.len() + dollars.len() / 3);
+    for (i, c) in dollars.chars().enumerate() {
+        if i > 0 && (dollars.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    format!("{grouped}.{:02}", cents % 100)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_format_cents() {
+        assert_eq!(format_cents(0), "0.00");
+        assert_eq!(format_cents(7), "0.07");
+        assert_eq!(format_cents(123_456), "1,234.56");
+        assert_eq!(format_cents(100_000_000), "1,000,000.00");
+    }
+
+    #[test]
+    fn test_summary() {
+        let text = fixtures::sample_returns_file();
+        let summary = AchFile::parse(&text).unwrap().summary();
+        assert_eq!(summary.immediate_destination, "123456780");
+        assert_eq!(summary.batch_count, 1);
+        assert_eq!(summary.entry_count, 2);
+        assert_eq!(summary.addenda_count, 2);
+        assert_eq!(summary.total_debit_amount, 15_000);
+        assert_eq!(summary.total_credit_amount, 1_000);
+        assert_eq!(
+            summary.to_string(),
+            "Destination: 123456780 YOUR BANK\n\
+             Origin:      1234567801 YOUR COMPANY\n\
+             Created:     140902 0123 (file ID A)\n\
+             Batches: 1  Entries: 2  Addenda: 2\n\
+             Debits: 150.00  Credits: 10.00\n\
+             By code: 21: 1 for 10.00, 26: 1 for 150.00"
+        );
+        assert_eq!(
+            summary.transaction_codes[&TransactionCode::CheckingReturnDebit],
+            CodeTotals {
+                count: 1,
+                total: 15_000
+            }
+        );
+        assert!(summary.government_entry_counts.is_empty());
+    }
+
+    #[test]
+    fn test_government_entry_counts() {
+        let text = fixtures::sample_multi_company_file();
+        let mut file = AchFile::parse(&text).unwrap();
+        file.batches[0].header.company_name = "IRS TREAS 310".into();
+        file.batches[1].header.company_name = "IRS TREAS 310".into();
+        file.batches[2].header.originator_status_code = "2".into();
+        let summary = file.summary();
+
+        assert_eq!(
+            summary.government_entry_counts,
+            BTreeMap::from([
+                (GovPaymentKind::IrsRefund, 4),
+                (GovPaymentKind::OtherFederal, 3)
+            ])
+        );
+        assert!(summary
+            .to_string()
+            .ends_with("\nGovernment: IrsRefund 4 OtherFederal 3"));
+    }
+}
@@ -0,0 +1,167 @@
+//! Point-of-sale terminal addenda (type 02), carried by the card entries
+//! of POS, MTE, and SHR batches.
+
+use crate::records::{Addenda, EntryDetail};
+
+/// A type 02 addenda read field by field, from [`Addenda::as_terminal`].
+/// The addenda's [`payment_related_information`] still holds the raw
+/// positions 4-83 for networks that fill them differently.
+///
+/// [`payment_related_information`]: Addenda::payment_related_information
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct TerminalAddenda {
+    /// Reference information #1 (positions 4-10)
+    pub reference_information_1: String,
+
+    /// Reference information #2 (positions 11-13)
+    pub reference_information_2: String,
+
+    /// Terminal identification code (positions 14-19)
+    pub terminal_identification_code: String,
+
+    /// Transaction serial number (positions 20-25)
+    pub transaction_serial_number: String,
+
+    /// Transaction date as `MMDD`; see [`TerminalAddenda::transaction_month_day`]
+    /// (positions 26-29)
+    pub transaction_date: String,
+
+    /// Authorization code or card expiration date (positions 30-35)
+    pub authorization_code_or_card_expiration_date: String,
+
+    /// Terminal location (positions 36-62)
+    pub terminal_location: String,
+
+    /// Terminal city (positions 63-77)
+    pub terminal_city: String,
+
+    /// Terminal state (positions 78-79)
+    pub terminal_state: String,
+
+    /// Trace number of the entry (positions 80-94)
+    pub trace_number: String,
+}
+
+impl TerminalAddenda {
+    /// The transaction date as month and day, or `None` unless it is four
+    /// digits naming a day that exists in some year. The field has no year.
+    pub fn transaction_month_day(&self) -> Option<(u8, u8)> {
+        let date = self.transaction_date.as_bytes();
+        if date.len() != 4 || !date.iter().all(u8::is_ascii_digit) {
+            return None;
+        }
+        let month = (date[0] - b'0') * 10 + (date[1] - b'0');
+        let day = (date[2] - b'0') * 10 + (date[3] - b'0');
+        let days = match month {
+            2 => 29,
+            4 | 6 | 9 | 11 => 30,
+            1..=12 => 31,
+            _ => return None,
+        };
+        (1..=days).contains(&day).then_some((month, day))
+    }
+}
+
+impl Addenda<'_> {
+    /// Read this addenda as a point-of-sale terminal addenda, or `None`
+    /// unless its type code is `02`.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile};
+    ///
+    /// let text = fixtures::sample_pos_file();
+    /// let file = AchFile::parse(&text).unwrap();
+    /// let terminal = file.batches[0].entries[0].addenda[0].as_terminal().unwrap();
+    /// assert_eq!(terminal.terminal_city, "SPRINGFIELD");
+    /// assert_eq!(terminal.transaction_month_day(), Some((9, 2)));
+    /// ```
+    pub fn as_terminal(&self) -> Option<TerminalAddenda> {
+        if self.addenda_type_code != "02" {
+            return None;
+        }
+        // As for type 99, reassemble positions 4-94 and slice them again.
+        let rest = format!(
+            "{}{}{}",
+            self.payment_related_information,
+            self.addenda_sequence_number,
+            self.entry_detail_sequence_number
+        );
+        let slice = |from: usize, to: usize| rest.get(from..to).unwrap_or("").trim().to_string();
+
+        Some(TerminalAddenda {
+            reference_information_1: slice(0, 7),
+            reference_information_2: slice(7, 10),
+            terminal_identification_code: slice(10, 16),
+            transaction_serial_number: slice(16, 22),
+            transaction_date: slice(22, 26),
+            authorization_code_or_card_expiration_date: slice(26, 32),
+            terminal_location: slice(32, 59),
+            terminal_city: slice(59, 74),
+            terminal_state: slice(74, 76),
+            trace_number: slice(76, 91),
+        })
+    }
+}
+
+impl EntryDetail<'_> {
+    /// The entry's terminal addenda, or `None` if it has none.
+    pub fn as_terminal(&self) -> Option<TerminalAddenda> {
+        self.addenda.iter().find_map(Addenda::as_terminal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, AchFile};
+
+    #[test]
+    fn test_terminal_addenda() {
+        let text = fixtures::sample_pos_file();
+        let file = AchFile::parse(&text).unwrap();
+        let entries = &file.batches[0].entries;
+        assert_eq!(
+            entries[1].as_terminal().unwrap(),
+            TerminalAddenda {
+                reference_information_1: "4012".to_string(),
+                reference_information_2: "".to_string(),
+                terminal_identification_code: "T00417".to_string(),
+                transaction_serial_number: "000912".to_string(),
+                transaction_date: "0903".to_string(),
+                authorization_code_or_card_expiration_date: "A7731Q".to_string(),
+                terminal_location: "4400 LINCOLN AVE".to_string(),
+                terminal_city: "CHICAGO".to_string(),
+                terminal_state: "IL".to_string(),
+                trace_number: entries[1].trace_number.to_string(),
+            }
+        );
+        let raw = &entries[1].addenda[0].payment_related_information;
+        assert!(raw.starts_with("4012      T00417000912"));
+        assert_eq!(file.batches[0].entries[0].addenda.len(), 1);
+
+        let ctx = fixtures::sample_ctx_with_addenda();
+        let ctx = AchFile::parse(&ctx).unwrap();
+        assert_eq!(ctx.batches[0].entries[0].as_terminal(), None);
+    }
+
+    #[test]
+    fn test_transaction_month_day() {
+        let text = fixtures::sample_pos_file();
+        let file = AchFile::parse(&text).unwrap();
+        let mut terminal = file.batches[0].entries[0].as_terminal().unwrap();
+        for (date, expected) in [
+            ("0229", Some((2, 29))),
+            ("1231", Some((12, 31))),
+            ("0431", None),
+            ("1301", None),
+            ("0900", None),
+            ("902", None),
+            ("09 2", None),
+        ] {
+            terminal.transaction_date = date.to_string();
+            assert_eq!(terminal.transaction_month_day(), expected, "{date}");
+        }
+    }
+}
@@ -0,0 +1,457 @@
+//! Property-testing support and assertions for test suites.
+//!
+//! Available with the `testing` feature. The [proptest](mod@proptest) strategies here
+//! generate files that are random but structurally valid: routing numbers
+//! carry correct check digits, trace numbers start with the batch's ODFI,
+//! and every batch and file control agrees with the records it covers.
+//! Failing cases shrink towards files with fewer batches and entries.
+//!
+//! ```
+//! use proptest::prelude::*;
+//! use rs_ach::testing;
+//!
+//! proptest!(|(text in testing::ach_text())| {
+//!     let file = rs_ach::AchFile::parse(&text).unwrap();
+//!     prop_assert!(!file.batches.is_empty());
+//! });
+//! ```
+//!
+//! The `assert_*` functions panic, like `assert!`, with a message naming
+//! the batch, entry, and field at fault. Their findings are
+//! [`ValidationIssue`]s, shown as [`AchFile::validate`] shows them.
+//!
+//! ```
+//! use rs_ach::{fixtures, testing, AchFile};
+//!
+//! let text = fixtures::sample_ppd_file(3);
+//! testing::assert_round_trip(&text);
+//! let file = AchFile::parse(&text).unwrap();
+//! testing::assert_valid(&file);
+//! testing::assert_unique_traces(&file);
+//! ```
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use proptest::collection::vec;
+use proptest::option;
+use proptest::prelude::*;
+
+use crate::controls::{blocking_factor, is_credit, is_debit};
+use crate::records::{FileControl, FileHeader};
+use crate::summary::format_cents;
+use crate::{
+    parser, routing, writer, AchFile, AchFileOwned, Batch, BatchBuilder, Direction, Severity,
+    SimpleEntry, ValidationIssue,
+};
+
+/// Standard entry class codes used for generated consumer and corporate batches.
+const SEC_CODES: &[&str] = &["PPD", "CCD", "WEB", "TEL"];
+
+/// A 9-digit routing number with a valid check digit.
+pub fn routing_number() -> impl Strategy<Value = String> {
+    "[0-9]{8}".prop_map(|dfi| {
+        let check = routing::check_digit(&dfi).expect("8 digits");
+        format!("{dfi}{check}")
+    })
+}
+
+/// A `YYMMDD` date that exists in every year.
+pub fn yymmdd() -> impl Strategy<Value = String> {
+    (0u32..100, 1u32..=12, 1u32..=28).prop_map(|(y, m, d)| format!("{y:02}{m:02}{d:02}"))
+}
+
+/// An entry accepted by [`BatchBuilder::add_entry`].
+pub fn simple_entry() -> impl Strategy<Value = SimpleEntry> {
+    (
+        routing_number(),
+        "[0-9]{4,17}",
+        0..=crate::MAX_ENTRY_AMOUNT,
+        "[A-Z][A-Z ]{0,21}",
+        option::of("[A-Z0-9]{1,15}"),
+        prop_oneof![Just(Direction::Credit), Just(Direction::Debit)],
+    )
+        .prop_map(
+            |(
+                routing_number,
+                account_number,
+                amount,
+                individual_name,
+                identification,
+                direction,
+            )| {
+                SimpleEntry {
+                    routing_number,
+                    account_number,
+                    amount,
+                    individual_name,
+                    identification,
+                    direction,
+                }
+            },
+        )
+}
+
+/// A batch builder with between one and nine entries already added.
+pub fn batch_builder() -> impl Strategy<Value = BatchBuilder> {
+    (
+        "[A-Z][A-Z ]{0,15}",
+        "[0-9]{10}",
+        proptest::sample::select(SEC_CODES),
+        "[A-Z]{1,10}",
+        "[0-9]{8}",
+        yymmdd(),
+        vec(simple_entry(), 1..10),
+    )
+        .prop_map(|(name, id, sec, description, odfi, date, entries)| {
+            let mut builder = BatchBuilder::new(&name, &id, sec, &description, &odfi);
+            builder.effective_entry_date(&date);
+            for entry in entries {
+                builder.add_entry(entry).expect("generated entry is valid");
+            }
+            builder
+        })
+}
+
+/// A built batch with consistent controls.
+pub fn batch() -> impl Strategy<Value = Batch<'static>> {
+    batch_builder().prop_map(|builder| builder.build().expect("generated batch is valid"))
+}
+
+/// A file with between one and four batches, numbered from 1.
+pub fn ach_file() -> impl Strategy<Value = AchFileOwned> {
+    (file_header(), vec(batch_builder(), 1..5)).prop_map(|(file_header, builders)| {
+        let batches: Vec<Batch<'static>> = builders
+            .into_iter()
+            .enumerate()
+            .map(|(i, mut builder)| {
+                builder
+                    .batch_number(i as u64 + 1)
+                    .build()
+                    .expect("generated batch is valid")
+            })
+            .collect();
+        let mut file = AchFileOwned {
+            file_header,
+            batches,
+            file_control: FileControl {
+                record_type: "9".to_string(),
+                batch_count: 0,
+                block_count: 0,
+                entry_addenda_count: 0,
+                entry_hash: 0,
+                total_debit_amount: 0,
+                total_credit_amount: 0,
+                reserved: " ".repeat(39),
+            },
+            unknown_records: Vec::new(),
+        };
+        file.recompute_file_control();
+        file
+    })
+}
+
+/// NACHA text of a generated [`ach_file`].
+pub fn ach_text() -> impl Strategy<Value = String> {
+    ach_file().prop_map(|file| {
+        writer::render_lines(&file)
+            .expect("generated values fit their fields")
+            .join("\n")
+    })
+}
+
+fn file_header() -> impl Strategy<Value = FileHeader<'static>> {
+    (
+        routing_number(),
+        "[0-9]{10}",
+        yymmdd(),
+        (0u32..24, 0u32..60),
+        "[A-Z0-9]",
+        "[A-Z][A-Z ]{0,22}",
+        "[A-Z][A-Z ]{0,22}",
+    )
+        .prop_map(
+            |(destination, origin, date, (hour, minute), modifier, dest_name, origin_name)| {
+                FileHeader {
+                    record_type: Cow::Borrowed("1"),
+                    priority_code: Cow::Borrowed("01"),
+                    immediate_destination: Cow::Owned(format!(" {destination}")),
+                    immediate_origin: Cow::Owned(origin),
+                    file_creation_date: Cow::Owned(date),
+                    file_creation_time: Cow::Owned(format!("{hour:02}{minute:02}")),
+                    file_id_modifier: Cow::Owned(modifier),
+                    record_size: Cow::Borrowed("094"),
+                    blocking_factor: Cow::Borrowed("10"),
+                    format_code: Cow::Borrowed("1"),
+                    immediate_destination_name: Cow::Owned(format!("{dest_name:<23}")),
+                    immediate_origin_name: Cow::Owned(format!("{origin_name:<23}")),
+                    reference_code: Cow::Borrowed("        "),
+                }
+            },
+        )
+}
+
+/// Panic listing `issues` under `summary`, unless there are none.
+#[track_caller]
+fn fail_with<'i>(summary: &str, issues: impl IntoIterator<Item = &'i ValidationIssue>) {
+    let mut message = String::new();
+    for issue in issues {
+        let _ = write!(message, "\n  {issue}");
+        if let Some(control) = &issue.control {
+            let _ = write!(
+                message,
+                "\n    - declared: {}\n    + computed: {}",
+                control.declared, control.computed
+            );
+        }
+    }
+    if !message.is_empty() {
+        panic!("assertion failed: {summary}:{message}");
+    }
+}
+
+/// An error on a batch, or on the file when `batch_number` is `None`.
+fn issue(
+    batch_number: Option<&str>,
+    trace_number: Option<&str>,
+    field: &str,
+    message: String,
+) -> ValidationIssue {
+    ValidationIssue {
+        severity: Severity::Error,
+        batch_number: batch_number.map(|b| b.trim().to_string()),
+        trace_number: trace_number.map(|t| t.trim().to_string()),
+        field: field.to_string(),
+        message,
+        control: None,
+    }
+}
+
+/// Assert that [`AchFile::validate`] finds no errors; warnings are allowed.
+#[track_caller]
+pub fn assert_valid(file: &AchFile<'_>) {
+    let report = file.validate();
+    fail_with("file is not valid", report.errors());
+}
+
+/// Assert that every batch and file control agrees with the records it
+/// covers, as [`ValidationReport::control_mismatches`](crate::ValidationReport::control_mismatches)
+/// sees it. Other
+/// validation errors are ignored.
+#[track_caller]
+pub fn assert_controls_consistent(file: &AchFile<'_>) {
+    let report = file.validate();
+    let mismatches = report.issues.iter().filter(|i| i.control.is_some());
+    fail_with("controls do not match their records", mismatches);
+}
+
+/// Assert that each batch's entries debit as much as they credit, as in a
+/// file with offsetting entries.
+#[track_caller]
+pub fn assert_balanced(file: &AchFile<'_>) {
+    let issues: Vec<ValidationIssue> = file
+        .batches
+        .iter()
+        .filter_map(|batch| {
+            let total = |keep: fn(&str) -> bool| -> u64 {
+                batch
+                    .entries
+                    .iter()
+                    .filter(|e| keep(&e.transaction_code))
+                    .map(|e| e.amount)
+                    .sum()
+            };
+            let (debits, credits) = (total(is_debit), total(is_credit));
+            (debits != credits).then(|| {
+                issue(
+                    Some(&batch.header.batch_number),
+                    None,
+                    "balance",
+                    format!(
+                        "debits {}, credits {}",
+                        format_cents(debits),
+                        format_cents(credits)
+                    ),
+                )
+            })
+        })
+        .collect();
+    fail_with("batches are not balanced", &issues);
+}
+
+/// Assert that no two entries in the file share a trace number.
+#[track_caller]
+pub fn assert_unique_traces(file: &AchFile<'_>) {
+    let mut seen = HashMap::new();
+    let mut issues = Vec::new();
+    for batch in &file.batches {
+        for entry in &batch.entries {
+            let trace = entry.trace_number.trim();
+            if let Some(first) = seen.insert(trace, batch.header.batch_number.trim()) {
+                issues.push(issue(
+                    Some(&batch.header.batch_number),
+                    Some(trace),
+                    "trace_number",
+                    format!("also used in batch {first}"),
+                ));
+            }
+        }
+    }
+    fail_with("trace numbers are not unique", &issues);
+}
+
+/// Assert that `content` parses and is written back byte for byte: the
+/// same records, line endings, final line ending, and block padding.
+///
+/// On failure the message shows the first line that differs, the line
+/// read (`-`) above the line written (`+`), and a `^` under the first
+/// differing character.
+#[track_caller]
+pub fn assert_round_trip(content: &str) {
+    let file = match AchFile::parse(content) {
+        Ok(file) => file,
+        Err(error) => panic!("assertion failed: content does not parse: {error}"),
+    };
+
+    let mut lines = match writer::render_lines(&file) {
+        Ok(lines) => lines,
+        Err(error) => panic!("assertion failed: parsed file does not write back: {error}"),
+    };
+    if content.lines().any(parser::is_filler) {
+        writer::pad_block(&mut lines, blocking_factor(&file.file_header));
+    }
+    let newline = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut written = lines.join(newline);
+    if content.ends_with('\n') {
+        written.push_str(newline);
+    }
+    if written == content {
+        return;
+    }
+
+    let read: Vec<&str> = content.split('\n').collect();
+    let wrote: Vec<&str> = written.split('\n').collect();
+    let (number, (expected, actual)) = read
+        .iter()
+        .chain(std::iter::repeat(&"<end>"))
+        .zip(wrote.iter().chain(std::iter::repeat(&"<end>")))
+        .enumerate()
+        .find(|(_, (r, w))| r != w)
+        .expect("texts differ");
+    let column = expected
+        .bytes()
+        .zip(actual.bytes())
+        .take_while(|(r, w)| r == w)
+        .count();
+    panic!(
+        "assertion failed: content does not round-trip, line {}, column {}:\n  - {expected:?}\n  + {actual:?}\n    {}^",
+        number + 1,
+        column + 1,
+        " ".repeat(column + 1)
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AchFile;
+
+    proptest! {
+        #[test]
+        fn routing_numbers_are_valid(routing in routing_number()) {
+            prop_assert!(routing::is_valid(&routing));
+        }
+
+        #[test]
+        fn parse_of_written_file_round_trips(file in ach_file()) {
+            let text = writer::render_lines(&file).unwrap().join("\n");
+            prop_assert!(text.lines().all(|line| line.len() == writer::RECORD_LENGTH));
+            let parsed = AchFile::parse(&text).unwrap();
+            prop_assert_eq!(parsed, file);
+        }
+
+        #[test]
+        fn traces_start_with_odfi(batch in batch()) {
+            for entry in &batch.entries {
+                prop_assert!(entry.trace_number.starts_with(batch.header.originating_dfi_identification.as_ref()));
+            }
+        }
+    }
+
+    /// The message `check` panics with.
+    fn panic_message(check: impl FnOnce() + std::panic::UnwindSafe) -> String {
+        let payload = std::panic::catch_unwind(check).unwrap_err();
+        payload
+            .downcast_ref::<String>()
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_assertions_pass_on_fixtures() {
+        for text in [
+            crate::fixtures::sample_ppd_file(12),
+            crate::fixtures::sample_ctx_with_addenda().replace('\n', "\r\n"),
+            crate::fixtures::sample_returns_file(),
+            crate::fixtures::sample_empty_file(),
+        ] {
+            assert_round_trip(&text);
+            let file = AchFile::parse(&text).unwrap();
+            assert_valid(&file);
+            assert_controls_consistent(&file);
+            assert_unique_traces(&file);
+        }
+        assert_round_trip(crate::fixtures::sample_ppd_file(1).trim_end_matches(['9', '\n']));
+    }
+
+    #[test]
+    fn test_assertion_messages() {
+        let text = crate::fixtures::sample_ppd_file(2);
+        let mut file = AchFile::parse(&text).unwrap();
+        file.batches[0].control.total_credit_amount += 1;
+        file.batches[0].entries[1].trace_number = file.batches[0].entries[0].trace_number.clone();
+
+        let message = panic_message(|| assert_controls_consistent(&file));
+        assert_eq!(
+            message,
+            "assertion failed: controls do not match their records:\n  \
+             error: batch 0000001: total_credit_amount: declared 1251, computed 1250 (difference +1)\n    \
+             - declared: 1251\n    \
+             + computed: 1250"
+        );
+        assert!(panic_message(|| assert_valid(&file)).contains("total_credit_amount"));
+        assert_eq!(
+            panic_message(|| assert_unique_traces(&file)),
+            "assertion failed: trace numbers are not unique:\n  \
+             error: batch 0000001: entry 123456780000001: trace_number: also used in batch 0000001"
+        );
+        assert_eq!(
+            panic_message(|| assert_balanced(&file)),
+            "assertion failed: batches are not balanced:\n  \
+             error: batch 0000001: balance: debits 25.00, credits 12.50"
+        );
+        file.batches[0].entries[1].amount = 1_250;
+        assert_balanced(&file);
+
+        // A space-padded amount parses, but is written zero-padded.
+        let spaced = text.replacen("0000002500", "   2500   ", 1);
+        let message = panic_message(|| assert_round_trip(&spaced));
+        assert_eq!(
+            message,
+            format!(
+                "assertion failed: content does not round-trip, line 4, column 30:\n  \
+                 - {:?}\n  \
+                 + {:?}\n    \
+                 {}^",
+                spaced.lines().nth(3).unwrap(),
+                text.lines().nth(3).unwrap(),
+                " ".repeat(30)
+            )
+        );
+    }
+}
@@ -0,0 +1,286 @@
+//! Alerts for entries, batches, and files over amount thresholds.
+
+use std::collections::HashMap;
+
+use crate::controls::{is_credit, is_debit};
+use crate::AchFile;
+
+/// Amount thresholds in cents. `None` sets no threshold.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Thresholds {
+    /// Largest amount of one entry
+    pub entry_amount: Option<u64>,
+
+    /// Largest total of a batch's debit entries
+    pub batch_debit_total: Option<u64>,
+
+    /// Largest total of a batch's credit entries
+    pub batch_credit_total: Option<u64>,
+
+    /// Largest total of the file's debit entries
+    pub file_debit_total: Option<u64>,
+
+    /// Largest total of the file's credit entries
+    pub file_credit_total: Option<u64>,
+}
+
+/// Thresholds for [`AchFile::threshold_alerts`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ThresholdConfig {
+    /// Thresholds for every company
+    pub global: Thresholds,
+
+    /// Entry and batch thresholds by company identification, replacing
+    /// the global ones they set; file thresholds here are ignored
+    pub per_company: HashMap<String, Thresholds>,
+}
+
+/// The threshold an [`Alert`] crossed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum ThresholdKind {
+    /// [`Thresholds::entry_amount`]
+    EntryAmount,
+
+    /// [`Thresholds::batch_debit_total`]
+    BatchDebitTotal,
+
+    /// [`Thresholds::batch_credit_total`]
+    BatchCreditTotal,
+
+    /// [`Thresholds::file_debit_total`]
+    FileDebitTotal,
+
+    /// [`Thresholds::file_credit_total`]
+    FileCreditTotal,
+}
+
+/// What an [`Alert`] is about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum AlertScope {
+    /// One entry
+    Entry {
+        /// Batch number of the entry's batch, trimmed
+        batch_number: String,
+
+        /// Trace number of the entry
+        trace_number: String,
+    },
+
+    /// One batch
+    Batch {
+        /// Batch number, trimmed
+        batch_number: String,
+    },
+
+    /// The whole file
+    File,
+}
+
+/// An amount over its threshold, from [`AchFile::threshold_alerts`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Alert {
+    /// The entry, batch, or file
+    pub scope: AlertScope,
+
+    /// Company identification of the entry or batch, trimmed; `None` for
+    /// the file
+    pub company_identification: Option<String>,
+
+    /// The threshold crossed
+    pub kind: ThresholdKind,
+
+    /// The threshold, in cents
+    pub threshold: u64,
+
+    /// The amount over it, in cents
+    pub actual: u64,
+}
+
+impl AchFile<'_> {
+    /// Report every entry, batch, and file amount over its threshold in
+    /// `config`.
+    ///
+    /// A company's thresholds in [`ThresholdConfig::per_company`] replace
+    /// the global ones they set. Totals are summed from the entries, not
+    /// read from the control records. Alerts are in file order, each
+    /// batch's entries before the batch, and the file last.
+    pub fn threshold_alerts(&self, config: &ThresholdConfig) -> Vec<Alert> {
+        let per_company: HashMap<&str, &Thresholds> = config
+            .per_company
+            .iter()
+            .map(|(k, v)| (k.trim(), v))
+            .collect();
+
+        let mut alerts = Vec::new();
+        let mut check = |scope: &AlertScope, company: Option<&str>, kind, threshold, actual| {
+            if let Some(threshold) = threshold {
+                if actual > threshold {
+                    alerts.push(Alert {
+                        scope: scope.clone(),
+                        company_identification: company.map(str::to_string),
+                        kind,
+                        threshold,
+                        actual,
+                    });
+                }
+            }
+        };
+
+        let (mut file_debits, mut file_credits) = (0u64, 0u64);
+        for batch in &self.batches {
+            let company = batch.header.company_identification.trim();
+            let batch_number = batch.header.batch_number.trim();
+            let own = per_company.get(company);
+            let threshold = |field: fn(&Thresholds) -> Option<u64>| {
+                own.and_then(|t| field(t)).or(field(&config.global))
+            };
+
+            let (mut debits, mut credits) = (0u64, 0u64);
+            for entry in &batch.entries {
+                if is_debit(&entry.transaction_code) {
+                    debits += entry.amount;
+                } else if is_credit(&entry.transaction_code) {
+                    credits += entry.amount;
+                }
+                let scope = AlertScope::Entry {
+                    batch_number: batch_number.to_string(),
+                    trace_number: entry.trace_number.to_string(),
+                };
+                check(
+                    &scope,
+                    Some(company),
+                    ThresholdKind::EntryAmount,
+                    threshold(|t| t.entry_amount),
+                    entry.amount,
+                );
+            }
+
+            let scope = AlertScope::Batch {
+                batch_number: batch_number.to_string(),
+            };
+            check(
+                &scope,
+                Some(company),
+                ThresholdKind::BatchDebitTotal,
+                threshold(|t| t.batch_debit_total),
+                debits,
+            );
+            check(
+                &scope,
+                Some(company),
+                ThresholdKind::BatchCreditTotal,
+                threshold(|t| t.batch_credit_total),
+                credits,
+            );
+            file_debits += debits;
+            file_credits += credits;
+        }
+
+        let global = &config.global;
+        check(
+            &AlertScope::File,
+            None,
+            ThresholdKind::FileDebitTotal,
+            global.file_debit_total,
+            file_debits,
+        );
+        check(
+            &AlertScope::File,
+            None,
+            ThresholdKind::FileCreditTotal,
+            global.file_credit_total,
+            file_credits,
+        );
+        alerts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_threshold_alerts() {
+        let text = fixtures::sample_multi_company_file();
+        let file = AchFile::parse(&text).unwrap();
+        let config = ThresholdConfig {
+            global: Thresholds {
+                entry_amount: Some(500_000),
+                batch_credit_total: Some(400_000),
+                file_credit_total: Some(1_000_000),
+                ..Thresholds::default()
+            },
+            per_company: HashMap::from([(
+                "2222222222".to_string(),
+                Thresholds {
+                    batch_debit_total: Some(20_000),
+                    ..Thresholds::default()
+                },
+            )]),
+        };
+
+        let alerts = file.threshold_alerts(&config);
+        let found: Vec<(&AlertScope, ThresholdKind, u64)> = alerts
+            .iter()
+            .map(|a| (&a.scope, a.kind, a.actual))
+            .collect();
+        let batch = |n: &str| AlertScope::Batch {
+            batch_number: n.to_string(),
+        };
+        let vendor = AlertScope::Entry {
+            batch_number: "0000002".to_string(),
+            trace_number: file.batches[1].entries[0].trace_number.to_string(),
+        };
+        assert_eq!(
+            found,
+            [
+                (&batch("0000001"), ThresholdKind::BatchCreditTotal, 430_000),
+                (&vendor, ThresholdKind::EntryAmount, 900_000),
+                (&batch("0000002"), ThresholdKind::BatchCreditTotal, 900_000),
+                (&batch("0000003"), ThresholdKind::BatchDebitTotal, 21_000),
+                (&AlertScope::File, ThresholdKind::FileCreditTotal, 1_330_000),
+            ]
+        );
+        assert_eq!(alerts[1].threshold, 500_000);
+        assert_eq!(
+            alerts[1].company_identification.as_deref(),
+            Some("1111111111")
+        );
+        assert_eq!(alerts[4].company_identification, None);
+    }
+
+    #[test]
+    fn test_no_thresholds() {
+        let text = fixtures::sample_multi_company_file();
+        let file = AchFile::parse(&text).unwrap();
+        assert_eq!(file.threshold_alerts(&ThresholdConfig::default()), []);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_alert_serializes() {
+        let alert = Alert {
+            scope: AlertScope::Batch {
+                batch_number: "0000001".to_string(),
+            },
+            company_identification: Some("1111111111".to_string()),
+            kind: ThresholdKind::BatchCreditTotal,
+            threshold: 400_000,
+            actual: 430_000,
+        };
+        let json = serde_json::to_value(&alert).unwrap();
+        assert_eq!(json["scope"]["Batch"]["batch_number"], "0000001");
+        assert_eq!(json["kind"], "BatchCreditTotal");
+    }
+}
@@ -0,0 +1,426 @@
+//! Transaction codes and per-code entry counts and totals.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::controls::{is_credit, is_debit};
+use crate::records::EntryDetail;
+use crate::summary::format_cents;
+use crate::{AchError, AchFile, Batch};
+
+/// Defines [`TransactionCode`] from one row per code: the variant, the code,
+/// and its description.
+macro_rules! transaction_codes {
+    ($($variant:ident, $code:literal, $description:literal;)*) => {
+        /// An entry's two-digit transaction code.
+        ///
+        /// Variants are ordered by code. Codes not defined by NACHA are kept
+        /// as [`TransactionCode::Other`], after all the others. With the
+        /// `serde` feature a code serializes as its digits, e.g. `"22"`.
+        #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+        #[non_exhaustive]
+        pub enum TransactionCode {
+            $(
+                #[doc = concat!($description, " (", $code, ")")]
+                #[cfg_attr(feature = "serde", serde(rename = $code))]
+                $variant,
+            )*
+
+            /// A code not in the NACHA table, as it appeared in the file
+            #[cfg_attr(feature = "serde", serde(untagged))]
+            Other(String),
+        }
+
+        impl TransactionCode {
+            /// Read a code such as `22`. Surrounding spaces are ignored.
+            pub fn from_code(code: &str) -> TransactionCode {
+                match code.trim() {
+                    $($code => TransactionCode::$variant,)*
+                    other => TransactionCode::Other(other.to_string()),
+                }
+            }
+
+            /// The code as it appears in the file, e.g. `22`.
+            pub fn code(&self) -> &str {
+                match self {
+                    $(TransactionCode::$variant => $code,)*
+                    TransactionCode::Other(code) => code,
+                }
+            }
+
+            /// Description of a known code.
+            fn row(&self) -> Option<&'static str> {
+                match self {
+                    $(TransactionCode::$variant => Some($description),)*
+                    TransactionCode::Other(_) => None,
+                }
+            }
+        }
+    };
+}
+
+#[rustfmt::skip]
+transaction_codes! {
+    CheckingReturnCredit, "21", "Checking credit return or NOC";
+    CheckingCredit, "22", "Checking credit";
+    CheckingPrenoteCredit, "23", "Checking credit prenote";
+    CheckingZeroDollarCredit, "24", "Checking zero-dollar credit with remittance data";
+    CheckingReturnDebit, "26", "Checking debit return or NOC";
+    CheckingDebit, "27", "Checking debit";
+    CheckingPrenoteDebit, "28", "Checking debit prenote";
+    CheckingZeroDollarDebit, "29", "Checking zero-dollar debit with remittance data";
+    SavingsReturnCredit, "31", "Savings credit return or NOC";
+    SavingsCredit, "32", "Savings credit";
+    SavingsPrenoteCredit, "33", "Savings credit prenote";
+    SavingsZeroDollarCredit, "34", "Savings zero-dollar credit with remittance data";
+    SavingsReturnDebit, "36", "Savings debit return or NOC";
+    SavingsDebit, "37", "Savings debit";
+    SavingsPrenoteDebit, "38", "Savings debit prenote";
+    SavingsZeroDollarDebit, "39", "Savings zero-dollar debit with remittance data";
+    LedgerReturnCredit, "41", "General ledger credit return or NOC";
+    LedgerCredit, "42", "General ledger credit";
+    LedgerPrenoteCredit, "43", "General ledger credit prenote";
+    LedgerZeroDollarCredit, "44", "General ledger zero-dollar credit with remittance data";
+    LedgerReturnDebit, "46", "General ledger debit return or NOC";
+    LedgerDebit, "47", "General ledger debit";
+    LedgerPrenoteDebit, "48", "General ledger debit prenote";
+    LedgerZeroDollarDebit, "49", "General ledger zero-dollar debit with remittance data";
+    LoanReturnCredit, "51", "Loan credit return or NOC";
+    LoanCredit, "52", "Loan credit";
+    LoanPrenoteCredit, "53", "Loan credit prenote";
+    LoanZeroDollarCredit, "54", "Loan zero-dollar credit with remittance data";
+    LoanDebit, "55", "Loan debit, for reversals only";
+    LoanReturnDebit, "56", "Loan debit return or NOC";
+}
+
+impl TransactionCode {
+    /// NACHA description, e.g. `Checking credit`, or `Unknown transaction
+    /// code` for [`TransactionCode::Other`].
+    pub fn description(&self) -> &'static str {
+        self.row().unwrap_or("Unknown transaction code")
+    }
+
+    /// Whether the code credits the receiver (second digit 1 through 4).
+    pub fn is_credit(&self) -> bool {
+        is_credit(self.code())
+    }
+
+    /// Whether the code debits the receiver (second digit 5 through 9).
+    pub fn is_debit(&self) -> bool {
+        is_debit(self.code()) || self.code().as_bytes().get(1) == Some(&b'5')
+    }
+
+    /// Whether the code is a prenotification (second digit 3 or 8).
+    pub fn is_prenote(&self) -> bool {
+        matches!(self.code().as_bytes().get(1), Some(b'3' | b'8'))
+    }
+
+    /// Whether a known code is for a zero-dollar entry carrying remittance
+    /// data (second digit 4 or 9), as CCD and CTX entries may be.
+    pub fn is_zero_dollar(&self) -> bool {
+        self.row().is_some() && matches!(self.code().as_bytes().get(1), Some(b'4' | b'9'))
+    }
+
+    /// Whether a known code is a live credit or debit, moving money (second
+    /// digit 2 or 7).
+    pub fn is_live(&self) -> bool {
+        self.row().is_some() && matches!(self.code().as_bytes().get(1), Some(b'2' | b'7'))
+    }
+
+    /// The kind of account a known code posts to, from its first digit, or
+    /// `None` for [`TransactionCode::Other`].
+    pub fn account_type(&self) -> Option<AccountType> {
+        self.row()?;
+        match self.code().as_bytes().first()? {
+            b'2' => Some(AccountType::Checking),
+            b'3' => Some(AccountType::Savings),
+            b'4' => Some(AccountType::GeneralLedger),
+            b'5' => Some(AccountType::Loan),
+            _ => None,
+        }
+    }
+}
+
+/// The kind of account a [`TransactionCode`] posts to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum AccountType {
+    /// Checking, or demand deposit, account (codes 2x)
+    Checking,
+
+    /// Savings account (codes 3x)
+    Savings,
+
+    /// Financial institution general ledger account (codes 4x)
+    GeneralLedger,
+
+    /// Loan account (codes 5x)
+    Loan,
+}
+
+impl EntryDetail<'_> {
+    /// The entry's transaction code, typed. A two-digit code not in the
+    /// NACHA table is [`TransactionCode::Other`]; anything else, such as a
+    /// blank or non-numeric field, is [`AchError::InvalidValue`].
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AccountType, AchFile, TransactionCode};
+    ///
+    /// let text = fixtures::sample_ppd_file(1);
+    /// let file = AchFile::parse(&text).unwrap();
+    /// let code = file.batches[0].entries[0].transaction_code().unwrap();
+    /// assert_eq!(code, TransactionCode::CheckingCredit);
+    /// assert_eq!(code.account_type(), Some(AccountType::Checking));
+    /// ```
+    pub fn transaction_code(&self) -> Result<TransactionCode, AchError> {
+        let code = self.transaction_code.trim();
+        if code.len() != 2 || !code.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(AchError::InvalidValue {
+                field: "transaction_code",
+                reason: format!("{:?} is not a two-digit code", self.transaction_code),
+            });
+        }
+        Ok(TransactionCode::from_code(code))
+    }
+
+    /// Whether the entry is a prenotification, with a known prenote
+    /// transaction code such as `23`: a zero-dollar entry sent ahead of live
+    /// entries to check the receiver's account.
+    pub fn is_prenote(&self) -> bool {
+        let code = TransactionCode::from_code(&self.transaction_code);
+        code.row().is_some() && code.is_prenote()
+    }
+}
+
+impl AchFile<'_> {
+    /// Every prenotification entry, with its batch, in file order.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile};
+    ///
+    /// let text = fixtures::sample_ppd_file(2);
+    /// let mut file = AchFile::parse(&text).unwrap();
+    /// assert_eq!(file.prenotes().count(), 0);
+    ///
+    /// let entry = &mut file.batches[0].entries[1];
+    /// entry.transaction_code = "28".into();
+    /// entry.amount = 0;
+    /// let prenotes: Vec<_> = file.prenotes().map(|(_, entry)| entry).collect();
+    /// assert_eq!(prenotes, [&file.batches[0].entries[1]]);
+    /// ```
+    pub fn prenotes(&self) -> impl Iterator<Item = (&Batch<'_>, &EntryDetail<'_>)> {
+        self.batches.iter().flat_map(|batch| {
+            batch
+                .entries
+                .iter()
+                .filter(|entry| entry.is_prenote())
+                .map(move |entry| (batch, entry))
+        })
+    }
+}
+
+impl fmt::Display for TransactionCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// How many entries have one transaction code, and their total.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct CodeTotals {
+    /// Number of entries
+    pub count: usize,
+
+    /// Sum of their amounts, in cents
+    pub total: u64,
+}
+
+impl Batch<'_> {
+    /// The number and total of the batch's entries under each transaction
+    /// code, in code order.
+    ///
+    /// Checking and savings credits usually post to different accounts,
+    /// so the breakdown gives journal lines directly:
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile, TransactionCode};
+    ///
+    /// let text = fixtures::sample_ppd_file(3);
+    /// let file = AchFile::parse(&text).unwrap();
+    /// let journal: Vec<(&str, u64)> = file.batches[0]
+    ///     .breakdown_by_transaction_code()
+    ///     .iter()
+    ///     .map(|(code, totals)| {
+    ///         let account = match code {
+    ///             TransactionCode::CheckingCredit => "2100 DDA credits",
+    ///             TransactionCode::SavingsCredit => "2200 Savings credits",
+    ///             TransactionCode::CheckingDebit => "1100 DDA collections",
+    ///             _ => "9999 Suspense",
+    ///         };
+    ///         (account, totals.total)
+    ///     })
+    ///     .collect();
+    /// assert_eq!(
+    ///     journal,
+    ///     [("2100 DDA credits", 5_000), ("1100 DDA collections", 2_500)]
+    /// );
+    /// ```
+    pub fn breakdown_by_transaction_code(&self) -> BTreeMap<TransactionCode, CodeTotals> {
+        let mut breakdown = BTreeMap::new();
+        self.add_to_breakdown(&mut breakdown);
+        breakdown
+    }
+
+    fn add_to_breakdown(&self, breakdown: &mut BTreeMap<TransactionCode, CodeTotals>) {
+        for entry in &self.entries {
+            let totals: &mut CodeTotals = breakdown
+                .entry(TransactionCode::from_code(&entry.transaction_code))
+                .or_default();
+            totals.count += 1;
+            totals.total += entry.amount;
+        }
+    }
+}
+
+impl AchFile<'_> {
+    /// Like [`Batch::breakdown_by_transaction_code`], across all batches.
+    pub fn breakdown_by_transaction_code(&self) -> BTreeMap<TransactionCode, CodeTotals> {
+        let mut breakdown = BTreeMap::new();
+        for batch in &self.batches {
+            batch.add_to_breakdown(&mut breakdown);
+        }
+        breakdown
+    }
+}
+
+/// A breakdown on one line, e.g. `22: 2 for 50.00, 27: 1 for 25.00`.
+pub(crate) fn format_breakdown(breakdown: &BTreeMap<TransactionCode, CodeTotals>) -> String {
+    breakdown
+        .iter()
+        .map(|(code, totals)| {
+            format!(
+                "{code}: {} for {}",
+                totals.count,
+                format_cents(totals.total)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_transaction_code_table() {
+        let code = TransactionCode::from_code(" 32");
+        assert_eq!(code, TransactionCode::SavingsCredit);
+        assert_eq!(code.code(), "32");
+        assert_eq!(code.description(), "Savings credit");
+        assert!(code.is_credit() && !code.is_debit() && !code.is_prenote());
+        assert!(TransactionCode::CheckingPrenoteDebit.is_prenote());
+        assert!(TransactionCode::CheckingZeroDollarDebit.is_zero_dollar());
+        assert!(TransactionCode::LoanCredit.is_live() && !code.is_zero_dollar());
+        assert!(code.is_live() && !TransactionCode::SavingsReturnCredit.is_live());
+        assert!(TransactionCode::LoanDebit.is_debit());
+        assert!(TransactionCode::CheckingCredit < TransactionCode::CheckingDebit);
+
+        let unknown = TransactionCode::from_code("99");
+        assert_eq!(unknown, TransactionCode::Other("99".to_string()));
+        assert_eq!(unknown.description(), "Unknown transaction code");
+        assert!(TransactionCode::LoanReturnDebit < unknown);
+        assert_eq!(unknown.to_string(), "99");
+    }
+
+    #[test]
+    fn test_account_type() {
+        assert_eq!(
+            TransactionCode::CheckingDebit.account_type(),
+            Some(AccountType::Checking)
+        );
+        assert_eq!(
+            TransactionCode::SavingsPrenoteCredit.account_type(),
+            Some(AccountType::Savings)
+        );
+        assert_eq!(
+            TransactionCode::LedgerReturnDebit.account_type(),
+            Some(AccountType::GeneralLedger)
+        );
+        assert_eq!(
+            TransactionCode::LoanDebit.account_type(),
+            Some(AccountType::Loan)
+        );
+        assert_eq!(TransactionCode::from_code("25").account_type(), None);
+    }
+
+    #[test]
+    fn test_entry_transaction_code() {
+        let text = fixtures::sample_multi_company_file();
+        let mut file = AchFile::parse(&text).unwrap();
+        let entry = &mut file.batches[1].entries[1];
+        let code = entry.transaction_code().unwrap();
+        assert!(code.is_debit() && !code.is_credit());
+
+        entry.transaction_code = "81".into();
+        assert_eq!(
+            entry.transaction_code().unwrap(),
+            TransactionCode::Other("81".to_string())
+        );
+        entry.transaction_code = "2A".into();
+        let err = entry.transaction_code().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid value for 'transaction_code': \"2A\" is not a two-digit code"
+        );
+    }
+
+    #[test]
+    fn test_breakdown() {
+        let text = fixtures::sample_multi_company_file();
+        let mut file = AchFile::parse(&text).unwrap();
+        file.batches[1].entries[0].transaction_code = "32".into();
+        file.batches[1].entries[1].transaction_code = "23".into();
+        file.batches[1].entries[1].amount = 0;
+
+        let batch = file.batches[1].breakdown_by_transaction_code();
+        let codes: Vec<&str> = batch.keys().map(TransactionCode::code).collect();
+        assert_eq!(codes, ["23", "32"]);
+        assert_eq!(batch[&TransactionCode::CheckingPrenoteCredit].total, 0);
+
+        let breakdown = file.breakdown_by_transaction_code();
+        let count: usize = breakdown.values().map(|t| t.count).sum();
+        let total: u64 = breakdown.values().map(|t| t.total).sum();
+        assert_eq!(count, 7);
+        let amounts: u64 = file
+            .batches
+            .iter()
+            .flat_map(|b| &b.entries)
+            .map(|e| e.amount)
+            .sum();
+        assert_eq!(total, amounts);
+        assert_eq!(
+            breakdown[&TransactionCode::SavingsCredit],
+            file.batches[1].breakdown_by_transaction_code()[&TransactionCode::SavingsCredit]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_as_digits() {
+        let codes = [
+            TransactionCode::CheckingDebit,
+            TransactionCode::from_code("99"),
+        ];
+        let json = serde_json::to_string(&codes).unwrap();
+        assert_eq!(json, r#"["27","99"]"#);
+        let back: Vec<TransactionCode> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, codes);
+    }
+}
@@ -0,0 +1,1664 @@
+//! Validation of a parsed file against its control records.
+//!
+//! [`AchFile::validate`] checks a parsed file; [`validate_stream`] runs the
+//! same checks over its records as they are read.
+
+use std::fmt;
+
+use crate::check_conversion;
+use crate::controls::{self, is_debit, FileTotals};
+use crate::dates::{descriptive_date, CivilDate, YearPivot};
+use crate::error::AchResult;
+use crate::parse_options::DEFAULT_MAX_ERRORS;
+use crate::parser;
+use crate::record_iter::Record;
+use crate::records::{BatchHeader, EntryDetail, FileControl, UnknownRecord};
+use crate::routing::{self, RoutingDirectory};
+use crate::summary::format_cents;
+use crate::{AchFile, Batch, EntryDescriptionKind, ServiceClassCode, TransactionCode};
+
+/// How serious a [`ValidationIssue`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum Severity {
+    /// Worth a look, but an ODFI would normally accept the file
+    Warning,
+
+    /// The file would be rejected
+    Error,
+}
+
+/// Which control record a [`ControlMismatch`] is in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum ControlScope {
+    /// A batch control
+    Batch,
+
+    /// The file control
+    File,
+}
+
+/// A computed field of a batch or file control.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum ControlField {
+    /// Batch count (file control only)
+    BatchCount,
+
+    /// Block count (file control only)
+    BlockCount,
+
+    /// Entry/addenda count
+    EntryAddendaCount,
+
+    /// Entry hash
+    EntryHash,
+
+    /// Total debit entry dollar amount
+    TotalDebitAmount,
+
+    /// Total credit entry dollar amount
+    TotalCreditAmount,
+}
+
+impl ControlField {
+    /// The field's name, as used by `fields()`.
+    pub fn name(self) -> &'static str {
+        match self {
+            ControlField::BatchCount => "batch_count",
+            ControlField::BlockCount => "block_count",
+            ControlField::EntryAddendaCount => "entry_addenda_count",
+            ControlField::EntryHash => "entry_hash",
+            ControlField::TotalDebitAmount => "total_debit_amount",
+            ControlField::TotalCreditAmount => "total_credit_amount",
+        }
+    }
+}
+
+impl fmt::Display for ControlField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A control field whose declared value differs from the one computed from
+/// the entries, carried by the [`ValidationIssue`] that reports it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ControlMismatch {
+    /// Batch or file control
+    pub scope: ControlScope,
+
+    /// The mismatched field
+    pub field: ControlField,
+
+    /// Value in the control record
+    pub declared: u64,
+
+    /// Value computed from the entries, or for the file control from the
+    /// batch controls
+    pub computed: u64,
+
+    /// Batch number for a batch control, `None` for the file control
+    pub batch_number: Option<String>,
+}
+
+impl ControlMismatch {
+    /// How far the declared value is above the computed one; negative when
+    /// it is below.
+    pub fn difference(&self) -> i128 {
+        i128::from(self.declared) - i128::from(self.computed)
+    }
+}
+
+impl fmt::Display for ControlMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.batch_number {
+            Some(batch_number) => write!(f, "batch {batch_number} ")?,
+            None => write!(f, "file ")?,
+        }
+        write!(
+            f,
+            "{}: declared {}, computed {} (difference {:+})",
+            self.field,
+            self.declared,
+            self.computed,
+            self.difference()
+        )
+    }
+}
+
+/// One problem found by [`AchFile::validate`].
+///
+/// With the `serde` feature, issues serialize in the same shape as
+/// [`AchError`](crate::AchError), with `code` always `ValidationIssue`:
+/// `{"code":"ValidationIssue","severity":"Error","batch":"0000001","field":"entry_hash","message":"..."}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "code"))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ValidationIssue {
+    /// How serious the problem is
+    pub severity: Severity,
+
+    /// Batch number of the offending batch, or `None` for file-level issues
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "batch", default, skip_serializing_if = "Option::is_none")
+    )]
+    pub batch_number: Option<String>,
+
+    /// Trace number of the offending entry, if the issue concerns one entry
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "trace", default, skip_serializing_if = "Option::is_none")
+    )]
+    pub trace_number: Option<String>,
+
+    /// Name of the offending field, as used by `fields()`
+    pub field: String,
+
+    /// Human-readable description
+    pub message: String,
+
+    /// The declared and computed values, when a control field is wrong
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub control: Option<ControlMismatch>,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.severity {
+            Severity::Warning => write!(f, "warning")?,
+            Severity::Error => write!(f, "error")?,
+        }
+        if let Some(batch_number) = &self.batch_number {
+            write!(f, ": batch {batch_number}")?;
+        }
+        if let Some(trace_number) = &self.trace_number {
+            write!(f, ": entry {trace_number}")?;
+        }
+        write!(f, ": {}: {}", self.field, self.message)
+    }
+}
+
+/// Every issue found by [`AchFile::validate`], in file order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ValidationReport {
+    /// Issues found, in file order
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// Returns `true` if no issue is an error. Warnings are allowed.
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// Issues with [`Severity::Error`].
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues.iter().filter(|i| i.severity == Severity::Error)
+    }
+
+    /// Whether issues were left out past
+    /// [`ValidationOptions::max_errors`]. The list then ends with an error
+    /// on the field `max_errors`, because those left out may be errors.
+    pub fn is_truncated(&self) -> bool {
+        self.issues
+            .last()
+            .is_some_and(|i| i.field == TRUNCATED_FIELD && i.batch_number.is_none())
+    }
+
+    /// Whether `max_errors` issues have been found.
+    fn is_full(&self, max_errors: Option<usize>) -> bool {
+        max_errors.is_some_and(|max| self.issues.len() >= max)
+    }
+
+    /// Cut the issues to `max_errors`, ending them with the marker. A
+    /// report that is exactly full still gets the marker when `skipped`
+    /// says checks were left out to stop there.
+    fn limit(&mut self, max_errors: Option<usize>, skipped: bool) {
+        let Some(max) = max_errors else {
+            return;
+        };
+        if skipped || self.issues.len() > max {
+            self.issues.truncate(max);
+            self.issues.push(ValidationIssue {
+                severity: Severity::Error,
+                batch_number: None,
+                trace_number: None,
+                field: TRUNCATED_FIELD.to_string(),
+                message: format!("stopped after {max} issues"),
+                control: None,
+            });
+        }
+    }
+
+    /// The control mismatches among the issues.
+    pub fn control_mismatches(&self) -> impl Iterator<Item = &ControlMismatch> {
+        self.issues.iter().filter_map(|i| i.control.as_ref())
+    }
+
+    /// Issues with [`Severity::Warning`].
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|i| i.severity == Severity::Warning)
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for issue in &self.issues {
+            writeln!(f, "{issue}")?;
+        }
+        write!(
+            f,
+            "{} error(s), {} warning(s)",
+            self.errors().count(),
+            self.warnings().count()
+        )
+    }
+}
+
+/// The field of the issue that ends a truncated report.
+const TRUNCATED_FIELD: &str = "max_errors";
+
+/// Tunable checks for [`AchFile::validate_with`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ValidationOptions {
+    /// Warn when a company descriptive date that reads as a date is more
+    /// than this many days from the effective entry date; `None` skips the
+    /// check
+    pub descriptive_date_tolerance_days: Option<u32>,
+
+    /// Issues to report before validation stops; `None` reports them all.
+    /// See [`ValidationReport::is_truncated`].
+    pub max_errors: Option<usize>,
+
+    /// How two-digit years in the effective entry and descriptive dates are
+    /// placed
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub year_pivot: YearPivot,
+}
+
+impl Default for ValidationOptions {
+    /// A 30-day descriptive date tolerance, at most
+    /// [`DEFAULT_MAX_ERRORS`] issues, and the
+    /// default [`YearPivot`].
+    fn default() -> Self {
+        ValidationOptions {
+            descriptive_date_tolerance_days: Some(30),
+            max_errors: Some(DEFAULT_MAX_ERRORS),
+            year_pivot: YearPivot::default(),
+        }
+    }
+}
+
+impl AchFile<'_> {
+    /// Check the file against its own control records, with the default
+    /// [`ValidationOptions`].
+    ///
+    /// Batch and file controls are recomputed from the entries and compared
+    /// field by field, batch headers are compared with their controls, and
+    /// every entry's routing number check digit is verified. A block count
+    /// that disagrees with the blocking factor is only a warning, as is a
+    /// company descriptive date far from the effective entry date. A file
+    /// with no batches must have zero counts and totals and a block count
+    /// of 1.
+    pub fn validate(&self) -> ValidationReport {
+        self.validate_with(&ValidationOptions::default())
+    }
+
+    /// Like [`validate`](AchFile::validate), with the given options.
+    pub fn validate_with(&self, options: &ValidationOptions) -> ValidationReport {
+        self.validate_against(options, None)
+    }
+
+    /// Like [`validate_with`](AchFile::validate_with), also warning about
+    /// receiving routing numbers that are missing from `directory` or
+    /// inactive.
+    pub fn validate_with_directory(
+        &self,
+        options: &ValidationOptions,
+        directory: &dyn RoutingDirectory,
+    ) -> ValidationReport {
+        self.validate_against(options, Some(directory))
+    }
+
+    fn validate_against(
+        &self,
+        options: &ValidationOptions,
+        directory: Option<&dyn RoutingDirectory>,
+    ) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        let mut skipped = false;
+        for batch in &self.batches {
+            if report.is_full(options.max_errors) {
+                skipped = true;
+                break;
+            }
+            check_batch(&mut report, batch, options, directory);
+        }
+
+        let mut computed = self.clone();
+        for batch in &mut computed.batches {
+            recompute_control(batch);
+        }
+        computed.recompute_file_control();
+        let declared = declared_file_control(&mut report, &self.file_control, self.is_adv());
+        check_file_control(
+            &mut report,
+            &declared,
+            &computed.file_control,
+            self.batches.is_empty(),
+        );
+        report.limit(options.max_errors, skipped);
+        report
+    }
+}
+
+impl Batch<'_> {
+    /// Check the batch on its own, with the default [`ValidationOptions`]:
+    /// its control is recomputed from the entries and compared field by
+    /// field, and its header is compared with its control, as
+    /// [`AchFile::validate`] does for each batch.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile, ControlField};
+    ///
+    /// let text = fixtures::sample_ppd_file(3);
+    /// let mut file = AchFile::parse(&text).unwrap();
+    /// let batch = &mut file.batches[0];
+    /// batch.entries.pop();
+    ///
+    /// let report = batch.validate();
+    /// let fields: Vec<ControlField> = report.control_mismatches().map(|m| m.field).collect();
+    /// assert_eq!(
+    ///     fields,
+    ///     [ControlField::EntryAddendaCount, ControlField::EntryHash, ControlField::TotalCreditAmount]
+    /// );
+    /// ```
+    pub fn validate(&self) -> ValidationReport {
+        self.validate_with(&ValidationOptions::default())
+    }
+
+    /// Like [`validate`](Batch::validate), with the given options.
+    pub fn validate_with(&self, options: &ValidationOptions) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        check_batch(&mut report, self, options, None);
+        report.limit(options.max_errors, false);
+        report
+    }
+}
+
+/// Validate a file from its records as they are read, holding no more than
+/// the current batch, and with the same results as
+/// [`AchFile::validate_with`] on the parsed file.
+///
+/// Each batch is checked when its control record arrives and the file
+/// control is checked against the batches seen when it arrives. None of
+/// the checks needs more of the file than that, so none is skipped. A
+/// record that fails to parse, as yielded by [lossy](crate::Records::lossy)
+/// iteration or by a strict iteration before it ends, is reported as an
+/// error on the field `record`. Records out of place are reported the same
+/// way and ignored.
+///
+/// ```
+/// use rs_ach::{fixtures, validation, AchFile, ValidationOptions};
+///
+/// let text = fixtures::corrupt(
+///     &fixtures::sample_ppd_file(2),
+///     fixtures::Corruption::TotalsMismatch,
+/// );
+/// let options = ValidationOptions::default();
+/// let report = validation::validate_stream(AchFile::records(&text), &options);
+/// assert_eq!(report, AchFile::parse(&text).unwrap().validate_with(&options));
+/// assert!(!report.is_valid());
+/// ```
+pub fn validate_stream<'a>(
+    records: impl IntoIterator<Item = AchResult<Record<'a>>>,
+    options: &ValidationOptions,
+) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    let mut skipped = false;
+    let mut blocking_factor = 10;
+    let mut totals = FileTotals::default();
+    let mut batches = 0;
+    let mut adv_batches = 0;
+    // The current batch: its header, entries, and records of unknown type.
+    let mut open: Option<(
+        BatchHeader<'a>,
+        Vec<EntryDetail<'a>>,
+        Vec<UnknownRecord<'a>>,
+    )> = None;
+
+    let record_error = |message: String| ValidationIssue {
+        severity: Severity::Error,
+        batch_number: None,
+        trace_number: None,
+        field: "record".to_string(),
+        message,
+        control: None,
+    };
+    for record in records {
+        match (record, &mut open) {
+            (Err(error), _) => report.issues.push(record_error(error.to_string())),
+            (Ok(Record::FileHeader(header)), _) => {
+                blocking_factor = controls::blocking_factor(&header);
+            }
+            (Ok(Record::BatchHeader(header)), None) => {
+                open = Some((header, Vec::new(), Vec::new()));
+            }
+            (Ok(Record::EntryDetail(entry)), Some((_, entries, _))) => entries.push(entry),
+            (Ok(Record::Addenda(addenda)), Some((_, entries, _))) if !entries.is_empty() => {
+                entries.last_mut().unwrap().addenda.push(addenda);
+            }
+            (Ok(Record::Unknown(unknown)), Some((_, _, unknown_records))) => {
+                unknown_records.push(unknown);
+            }
+            (Ok(Record::Unknown(_)), None) => totals.add_unknown(1),
+            (Ok(Record::BatchControl(control)), Some(_)) => {
+                let (header, entries, unknown_records) = open.take().unwrap();
+                let mut batch = Batch {
+                    header,
+                    entries,
+                    control,
+                    unknown_records,
+                    adv: None,
+                    iat: None,
+                };
+                if report.is_full(options.max_errors) {
+                    skipped = true;
+                } else {
+                    check_batch(&mut report, &batch, options, None);
+                }
+                recompute_control(&mut batch);
+                totals.add_batch(&batch.control, batch.unknown_records.len());
+                batches += 1;
+                adv_batches += usize::from(batch.is_adv());
+            }
+            (Ok(Record::FileControl(declared)), None) => {
+                let mut computed = declared.clone();
+                totals.apply(blocking_factor, &mut computed);
+                let is_adv = batches > 0 && adv_batches == batches;
+                let declared = declared_file_control(&mut report, &declared, is_adv);
+                check_file_control(&mut report, &declared, &computed, batches == 0);
+            }
+            (Ok(record), _) => {
+                let code = record.get_field("record_type").unwrap_or_default();
+                let name = parser::record_name(&code).unwrap_or("unknown");
+                report
+                    .issues
+                    .push(record_error(format!("{name} record out of place")));
+            }
+        }
+    }
+    report.limit(options.max_errors, skipped);
+    report
+}
+
+/// The batch rules [`AchFile::validate_with`] checks, with the options'
+/// checks and the directory's, when there is one.
+fn check_batch(
+    report: &mut ValidationReport,
+    batch: &Batch<'_>,
+    options: &ValidationOptions,
+    directory: Option<&dyn RoutingDirectory>,
+) {
+    validate_batch(report, batch);
+    if let Some(tolerance) = options.descriptive_date_tolerance_days {
+        check_descriptive_date(report, batch, tolerance, options.year_pivot);
+    }
+    if let Some(directory) = directory {
+        check_directory(report, batch, directory);
+    }
+}
+
+/// Recompute the batch's control for comparison with the declared one. An
+/// ADV batch's totals are summed from its entries read with the ADV layout;
+/// if an entry cannot be read, which is reported, they are left as declared.
+fn recompute_control(batch: &mut Batch<'_>) {
+    let declared = batch.control.clone();
+    batch.recompute_control();
+    if batch.is_adv() {
+        let (debit, credit) = batch.adv_totals().unwrap_or_else(|_| {
+            declared.as_adv().map_or(
+                (declared.total_debit_amount, declared.total_credit_amount),
+                |adv| (adv.total_debit_amount, adv.total_credit_amount),
+            )
+        });
+        batch.control.total_debit_amount = debit;
+        batch.control.total_credit_amount = credit;
+    }
+}
+
+/// The file control as declared, with the totals of the ADV layout for an
+/// ADV file. Totals that cannot be read are reported and left as parsed.
+fn declared_file_control(
+    report: &mut ValidationReport,
+    file_control: &FileControl,
+    is_adv: bool,
+) -> FileControl {
+    let mut declared = file_control.clone();
+    if is_adv {
+        match file_control.as_adv() {
+            Ok(adv) => {
+                declared.total_debit_amount = adv.total_debit_amount;
+                declared.total_credit_amount = adv.total_credit_amount;
+            }
+            Err(error) => report.issues.push(ValidationIssue {
+                severity: Severity::Error,
+                batch_number: None,
+                trace_number: None,
+                field: "file_control".to_string(),
+                message: error.to_string(),
+                control: None,
+            }),
+        }
+    }
+    declared
+}
+
+/// Compare the file control with the one computed from the batches.
+fn check_file_control(
+    report: &mut ValidationReport,
+    declared: &FileControl,
+    computed: &FileControl,
+    no_batches: bool,
+) {
+    for (severity, field, declared, computed) in [
+        (
+            Severity::Error,
+            ControlField::BatchCount,
+            declared.batch_count,
+            computed.batch_count,
+        ),
+        (
+            // A file without batches is always exactly one block.
+            if no_batches {
+                Severity::Error
+            } else {
+                Severity::Warning
+            },
+            ControlField::BlockCount,
+            declared.block_count,
+            computed.block_count,
+        ),
+        (
+            Severity::Error,
+            ControlField::EntryAddendaCount,
+            declared.entry_addenda_count,
+            computed.entry_addenda_count,
+        ),
+        (
+            Severity::Error,
+            ControlField::EntryHash,
+            declared.entry_hash,
+            computed.entry_hash,
+        ),
+        (
+            Severity::Error,
+            ControlField::TotalDebitAmount,
+            declared.total_debit_amount,
+            computed.total_debit_amount,
+        ),
+        (
+            Severity::Error,
+            ControlField::TotalCreditAmount,
+            declared.total_credit_amount,
+            computed.total_credit_amount,
+        ),
+    ] {
+        if declared != computed {
+            report.issues.push(control_issue(
+                severity,
+                ControlMismatch {
+                    scope: ControlScope::File,
+                    field,
+                    declared,
+                    computed,
+                    batch_number: None,
+                },
+            ));
+        }
+    }
+}
+
+/// The issue reporting `mismatch`.
+fn control_issue(severity: Severity, mismatch: ControlMismatch) -> ValidationIssue {
+    ValidationIssue {
+        severity,
+        batch_number: mismatch.batch_number.clone(),
+        trace_number: None,
+        field: mismatch.field.name().to_string(),
+        message: format!(
+            "declared {}, computed {} (difference {:+})",
+            mismatch.declared,
+            mismatch.computed,
+            mismatch.difference()
+        ),
+        control: Some(mismatch),
+    }
+}
+
+fn validate_batch(report: &mut ValidationReport, batch: &Batch<'_>) {
+    let header = &batch.header;
+    let batch_number = Some(header.batch_number.to_string());
+    let mut push = |trace_number: Option<String>, field: &str, message: String| {
+        report.issues.push(ValidationIssue {
+            severity: Severity::Error,
+            batch_number: batch_number.clone(),
+            trace_number,
+            field: field.to_string(),
+            message,
+            control: None,
+        });
+    };
+
+    for entry in &batch.entries {
+        let routing = format!(
+            "{}{}",
+            entry.receiving_dfi_identification, entry.check_digit
+        );
+        if !routing::is_valid(&routing) {
+            push(
+                Some(entry.trace_number.to_string()),
+                "check_digit",
+                format!("routing number {routing} fails its check digit"),
+            );
+        }
+    }
+
+    let kind = header.entry_description_kind();
+    let sec = header.standard_entry_class_code.trim();
+    if let Some(required) = kind.required_sec_code() {
+        if sec != required {
+            push(
+                None,
+                "company_entry_description",
+                format!("{kind} is reserved for {required} batches, batch is {sec}"),
+            );
+        }
+    } else if sec == "RCK" {
+        push(
+            None,
+            "company_entry_description",
+            format!(
+                "RCK batches must be described REDEPCHECK, not {:?}",
+                kind.description()
+            ),
+        );
+    }
+    if kind == EntryDescriptionKind::RetryPayment {
+        // Only debits returned R01 or R09 may be reinitiated. The return
+        // is in an earlier file, so only the debit side can be checked here.
+        for entry in &batch.entries {
+            if !is_debit(&entry.transaction_code) {
+                push(
+                    Some(entry.trace_number.to_string()),
+                    "transaction_code",
+                    format!(
+                        "RETRY PYMT entries reinitiate debits, transaction code {} is not one",
+                        entry.transaction_code
+                    ),
+                );
+            }
+        }
+    }
+
+    // A one-sided batch may only hold entries of its side.
+    let (allowed, forbidden): (&str, fn(&TransactionCode) -> bool) =
+        match header.service_class_code() {
+            Ok(ServiceClassCode::CreditsOnly) => ("credits", TransactionCode::is_debit),
+            Ok(ServiceClassCode::DebitsOnly) => ("debits", TransactionCode::is_credit),
+            _ => ("", |_| false),
+        };
+    for entry in &batch.entries {
+        if forbidden(&TransactionCode::from_code(&entry.transaction_code)) {
+            push(
+                Some(entry.trace_number.to_string()),
+                "transaction_code",
+                format!(
+                    "service class {} allows only {allowed}, transaction code {} is not one",
+                    header.service_class_code, entry.transaction_code
+                ),
+            );
+        }
+    }
+
+    // Prenotes are for zero, and otherwise only zero-dollar codes may be,
+    // on CCD and CTX entries with remittance addenda. Returns and NOCs may
+    // be for zero under their own codes.
+    if !batch.is_adv() {
+        for entry in &batch.entries {
+            let code = TransactionCode::from_code(&entry.transaction_code);
+            let amount = format_cents(entry.amount);
+            let (field, message) = if entry.is_prenote() && entry.amount > 0 {
+                (
+                    "amount",
+                    format!(
+                        "prenote transaction code {code} must be for zero, entry is for {amount}"
+                    ),
+                )
+            } else if code.is_zero_dollar() && entry.amount > 0 {
+                (
+                    "amount",
+                    format!("zero-dollar transaction code {code} must be for zero, entry is for {amount}"),
+                )
+            } else if code.is_zero_dollar() && sec != "CCD" && sec != "CTX" {
+                (
+                    "transaction_code",
+                    format!("zero-dollar transaction code {code} is for CCD and CTX batches, batch is {sec}"),
+                )
+            } else if code.is_zero_dollar() && entry.addenda.is_empty() {
+                (
+                    "transaction_code",
+                    format!("zero-dollar transaction code {code} needs remittance addenda"),
+                )
+            } else if code.is_live() && entry.amount == 0 {
+                (
+                    "amount",
+                    format!(
+                        "live transaction code {code} with a zero amount; \
+                         use a prenote code, or a zero-dollar code in a CCD or CTX batch"
+                    ),
+                )
+            } else {
+                continue;
+            };
+            push(Some(entry.trace_number.to_string()), field, message);
+        }
+    }
+
+    if let Some(check) = batch.micro_entry_check() {
+        if !check.described {
+            push(
+                None,
+                "company_entry_description",
+                format!(
+                    "micro-entry batches must be described ACCTVERIFY, not {:?}",
+                    kind.description()
+                ),
+            );
+        }
+        for trace_number in &check.large_credits {
+            push(
+                Some(trace_number.clone()),
+                "amount",
+                "micro-entry credits must be for less than 1.00".to_string(),
+            );
+        }
+        if !check.is_offset() {
+            push(
+                None,
+                "total_debit_amount",
+                format!(
+                    "micro-entry credits of {} must be offset by debits of the same total, debits are {}",
+                    format_cents(check.total_credit_amount),
+                    format_cents(check.total_debit_amount)
+                ),
+            );
+        }
+    }
+
+    if let Some(limit) = check_conversion::entry_limit(sec) {
+        for entry in batch.entries.iter().filter(|e| e.amount > limit) {
+            push(
+                Some(entry.trace_number.to_string()),
+                "amount",
+                format!(
+                    "{sec} entries are limited to {}, entry is for {}",
+                    format_cents(limit),
+                    format_cents(entry.amount)
+                ),
+            );
+        }
+    }
+
+    if sec == "CTX" {
+        for entry in &batch.entries {
+            let stated = entry.as_ctx().addenda_count;
+            if stated.map(usize::from) != Some(entry.addenda.len()) {
+                push(
+                    Some(entry.trace_number.to_string()),
+                    "addenda_count",
+                    format!(
+                        "CTX entry states {:?} addenda but has {}",
+                        entry.addenda_count_field(),
+                        entry.addenda.len()
+                    ),
+                );
+            }
+        }
+    }
+
+    if sec == "IAT" {
+        for entry in &batch.entries {
+            let missing = entry.missing_iat_addenda();
+            if !missing.is_empty() {
+                push(
+                    Some(entry.trace_number.to_string()),
+                    "addenda_type_code",
+                    format!(
+                        "IAT entries need addenda 10 through 16, missing {}",
+                        missing.join(", ")
+                    ),
+                );
+            }
+        }
+    }
+
+    let mut declared = batch.control.clone();
+    if batch.is_adv() {
+        for entry in &batch.entries {
+            if let Err(error) = entry.as_adv() {
+                push(
+                    Some(entry.trace_number.to_string()),
+                    "amount",
+                    error.to_string(),
+                );
+            }
+        }
+        match batch.control.as_adv() {
+            Ok(adv) => {
+                declared.total_debit_amount = adv.total_debit_amount;
+                declared.total_credit_amount = adv.total_credit_amount;
+            }
+            Err(error) => push(None, "batch_control", error.to_string()),
+        }
+    }
+    let declared = &declared;
+    for (field, in_header, in_control) in [
+        (
+            "service_class_code",
+            header.service_class_code.as_ref(),
+            declared.service_class_code.as_str(),
+        ),
+        (
+            "company_identification",
+            header.company_identification.as_ref(),
+            declared.company_identification.as_str(),
+        ),
+        (
+            "batch_number",
+            header.batch_number.as_ref(),
+            declared.batch_number.as_str(),
+        ),
+    ] {
+        // ADV batch controls carry ACH operator data where others carry the
+        // company identification.
+        if field == "company_identification" && batch.is_adv() {
+            continue;
+        }
+        if in_header.trim() != in_control.trim() {
+            push(
+                None,
+                field,
+                format!("header has {in_header:?}, control has {in_control:?}"),
+            );
+        }
+    }
+
+    let mut computed = batch.clone();
+    recompute_control(&mut computed);
+    let computed = &computed.control;
+    for (field, declared, computed) in [
+        (
+            ControlField::EntryAddendaCount,
+            declared.entry_addenda_count,
+            computed.entry_addenda_count,
+        ),
+        (
+            ControlField::EntryHash,
+            declared.entry_hash,
+            computed.entry_hash,
+        ),
+        (
+            ControlField::TotalDebitAmount,
+            declared.total_debit_amount,
+            computed.total_debit_amount,
+        ),
+        (
+            ControlField::TotalCreditAmount,
+            declared.total_credit_amount,
+            computed.total_credit_amount,
+        ),
+    ] {
+        if declared != computed {
+            report.issues.push(control_issue(
+                Severity::Error,
+                ControlMismatch {
+                    scope: ControlScope::Batch,
+                    field,
+                    declared,
+                    computed,
+                    batch_number: batch_number.clone(),
+                },
+            ));
+        }
+    }
+}
+
+/// Warn about receiving routing numbers missing from `directory` or
+/// inactive. Numbers failing their check digit are already errors.
+fn check_directory(
+    report: &mut ValidationReport,
+    batch: &Batch<'_>,
+    directory: &dyn RoutingDirectory,
+) {
+    for entry in &batch.entries {
+        let routing = format!(
+            "{}{}",
+            entry.receiving_dfi_identification, entry.check_digit
+        );
+        if !routing::is_valid(&routing) {
+            continue;
+        }
+        let message = match directory.lookup(&routing) {
+            None => format!("routing number {routing} is not in the directory"),
+            Some(info) if !info.active => {
+                format!("routing number {routing} ({}) is inactive", info.name)
+            }
+            Some(_) => continue,
+        };
+        report.issues.push(ValidationIssue {
+            severity: Severity::Warning,
+            batch_number: Some(batch.header.batch_number.to_string()),
+            trace_number: Some(entry.trace_number.to_string()),
+            field: "receiving_dfi_identification".to_string(),
+            message,
+            control: None,
+        });
+    }
+}
+
+/// Warn if the batch's descriptive date reads as a date more than
+/// `tolerance` days from its effective entry date.
+fn check_descriptive_date(
+    report: &mut ValidationReport,
+    batch: &Batch<'_>,
+    tolerance: u32,
+    pivot: YearPivot,
+) {
+    let header = &batch.header;
+    let Some(effective) = CivilDate::parse_with(&header.effective_entry_date, pivot) else {
+        return;
+    };
+    let Some(described) = descriptive_date(&header.company_descriptive_date, effective, pivot)
+    else {
+        return;
+    };
+    let days = (described.days_since_epoch() - effective.days_since_epoch()).unsigned_abs();
+    if days > tolerance {
+        report.issues.push(ValidationIssue {
+            severity: Severity::Warning,
+            batch_number: Some(header.batch_number.to_string()),
+            trace_number: None,
+            field: "company_descriptive_date".to_string(),
+            message: format!(
+                "descriptive date {:?} is {days} days from effective entry date {:?}",
+                header.company_descriptive_date.trim(),
+                header.effective_entry_date.as_ref()
+            ),
+            control: None,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{self, Corruption};
+
+    /// The validation of `file` when streamed from its written records.
+    fn streamed(file: &AchFile<'_>, options: &ValidationOptions) -> ValidationReport {
+        let text = crate::writer::render_lines(file).unwrap().join("\n");
+        let parse = crate::ParseOptions {
+            keep_unknown_records: true,
+            ..crate::ParseOptions::default()
+        };
+        validate_stream(AchFile::records_with(&text, &parse), options)
+    }
+
+    #[test]
+    fn test_validate_stream_matches_validate() {
+        let mut files = Vec::new();
+        for text in [
+            fixtures::sample_ppd_file(5),
+            fixtures::sample_ctx_with_addenda(),
+            fixtures::sample_returns_file(),
+            fixtures::sample_multi_company_file(),
+            fixtures::sample_empty_file(),
+            fixtures::corrupt(&fixtures::sample_ppd_file(3), Corruption::TotalsMismatch),
+        ] {
+            files.push(AchFile::parse(&text).unwrap().into_owned());
+        }
+
+        let mut file = AchFile::parse(&fixtures::sample_multi_company_file())
+            .unwrap()
+            .into_owned();
+        file.batches[0].entries[1].check_digit = "9".into();
+        file.batches[0].header.company_descriptive_date = "0601  ".into();
+        file.batches[1].control.batch_number = "0000009".into();
+        file.batches[1].entries[0].amount += 1;
+        file.batches[2].header.company_entry_description = "RETRY PYMT".into();
+        file.file_control.block_count += 1;
+        file.file_control.entry_hash = 0;
+        files.push(file.clone());
+
+        // Records of unknown type count toward the blocks.
+        file.unknown_records.push(crate::UnknownRecord {
+            type_code: "0".into(),
+            raw_line: format!("0{:<93}", "PROPRIETARY").into(),
+            line_number: 2,
+            preceding: 1,
+        });
+        file.file_control.block_count -= 1;
+        files.push(file);
+
+        let mut empty = files[4].clone();
+        empty.file_control.block_count = 2;
+        files.push(empty);
+
+        for max_errors in [None, Some(3), Some(0)] {
+            let options = ValidationOptions {
+                max_errors,
+                ..ValidationOptions::default()
+            };
+            for file in &files {
+                assert_eq!(
+                    streamed(file, &options),
+                    file.validate_with(&options),
+                    "{max_errors:?}"
+                );
+            }
+        }
+        assert!(!files[6].validate().is_valid());
+    }
+
+    #[test]
+    fn test_validate_stream_record_errors() {
+        let sample = fixtures::sample_ppd_file(2);
+        let text = fixtures::corrupt(&sample, Corruption::BadAmount);
+        let report = validate_stream(
+            AchFile::records(&text).lossy(),
+            &ValidationOptions::default(),
+        );
+        let record: Vec<_> = report.errors().filter(|i| i.field == "record").collect();
+        assert_eq!(record.len(), 1);
+        assert!(record[0].message.contains("amount"), "{}", record[0]);
+
+        // A record out of place is reported and left out.
+        let file = AchFile::parse(&sample).unwrap();
+        let mut records: Vec<_> = AchFile::records(&sample).collect();
+        records.insert(1, Ok(Record::BatchControl(file.batches[0].control.clone())));
+        let report = validate_stream(records, &ValidationOptions::default());
+        assert_eq!(
+            report.errors().next().unwrap().to_string(),
+            "error: record: batch control record out of place"
+        );
+    }
+
+    #[test]
+    fn test_fixtures_are_valid() {
+        for text in [
+            fixtures::sample_ppd_file(5),
+            fixtures::sample_ctx_with_addenda(),
+            fixtures::sample_returns_file(),
+            fixtures::sample_multi_company_file(),
+            fixtures::sample_empty_file(),
+        ] {
+            let report = AchFile::parse(&text).unwrap().validate();
+            assert_eq!(report.issues, [], "{report}");
+            assert!(report.is_valid());
+        }
+    }
+
+    #[test]
+    fn test_totals_mismatch() {
+        let text = fixtures::corrupt(&fixtures::sample_ppd_file(1), Corruption::TotalsMismatch);
+        let report = AchFile::parse(&text).unwrap().validate();
+
+        assert!(!report.is_valid());
+        let issue = report.errors().next().unwrap();
+        assert_eq!(issue.field, "total_credit_amount");
+        assert_eq!(issue.batch_number.as_deref(), Some("0000001"));
+        assert_eq!(
+            issue.to_string(),
+            "error: batch 0000001: total_credit_amount: declared 1251, computed 1250 \
+             (difference +1)"
+        );
+
+        let mismatches: Vec<&ControlMismatch> = report.control_mismatches().collect();
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(
+            *mismatches[0],
+            ControlMismatch {
+                scope: ControlScope::Batch,
+                field: ControlField::TotalCreditAmount,
+                declared: 1251,
+                computed: 1250,
+                batch_number: Some("0000001".to_string()),
+            }
+        );
+
+        let text = fixtures::sample_ppd_file(1);
+        let mut file = AchFile::parse(&text).unwrap();
+        file.file_control.entry_hash -= 5;
+        let report = file.validate();
+        let mismatch = report.control_mismatches().next().unwrap();
+        assert_eq!(mismatch.scope, ControlScope::File);
+        assert_eq!(mismatch.difference(), -5);
+        assert_eq!(
+            mismatch.to_string(),
+            format!(
+                "file entry_hash: declared {}, computed {} (difference -5)",
+                mismatch.declared, mismatch.computed
+            )
+        );
+    }
+
+    #[test]
+    fn test_validate_batch() {
+        let text = fixtures::sample_multi_company_file();
+        let mut file = AchFile::parse(&text).unwrap();
+        assert!(file.batches.iter().all(|b| b.validate().is_valid()));
+
+        file.batches[2].control.entry_addenda_count = 4;
+        file.batches[2].control.total_debit_amount += 100;
+        let report = file.batches[2].validate();
+        let mismatches: Vec<(ControlField, u64, u64)> = report
+            .control_mismatches()
+            .map(|m| (m.field, m.declared, m.computed))
+            .collect();
+        assert_eq!(
+            mismatches,
+            [
+                (ControlField::EntryAddendaCount, 4, 3),
+                (ControlField::TotalDebitAmount, 21_100, 21_000),
+            ]
+        );
+        let in_file: Vec<ValidationIssue> = file
+            .validate()
+            .issues
+            .into_iter()
+            .filter(|i| i.batch_number.as_deref() == Some("0000003"))
+            .collect();
+        assert_eq!(in_file, report.issues);
+    }
+
+    #[test]
+    fn test_max_errors() {
+        let text = fixtures::sample_ppd_file(5);
+        let mut file = AchFile::parse(&text).unwrap();
+        for entry in &mut file.batches[0].entries {
+            entry.check_digit = "9".into();
+        }
+        assert_eq!(file.validate().issues.len(), 5);
+        assert!(!file.validate().is_truncated());
+
+        let options = ValidationOptions {
+            max_errors: Some(2),
+            ..ValidationOptions::default()
+        };
+        let report = file.validate_with(&options);
+        assert!(report.is_truncated());
+        let fields: Vec<&str> = report.issues.iter().map(|i| i.field.as_str()).collect();
+        assert_eq!(fields, ["check_digit", "check_digit", "max_errors"]);
+        assert_eq!(
+            report.issues[2].to_string(),
+            "error: max_errors: stopped after 2 issues"
+        );
+    }
+
+    #[test]
+    fn test_max_errors_reached_exactly() {
+        let text = fixtures::sample_multi_company_file();
+        let mut file = AchFile::parse(&text).unwrap();
+        for entry in &mut file.batches[0].entries {
+            entry.check_digit = "9".into();
+        }
+        let max = file.batches[0].entries.len();
+        // Past the cutoff, and so unchecked.
+        file.batches[1].entries[0].check_digit = "9".into();
+        assert_eq!(file.validate().issues.len(), max + 1);
+
+        let options = ValidationOptions {
+            max_errors: Some(max),
+            ..ValidationOptions::default()
+        };
+        let report = file.validate_with(&options);
+        assert!(report.is_truncated());
+        assert_eq!(report.issues.len(), max + 1);
+        assert!(report.issues[..max]
+            .iter()
+            .all(|i| i.field == "check_digit"));
+
+        let written = file.to_ach_string().unwrap();
+        let streamed = validate_stream(AchFile::records(&written), &options);
+        assert_eq!(streamed, report);
+    }
+
+    #[test]
+    fn test_descriptive_date_year_pivot() {
+        let text = fixtures::sample_ppd_file(1);
+        let mut file = AchFile::parse(&text).unwrap();
+        file.batches[0].header.effective_entry_date = "991231".into();
+        file.batches[0].header.company_descriptive_date = "000102".into();
+
+        // Read in one century, the two dates are two days apart...
+        let options = ValidationOptions {
+            year_pivot: YearPivot::Fixed(1950),
+            ..ValidationOptions::default()
+        };
+        assert_eq!(file.validate_with(&options).warnings().count(), 0);
+
+        // ...but a window starting in 2000 puts the effective date in 2099.
+        let options = ValidationOptions {
+            year_pivot: YearPivot::Fixed(2000),
+            ..ValidationOptions::default()
+        };
+        assert_eq!(file.validate_with(&options).warnings().count(), 1);
+    }
+
+    #[test]
+    fn test_descriptive_date_mismatch() {
+        let text = fixtures::sample_multi_company_file();
+        let mut file = AchFile::parse(&text).unwrap();
+        // Effective September 3, 2014.
+        file.batches[0].header.company_descriptive_date = "0601  ".into();
+        file.batches[1].header.company_descriptive_date = "SEP 01".into();
+        file.batches[2].header.company_descriptive_date = "DUES  ".into();
+
+        let report = file.validate();
+        assert!(report.is_valid());
+        let warnings: Vec<_> = report.warnings().collect();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].to_string(),
+            "warning: batch 0000001: company_descriptive_date: descriptive date \"0601\" \
+             is 94 days from effective entry date \"140903\""
+        );
+
+        let options = ValidationOptions {
+            descriptive_date_tolerance_days: Some(1),
+            ..ValidationOptions::default()
+        };
+        assert_eq!(file.validate_with(&options).warnings().count(), 2);
+        let options = ValidationOptions {
+            descriptive_date_tolerance_days: None,
+            ..ValidationOptions::default()
+        };
+        assert_eq!(file.validate_with(&options).issues, []);
+    }
+
+    #[test]
+    fn test_reserved_descriptions() {
+        let text = fixtures::sample_ppd_file(2);
+        let mut file = AchFile::parse(&text).unwrap();
+        file.batches[0].header.company_entry_description = "REDEPCHECK".into();
+        let report = file.validate();
+        let errors: Vec<String> = report.errors().map(|i| i.to_string()).collect();
+        assert_eq!(
+            errors,
+            ["error: batch 0000001: company_entry_description: \
+              REDEPCHECK is reserved for RCK batches, batch is PPD"]
+        );
+
+        file.batches[0].header.standard_entry_class_code = "RCK".into();
+        assert!(file.validate().is_valid());
+        file.batches[0].header.company_entry_description = "CHECKS    ".into();
+        assert_eq!(
+            file.validate().errors().next().unwrap().message,
+            "RCK batches must be described REDEPCHECK, not \"CHECKS\""
+        );
+
+        // The fixture's first entry is a credit.
+        file.batches[0].header.standard_entry_class_code = "PPD".into();
+        file.batches[0].header.company_entry_description = "RETRY PYMT".into();
+        let report = file.validate();
+        let errors: Vec<_> = report.errors().collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "transaction_code");
+        assert_eq!(errors[0].trace_number.as_deref(), Some("123456780000001"));
+        assert_eq!(
+            errors[0].message,
+            "RETRY PYMT entries reinitiate debits, transaction code 22 is not one"
+        );
+
+        // As debits the same entries pass: which return they retry is not
+        // in the file.
+        for entry in &mut file.batches[0].entries {
+            entry.transaction_code = "27".into();
+        }
+        file.batches[0].recompute_control();
+        file.recompute_file_control();
+        assert!(file.validate().is_valid(), "{:?}", file.validate().issues);
+    }
+
+    #[test]
+    fn test_one_sided_service_class() {
+        let text = fixtures::sample_multi_company_file();
+        let mut file = AchFile::parse(&text).unwrap();
+        // The second batch holds a credit and a debit.
+        file.batches[1].header.service_class_code = "220".into();
+        file.batches[1].control.service_class_code = "220".to_string();
+        let report = file.validate();
+        let errors: Vec<String> = report.errors().map(|i| i.to_string()).collect();
+        assert_eq!(
+            errors,
+            [
+                "error: batch 0000002: entry 123456780000002: transaction_code: \
+              service class 220 allows only credits, transaction code 27 is not one"
+            ]
+        );
+
+        file.batches[1].header.service_class_code = "225".into();
+        file.batches[1].control.service_class_code = "225".to_string();
+        let report = file.validate();
+        let errors: Vec<_> = report.errors().collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].trace_number.as_deref(), Some("123456780000001"));
+
+        // Header and control must agree.
+        file.batches[1].header.service_class_code = "200".into();
+        let report = file.validate();
+        let errors: Vec<_> = report.errors().collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "service_class_code");
+        assert_eq!(errors[0].message, "header has \"200\", control has \"225\"");
+    }
+
+    #[test]
+    fn test_micro_entry_batches() {
+        let text = fixtures::sample_ppd_file(3);
+        let mut file = AchFile::parse(&text).unwrap();
+        let batch = &mut file.batches[0];
+        batch.header.company_entry_description = "ACCTVERIFY".into();
+        batch.entries[0].amount = 32;
+        batch.entries[1].amount = 77;
+        batch.entries[2].amount = 45;
+        batch.recompute_control();
+        file.recompute_file_control();
+        assert!(file.validate().is_valid());
+
+        // Undescribed micro-entries, then a large credit left unoffset.
+        file.batches[0].header.company_entry_description = "VERIFY".into();
+        let issues: Vec<String> = file.validate().errors().map(|i| i.to_string()).collect();
+        assert_eq!(
+            issues,
+            ["error: batch 0000001: company_entry_description: \
+              micro-entry batches must be described ACCTVERIFY, not \"VERIFY\""]
+        );
+        let batch = &mut file.batches[0];
+        batch.header.company_entry_description = "ACCTVERIFY".into();
+        batch.entries[2].amount = 150;
+        batch.recompute_control();
+        file.recompute_file_control();
+        let trace = file.batches[0].entries[2].trace_number.to_string();
+        let issues: Vec<String> = file.validate().errors().map(|i| i.to_string()).collect();
+        assert_eq!(
+            issues,
+            [
+                format!(
+                    "error: batch 0000001: entry {trace}: amount: \
+                     micro-entry credits must be for less than 1.00"
+                ),
+                "error: batch 0000001: total_debit_amount: \
+                 micro-entry credits of 1.82 must be offset by debits of the same total, \
+                 debits are 0.77"
+                    .to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prenotes_and_zero_amounts() {
+        let text = fixtures::sample_ppd_file(3);
+        let mut file = AchFile::parse(&text).unwrap();
+        let entries = &mut file.batches[0].entries;
+        entries[0].transaction_code = "23".into();
+        entries[1].amount = 0;
+        entries[2].transaction_code = "24".into();
+        entries[2].amount = 0;
+        file.batches[0].recompute_control();
+        file.recompute_file_control();
+        let report = file.validate();
+        let errors: Vec<String> = report.errors().map(|i| i.to_string()).collect();
+        let trace = |i: usize| file.batches[0].entries[i].trace_number.to_string();
+        assert_eq!(
+            errors,
+            [
+                format!(
+                    "error: batch 0000001: entry {}: amount: \
+                     prenote transaction code 23 must be for zero, entry is for 12.50",
+                    trace(0)
+                ),
+                format!(
+                    "error: batch 0000001: entry {}: amount: \
+                     live transaction code 27 with a zero amount; \
+                     use a prenote code, or a zero-dollar code in a CCD or CTX batch",
+                    trace(1)
+                ),
+                format!(
+                    "error: batch 0000001: entry {}: transaction_code: \
+                     zero-dollar transaction code 24 is for CCD and CTX batches, batch is PPD",
+                    trace(2)
+                ),
+            ]
+        );
+
+        // A prenote for zero is valid.
+        file.batches[0].entries[0].amount = 0;
+        file.batches[0].entries[1].transaction_code = "28".into();
+        file.batches[0].entries.truncate(2);
+        file.batches[0].recompute_control();
+        file.recompute_file_control();
+        assert!(file.validate().is_valid());
+        assert_eq!(file.prenotes().count(), 2);
+
+        // Zero-dollar CTX entries carry remittance.
+        let text = fixtures::sample_ctx_with_addenda();
+        let mut file = AchFile::parse(&text).unwrap();
+        file.batches[0].entries[0].transaction_code = "24".into();
+        let report = file.validate();
+        let errors: Vec<_> = report.errors().collect();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0]
+            .message
+            .starts_with("zero-dollar transaction code 24 must be for zero"));
+        file.batches[0].entries[0].amount = 0;
+        file.batches[0].recompute_control();
+        file.recompute_file_control();
+        assert!(file.validate().is_valid());
+    }
+
+    #[test]
+    fn test_check_conversion_limits() {
+        let text = fixtures::sample_ppd_file(2);
+        let mut file = AchFile::parse(&text).unwrap();
+        let batch = &mut file.batches[0];
+        batch.header.standard_entry_class_code = "RCK".into();
+        batch.header.company_entry_description = "REDEPCHECK".into();
+        batch.entries[0].amount = 250_000;
+        batch.entries[1].amount = 250_001;
+        batch.recompute_control();
+        file.recompute_file_control();
+        let report = file.validate();
+        let errors: Vec<String> = report.errors().map(|i| i.to_string()).collect();
+        let trace = file.batches[0].entries[1].trace_number.to_string();
+        assert_eq!(
+            errors,
+            [format!(
+                "error: batch 0000001: entry {trace}: amount: \
+                 RCK entries are limited to 2,500.00, entry is for 2,500.01"
+            )]
+        );
+
+        file.batches[0].header.standard_entry_class_code = "BOC".into();
+        file.batches[0].header.company_entry_description = "CHECK PYMT".into();
+        assert!(file.validate().is_valid());
+        file.batches[0].entries[0].amount = 2_500_001;
+        file.batches[0].recompute_control();
+        file.recompute_file_control();
+        let report = file.validate();
+        let errors: Vec<_> = report.errors().collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].message,
+            "BOC entries are limited to 25,000.00, entry is for 25,000.01"
+        );
+    }
+
+    #[test]
+    fn test_adv_totals() {
+        let text = fixtures::sample_adv_file();
+        let options = ValidationOptions::default();
+        let stream = validate_stream(AchFile::records(&text), &options);
+        assert_eq!(stream.issues, []);
+
+        // One cent more on the batch's 20-digit credit total.
+        let text = text.replacen("00000000123456789012 ", "00000000123456789013 ", 1);
+        let file = AchFile::parse(&text).unwrap();
+        let report = file.validate();
+        let errors: Vec<String> = report.errors().map(|i| i.to_string()).collect();
+        assert_eq!(
+            errors,
+            ["error: batch 0000001: total_credit_amount: \
+              declared 123456789013, computed 123456789012 (difference +1)"]
+        );
+        assert_eq!(validate_stream(AchFile::records(&text), &options), report);
+
+        // An amount that cannot be read is reported once, not as a total
+        // that is off by it.
+        let text = fixtures::sample_adv_file();
+        let mut file = AchFile::parse(&text).unwrap();
+        file.batches[0].entries[0].dfi_account_number = "4100001234     AB".into();
+        let report = file.validate();
+        let errors: Vec<String> = report.errors().map(|i| i.to_string()).collect();
+        assert_eq!(
+            errors,
+            ["error: batch 0000001: entry 091012982460001: amount: \
+              Invalid value for 'amount': \"AB3456789012\" is not a number"]
+        );
+        let text = file.to_ach_string().unwrap();
+        assert_eq!(validate_stream(AchFile::records(&text), &options), report);
+    }
+
+    #[test]
+    fn test_ctx_addenda_count() {
+        let text = fixtures::sample_ctx_with_addenda();
+        let mut file = AchFile::parse(&text).unwrap();
+        assert!(file.validate().is_valid());
+
+        file.batches[0].entries[0].addenda.pop();
+        file.batches[0].recompute_control();
+        file.recompute_file_control();
+        let report = file.validate();
+        let errors: Vec<String> = report.errors().map(|i| i.to_string()).collect();
+        let trace = file.batches[0].entries[0].trace_number.to_string();
+        assert_eq!(
+            errors,
+            [format!(
+                "error: batch 0000001: entry {trace}: addenda_count: \
+                 CTX entry states \"0003\" addenda but has 2"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_missing_iat_addenda() {
+        let text = fixtures::sample_iat_file();
+        let mut file = AchFile::parse(&text).unwrap();
+        let entry = &mut file.batches[0].entries[0];
+        entry.addenda.retain(|a| a.addenda_type_code != "13");
+        entry.addenda.truncate(4);
+        file.batches[0].recompute_control();
+        file.recompute_file_control();
+
+        let report = file.validate();
+        let errors: Vec<String> = report.errors().map(|i| i.to_string()).collect();
+        assert_eq!(
+            errors,
+            [
+                "error: batch 0000001: entry 123456780000001: addenda_type_code: \
+              IAT entries need addenda 10 through 16, missing 13, 15, 16"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_routing_directory() {
+        use crate::routing::InstitutionInfo;
+        use std::collections::HashMap;
+
+        let text = fixtures::sample_ppd_file(3);
+        let file = AchFile::parse(&text).unwrap();
+        let institution = |routing: &str, active| InstitutionInfo {
+            routing_number: routing.to_string(),
+            name: format!("BANK {routing}"),
+            active,
+        };
+        let directory: HashMap<String, InstitutionInfo> = [
+            institution("123456780", true),
+            institution("123232315", false),
+        ]
+        .into_iter()
+        .map(|i| (i.routing_number.clone(), i))
+        .collect();
+
+        let report = file.validate_with_directory(&ValidationOptions::default(), &directory);
+        assert!(report.is_valid());
+        let warnings: Vec<String> = report.warnings().map(|i| i.to_string()).collect();
+        assert_eq!(
+            warnings,
+            [
+                "warning: batch 0000001: entry 123456780000002: receiving_dfi_identification: \
+                 routing number 123232315 (BANK 123232315) is inactive",
+                "warning: batch 0000001: entry 123456780000003: receiving_dfi_identification: \
+                 routing number 021000021 is not in the directory",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bad_check_digit_and_block_count() {
+        let text = fixtures::sample_ppd_file(1);
+        let mut file = AchFile::parse(&text).unwrap();
+        file.batches[0].entries[0].check_digit = "1".into();
+        file.file_control.block_count += 1;
+        let report = file.validate();
+
+        let errors: Vec<_> = report.errors().collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "check_digit");
+        assert_eq!(errors[0].trace_number.as_deref(), Some("123456780000001"));
+        assert_eq!(report.warnings().next().unwrap().field, "block_count");
+        assert!(report.to_string().ends_with("1 error(s), 1 warning(s)"));
+    }
+
+    #[test]
+    fn test_file_without_batches() {
+        let text = fixtures::sample_empty_file();
+        let mut file = AchFile::parse(&text).unwrap();
+        assert!(file.validate().is_valid());
+
+        file.file_control.block_count = 2;
+        file.file_control.batch_count = 1;
+        let fields: Vec<String> = file.validate().errors().map(|i| i.field.clone()).collect();
+        assert_eq!(fields, ["batch_count", "block_count"]);
+    }
+}
@@ -0,0 +1,292 @@
+//! Velocity checks on debits against one receiving account.
+
+use std::collections::HashMap;
+
+use crate::controls::is_debit;
+use crate::AchFile;
+
+/// Limits for [`AchFile::velocity_report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct VelocityPolicy {
+    /// Most debits allowed against one account, including prior activity
+    pub max_debits: usize,
+
+    /// Count each originating company separately instead of all companies
+    /// together
+    pub per_company: bool,
+
+    /// Standard entry class codes to count, such as `RCK` and `WEB`; empty
+    /// counts every code
+    pub sec_codes: Vec<String>,
+}
+
+impl Default for VelocityPolicy {
+    /// Three debits per account from any company, with any SEC code: an
+    /// original and two re-presentments.
+    fn default() -> Self {
+        VelocityPolicy {
+            max_debits: 3,
+            per_company: false,
+            sec_codes: Vec::new(),
+        }
+    }
+}
+
+/// Number of debits against one account, as reported by
+/// [`VelocityReport::activity`] and accepted back as prior activity.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct VelocityCount {
+    /// Receiving routing number, including the check digit
+    pub routing_number: String,
+
+    /// Receiving DFI account number, trimmed
+    pub account_number: String,
+
+    /// Company identification, trimmed, when counted per company
+    pub company_identification: Option<String>,
+
+    /// Number of debits
+    pub debit_count: usize,
+}
+
+/// An account debited more often than [`VelocityPolicy::max_debits`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct VelocityViolation {
+    /// Receiving routing number, including the check digit
+    pub routing_number: String,
+
+    /// Receiving DFI account number, trimmed
+    pub account_number: String,
+
+    /// Company identification, trimmed, when counted per company
+    pub company_identification: Option<String>,
+
+    /// Debits from prior activity
+    pub prior_count: usize,
+
+    /// Debits in prior activity and this file together
+    pub debit_count: usize,
+
+    /// The limit exceeded
+    pub limit: usize,
+
+    /// Trace numbers of this file's contributing debits, in file order
+    pub trace_numbers: Vec<String>,
+}
+
+/// Debit counts and limit violations from [`AchFile::velocity_report`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct VelocityReport {
+    /// This file's debit counts, in order of each account's first debit,
+    /// for passing as prior activity on later runs
+    pub activity: Vec<VelocityCount>,
+
+    /// Accounts over the limit, in the same order
+    pub violations: Vec<VelocityViolation>,
+}
+
+impl VelocityReport {
+    /// Returns `true` if no account is over the limit.
+    pub fn is_clean(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+type Key = (String, String, Option<String>);
+
+impl AchFile<'_> {
+    /// Count debits per receiving account in this file and report accounts
+    /// over `policy`'s limit.
+    pub fn velocity_report(&self, policy: &VelocityPolicy) -> VelocityReport {
+        self.velocity_report_with_prior(policy, &[])
+    }
+
+    /// Like [`velocity_report`](AchFile::velocity_report), with debits from
+    /// earlier files (such as yesterday's [`VelocityReport::activity`])
+    /// counted toward the limit.
+    ///
+    /// Prior counts are matched by routing and account number, and by
+    /// company when [`VelocityPolicy::per_company`] is set; prior counts
+    /// without a company are then ignored. Only accounts debited in this
+    /// file are reported.
+    pub fn velocity_report_with_prior(
+        &self,
+        policy: &VelocityPolicy,
+        prior: &[VelocityCount],
+    ) -> VelocityReport {
+        let mut prior_counts: HashMap<Key, usize> = HashMap::new();
+        for count in prior {
+            let company = if policy.per_company {
+                match &count.company_identification {
+                    Some(company) => Some(company.trim().to_string()),
+                    None => continue,
+                }
+            } else {
+                None
+            };
+            let key = (
+                count.routing_number.trim().to_string(),
+                count.account_number.trim().to_string(),
+                company,
+            );
+            *prior_counts.entry(key).or_default() += count.debit_count;
+        }
+
+        let mut keys: Vec<Key> = Vec::new();
+        let mut traces: HashMap<Key, Vec<String>> = HashMap::new();
+        for batch in &self.batches {
+            let header = &batch.header;
+            let sec = header.standard_entry_class_code.trim();
+            if !policy.sec_codes.is_empty() && !policy.sec_codes.iter().any(|c| c.trim() == sec) {
+                continue;
+            }
+            let company = policy
+                .per_company
+                .then(|| header.company_identification.trim().to_string());
+            for entry in batch
+                .entries
+                .iter()
+                .filter(|e| is_debit(&e.transaction_code))
+            {
+                let key = (
+                    format!(
+                        "{}{}",
+                        entry.receiving_dfi_identification.trim(),
+                        entry.check_digit.trim()
+                    ),
+                    entry.dfi_account_number.trim().to_string(),
+                    company.clone(),
+                );
+                traces
+                    .entry(key.clone())
+                    .or_insert_with(|| {
+                        keys.push(key);
+                        Vec::new()
+                    })
+                    .push(entry.trace_number.to_string());
+            }
+        }
+
+        let mut report = VelocityReport::default();
+        for key in keys {
+            let trace_numbers = traces.remove(&key).unwrap_or_default();
+            let prior_count = prior_counts.get(&key).copied().unwrap_or(0);
+            let debit_count = prior_count + trace_numbers.len();
+            let (routing_number, account_number, company_identification) = key;
+            report.activity.push(VelocityCount {
+                routing_number: routing_number.clone(),
+                account_number: account_number.clone(),
+                company_identification: company_identification.clone(),
+                debit_count: trace_numbers.len(),
+            });
+            if debit_count > policy.max_debits {
+                report.violations.push(VelocityViolation {
+                    routing_number,
+                    account_number,
+                    company_identification,
+                    prior_count,
+                    debit_count,
+                    limit: policy.max_debits,
+                    trace_numbers,
+                });
+            }
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    /// The multi-company fixture with every entry pointed at one account.
+    fn one_account() -> AchFile<'static> {
+        let text = fixtures::sample_multi_company_file();
+        let mut file = AchFile::parse(&text).unwrap().into_owned();
+        for entry in file.batches.iter_mut().flat_map(|b| &mut b.entries) {
+            entry.receiving_dfi_identification = "12345678".into();
+            entry.check_digit = "0".into();
+            entry.dfi_account_number = "55501234".into();
+        }
+        file
+    }
+
+    #[test]
+    fn test_velocity_report() {
+        // One ACME debit and three BETA debits.
+        let file = one_account();
+        let report = file.velocity_report(&VelocityPolicy::default());
+        assert_eq!(report.activity.len(), 1);
+        assert_eq!(report.activity[0].debit_count, 4);
+        let violation = &report.violations[0];
+        assert_eq!(violation.routing_number, "123456780");
+        assert_eq!(violation.debit_count, 4);
+        assert_eq!(violation.limit, 3);
+        assert_eq!(violation.trace_numbers.len(), 4);
+        assert_eq!(
+            violation.trace_numbers[0],
+            file.batches[1].entries[1].trace_number
+        );
+
+        let per_company = VelocityPolicy {
+            per_company: true,
+            ..VelocityPolicy::default()
+        };
+        let report = file.velocity_report(&per_company);
+        assert_eq!(report.activity.len(), 2);
+        assert!(report.is_clean());
+
+        let ccd_only = VelocityPolicy {
+            max_debits: 0,
+            sec_codes: vec!["CCD".to_string()],
+            ..VelocityPolicy::default()
+        };
+        let report = file.velocity_report(&ccd_only);
+        assert_eq!(report.violations[0].trace_numbers.len(), 1);
+    }
+
+    #[test]
+    fn test_prior_activity() {
+        let file = one_account();
+        let per_company = VelocityPolicy {
+            per_company: true,
+            ..VelocityPolicy::default()
+        };
+        let yesterday = file.velocity_report(&per_company).activity;
+        let report = file.velocity_report_with_prior(&per_company, &yesterday);
+
+        // ACME: 1 + 1 debits, within the limit; BETA: 3 + 3.
+        assert_eq!(report.violations.len(), 1);
+        let violation = &report.violations[0];
+        assert_eq!(
+            violation.company_identification.as_deref(),
+            Some("2222222222")
+        );
+        assert_eq!(violation.prior_count, 3);
+        assert_eq!(violation.debit_count, 6);
+        assert_eq!(violation.trace_numbers.len(), 3);
+        // Activity covers this file only.
+        assert_eq!(report.activity, yesterday);
+
+        // Without a company, prior counts are ignored per company but count
+        // toward the account otherwise.
+        let anonymous = VelocityCount {
+            company_identification: None,
+            ..yesterday[0].clone()
+        };
+        let report =
+            file.velocity_report_with_prior(&per_company, std::slice::from_ref(&anonymous));
+        assert!(report.is_clean());
+        let report = file.velocity_report_with_prior(&VelocityPolicy::default(), &[anonymous]);
+        assert_eq!(report.violations[0].prior_count, 1);
+    }
+}
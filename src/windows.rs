@@ -0,0 +1,628 @@
+//! Same-day processing window classification.
+//!
+//! NACHA files carry a creation time but no time zone, and Federal Reserve
+//! same-day cutoffs are set in Eastern time, so [`WindowConfig`] names both
+//! zones explicitly and no local clock is ever consulted.
+
+use crate::banking_days::{nth_weekday, FederalReserve, HolidayCalendar};
+use crate::dates::{parse_hhmm, CalendarDate, CivilDate, YearPivot};
+use crate::{AchFile, Batch};
+
+const MINUTES_PER_DAY: i64 = 24 * 60;
+
+/// A time zone for file creation times and window cutoffs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum Zone {
+    /// A fixed offset from UTC, in minutes east of UTC
+    Fixed(i32),
+
+    /// A US zone with the given standard offset in minutes east of UTC,
+    /// observing daylight saving time from 02:00 on the second Sunday of
+    /// March until 02:00 on the first Sunday of November
+    UnitedStates(i32),
+}
+
+impl Zone {
+    /// Coordinated Universal Time.
+    pub const UTC: Zone = Zone::Fixed(0);
+
+    /// US Eastern time, the zone of the Federal Reserve cutoffs.
+    pub const US_EASTERN: Zone = Zone::UnitedStates(-5 * 60);
+
+    /// US Central time.
+    pub const US_CENTRAL: Zone = Zone::UnitedStates(-6 * 60);
+
+    /// US Mountain time.
+    pub const US_MOUNTAIN: Zone = Zone::UnitedStates(-7 * 60);
+
+    /// US Pacific time.
+    pub const US_PACIFIC: Zone = Zone::UnitedStates(-8 * 60);
+
+    /// Minutes since the Unix epoch, in UTC, of a wall-clock time in this
+    /// zone. Times repeated when daylight saving time ends are read as
+    /// daylight time.
+    fn to_utc(self, date: CivilDate, minutes: u32) -> i64 {
+        let local = wall_minutes(date, minutes);
+        let offset = match self {
+            Zone::Fixed(offset) => offset,
+            Zone::UnitedStates(standard) => {
+                let (start, end) = daylight_saving(date.year());
+                // Wall clocks skip 02:00-03:00 at the start and repeat
+                // 01:00-02:00 at the end.
+                let daylight = local >= start + 120 && local < end + 120;
+                standard + if daylight { 60 } else { 0 }
+            }
+        };
+        local - i64::from(offset)
+    }
+
+    /// The wall-clock date and minute of the day in this zone at `utc`
+    /// minutes since the Unix epoch.
+    fn wall_clock(self, utc: i64) -> (CivilDate, u32) {
+        let local = match self {
+            Zone::Fixed(offset) => utc + i64::from(offset),
+            Zone::UnitedStates(standard) => {
+                let standard_time = utc + i64::from(standard);
+                let year = split(standard_time).0.year();
+                let (start, end) = daylight_saving(year);
+                // In standard time the change happens at 02:00 in March and
+                // 01:00 in November.
+                let daylight = standard_time >= start + 120 && standard_time < end + 60;
+                standard_time + if daylight { 60 } else { 0 }
+            }
+        };
+        split(local)
+    }
+}
+
+/// Minutes since the epoch of a wall-clock time, ignoring zones.
+fn wall_minutes(date: CivilDate, minutes: u32) -> i64 {
+    i64::from(date.days_since_epoch()) * MINUTES_PER_DAY + i64::from(minutes)
+}
+
+/// Inverse of [`wall_minutes`].
+fn split(minutes: i64) -> (CivilDate, u32) {
+    let days = minutes.div_euclid(MINUTES_PER_DAY);
+    let date = CivilDate::from_days_since_epoch(days as i32);
+    (date, minutes.rem_euclid(MINUTES_PER_DAY) as u32)
+}
+
+/// Midnight at the start of the US daylight saving time transition days of
+/// `year`, as wall-clock minutes.
+fn daylight_saving(year: i32) -> (i64, i64) {
+    let start = nth_weekday(year, 3, 6, 2);
+    let end = nth_weekday(year, 11, 6, 1);
+    (wall_minutes(start, 0), wall_minutes(end, 0))
+}
+
+/// Same-day submission windows and the zones to read times in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct WindowConfig {
+    /// Window cutoffs as `(hour, minute)` in `cutoff_zone`, earliest first.
+    /// A file created at or before a cutoff makes that window.
+    pub cutoffs: Vec<(u32, u32)>,
+
+    /// Zone of the cutoffs
+    pub cutoff_zone: Zone,
+
+    /// Zone of the file creation time
+    pub file_zone: Zone,
+
+    /// Largest entry eligible for same-day processing, in cents
+    pub same_day_entry_limit: u64,
+
+    /// How two-digit years in the file creation and effective entry dates
+    /// are placed
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub year_pivot: YearPivot,
+}
+
+impl Default for WindowConfig {
+    /// The Federal Reserve windows (10:30, 14:45, and 16:45 Eastern), files
+    /// created in Eastern time, the $1,000,000 per-entry limit, and the
+    /// default [`YearPivot`].
+    fn default() -> Self {
+        WindowConfig {
+            cutoffs: vec![(10, 30), (14, 45), (16, 45)],
+            cutoff_zone: Zone::US_EASTERN,
+            file_zone: Zone::US_EASTERN,
+            same_day_entry_limit: 100_000_000,
+            year_pivot: YearPivot::default(),
+        }
+    }
+}
+
+/// How a batch is expected to be processed, from [`AchFile::window_classification`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum WindowClass {
+    /// Settles the day it is submitted, in the window at this index of
+    /// [`WindowConfig::cutoffs`]
+    SameDay(usize),
+
+    /// Due the day it is submitted, but misses every window or is
+    /// ineligible, so settles the next banking day
+    NextDay,
+
+    /// Effective after the submission date; settles on its effective date
+    Forward,
+
+    /// The file creation date is blank or invalid
+    Unknown,
+}
+
+/// The expected processing of one batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct BatchWindow {
+    /// Batch number, trimmed
+    pub batch_number: String,
+
+    /// Effective entry date rolled forward to a banking day, or `None` when
+    /// blank or invalid (processed at the first opportunity)
+    pub effective_entry_date: Option<CivilDate>,
+
+    /// Whether the effective date asks for settlement on or before the
+    /// submission date, making same-day processing meaningful
+    pub same_day_requested: bool,
+
+    /// Whether the batch may be processed same day: not IAT, and every entry
+    /// within [`WindowConfig::same_day_entry_limit`]
+    pub same_day_eligible: bool,
+
+    /// Expected processing
+    pub class: WindowClass,
+}
+
+/// The processing windows a file and its batches hit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct WindowReport {
+    /// Banking day, in the cutoff zone, on which the file is processed, or
+    /// `None` when the file creation date is blank or invalid
+    pub submission_date: Option<CivilDate>,
+
+    /// Index into [`WindowConfig::cutoffs`] of the first window the file
+    /// makes on `submission_date`, or `None` if it misses them all
+    pub window: Option<usize>,
+
+    /// One entry per batch, in file order
+    pub batches: Vec<BatchWindow>,
+}
+
+/// Something keeping a file from same-day processing, from
+/// [`AchFile::same_day_eligibility`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum SameDayReason {
+    /// An entry over [`WindowConfig::same_day_entry_limit`]
+    EntryOverLimit {
+        /// Batch number, trimmed
+        batch_number: String,
+
+        /// Trace number of the entry
+        trace_number: String,
+
+        /// Amount of the entry, in cents
+        amount: u64,
+    },
+
+    /// An IAT batch, which is never processed same day
+    IatBatch {
+        /// Batch number, trimmed
+        batch_number: String,
+
+        /// Trace numbers of the batch's entries
+        trace_numbers: Vec<String>,
+    },
+
+    /// A batch whose effective entry date is neither blank nor the
+    /// submission date
+    EffectiveDate {
+        /// Batch number, trimmed
+        batch_number: String,
+
+        /// The effective entry date as written
+        effective_entry_date: String,
+
+        /// Trace numbers of the batch's entries
+        trace_numbers: Vec<String>,
+    },
+}
+
+/// Whether a file qualifies for same-day processing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum SameDayEligibility {
+    /// Every batch may be processed same day
+    Eligible,
+
+    /// The reasons it may not, in file order
+    Ineligible(Vec<SameDayReason>),
+}
+
+impl SameDayEligibility {
+    /// Whether the file qualifies.
+    pub fn is_eligible(&self) -> bool {
+        matches!(self, SameDayEligibility::Eligible)
+    }
+}
+
+impl WindowReport {
+    /// Whether any batch is processed same day.
+    pub fn any_same_day(&self) -> bool {
+        self.batches
+            .iter()
+            .any(|b| matches!(b.class, WindowClass::SameDay(_)))
+    }
+}
+
+impl AchFile<'_> {
+    /// Classify which same-day window the file hits and how each batch is
+    /// expected to be processed.
+    ///
+    /// The creation time (midnight if blank) is converted from
+    /// [`WindowConfig::file_zone`] to [`WindowConfig::cutoff_zone`]. A file
+    /// created on a weekend or Federal Reserve holiday is processed in the
+    /// first window of the next banking day.
+    pub fn window_classification(&self, config: &WindowConfig) -> WindowReport {
+        let (submission_date, window) = match self.submission(config) {
+            Some((date, window)) => (Some(date), window),
+            None => (None, None),
+        };
+
+        let batches = self
+            .batches
+            .iter()
+            .map(|batch| classify(batch, config, submission_date, window))
+            .collect();
+        WindowReport {
+            submission_date,
+            window,
+            batches,
+        }
+    }
+
+    /// Check whether the file qualifies for same-day processing when
+    /// submitted on `today`: no IAT batches, every entry within
+    /// [`WindowConfig::same_day_entry_limit`], and every effective entry
+    /// date blank or `today`. Dates are read with
+    /// [`WindowConfig::year_pivot`]; the cutoffs and zones are not used.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile, CivilDate, SameDayReason, WindowConfig};
+    ///
+    /// let text = fixtures::sample_ppd_file(2);
+    /// let mut file = AchFile::parse(&text).unwrap();
+    /// file.batches[0].header.effective_entry_date = "250602".into();
+    /// let today = CivilDate::new(2025, 6, 2).unwrap();
+    /// assert!(file.same_day_eligibility(&today, &WindowConfig::default()).is_eligible());
+    ///
+    /// let config = WindowConfig {
+    ///     same_day_entry_limit: 2_000,
+    ///     ..WindowConfig::default()
+    /// };
+    /// let rs_ach::SameDayEligibility::Ineligible(reasons) =
+    ///     file.same_day_eligibility(&today, &config)
+    /// else {
+    ///     panic!("the $25.00 entry is over the limit");
+    /// };
+    /// assert!(matches!(reasons[..], [SameDayReason::EntryOverLimit { amount: 2_500, .. }]));
+    /// ```
+    pub fn same_day_eligibility<D: CalendarDate>(
+        &self,
+        today: &D,
+        config: &WindowConfig,
+    ) -> SameDayEligibility {
+        let today = CivilDate::of(today);
+        let mut reasons = Vec::new();
+        for batch in &self.batches {
+            let header = &batch.header;
+            let batch_number = header.batch_number.trim().to_string();
+            let trace_numbers = || {
+                batch
+                    .entries
+                    .iter()
+                    .map(|e| e.trace_number.to_string())
+                    .collect()
+            };
+            if header.standard_entry_class_code.trim() == "IAT" {
+                reasons.push(SameDayReason::IatBatch {
+                    batch_number: batch_number.clone(),
+                    trace_numbers: trace_numbers(),
+                });
+            }
+            let effective = &header.effective_entry_date;
+            if !effective.trim().is_empty()
+                && CivilDate::parse_with(effective, config.year_pivot) != Some(today)
+            {
+                reasons.push(SameDayReason::EffectiveDate {
+                    batch_number: batch_number.clone(),
+                    effective_entry_date: effective.to_string(),
+                    trace_numbers: trace_numbers(),
+                });
+            }
+            for entry in &batch.entries {
+                if entry.amount > config.same_day_entry_limit {
+                    reasons.push(SameDayReason::EntryOverLimit {
+                        batch_number: batch_number.clone(),
+                        trace_number: entry.trace_number.to_string(),
+                        amount: entry.amount,
+                    });
+                }
+            }
+        }
+        if reasons.is_empty() {
+            SameDayEligibility::Eligible
+        } else {
+            SameDayEligibility::Ineligible(reasons)
+        }
+    }
+
+    /// Processing date and window, or `None` when the creation date is
+    /// unreadable.
+    fn submission(&self, config: &WindowConfig) -> Option<(CivilDate, Option<usize>)> {
+        let header = &self.file_header;
+        let date = CivilDate::parse_with(&header.file_creation_date, config.year_pivot)?;
+        let (hour, minute) = parse_hhmm(&header.file_creation_time).unwrap_or((0, 0));
+        let utc = config.file_zone.to_utc(date, hour * 60 + minute);
+        let (date, minutes) = config.cutoff_zone.wall_clock(utc);
+
+        let first_window = (!config.cutoffs.is_empty()).then_some(0);
+        if !FederalReserve.is_banking_day(date) {
+            return Some((FederalReserve.next_banking_day(date)?, first_window));
+        }
+        let window = config
+            .cutoffs
+            .iter()
+            .position(|&(hour, minute)| minutes <= hour * 60 + minute);
+        Some((date, window))
+    }
+}
+
+fn classify(
+    batch: &Batch<'_>,
+    config: &WindowConfig,
+    submission_date: Option<CivilDate>,
+    window: Option<usize>,
+) -> BatchWindow {
+    let pivot = config.year_pivot;
+    let effective_entry_date = CivilDate::parse_with(&batch.header.effective_entry_date, pivot)
+        .and_then(|d| {
+            if FederalReserve.is_banking_day(d) {
+                Some(d)
+            } else {
+                FederalReserve.next_banking_day(d)
+            }
+        });
+    let same_day_requested = match (effective_entry_date, submission_date) {
+        (Some(effective), Some(submitted)) => effective <= submitted,
+        (None, Some(_)) => true,
+        (_, None) => false,
+    };
+    let same_day_eligible = batch.header.standard_entry_class_code.trim() != "IAT"
+        && batch
+            .entries
+            .iter()
+            .all(|e| e.amount <= config.same_day_entry_limit);
+
+    let class = match (submission_date, window) {
+        (None, _) => WindowClass::Unknown,
+        _ if !same_day_requested => WindowClass::Forward,
+        (Some(_), Some(window)) if same_day_eligible => WindowClass::SameDay(window),
+        (Some(_), _) => WindowClass::NextDay,
+    };
+    BatchWindow {
+        batch_number: batch.header.batch_number.trim().to_string(),
+        effective_entry_date,
+        same_day_requested,
+        same_day_eligible,
+        class,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    fn date(year: i32, month: u32, day: u32) -> CivilDate {
+        CivilDate::new(year, month, day).unwrap()
+    }
+
+    /// Classify the single-batch fixture created at `created` `time`,
+    /// effective `effective`.
+    fn classified(
+        created: &str,
+        time: &str,
+        effective: &str,
+        config: &WindowConfig,
+    ) -> WindowReport {
+        let text = fixtures::sample_ppd_file(2);
+        let mut file = AchFile::parse(&text).unwrap();
+        file.file_header.file_creation_date = created.to_string().into();
+        file.file_header.file_creation_time = time.to_string().into();
+        file.batches[0].header.effective_entry_date = effective.to_string().into();
+        file.window_classification(config)
+    }
+
+    #[test]
+    fn test_cutoff_boundaries() {
+        let config = WindowConfig::default();
+        // Monday, June 2, 2025.
+        for (time, window) in [
+            ("0000", Some(0)),
+            ("1030", Some(0)),
+            ("1031", Some(1)),
+            ("1445", Some(1)),
+            ("1446", Some(2)),
+            ("1645", Some(2)),
+            ("1646", None),
+        ] {
+            let report = classified("250602", time, "250602", &config);
+            assert_eq!(report.submission_date, Some(date(2025, 6, 2)), "{time}");
+            assert_eq!(report.window, window, "{time}");
+            let expected = window.map_or(WindowClass::NextDay, WindowClass::SameDay);
+            assert_eq!(report.batches[0].class, expected, "{time}");
+        }
+    }
+
+    #[test]
+    fn test_effective_dates() {
+        let config = WindowConfig::default();
+        // Forward-dated: same day is not meaningful.
+        let report = classified("250602", "0900", "250603", &config);
+        assert_eq!(report.batches[0].class, WindowClass::Forward);
+        assert!(!report.batches[0].same_day_requested);
+        assert!(!report.any_same_day());
+
+        // Stale and blank effective dates are processed at once.
+        let report = classified("250602", "0900", "250530", &config);
+        assert_eq!(report.batches[0].class, WindowClass::SameDay(0));
+        let report = classified("250602", "0900", "      ", &config);
+        assert_eq!(report.batches[0].effective_entry_date, None);
+        assert!(report.any_same_day());
+
+        // Created Saturday for Monday: Monday's first window.
+        let report = classified("250607", "2300", "250609", &config);
+        assert_eq!(report.submission_date, Some(date(2025, 6, 9)));
+        assert_eq!(report.batches[0].class, WindowClass::SameDay(0));
+
+        let report = classified("      ", "0900", "250602", &config);
+        assert_eq!(report.submission_date, None);
+        assert_eq!(report.batches[0].class, WindowClass::Unknown);
+    }
+
+    #[test]
+    fn test_time_zones() {
+        // 07:30 Pacific daylight time is exactly 10:30 Eastern.
+        let pacific = WindowConfig {
+            file_zone: Zone::US_PACIFIC,
+            ..WindowConfig::default()
+        };
+        assert_eq!(
+            classified("250602", "0730", "250602", &pacific).window,
+            Some(0)
+        );
+        assert_eq!(
+            classified("250602", "0731", "250602", &pacific).window,
+            Some(1)
+        );
+
+        // 14:30 UTC is 10:30 EDT in June but 09:30 EST in January.
+        let utc = WindowConfig {
+            file_zone: Zone::UTC,
+            ..WindowConfig::default()
+        };
+        assert_eq!(classified("250602", "1430", "250602", &utc).window, Some(0));
+        assert_eq!(classified("250602", "1431", "250602", &utc).window, Some(1));
+        assert_eq!(classified("250106", "1530", "250106", &utc).window, Some(0));
+        assert_eq!(classified("250106", "1531", "250106", &utc).window, Some(1));
+
+        // 01:00 UTC Tuesday is still Monday evening in Eastern time.
+        let report = classified("250603", "0100", "250602", &utc);
+        assert_eq!(report.submission_date, Some(date(2025, 6, 2)));
+        assert_eq!(report.window, None);
+    }
+
+    #[test]
+    fn test_daylight_saving_transitions() {
+        let eastern = Zone::US_EASTERN;
+        // March 9, 2025: 01:59 EST, then 03:00 EDT a minute later.
+        let march = date(2025, 3, 9);
+        assert_eq!(
+            eastern.wall_clock(eastern.to_utc(march, 119) + 1),
+            (march, 180)
+        );
+        // November 2, 2025: 01:59 EDT, then 01:00 EST a minute later.
+        let november = date(2025, 11, 2);
+        let last_daylight = eastern.to_utc(november, 119);
+        assert_eq!(eastern.wall_clock(last_daylight), (november, 119));
+        assert_eq!(eastern.wall_clock(last_daylight + 1), (november, 60));
+        assert_eq!(eastern.wall_clock(last_daylight + 61), (november, 120));
+    }
+
+    #[test]
+    fn test_same_day_eligibility() {
+        let limited = WindowConfig {
+            same_day_entry_limit: 2_000,
+            ..WindowConfig::default()
+        };
+        // The second entry is $25.00.
+        let report = classified("250602", "0900", "250602", &limited);
+        assert!(report.batches[0].same_day_requested);
+        assert!(!report.batches[0].same_day_eligible);
+        assert_eq!(report.batches[0].class, WindowClass::NextDay);
+
+        let text = fixtures::sample_ppd_file(1);
+        let mut file = AchFile::parse(&text).unwrap();
+        file.batches[0].header.standard_entry_class_code = "IAT".into();
+        file.file_header.file_creation_date = "140903".into();
+        let report = file.window_classification(&WindowConfig::default());
+        assert!(!report.batches[0].same_day_eligible);
+        assert_eq!(report.batches[0].batch_number, "0000001");
+    }
+
+    #[test]
+    fn test_same_day_eligibility_reasons() {
+        let text = fixtures::sample_multi_company_file();
+        let mut file = AchFile::parse(&text).unwrap();
+        let today = date(2025, 6, 2);
+        for batch in &mut file.batches {
+            batch.header.effective_entry_date = "250602".into();
+        }
+        file.batches[1].header.effective_entry_date = "      ".into();
+        let config = WindowConfig::default();
+        assert_eq!(
+            file.same_day_eligibility(&today, &config),
+            SameDayEligibility::Eligible
+        );
+
+        file.batches[0].header.standard_entry_class_code = "IAT".into();
+        file.batches[1].header.effective_entry_date = "250603".into();
+        file.batches[2].entries[0].amount = 100_000_001;
+        let traces = |batch: usize| -> Vec<String> {
+            file.batches[batch]
+                .entries
+                .iter()
+                .map(|e| e.trace_number.to_string())
+                .collect()
+        };
+        let expected = SameDayEligibility::Ineligible(vec![
+            SameDayReason::IatBatch {
+                batch_number: "0000001".to_string(),
+                trace_numbers: traces(0),
+            },
+            SameDayReason::EffectiveDate {
+                batch_number: "0000002".to_string(),
+                effective_entry_date: "250603".to_string(),
+                trace_numbers: traces(1),
+            },
+            SameDayReason::EntryOverLimit {
+                batch_number: "0000003".to_string(),
+                trace_number: traces(2)[0].clone(),
+                amount: 100_000_001,
+            },
+        ]);
+        assert_eq!(file.same_day_eligibility(&today, &config), expected);
+
+        // The limit has been raised over the years.
+        let raised = WindowConfig {
+            same_day_entry_limit: 200_000_000,
+            ..WindowConfig::default()
+        };
+        let eligibility = file.same_day_eligibility(&today, &raised);
+        assert!(
+            matches!(&eligibility, SameDayEligibility::Ineligible(reasons) if reasons.len() == 2)
+        );
+    }
+}
@@ -0,0 +1,275 @@
+//! Serialization of records back to NACHA fixed-width text.
+
+use std::convert::Infallible;
+use std::io;
+
+use crate::controls::blocking_factor;
+use crate::fields::FieldInfo;
+use crate::{AchError, AchFile, AchResult};
+
+/// Length of every NACHA record.
+pub(crate) const RECORD_LENGTH: usize = 94;
+
+/// Render one record from its fields.
+///
+/// Each field is placed at its layout position and values shorter than the
+/// field are space-padded on the right, so the result is always exactly
+/// [`RECORD_LENGTH`] characters. A value longer than its field fails with
+/// [`AchError::InvalidValue`] naming the field rather than being cut off,
+/// since a cut amount or total still makes a well-formed record.
+pub(crate) fn render_record(fields: &[FieldInfo<'_>]) -> AchResult<String> {
+    for field in fields {
+        let len = field.raw.chars().count();
+        if len > field.width() {
+            return Err(AchError::InvalidValue {
+                field: field.name,
+                reason: format!(
+                    "{:?} is {len} characters, the field holds {}",
+                    field.raw,
+                    field.width()
+                ),
+            });
+        }
+    }
+    Ok(layout_line(fields))
+}
+
+/// Place each field at its layout position, space-padding short values and
+/// cutting off long ones. Only for reading a record back by position, as
+/// the IAT views do; [`render_record`] is for writing.
+pub(crate) fn layout_line(fields: &[FieldInfo<'_>]) -> String {
+    let mut line = String::with_capacity(RECORD_LENGTH);
+    for field in fields {
+        let width = field.width();
+        line.extend(field.raw.chars().chain(std::iter::repeat(' ')).take(width));
+    }
+    line
+}
+
+/// Render every record of a file, one per line, without block padding.
+///
+/// Records of unknown type are written as they were read, each in its
+/// place among the batches or entries. Fails on the first value too long
+/// for its field; see [`render_record`].
+pub(crate) fn render_lines(file: &AchFile<'_>) -> AchResult<Vec<String>> {
+    lines_with(file, render_record)
+}
+
+/// Lay out every record of a file as [`render_lines`] does, cutting values
+/// too long for their field as [`layout_line`] does instead of failing.
+pub(crate) fn layout_lines(file: &AchFile<'_>) -> Vec<String> {
+    lines_with(file, |fields| Ok::<_, Infallible>(layout_line(fields)))
+        .unwrap_or_else(|never| match never {})
+}
+
+/// Every record of a file in order, each passed through `render`.
+fn lines_with<E>(
+    file: &AchFile<'_>,
+    render: impl Fn(&[FieldInfo<'_>]) -> Result<String, E>,
+) -> Result<Vec<String>, E> {
+    let mut lines = vec![render(&file.file_header.fields())?];
+    let mut unknown = file.unknown_records.iter().peekable();
+    for (batch_idx, batch) in file.batches.iter().enumerate() {
+        while let Some(record) = unknown.next_if(|r| r.preceding <= batch_idx) {
+            lines.push(record.raw_line.to_string());
+        }
+        lines.push(render(&batch.header.fields())?);
+        let mut unknown = batch.unknown_records.iter().peekable();
+        for (entry_idx, entry) in batch.entries.iter().enumerate() {
+            while let Some(record) = unknown.next_if(|r| r.preceding <= entry_idx) {
+                lines.push(record.raw_line.to_string());
+            }
+            lines.push(render(&entry.fields())?);
+            for addenda in &entry.addenda {
+                lines.push(render(&addenda.fields())?);
+            }
+        }
+        lines.extend(unknown.map(|r| r.raw_line.to_string()));
+        lines.push(render(&batch.control.fields())?);
+    }
+    lines.extend(unknown.map(|r| r.raw_line.to_string()));
+    lines.push(render(&file.file_control.fields())?);
+    Ok(lines)
+}
+
+/// Render every record of a file, followed by the `9` filler records that
+/// complete its last block.
+pub(crate) fn render_blocked(file: &AchFile<'_>) -> AchResult<Vec<String>> {
+    let mut lines = render_lines(file)?;
+    pad_block(&mut lines, blocking_factor(&file.file_header));
+    Ok(lines)
+}
+
+/// Add filler records until the number of lines is a multiple of
+/// `blocking_factor`.
+pub(crate) fn pad_block(lines: &mut Vec<String>, blocking_factor: u64) {
+    let blocking_factor = blocking_factor.max(1) as usize;
+    let filler = lines.len().next_multiple_of(blocking_factor) - lines.len();
+    lines.extend(std::iter::repeat_n("9".repeat(RECORD_LENGTH), filler));
+}
+
+impl AchFile<'_> {
+    /// The file as NACHA text: every record on its own line, ending in
+    /// `\n`, followed by `9` filler records up to a whole number of blocks
+    /// of the header's blocking factor.
+    ///
+    /// Every record is exactly 94 characters, with alphameric fields
+    /// space-padded on the right and numeric fields, including the amounts
+    /// and counts of the controls, zero-padded on the left. Records are
+    /// written as they are: controls that no longer match the entries are
+    /// not recomputed, and [`validate`](AchFile::validate) reports them.
+    ///
+    /// Fails with [`AchError::InvalidValue`] naming the field if a value is
+    /// too long for it, such as a total of more than 12 digits; nothing is
+    /// ever cut to fit.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile};
+    ///
+    /// let text = fixtures::sample_multi_company_file();
+    /// let mut file = AchFile::parse(&text).unwrap();
+    /// assert_eq!(file.to_ach_string().unwrap(), text);
+    ///
+    /// file.batches[0].entries[0].individual_name = "JANE ROE".into();
+    /// let written = file.to_ach_string().unwrap();
+    /// assert!(written.lines().all(|line| line.len() == 94));
+    /// let reparsed = AchFile::parse(&written).unwrap();
+    /// assert_eq!(reparsed.batches[0].entries[0].individual_name, "JANE ROE              ");
+    /// ```
+    pub fn to_ach_string(&self) -> AchResult<String> {
+        let lines = render_blocked(self)?;
+        let mut text = String::with_capacity(lines.len() * (RECORD_LENGTH + 1));
+        for line in lines {
+            text.push_str(&line);
+            text.push('\n');
+        }
+        Ok(text)
+    }
+
+    /// Write the file to `writer` as [`to_ach_string`](AchFile::to_ach_string)
+    /// renders it, one record at a time. Every record is rendered before
+    /// any is written, so a value too long for its field leaves `writer`
+    /// untouched; write failures are [`AchError::Io`].
+    pub fn write_to<W: io::Write + ?Sized>(&self, writer: &mut W) -> AchResult<()> {
+        for line in render_blocked(self)? {
+            writer.write_all(line.as_bytes())?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// The file unblocked, as some operators exchange it: the records of
+    /// [`to_ach_string`](AchFile::to_ach_string), filler included, one after
+    /// another with no line endings. [`AchFile::parse`] splits such text
+    /// into its records.
+    ///
+    /// ```
+    /// use rs_ach::{fixtures, AchFile};
+    ///
+    /// let text = fixtures::sample_ppd_file(2);
+    /// let file = AchFile::parse(&text).unwrap();
+    /// let unblocked = file.to_unblocked_string().unwrap();
+    /// assert_eq!(unblocked.len(), 10 * 94);
+    /// assert!(!unblocked.contains('\n'));
+    /// assert_eq!(AchFile::parse(&unblocked).unwrap(), file);
+    /// ```
+    pub fn to_unblocked_string(&self) -> AchResult<String> {
+        Ok(render_blocked(self)?.concat())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::fixtures;
+
+    #[test]
+    fn test_render_lines_reproduces_fixtures() {
+        for text in [
+            fixtures::sample_ppd_file(3),
+            fixtures::sample_ctx_with_addenda(),
+            fixtures::sample_returns_file(),
+            fixtures::sample_empty_file(),
+        ] {
+            let file = AchFile::parse(&text).unwrap();
+            let unpadded: Vec<&str> = text
+                .lines()
+                .filter(|l| !l.chars().all(|c| c == '9'))
+                .collect();
+            assert_eq!(render_lines(&file).unwrap(), unpadded);
+        }
+    }
+
+    #[test]
+    fn test_render_record_pads_short_fields() {
+        let text = fixtures::sample_ppd_file(1);
+        let mut file = AchFile::parse(&text).unwrap();
+        file.batches[0].entries[0].individual_name = "BOB".into();
+        let line = render_record(&file.batches[0].entries[0].fields()).unwrap();
+        assert_eq!(line.len(), RECORD_LENGTH);
+        assert_eq!(&line[54..76], "BOB                   ");
+    }
+
+    #[test]
+    fn test_values_too_long_for_their_field() {
+        let text = fixtures::sample_ppd_file(1);
+        let mut file = AchFile::parse(&text).unwrap();
+        file.file_control.total_credit_amount = 1_000_000_000_000;
+        let err = file.to_ach_string().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid value for 'total_credit_amount': \
+             \"1000000000000\" is 13 characters, the field holds 12"
+        );
+        let mut written = Vec::new();
+        assert!(file.write_to(&mut written).is_err());
+        assert!(written.is_empty());
+        assert!(file.to_unblocked_string().is_err());
+
+        let mut file = AchFile::parse(&text).unwrap();
+        file.batches[0].entries[0].individual_name = "A NAME TOO LONG FOR THE FIELD".into();
+        assert!(matches!(
+            file.to_ach_string(),
+            Err(AchError::InvalidValue {
+                field: "individual_name",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_to_ach_string_round_trips() {
+        for text in [
+            fixtures::sample_ppd_file(12),
+            fixtures::sample_multi_company_file(),
+            fixtures::sample_ctx_with_addenda(),
+            fixtures::sample_returns_file(),
+            fixtures::sample_empty_file(),
+        ] {
+            let file = AchFile::parse(&text).unwrap();
+            assert_eq!(file.to_ach_string().unwrap(), text);
+            let mut written = Vec::new();
+            file.write_to(&mut written).unwrap();
+            assert_eq!(written, text.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_to_ach_string_pads_blocks() {
+        let text = fixtures::sample_ppd_file(1);
+        let mut file = AchFile::parse(&text).unwrap();
+        file.file_header.blocking_factor = "04".into();
+        file.file_control.total_credit_amount = 125;
+        let written = file.to_ach_string().unwrap();
+        let lines: Vec<&str> = written.lines().collect();
+        assert_eq!(lines.len(), 8);
+        assert!(lines[5..].iter().all(|l| *l == "9".repeat(94)));
+        assert_eq!(&lines[4][43..55], "000000000125");
+
+        let unpadded = written.len() - 3 * 95;
+        file.file_header.blocking_factor = "01".into();
+        assert_eq!(file.to_ach_string().unwrap().len(), unpadded);
+    }
+}
@@ -1,5 +1,6 @@
 //! Tests to verify error handling
 
+use rs_ach::fixtures::{self, Corruption};
 use rs_ach::{AchError, AchFile};
 use std::error::Error;
 
@@ -29,36 +30,96 @@ fn test_errors_implement_std_error_trait() {
 }
 
 #[test]
-fn test_invalid_number_error_preserves_source() {
-    // Test that InvalidNumber error preserves the source error
+fn test_invalid_field_error_reports_location() {
     // Using invalid characters "XX" in the amount field (positions 30-39)
-    let invalid_ach = concat!(
-        "101 12345678012345678011409020123A094101YOUR BANK              YOUR COMPANY                   \n",
-        "5200YOUR COMPANY                        1234567890PPDPAYROLL         140903   1123456780000001\n",
-        "62212345678011232132         00000000XX               ALICE WANDERDUST        1123456780000001\n",
-        "820000000100123456780000000000000000000010001234567890                         123456780000001\n",
-        "9000001000001000000010012345678000000000000000000001000                                       ",
+    let invalid_ach = fixtures::corrupt(&fixtures::sample_ppd_file(1), Corruption::BadAmount);
+
+    let err = AchFile::parse(&invalid_ach).unwrap_err();
+    match err.root_cause() {
+        AchError::InvalidField {
+            record_type,
+            field,
+            line,
+            range,
+            reason,
+        } => {
+            assert_eq!(*record_type, "entry detail");
+            assert_eq!(*field, "amount");
+            // After the file header and batch header
+            assert_eq!(*line, 3);
+            assert_eq!(*range, 29..39);
+            assert!(!reason.is_empty());
+        }
+        e => panic!("Wrong error type: {}", e),
+    }
+
+    let message = err.to_string();
+    assert!(
+        message.contains("'amount' in entry detail record at line 3, positions 30-39"),
+        "{message}"
     );
+}
 
-    match AchFile::parse(invalid_ach) {
-        Err(AchError::InvalidNumber { field, source }) => {
-            // Should have field name
-            assert_eq!(field, "amount");
-
-            // Should have source error
-            let source_msg = format!("{}", source);
-            assert!(!source_msg.is_empty());
-
-            // Should be able to access source via Error trait
-            let err: &dyn Error = &AchError::InvalidNumber {
-                field: "amount",
-                source: source.clone(),
-            };
+#[test]
+fn test_errors_carry_batch_and_entry_context() {
+    let mut sample = fixtures::sample_multi_company_file();
+    // Break the amount of the second entry of the third batch.
+    let line = sample
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| l.starts_with('5'))
+        .nth(2)
+        .map(|(i, _)| i + 2)
+        .unwrap();
+    let offset: usize = sample.lines().take(line).map(|l| l.len() + 1).sum();
+    sample.replace_range(offset + 30..offset + 32, "XX");
+
+    let err = AchFile::parse(&sample).unwrap_err();
+    match &err {
+        AchError::InEntry {
+            batch_index,
+            batch_number,
+            entry_index,
+            trace_number,
+            source,
+        } => {
+            assert_eq!(*batch_index, 2);
+            assert_eq!(batch_number, "0000003");
+            assert_eq!(*entry_index, 1);
+            assert!(trace_number.is_some());
+            assert_eq!(err.root_cause().to_string(), source.to_string());
+            assert!(matches!(**source, AchError::InvalidField { .. }));
             assert!(err.source().is_some());
         }
-        Ok(_) => panic!("Should have failed with InvalidNumber"),
-        Err(e) => panic!("Wrong error type: {}", e),
+        e => panic!("Wrong error type: {}", e),
     }
+    let original = fixtures::sample_multi_company_file();
+    let trace = AchFile::parse(&original).unwrap().batches[2].entries[1]
+        .trace_number
+        .to_string();
+    assert!(
+        err.to_string()
+            .starts_with(&format!("in batch 0000003, entry 2 (trace {trace}): ")),
+        "{err}"
+    );
+
+    let incomplete = fixtures::corrupt(
+        &fixtures::sample_ppd_file(1),
+        Corruption::MissingBatchControl,
+    );
+    let err = AchFile::parse(&incomplete).unwrap_err();
+    assert!(matches!(err, AchError::InBatch { batch_index: 0, .. }));
+    assert!(err.to_string().starts_with("in batch 0000001: "), "{err}");
+}
+
+#[test]
+fn test_invalid_number_error_preserves_source() {
+    let source = "XX".parse::<u64>().unwrap_err();
+    let err: &dyn Error = &AchError::InvalidNumber {
+        field: "amount",
+        source,
+    };
+    assert!(err.source().is_some());
 }
 
 #[test]
@@ -85,6 +146,41 @@ fn test_error_messages_are_descriptive() {
     );
 }
 
+#[test]
+fn test_errors_report_file_line() {
+    // Three batches; a line of block padding before the second makes each
+    // record after it one line further down in the file.
+    let sample = fixtures::sample_multi_company_file();
+    let mut lines: Vec<String> = sample.lines().map(String::from).collect();
+    lines.insert(5, "9".repeat(94));
+    let parse = |lines: &[String]| AchFile::parse(&lines.join("\n")).unwrap_err();
+
+    // An entry of the second batch, at line 9 of the file.
+    let mut short = lines.clone();
+    short[8].pop();
+    let err = parse(&short);
+    assert_eq!(err.line(), Some(9));
+    assert!(err.to_string().contains("at line 9"), "{err}");
+
+    let mut bad_amount = lines.clone();
+    bad_amount[8].replace_range(29..31, "XX");
+    assert_eq!(parse(&bad_amount).line(), Some(9));
+
+    let mut bad_type = lines.clone();
+    bad_type[8].replace_range(0..1, "X");
+    let err = parse(&bad_type);
+    assert!(matches!(
+        err.root_cause(),
+        AchError::UnexpectedRecord { line: 9, .. }
+    ));
+    assert_eq!(err.line(), Some(9));
+
+    // The third batch's header in place of the second's control.
+    let mut no_control = lines.clone();
+    no_control.remove(9);
+    assert_eq!(parse(&no_control).line(), Some(10));
+}
+
 #[test]
 fn test_error_provides_line_context_where_available() {
     // Test that errors include line numbers when they can
@@ -132,58 +228,105 @@ fn test_empty_file_error() {
 fn test_invalid_line_length_error() {
     let short_line = "101 123";
     match AchFile::parse(short_line) {
-        Err(AchError::InvalidLineLength(len)) => {
-            assert_eq!(len, 7);
-            let msg = format!("{}", AchError::InvalidLineLength(len));
+        Err(AchError::InRecord {
+            record_type,
+            line,
+            source,
+        }) => {
+            assert_eq!(record_type, "file header");
+            assert_eq!(line, 1);
+            assert!(matches!(*source, AchError::InvalidLineLength(7)));
+            let msg = format!("{}", AchError::InvalidLineLength(7));
             assert!(msg.contains("94"));
             assert!(msg.contains("7"));
         }
         Ok(_) => panic!("Should have failed with InvalidLineLength"),
         Err(e) => panic!("Wrong error type: {}", e),
     }
+
+    // The message names the record being parsed.
+    let short = fixtures::corrupt(&fixtures::sample_ppd_file(1), Corruption::ShortLine);
+    let msg = AchFile::parse(&short).unwrap_err().to_string();
+    assert!(
+        msg.ends_with("entry detail record at line 3: Invalid line length: expected 94, got 93"),
+        "{msg}"
+    );
+}
+
+#[test]
+fn test_control_character_error() {
+    let sample = fixtures::sample_ppd_file(1);
+    let text = sample.replacen("RECEIVER 1 ", "RECEIVER\t1 ", 1);
+    let err = AchFile::parse(&text).unwrap_err();
+    assert!(matches!(
+        err.root_cause(),
+        AchError::ControlCharacter {
+            character: '\t',
+            position: 63
+        }
+    ));
+    assert_eq!(err.line(), Some(3));
+    assert!(
+        err.to_string()
+            .ends_with("entry detail record at line 3: Control character U+0009 at position 63"),
+        "{err}"
+    );
+    assert_eq!(err.kind(), rs_ach::ErrorKind::Record);
 }
 
 #[test]
 fn test_invalid_record_type_error() {
     let invalid_record = "X01 12345678012345678011409020123A094101YOUR BANK              YOUR COMPANY                   ";
-    match AchFile::parse(invalid_record) {
-        Err(AchError::InvalidRecordType(rt)) => {
+    let err = AchFile::parse(invalid_record).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "file header record at line 1: Invalid record type: X"
+    );
+    match err.root_cause() {
+        AchError::InvalidRecordType(rt) => {
             assert_eq!(rt, "X");
+            let rt = rt.clone();
             let msg = format!("{}", AchError::InvalidRecordType(rt));
             assert!(msg.contains("X"));
         }
-        Ok(_) => panic!("Should have failed with InvalidRecordType"),
-        Err(e) => panic!("Wrong error type: {}", e),
+        e => panic!("Wrong error type: {}", e),
     }
 }
 
 #[test]
 fn test_incomplete_batch_error() {
     // Batch without control record
-    let incomplete_batch = concat!(
-        "101 12345678012345678011409020123A094101YOUR BANK              YOUR COMPANY                   \n",
-        "5200YOUR COMPANY                        1234567890PPDPAYROLL         140903   1123456780000001\n",
-        "62212345678011232132         0000001000               ALICE WANDERDUST        1123456780000001\n",
-        // Missing batch control (8) record - file control comes directly after entry
-        "9000001000001000000010012345678000000000000000000001000                                       ",
+    let incomplete_batch = fixtures::corrupt(
+        &fixtures::sample_ppd_file(1),
+        Corruption::MissingBatchControl,
     );
 
-    match AchFile::parse(incomplete_batch) {
+    let err = AchFile::parse(&incomplete_batch).unwrap_err();
+    match err.root_cause() {
         // Both IncompleteBatch and InvalidStructure are valid errors for this case
-        Err(AchError::IncompleteBatch(msg)) => {
+        AchError::IncompleteBatch(msg) => {
             assert!(
                 msg.contains("control") || msg.contains("Missing"),
                 "Error should mention missing control: {}",
                 msg
             );
         }
-        Err(AchError::InvalidStructure(msg)) => {
+        AchError::InvalidStructure(msg) => {
             // Also valid - parser detected wrong record type in batch
             assert!(!msg.is_empty(), "Error should have a message");
         }
-        Ok(_) => panic!("Should have failed with batch error"),
-        Err(e) => panic!("Wrong error type: {}", e),
+        AchError::UnexpectedRecord { record_type, .. } => {
+            // Also valid - parser detected wrong record type in batch
+            assert_eq!(record_type, "9");
+        }
+        e => panic!("Wrong error type: {}", e),
     }
+    // The record found in place of the batch control is named, at its line.
+    assert!(
+        err.to_string()
+            .ends_with("Unexpected file control record in batch at line 4"),
+        "{err}"
+    );
 }
 
 #[test]
@@ -234,3 +377,70 @@ fn test_error_can_be_compared() {
     // Should be able to format both the same way
     assert_eq!(format!("{:?}", err1), format!("{:?}", err2));
 }
+
+#[test]
+fn test_error_kinds() {
+    use rs_ach::ErrorKind;
+
+    let sample = fixtures::sample_ppd_file(1);
+    let kind = |c| {
+        AchFile::parse(&fixtures::corrupt(&sample, c))
+            .unwrap_err()
+            .kind()
+    };
+    // Context around an error does not change its kind.
+    assert_eq!(kind(Corruption::BadAmount), ErrorKind::Field);
+    assert_eq!(kind(Corruption::ShortLine), ErrorKind::Record);
+    assert_eq!(kind(Corruption::MissingFileControl), ErrorKind::Structure);
+    assert_eq!(AchError::EmptyFile.kind(), ErrorKind::Structure);
+    assert_eq!(
+        AchError::InvalidValue {
+            field: "amount",
+            reason: "too large".to_string(),
+        }
+        .kind(),
+        ErrorKind::Field
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_errors_serialize_in_stable_shape() {
+    let text = fixtures::corrupt(&fixtures::sample_ppd_file(1), Corruption::BadAmount);
+    let err = AchFile::parse(&text).unwrap_err();
+    let json = serde_json::to_value(&err).unwrap();
+    assert_eq!(json["code"], "InvalidField");
+    assert_eq!(json["kind"], "Field");
+    assert_eq!(json["message"], err.to_string());
+    assert_eq!(json["line"], 3);
+    assert_eq!(json["field"], "amount");
+    assert_eq!(json["batch"], "0000001");
+    assert_eq!(json["trace"], "123456780000001");
+
+    // Fields that do not apply are left out.
+    let json = serde_json::to_value(AchError::EmptyFile).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "code": "EmptyFile",
+            "kind": "Structure",
+            "message": "Empty file",
+        })
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_validation_issues_serialize_like_errors() {
+    let text = fixtures::corrupt(&fixtures::sample_ppd_file(1), Corruption::TotalsMismatch);
+    let file = AchFile::parse(&text).unwrap();
+    let report = file.validate();
+    let issue = serde_json::to_value(&report.issues[0]).unwrap();
+    assert_eq!(issue["code"], "ValidationIssue");
+    assert_eq!(issue["batch"], "0000001");
+    assert!(issue.get("trace").is_none());
+    assert!(issue["field"].is_string());
+
+    let back: rs_ach::ValidationIssue = serde_json::from_value(issue).unwrap();
+    assert_eq!(back, report.issues[0]);
+}
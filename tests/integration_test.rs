@@ -1,6 +1,7 @@
 //! Integration tests for rs-ach parsing
 
-use rs_ach::{AchError, AchFile};
+use rs_ach::fixtures::{self, Corruption};
+use rs_ach::{AchError, AchFile, AchFileOwned, AchReader, BatchOwned};
 
 /// Sample ACH file from the NACHA specification (similar to python-ach example)
 /// Each line is exactly 94 characters as per NACHA standard
@@ -58,13 +59,10 @@ fn test_parse_basic_ach_file() {
     assert_eq!(entry1.amount, 1000);
     assert_eq!(entry1.individual_name.trim(), "ALICE WANDERDUST");
     assert_eq!(entry1.addenda.len(), 1);
-    assert_eq!(
-        entry1.addenda[0]
-            .payment_related_information
-            .trim()
-            .starts_with("HERE IS SOME ADDITIONAL"),
-        true
-    );
+    assert!(entry1.addenda[0]
+        .payment_related_information
+        .trim()
+        .starts_with("HERE IS SOME ADDITIONAL"));
 
     // Second entry (no addenda)
     let entry2 = &batch.entries[1];
@@ -98,17 +96,16 @@ fn test_empty_file() {
 #[test]
 fn test_invalid_line_length() {
     let invalid_ach = "101 123";
-    let result = AchFile::parse(invalid_ach);
-    assert!(matches!(result, Err(AchError::InvalidLineLength(_))));
+    let err = AchFile::parse(invalid_ach).unwrap_err();
+    assert!(matches!(err.root_cause(), AchError::InvalidLineLength(_)));
 }
 
 #[test]
 fn test_invalid_record_type() {
-    let invalid_ach = concat!(
-        "X01 123456780 1234567801409020123A094101YOUR BANK              YOUR COMPANY                   ",
-    );
-    let result = AchFile::parse(invalid_ach);
-    assert!(matches!(result, Err(AchError::InvalidRecordType(_))));
+    let invalid_ach =
+        "X01 123456780 1234567801409020123A094101YOUR BANK              YOUR COMPANY                   ";
+    let err = AchFile::parse(invalid_ach).unwrap_err();
+    assert!(matches!(err.root_cause(), AchError::InvalidRecordType(_)));
 }
 
 #[test]
@@ -162,3 +159,137 @@ fn test_debits_only_batch() {
     assert_eq!(batch.entries[0].transaction_code, "27");
     assert_eq!(batch.entries[1].transaction_code, "27");
 }
+
+#[test]
+fn test_fixture_files_parse() {
+    let ppd = fixtures::sample_ppd_file(12);
+    let ach_file = AchFile::parse(&ppd).unwrap();
+    let batch = &ach_file.batches[0];
+    assert_eq!(batch.entries.len(), 12);
+    assert_eq!(batch.control.entry_addenda_count, 12);
+    assert_eq!(ach_file.file_control.block_count, 2);
+
+    let ctx = fixtures::sample_ctx_with_addenda();
+    let ach_file = AchFile::parse(&ctx).unwrap();
+    assert_eq!(ach_file.batches[0].header.standard_entry_class_code, "CTX");
+    assert_eq!(ach_file.batches[0].entries[0].addenda.len(), 3);
+
+    let returns = fixtures::sample_returns_file();
+    let ach_file = AchFile::parse(&returns).unwrap();
+    for entry in &ach_file.batches[0].entries {
+        assert_eq!(entry.addenda[0].addenda_type_code, "99");
+    }
+}
+
+#[test]
+fn test_corrupted_fixture_is_rejected() {
+    let sample = fixtures::sample_ppd_file(2);
+    let short = fixtures::corrupt(&sample, Corruption::ShortLine);
+    let err = AchFile::parse(&short).unwrap_err();
+    assert!(matches!(err.root_cause(), AchError::InvalidLineLength(93)));
+
+    let truncated = fixtures::corrupt(&sample, Corruption::MissingFileControl);
+    let result = AchFile::parse(&truncated);
+    assert!(matches!(result, Err(AchError::InvalidStructure(_))));
+}
+
+#[test]
+fn test_line_endings_and_byte_order_mark() {
+    let sample = fixtures::sample_multi_company_file();
+    let expected = AchFile::parse(&sample).unwrap();
+    for text in [
+        fixtures::with_line_ending(&sample, "\r\n"),
+        fixtures::with_line_ending(&sample, "\r"),
+        fixtures::with_bom(&fixtures::with_line_ending(&sample, "\r\n")),
+        fixtures::with_bom(&sample),
+    ] {
+        assert_eq!(AchFile::parse(&text).unwrap(), expected, "{text:?}");
+        assert!(AchFile::records(&text).all(|record| record.is_ok()));
+        assert_eq!(AchFile::records(&text).count(), 15);
+
+        // A one-byte buffer splits each `\r\n` across reads.
+        let input = std::io::BufReader::with_capacity(1, text.as_bytes());
+        let mut reader = AchReader::new(input).unwrap();
+        let batches = reader.by_ref().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(batches, expected.batches);
+        assert_eq!(reader.file_control(), Some(&expected.file_control));
+    }
+}
+
+#[test]
+fn test_unblocked_file() {
+    let sample = fixtures::sample_multi_company_file();
+    let expected = AchFile::parse(&sample).unwrap();
+    let unblocked = sample.replace('\n', "");
+    assert_eq!(unblocked, expected.to_unblocked_string().unwrap());
+    for text in [
+        unblocked.clone(),
+        format!("{unblocked}\r\n"),
+        fixtures::with_bom(&unblocked),
+    ] {
+        assert_eq!(AchFile::parse(&text).unwrap(), expected);
+        assert!(AchFile::records(&text).all(|record| record.is_ok()));
+
+        let input = std::io::BufReader::with_capacity(10, text.as_bytes());
+        let mut reader = AchReader::new(input).unwrap();
+        let batches = reader.by_ref().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(batches, expected.batches);
+        assert_eq!(reader.file_control(), Some(&expected.file_control));
+    }
+
+    // Not a whole number of records: one long line.
+    let err = AchFile::parse(&unblocked[1..]).unwrap_err();
+    assert!(matches!(
+        err.root_cause(),
+        AchError::InvalidLineLength(1879)
+    ));
+}
+
+#[test]
+fn test_file_without_batches() {
+    let text = fixtures::sample_empty_file();
+    let ach_file = AchFile::parse(&text).unwrap();
+    assert!(ach_file.batches.is_empty());
+    assert!(ach_file.validate().is_valid());
+
+    let summary = ach_file.summary();
+    assert_eq!(summary.batch_count, 0);
+    assert!(ach_file.to_rows().is_empty());
+    assert!(!ach_file.render_report().is_empty());
+}
+
+#[test]
+fn test_parse_owned_from_str() {
+    let text = fixtures::sample_ctx_with_addenda();
+    let owned: AchFileOwned = text.parse().unwrap();
+    assert_eq!(owned, AchFile::parse(&text).unwrap());
+    drop(text);
+    assert_eq!(owned.batches[0].entries[0].addenda.len(), 3);
+
+    let short = fixtures::corrupt(&fixtures::sample_ppd_file(1), Corruption::ShortLine);
+    let err = short.parse::<AchFileOwned>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        AchFile::parse(&short).unwrap_err().to_string()
+    );
+}
+
+#[test]
+fn test_consuming_iterators() {
+    fn assert_send<T: Send>(value: T) -> T {
+        value
+    }
+
+    let owned: AchFileOwned = fixtures::sample_multi_company_file().parse().unwrap();
+    let batches: Vec<BatchOwned> = assert_send(owned.clone().into_batches()).collect();
+    assert_eq!(batches, owned.batches);
+    let entries: Vec<_> = assert_send(batches[2].clone().into_entries()).collect();
+    assert_eq!(entries, owned.batches[2].entries);
+
+    let pairs: Vec<_> = assert_send(owned.clone().into_entries()).collect();
+    assert_eq!(pairs.len(), 7);
+    assert!(std::sync::Arc::ptr_eq(&pairs[0].0, &pairs[1].0));
+    assert!(!std::sync::Arc::ptr_eq(&pairs[1].0, &pairs[2].0));
+    assert_eq!(*pairs[2].0, owned.batches[1].header);
+    assert_eq!(pairs[6].1, owned.batches[2].entries[2]);
+}
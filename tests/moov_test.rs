@@ -0,0 +1,28 @@
+//! moov-io/ach JSON golden file tests (require the `moov` feature).
+//!
+//! Run with `UPDATE_SNAPSHOTS=1` to rewrite the golden files after an
+//! intended change to the output.
+
+#![cfg(feature = "moov")]
+
+use rs_ach::{fixtures, AchFile};
+
+fn assert_golden(name: &str, text: &str) {
+    let path = format!("{}/tests/snapshots/{name}", env!("CARGO_MANIFEST_DIR"));
+    let actual = AchFile::parse(text).unwrap().to_moov_json().unwrap();
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&path, &actual).unwrap();
+    }
+    let expected = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(actual, expected, "moov JSON for {name} changed");
+}
+
+#[test]
+fn test_ppd_golden() {
+    assert_golden("moov_ppd.json", &fixtures::sample_multi_company_file());
+}
+
+#[test]
+fn test_ctx_golden() {
+    assert_golden("moov_ctx.json", &fixtures::sample_ctx_with_addenda());
+}
@@ -0,0 +1,32 @@
+//! JSON Schema snapshot tests (require the `schemars` feature).
+//!
+//! Run with `UPDATE_SNAPSHOTS=1` to rewrite the snapshot after an intended
+//! change to the serialized shape.
+
+#![cfg(feature = "schemars")]
+
+use rs_ach::{AchFileOwned, FileSummary};
+
+fn assert_snapshot(name: &str, schema: schemars::Schema) {
+    let path = format!("{}/tests/snapshots/{name}", env!("CARGO_MANIFEST_DIR"));
+    let actual = serde_json::to_string_pretty(&schema).unwrap() + "\n";
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&path, &actual).unwrap();
+    }
+    let expected = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(actual, expected, "schema for {name} changed");
+}
+
+#[test]
+fn test_ach_file_schema_snapshot() {
+    assert_snapshot("ach_file.schema.json", schemars::schema_for!(AchFileOwned));
+}
+
+#[test]
+fn test_schema_carries_doc_comments() {
+    let schema = serde_json::to_value(schemars::schema_for!(FileSummary)).unwrap();
+    assert_eq!(
+        schema["properties"]["batch_count"]["description"],
+        "Number of batches"
+    );
+}